@@ -0,0 +1,632 @@
+//! Ring buffer protocol for sending CPU bitmaps to overlay through shared memory.
+//!
+//! A client without GPU access writes frames into a memory region shared with the overlay,
+//! and the overlay uploads the latest published frame to its texture.
+//! The protocol only relies on memory and atomics, so it is platform independent.
+//!
+//! # Layout
+//! All fields are native endian and every offset is aligned to 64 bytes.
+//! * Ring header, 64 bytes: magic `ASBR`, version, slot count, slot capacity and the last published sequence.
+//! * Slots, each starting with 64 bytes slot header followed by `slot_capacity` bytes of pixel data.
+//!
+//! Frame `seq` is written into slot `seq % slot_count`.
+//! Slot headers store `seq * 2 - 1` while the frame is being written and `seq * 2` after,
+//! so readers can detect frames overwritten while reading.
+//!
+//! There must be only one writer for a ring buffer.
+
+use core::{
+    fmt,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicU32, AtomicU64, Ordering, fence},
+};
+
+/// Magic bytes at the start of a ring buffer.
+pub const MAGIC: u32 = u32::from_le_bytes(*b"ASBR");
+
+/// Protocol version.
+pub const VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = 64;
+const SLOT_HEADER_SIZE: usize = 64;
+const ALIGN: usize = 64;
+
+/// Number of attempts to read a frame not being overwritten.
+const READ_ATTEMPTS: usize = 4;
+
+#[repr(C)]
+struct RingHeader {
+    magic: AtomicU32,
+    version: AtomicU32,
+    slot_count: AtomicU32,
+    slot_capacity: AtomicU32,
+    published: AtomicU64,
+    _reserved: [u64; 5],
+}
+
+#[repr(C)]
+struct SlotHeader {
+    seq: AtomicU64,
+    width: AtomicU32,
+    height: AtomicU32,
+    stride: AtomicU32,
+    format: AtomicU32,
+    _reserved: [u64; 5],
+}
+
+const _: () = assert!(size_of::<RingHeader>() == HEADER_SIZE);
+const _: () = assert!(size_of::<SlotHeader>() == SLOT_HEADER_SIZE);
+
+/// Pixel format of a bitmap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum BitmapFormat {
+    /// 8 bits per channel, in blue, green, red, alpha order.
+    Bgra8 = 0,
+
+    /// 8 bits per channel, in red, green, blue, alpha order.
+    Rgba8 = 1,
+}
+
+impl BitmapFormat {
+    /// Convert from the raw value stored in slot headers.
+    pub const fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            0 => Some(Self::Bgra8),
+            1 => Some(Self::Rgba8),
+            _ => None,
+        }
+    }
+
+    /// Size of a pixel in bytes.
+    pub const fn pixel_size(self) -> usize {
+        4
+    }
+
+    /// Offset of the alpha channel in a pixel.
+    pub const fn alpha_offset(self) -> usize {
+        3
+    }
+}
+
+/// Dimensions and format of a bitmap frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitmapInfo {
+    /// Width of the bitmap in pixels.
+    pub width: u32,
+
+    /// Height of the bitmap in pixels.
+    pub height: u32,
+
+    /// Size of a row in bytes.
+    pub stride: u32,
+
+    /// Pixel format of the bitmap.
+    pub format: BitmapFormat,
+}
+
+impl BitmapInfo {
+    /// Create [`BitmapInfo`] of a tightly packed bitmap.
+    pub const fn new(width: u32, height: u32, format: BitmapFormat) -> Self {
+        Self {
+            width,
+            height,
+            stride: width * format.pixel_size() as u32,
+            format,
+        }
+    }
+
+    /// Size of the bitmap data in bytes.
+    pub const fn data_size(&self) -> usize {
+        self.stride as usize * self.height as usize
+    }
+
+    fn validate(&self, capacity: u32) -> Result<(), RingError> {
+        let row = self.width as usize * self.format.pixel_size();
+        if self.width == 0
+            || self.height == 0
+            || (self.stride as usize) < row
+            || self.data_size() > capacity as usize
+        {
+            return Err(RingError::InvalidFrame);
+        }
+
+        Ok(())
+    }
+}
+
+/// A frame read from a ring buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    /// Sequence number of the frame, starting from `1`.
+    pub seq: u64,
+
+    /// Dimensions and format of the frame.
+    pub info: BitmapInfo,
+}
+
+/// Error of ring buffer operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RingError {
+    /// Memory is smaller than the ring buffer.
+    TooSmall,
+
+    /// Memory is not aligned to 8 bytes.
+    Misaligned,
+
+    /// Memory does not start with [`MAGIC`].
+    InvalidMagic,
+
+    /// Ring buffer uses a different protocol version.
+    UnsupportedVersion(u32),
+
+    /// Ring buffer has no slots or its size overflows.
+    InvalidLayout,
+
+    /// Frame is empty, does not fit in a slot or its stride is smaller than its rows.
+    InvalidFrame,
+}
+
+impl fmt::Display for RingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RingError::TooSmall => write!(f, "memory is smaller than the ring buffer"),
+            RingError::Misaligned => write!(f, "memory is not aligned to 8 bytes"),
+            RingError::InvalidMagic => write!(f, "invalid ring buffer magic"),
+            RingError::UnsupportedVersion(version) => {
+                write!(f, "unsupported ring buffer version: {version}")
+            }
+            RingError::InvalidLayout => write!(f, "invalid ring buffer layout"),
+            RingError::InvalidFrame => write!(f, "invalid bitmap frame"),
+        }
+    }
+}
+
+impl core::error::Error for RingError {}
+
+/// Size of memory required for a ring buffer.
+///
+/// Returns [`None`] if `slot_count` is zero or the size overflows.
+pub const fn required_size(slot_count: u32, slot_capacity: u32) -> Option<usize> {
+    if slot_count == 0 {
+        return None;
+    }
+
+    let Some(slot_size) = slot_size(slot_capacity) else {
+        return None;
+    };
+    let Some(slots) = slot_size.checked_mul(slot_count as usize) else {
+        return None;
+    };
+    slots.checked_add(HEADER_SIZE)
+}
+
+const fn slot_size(slot_capacity: u32) -> Option<usize> {
+    let Some(capacity) = (slot_capacity as usize).checked_next_multiple_of(ALIGN) else {
+        return None;
+    };
+    capacity.checked_add(SLOT_HEADER_SIZE)
+}
+
+/// Pointer to a validated ring buffer.
+struct RawRing {
+    ptr: NonNull<u8>,
+    slot_count: u32,
+    slot_capacity: u32,
+}
+
+impl RawRing {
+    fn check(ptr: *const u8, len: usize) -> Result<NonNull<u8>, RingError> {
+        let ptr = NonNull::new(ptr.cast_mut()).ok_or(RingError::Misaligned)?;
+        if !ptr.cast::<AtomicU64>().is_aligned() {
+            return Err(RingError::Misaligned);
+        }
+        if len < HEADER_SIZE {
+            return Err(RingError::TooSmall);
+        }
+
+        Ok(ptr)
+    }
+
+    fn new(
+        ptr: NonNull<u8>,
+        len: usize,
+        slot_count: u32,
+        slot_capacity: u32,
+    ) -> Result<Self, RingError> {
+        let size = required_size(slot_count, slot_capacity).ok_or(RingError::InvalidLayout)?;
+        if size > len {
+            return Err(RingError::TooSmall);
+        }
+
+        Ok(Self {
+            ptr,
+            slot_count,
+            slot_capacity,
+        })
+    }
+
+    fn header(&self) -> &RingHeader {
+        unsafe { self.ptr.cast().as_ref() }
+    }
+
+    fn slot_offset(&self, seq: u64) -> usize {
+        let index = (seq % self.slot_count as u64) as usize;
+        // Cannot overflow, as it is checked by `required_size`.
+        HEADER_SIZE + index * slot_size(self.slot_capacity).unwrap()
+    }
+
+    fn slot(&self, seq: u64) -> &SlotHeader {
+        unsafe { self.ptr.add(self.slot_offset(seq)).cast().as_ref() }
+    }
+
+    fn slot_data(&self, seq: u64) -> NonNull<u8> {
+        unsafe { self.ptr.add(self.slot_offset(seq) + SLOT_HEADER_SIZE) }
+    }
+}
+
+/// Writing side of a ring buffer.
+pub struct BitmapWriter {
+    raw: RawRing,
+}
+
+// SAFETY: Shared memory is only accessed through atomics and seqlock protected copies.
+unsafe impl Send for BitmapWriter {}
+unsafe impl Sync for BitmapWriter {}
+
+impl BitmapWriter {
+    /// Initialize a ring buffer on the memory.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads and writes of `len` bytes while the writer is alive,
+    /// and no other writer may use the memory.
+    pub unsafe fn init(
+        ptr: *mut u8,
+        len: usize,
+        slot_count: u32,
+        slot_capacity: u32,
+    ) -> Result<Self, RingError> {
+        let raw = RawRing::new(RawRing::check(ptr, len)?, len, slot_count, slot_capacity)?;
+
+        let header = raw.header();
+        header.version.store(VERSION, Ordering::Relaxed);
+        header.slot_count.store(slot_count, Ordering::Relaxed);
+        header.slot_capacity.store(slot_capacity, Ordering::Relaxed);
+        header.published.store(0, Ordering::Relaxed);
+        for index in 0..slot_count as u64 {
+            raw.slot(index).seq.store(0, Ordering::Relaxed);
+        }
+        // Readers check magic first, so it is stored after everything else.
+        header.magic.store(MAGIC, Ordering::Release);
+
+        Ok(Self { raw })
+    }
+
+    /// Number of slots.
+    pub fn slot_count(&self) -> u32 {
+        self.raw.slot_count
+    }
+
+    /// Maximum size of a frame in bytes.
+    pub fn slot_capacity(&self) -> u32 {
+        self.raw.slot_capacity
+    }
+
+    /// Sequence number of the last published frame, `0` if nothing is published.
+    pub fn published(&self) -> u64 {
+        self.raw.header().published.load(Ordering::Relaxed)
+    }
+
+    /// Publish a frame by copying `data`.
+    ///
+    /// Returns sequence number of the frame.
+    pub fn write(&mut self, info: BitmapInfo, data: &[u8]) -> Result<u64, RingError> {
+        let size = info.data_size();
+        if data.len() < size {
+            return Err(RingError::InvalidFrame);
+        }
+
+        self.write_with(info, |slot| slot.copy_from_slice(&data[..size]))
+    }
+
+    /// Publish a frame by filling its data in the slot with the closure.
+    ///
+    /// The closure receives `info.data_size()` bytes of the slot, holding contents of an older frame.
+    /// Returns sequence number of the frame.
+    pub fn write_with(
+        &mut self,
+        info: BitmapInfo,
+        f: impl FnOnce(&mut [u8]),
+    ) -> Result<u64, RingError> {
+        info.validate(self.raw.slot_capacity)?;
+
+        let header = self.raw.header();
+        let seq = header.published.load(Ordering::Relaxed) + 1;
+        let slot = self.raw.slot(seq);
+
+        slot.seq.store(seq * 2 - 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        slot.width.store(info.width, Ordering::Relaxed);
+        slot.height.store(info.height, Ordering::Relaxed);
+        slot.stride.store(info.stride, Ordering::Relaxed);
+        slot.format.store(info.format as u32, Ordering::Relaxed);
+        f(unsafe {
+            NonNull::slice_from_raw_parts(self.raw.slot_data(seq), info.data_size()).as_mut()
+        });
+
+        slot.seq.store(seq * 2, Ordering::Release);
+        header.published.store(seq, Ordering::Release);
+        Ok(seq)
+    }
+}
+
+/// Reading side of a ring buffer.
+pub struct BitmapReader {
+    raw: RawRing,
+}
+
+// SAFETY: Shared memory is only accessed through atomics and seqlock protected copies.
+unsafe impl Send for BitmapReader {}
+unsafe impl Sync for BitmapReader {}
+
+impl BitmapReader {
+    /// Open a ring buffer initialized by [`BitmapWriter::init`].
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `len` bytes while the reader is alive.
+    pub unsafe fn open(ptr: *const u8, len: usize) -> Result<Self, RingError> {
+        let ptr = RawRing::check(ptr, len)?;
+
+        let header = unsafe { ptr.cast::<RingHeader>().as_ref() };
+        if header.magic.load(Ordering::Acquire) != MAGIC {
+            return Err(RingError::InvalidMagic);
+        }
+        let version = header.version.load(Ordering::Relaxed);
+        if version != VERSION {
+            return Err(RingError::UnsupportedVersion(version));
+        }
+
+        let raw = RawRing::new(
+            ptr,
+            len,
+            header.slot_count.load(Ordering::Relaxed),
+            header.slot_capacity.load(Ordering::Relaxed),
+        )?;
+        Ok(Self { raw })
+    }
+
+    /// Number of slots.
+    pub fn slot_count(&self) -> u32 {
+        self.raw.slot_count
+    }
+
+    /// Maximum size of a frame in bytes.
+    pub fn slot_capacity(&self) -> u32 {
+        self.raw.slot_capacity
+    }
+
+    /// Sequence number of the last published frame, `0` if nothing is published.
+    pub fn published(&self) -> u64 {
+        self.raw.header().published.load(Ordering::Acquire)
+    }
+
+    /// Copy the last published frame into `buf`, if it is newer than sequence number `after`.
+    ///
+    /// Returns [`None`] if there is no new frame,
+    /// or the writer kept overwriting the frame while reading.
+    pub fn read_latest(&self, after: u64, buf: &mut Vec<u8>) -> Result<Option<Frame>, RingError> {
+        for _ in 0..READ_ATTEMPTS {
+            let seq = self.published();
+            if seq == 0 || seq == after {
+                return Ok(None);
+            }
+
+            let slot = self.raw.slot(seq);
+            let begin = slot.seq.load(Ordering::Acquire);
+            if begin != seq * 2 {
+                // Writer wrapped around and is overwriting the slot
+                continue;
+            }
+
+            let info = BitmapFormat::from_raw(slot.format.load(Ordering::Relaxed))
+                .map(|format| BitmapInfo {
+                    width: slot.width.load(Ordering::Relaxed),
+                    height: slot.height.load(Ordering::Relaxed),
+                    stride: slot.stride.load(Ordering::Relaxed),
+                    format,
+                })
+                .ok_or(RingError::InvalidFrame)
+                .and_then(|info| info.validate(self.raw.slot_capacity).map(|_| info));
+
+            if let Ok(ref info) = info {
+                let size = info.data_size();
+                buf.clear();
+                buf.reserve(size);
+                unsafe {
+                    ptr::copy_nonoverlapping(
+                        self.raw.slot_data(seq).as_ptr(),
+                        buf.as_mut_ptr(),
+                        size,
+                    );
+                    buf.set_len(size);
+                }
+            }
+
+            fence(Ordering::Acquire);
+            if slot.seq.load(Ordering::Relaxed) != begin {
+                continue;
+            }
+
+            return info.map(|info| Some(Frame { seq, info }));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::*;
+
+    /// 8 bytes aligned memory for a ring buffer.
+    fn memory(slot_count: u32, slot_capacity: u32) -> Vec<u64> {
+        vec![
+            0;
+            required_size(slot_count, slot_capacity)
+                .unwrap()
+                .div_ceil(8)
+        ]
+    }
+
+    fn ring(memory: &mut [u64]) -> (BitmapWriter, BitmapReader, usize) {
+        let len = size_of_val(memory);
+        let ptr = memory.as_mut_ptr().cast::<u8>();
+        let writer = unsafe { BitmapWriter::init(ptr, len, 2, 64) }.unwrap();
+        let reader = unsafe { BitmapReader::open(ptr, len) }.unwrap();
+        (writer, reader, len)
+    }
+
+    #[test]
+    fn write_read_cycle() {
+        let mut memory = memory(2, 64);
+        let (mut writer, reader, _) = ring(&mut memory);
+        let mut buf = Vec::new();
+        assert_eq!(reader.read_latest(0, &mut buf), Ok(None));
+
+        let info = BitmapInfo::new(2, 2, BitmapFormat::Rgba8);
+        for seq in 1..=5 {
+            assert_eq!(writer.write(info, &[seq as u8; 16]), Ok(seq));
+            assert_eq!(reader.published(), seq);
+
+            let frame = reader.read_latest(seq - 1, &mut buf).unwrap().unwrap();
+            assert_eq!(frame, Frame { seq, info });
+            assert_eq!(buf, [seq as u8; 16]);
+
+            // Already read
+            assert_eq!(reader.read_latest(seq, &mut buf), Ok(None));
+        }
+    }
+
+    #[test]
+    fn open_checks_header() {
+        let mut memory = memory(2, 64);
+        let len = size_of_val(&memory[..]);
+        let ptr = memory.as_mut_ptr().cast::<u8>();
+        assert_eq!(
+            unsafe { BitmapReader::open(ptr, len) }.err(),
+            Some(RingError::InvalidMagic)
+        );
+
+        let (_, _, len) = ring(&mut memory);
+        let ptr = memory.as_ptr().cast::<u8>();
+        assert_eq!(
+            unsafe { BitmapReader::open(ptr, len - 1) }.err(),
+            Some(RingError::TooSmall)
+        );
+        assert_eq!(
+            unsafe { BitmapReader::open(ptr.wrapping_add(1), len - 1) }.err(),
+            Some(RingError::Misaligned)
+        );
+    }
+
+    #[test]
+    fn torn_read_retry() {
+        let mut memory = memory(2, 64);
+        let (mut writer, reader, _) = ring(&mut memory);
+        let mut buf = Vec::new();
+
+        let info = BitmapInfo::new(2, 2, BitmapFormat::Bgra8);
+        writer.write(info, &[1; 16]).unwrap();
+
+        // Simulate the writer wrapping around and writing frame 3 into the published slot
+        let slot = reader.raw.slot(1);
+        slot.seq.store(3 * 2 - 1, Ordering::Relaxed);
+        assert_eq!(reader.read_latest(0, &mut buf), Ok(None));
+
+        // Readable again once the slot is consistent
+        slot.seq.store(2, Ordering::Relaxed);
+        assert_eq!(
+            reader.read_latest(0, &mut buf),
+            Ok(Some(Frame { seq: 1, info }))
+        );
+    }
+
+    #[test]
+    fn concurrent_reads_are_consistent() {
+        const FRAMES: u64 = 10_000;
+
+        let mut memory = memory(2, 64);
+        let (mut writer, reader, _) = ring(&mut memory);
+        let reader = Arc::new(reader);
+
+        let reading = thread::spawn({
+            let reader = reader.clone();
+            move || {
+                let mut buf = Vec::new();
+                let mut last = 0;
+                while last < FRAMES {
+                    if let Some(frame) = reader.read_latest(last, &mut buf).unwrap() {
+                        assert!(frame.seq > last);
+                        // Every byte of a frame is written with its sequence number
+                        assert!(buf.iter().all(|&byte| byte == frame.seq as u8));
+                        last = frame.seq;
+                    }
+                }
+            }
+        });
+
+        let info = BitmapInfo::new(4, 4, BitmapFormat::Rgba8);
+        for seq in 1..=FRAMES {
+            writer
+                .write_with(info, |data| data.fill(seq as u8))
+                .unwrap();
+        }
+        reading.join().unwrap();
+    }
+
+    #[test]
+    fn validate() {
+        let format = BitmapFormat::Bgra8;
+        assert!(BitmapInfo::new(4, 4, format).validate(64).is_ok());
+        assert!(BitmapInfo::new(4, 4, format).validate(63).is_err());
+
+        // Padded rows
+        let padded = BitmapInfo {
+            stride: 20,
+            ..BitmapInfo::new(2, 3, format)
+        };
+        assert!(padded.validate(60).is_ok());
+        assert!(padded.validate(59).is_err());
+
+        // Stride smaller than rows
+        let short = BitmapInfo {
+            stride: 7,
+            ..BitmapInfo::new(2, 2, format)
+        };
+        assert_eq!(short.validate(64), Err(RingError::InvalidFrame));
+
+        // Empty frames
+        assert!(BitmapInfo::new(0, 4, format).validate(64).is_err());
+        assert!(BitmapInfo::new(4, 0, format).validate(64).is_err());
+        let zero_stride = BitmapInfo {
+            stride: 0,
+            ..BitmapInfo::new(0, 0, format)
+        };
+        assert_eq!(zero_stride.validate(64), Err(RingError::InvalidFrame));
+    }
+
+    #[test]
+    fn write_rejects_invalid_frames() {
+        let mut memory = memory(2, 64);
+        let (mut writer, reader, _) = ring(&mut memory);
+
+        let empty = BitmapInfo::new(0, 0, BitmapFormat::Rgba8);
+        assert_eq!(writer.write(empty, &[]), Err(RingError::InvalidFrame));
+        let info = BitmapInfo::new(2, 2, BitmapFormat::Rgba8);
+        assert_eq!(writer.write(info, &[0; 15]), Err(RingError::InvalidFrame));
+        assert_eq!(reader.published(), 0);
+    }
+}
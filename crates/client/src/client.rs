@@ -2,11 +2,19 @@
 //!
 //! Provides interfaces for sending requests via ipc and receive events.
 
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
 use std::sync::{Arc, Weak};
 
 use anyhow::{Context as AnyhowContext, bail};
 use asdf_overlay_common::{
-    event::OverlayEvent,
+    event::{
+        OverlayEvent,
+        surface::SurfaceEvent,
+        window::{WindowEvent, input::CursorInput},
+    },
     ipc::{ClientRequest, Frame, ServerToClientPacket},
     request::{
         self, Request, Requestable,
@@ -15,6 +23,8 @@ use asdf_overlay_common::{
     },
 };
 use dashmap::DashMap;
+use futures_core::Stream;
+use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt, WriteHalf, split},
@@ -198,8 +208,12 @@ impl IpcClientConnSurface<'_> {
 }
 
 /// Event stream for receiving server events.
+///
+/// To subscribe to events of a specific window or surface, convert into [`IpcClientEventBroadcast`]
+/// using [`IpcClientEventStream::broadcast`], then use [`IpcClientEventBroadcast::window`]
+/// or [`IpcClientEventBroadcast::surface`].
 pub struct IpcClientEventStream {
-    inner: mpsc::UnboundedReceiver<OverlayEvent>,
+    pub(crate) inner: mpsc::UnboundedReceiver<OverlayEvent>,
 }
 
 impl IpcClientEventStream {
@@ -209,4 +223,427 @@ impl IpcClientEventStream {
     pub async fn recv(&mut self) -> Option<OverlayEvent> {
         self.inner.recv().await
     }
+
+    /// Convert into [`IpcClientEventBroadcast`] so multiple tasks can subscribe to events.
+    pub fn broadcast(mut self) -> IpcClientEventBroadcast {
+        let subscribers = Arc::new(Mutex::new(Some(Vec::<Subscriber>::new())));
+
+        tokio::spawn({
+            let subscribers = subscribers.clone();
+
+            async move {
+                while let Some(event) = self.recv().await {
+                    if let Some(ref mut subscribers) = *subscribers.lock() {
+                        subscribers.retain(|subscriber| subscriber.send(&event));
+                    }
+                }
+
+                // Drop every sender so subscribed streams end
+                subscribers.lock().take();
+            }
+        });
+
+        IpcClientEventBroadcast { subscribers }
+    }
+}
+
+impl Stream for IpcClientEventStream {
+    type Item = OverlayEvent;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_recv(cx)
+    }
+}
+
+/// Event broadcaster for subscribing to server events from multiple tasks.
+///
+/// Subscribers only receive events emitted after subscribing.
+#[derive(Clone)]
+pub struct IpcClientEventBroadcast {
+    subscribers: Arc<Mutex<Option<Vec<Subscriber>>>>,
+}
+
+impl IpcClientEventBroadcast {
+    /// Subscribe to every event.
+    pub fn subscribe(&self) -> IpcClientEventStream {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.add(Subscriber::All(tx));
+        IpcClientEventStream { inner: rx }
+    }
+
+    /// Subscribe to events of a specific window id.
+    /// The stream ends after the window is destroyed.
+    ///
+    /// The id must be of a window known to be alive, such as one from `OverlayState`.
+    /// If the window was already destroyed, the stream does not end until the connection is closed.
+    pub fn window(&self, id: u32) -> WindowEventStream {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.add(Subscriber::Window(id, tx));
+        WindowEventStream { id, inner: rx }
+    }
+
+    /// Subscribe to events of a specific surface id.
+    /// The stream ends after the surface is destroyed.
+    ///
+    /// The id must be of a surface known to be alive, such as one from `OverlayState`.
+    /// If the surface was already destroyed, the stream does not end until the connection is closed.
+    pub fn surface(&self, id: u64) -> SurfaceEventStream {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.add(Subscriber::Surface(id, tx));
+        SurfaceEventStream { id, inner: rx }
+    }
+
+    fn add(&self, subscriber: Subscriber) {
+        // If the connection is closed, subscriber is dropped and the stream ends immediately
+        if let Some(ref mut subscribers) = *self.subscribers.lock() {
+            subscribers.push(subscriber);
+        }
+    }
+}
+
+enum Subscriber {
+    All(mpsc::UnboundedSender<OverlayEvent>),
+    Window(u32, mpsc::UnboundedSender<WindowStreamEvent>),
+    Surface(u64, mpsc::UnboundedSender<SurfaceStreamEvent>),
+}
+
+impl Subscriber {
+    /// Send event to the subscriber.
+    /// Returns `false` if the subscriber should be removed.
+    fn send(&self, event: &OverlayEvent) -> bool {
+        match (self, event) {
+            (Subscriber::All(tx), _) => tx.send(event.clone()).is_ok(),
+
+            (
+                Subscriber::Window(id, tx),
+                OverlayEvent::Window {
+                    id: event_id,
+                    event,
+                },
+            ) if id == event_id => {
+                tx.send(WindowStreamEvent::Window(event.clone())).is_ok()
+                    && !matches!(event, WindowEvent::Destroyed)
+            }
+
+            (
+                Subscriber::Window(id, tx),
+                OverlayEvent::SurfaceCursorInput {
+                    id: surface_id,
+                    window_id,
+                    layer,
+                    input,
+                },
+            ) if id == window_id => tx
+                .send(WindowStreamEvent::SurfaceCursorInput {
+                    id: *surface_id,
+                    layer: *layer,
+                    input: input.clone(),
+                })
+                .is_ok(),
+
+            (
+                Subscriber::Surface(id, tx),
+                OverlayEvent::Surface {
+                    id: event_id,
+                    event,
+                },
+            ) if id == event_id => {
+                tx.send(SurfaceStreamEvent::Surface(event.clone())).is_ok()
+                    && !matches!(event, SurfaceEvent::Destroyed)
+            }
+
+            (
+                Subscriber::Surface(id, tx),
+                OverlayEvent::SurfaceCursorInput {
+                    id: event_id,
+                    layer,
+                    input,
+                    ..
+                },
+            ) if id == event_id => tx
+                .send(SurfaceStreamEvent::CursorInput {
+                    layer: *layer,
+                    input: input.clone(),
+                })
+                .is_ok(),
+
+            (Subscriber::Window(_, tx), _) => !tx.is_closed(),
+            (Subscriber::Surface(_, tx), _) => !tx.is_closed(),
+        }
+    }
+}
+
+/// Event of [`WindowEventStream`].
+#[derive(Debug, Clone)]
+pub enum WindowStreamEvent {
+    /// Event of the window.
+    Window(WindowEvent),
+
+    /// Cursor input routed to a surface of the window.
+    ///
+    /// You only receive this event if surface input routing is enabled.
+    SurfaceCursorInput {
+        /// Surface identifier.
+        id: u64,
+
+        /// Layer identifier. `0` is the base layer.
+        layer: u32,
+
+        /// Cursor input. The position is relative to the left-top corner of the layer texture.
+        input: CursorInput,
+    },
+}
+
+/// Event stream of a specific window.
+pub struct WindowEventStream {
+    id: u32,
+    inner: mpsc::UnboundedReceiver<WindowStreamEvent>,
+}
+
+impl WindowEventStream {
+    /// Window id of the stream.
+    #[inline]
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Receive the next event.
+    /// Returns `None` if the window is destroyed or the connection is closed.
+    #[inline]
+    pub async fn recv(&mut self) -> Option<WindowStreamEvent> {
+        self.inner.recv().await
+    }
+}
+
+impl Stream for WindowEventStream {
+    type Item = WindowStreamEvent;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_recv(cx)
+    }
+}
+
+/// Event of [`SurfaceEventStream`].
+#[derive(Debug, Clone)]
+pub enum SurfaceStreamEvent {
+    /// Event of the surface.
+    Surface(SurfaceEvent),
+
+    /// Cursor input routed to a layer of the surface.
+    ///
+    /// You only receive this event if surface input routing is enabled.
+    CursorInput {
+        /// Layer identifier. `0` is the base layer.
+        layer: u32,
+
+        /// Cursor input. The position is relative to the left-top corner of the layer texture.
+        input: CursorInput,
+    },
+}
+
+/// Event stream of a specific surface.
+pub struct SurfaceEventStream {
+    id: u64,
+    inner: mpsc::UnboundedReceiver<SurfaceStreamEvent>,
+}
+
+impl SurfaceEventStream {
+    /// Surface id of the stream.
+    #[inline]
+    pub const fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Receive the next event.
+    /// Returns `None` if the surface is destroyed or the connection is closed.
+    #[inline]
+    pub async fn recv(&mut self) -> Option<SurfaceStreamEvent> {
+        self.inner.recv().await
+    }
+}
+
+impl Stream for SurfaceEventStream {
+    type Item = SurfaceStreamEvent;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use asdf_overlay_common::event::window::input::{
+        CursorButtons, CursorEvent, InputPosition, Modifiers,
+    };
+
+    use super::*;
+
+    fn broadcast() -> (mpsc::UnboundedSender<OverlayEvent>, IpcClientEventBroadcast) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (tx, IpcClientEventStream { inner: rx }.broadcast())
+    }
+
+    fn resized(id: u32) -> OverlayEvent {
+        OverlayEvent::Window {
+            id,
+            event: WindowEvent::Resized {
+                width: 100,
+                height: 100,
+            },
+        }
+    }
+
+    fn cursor_input(id: u64, window_id: u32, layer: u32) -> OverlayEvent {
+        OverlayEvent::SurfaceCursorInput {
+            id,
+            window_id,
+            layer,
+            input: CursorInput {
+                event: CursorEvent::Enter,
+                pos: InputPosition { x: 0, y: 0 },
+                modifiers: Modifiers::empty(),
+                buttons: CursorButtons::empty(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn window_routing() {
+        let (tx, broadcast) = broadcast();
+        let mut stream = broadcast.window(1);
+
+        tx.send(resized(2)).unwrap();
+        tx.send(resized(1)).unwrap();
+        tx.send(cursor_input(10, 2, 0)).unwrap();
+        tx.send(cursor_input(10, 1, 3)).unwrap();
+        tx.send(OverlayEvent::Window {
+            id: 1,
+            event: WindowEvent::Destroyed,
+        })
+        .unwrap();
+        tx.send(resized(1)).unwrap();
+
+        assert!(matches!(
+            stream.recv().await,
+            Some(WindowStreamEvent::Window(WindowEvent::Resized { .. }))
+        ));
+        assert!(matches!(
+            stream.recv().await,
+            Some(WindowStreamEvent::SurfaceCursorInput {
+                id: 10,
+                layer: 3,
+                ..
+            })
+        ));
+        assert!(matches!(
+            stream.recv().await,
+            Some(WindowStreamEvent::Window(WindowEvent::Destroyed))
+        ));
+        assert!(stream.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn surface_routing() {
+        let (tx, broadcast) = broadcast();
+        let mut stream = broadcast.surface(10);
+
+        tx.send(resized(1)).unwrap();
+        tx.send(cursor_input(11, 1, 0)).unwrap();
+        tx.send(cursor_input(10, 1, 2)).unwrap();
+        tx.send(OverlayEvent::Surface {
+            id: 11,
+            event: SurfaceEvent::Destroyed,
+        })
+        .unwrap();
+        tx.send(OverlayEvent::Surface {
+            id: 10,
+            event: SurfaceEvent::Destroyed,
+        })
+        .unwrap();
+        tx.send(cursor_input(10, 1, 2)).unwrap();
+
+        assert!(matches!(
+            stream.recv().await,
+            Some(SurfaceStreamEvent::CursorInput { layer: 2, .. })
+        ));
+        assert!(matches!(
+            stream.recv().await,
+            Some(SurfaceStreamEvent::Surface(SurfaceEvent::Destroyed))
+        ));
+        assert!(stream.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn cursor_input_fan_out() {
+        let (tx, broadcast) = broadcast();
+        let mut all = broadcast.subscribe();
+        let mut window = broadcast.window(1);
+        let mut surface = broadcast.surface(10);
+
+        tx.send(cursor_input(10, 1, 0)).unwrap();
+        drop(tx);
+
+        assert!(matches!(
+            all.recv().await,
+            Some(OverlayEvent::SurfaceCursorInput { id: 10, .. })
+        ));
+        assert!(matches!(
+            window.recv().await,
+            Some(WindowStreamEvent::SurfaceCursorInput { id: 10, .. })
+        ));
+        assert!(matches!(
+            surface.recv().await,
+            Some(SurfaceStreamEvent::CursorInput { layer: 0, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn connection_closed() {
+        let (tx, broadcast) = broadcast();
+        let mut window = broadcast.window(1);
+        let mut surface = broadcast.surface(10);
+
+        drop(tx);
+        assert!(window.recv().await.is_none());
+        assert!(surface.recv().await.is_none());
+
+        // Subscribed after the connection is closed
+        assert!(broadcast.subscribe().recv().await.is_none());
+        assert!(broadcast.window(1).recv().await.is_none());
+    }
+
+    #[test]
+    fn dropped_subscriber() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let subscriber = Subscriber::Window(1, tx);
+        assert!(subscriber.send(&resized(2)));
+        assert!(subscriber.send(&resized(1)));
+
+        drop(rx);
+        assert!(!subscriber.send(&resized(2)));
+        assert!(!subscriber.send(&resized(1)));
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let subscriber = Subscriber::Surface(10, tx);
+        drop(rx);
+        assert!(!subscriber.send(&cursor_input(10, 1, 0)));
+        assert!(!subscriber.send(&cursor_input(11, 1, 0)));
+    }
+
+    #[test]
+    fn destroyed_removes_subscriber() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let subscriber = Subscriber::Surface(10, tx);
+        assert!(!subscriber.send(&OverlayEvent::Surface {
+            id: 10,
+            event: SurfaceEvent::Destroyed,
+        }));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SurfaceStreamEvent::Surface(SurfaceEvent::Destroyed))
+        ));
+    }
 }
@@ -0,0 +1,208 @@
+//! [`OverlayController`] implementation over IPC connection.
+
+use core::time::Duration;
+
+use asdf_overlay_common::{
+    controller::{OverlayController, SurfaceSnapshot, WindowSnapshot},
+    event::surface::{
+        blend::Blend,
+        layout::{Layout, Transform},
+        stats::FrameStats,
+    },
+    request::{
+        BlockInput,
+        surface::{
+            DirtyRect, GetFrameStats, HitRegion, InvalidateRegion, ListenPresent, ListenStats,
+            RemoveLayer, RequestFrame, SetBlend, SetHitRegion, SetLayout, SetPosition,
+            SetTransform, SetVisible, UpdateBitmapShm, UpdateLayer, UpdateSharedHandle,
+        },
+        window::ListenInput,
+    },
+};
+
+use crate::{client::IpcClientConn, state::OverlayState};
+
+/// [`OverlayController`] which controls overlay via IPC connection.
+///
+/// Windows and surfaces are queried from [`OverlayState`].
+pub struct IpcController {
+    conn: IpcClientConn,
+    state: OverlayState,
+}
+
+impl IpcController {
+    /// Create a new [`IpcController`] from IPC connection and state tracking the connection.
+    pub const fn new(conn: IpcClientConn, state: OverlayState) -> Self {
+        Self { conn, state }
+    }
+
+    /// IPC connection of the controller.
+    #[inline]
+    pub const fn conn(&mut self) -> &mut IpcClientConn {
+        &mut self.conn
+    }
+
+    /// Window and surface state of the controller.
+    #[inline]
+    pub const fn state(&self) -> &OverlayState {
+        &self.state
+    }
+
+    /// Split into IPC connection and state.
+    #[inline]
+    pub fn into_inner(self) -> (IpcClientConn, OverlayState) {
+        (self.conn, self.state)
+    }
+}
+
+impl OverlayController for IpcController {
+    async fn block_input(&mut self, block: bool) -> anyhow::Result<()> {
+        self.conn.request(BlockInput { block }).await
+    }
+
+    async fn listen_input(&mut self, window_id: u32, listen: ListenInput) -> anyhow::Result<()> {
+        self.conn.window(window_id).request(listen).await
+    }
+
+    async fn set_position(&mut self, surface_id: u64, x: i32, y: i32) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(SetPosition { x, y })
+            .await
+    }
+
+    async fn set_layout(&mut self, surface_id: u64, layout: Layout) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(SetLayout { layout })
+            .await
+    }
+
+    async fn set_transform(&mut self, surface_id: u64, transform: Transform) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(SetTransform { transform })
+            .await
+    }
+
+    async fn set_blend(&mut self, surface_id: u64, blend: Blend) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(SetBlend { blend })
+            .await
+    }
+
+    async fn set_visible(&mut self, surface_id: u64, visible: bool) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(SetVisible { visible })
+            .await
+    }
+
+    async fn listen_present(&mut self, surface_id: u64, listen: bool) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(ListenPresent { listen })
+            .await
+    }
+
+    async fn request_frame(&mut self, surface_id: u64) -> anyhow::Result<()> {
+        self.conn.surface(surface_id).request(RequestFrame).await
+    }
+
+    async fn frame_stats(&mut self, surface_id: u64) -> anyhow::Result<Option<FrameStats>> {
+        self.conn.surface(surface_id).request(GetFrameStats).await
+    }
+
+    async fn listen_stats(
+        &mut self,
+        surface_id: u64,
+        interval: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(ListenStats {
+                interval_ms: interval.map(|interval| interval.as_millis().min(u32::MAX as _) as _),
+            })
+            .await
+    }
+
+    async fn update_texture(
+        &mut self,
+        surface_id: u64,
+        handle: UpdateSharedHandle,
+    ) -> anyhow::Result<()> {
+        self.conn.surface(surface_id).request(handle).await
+    }
+
+    async fn update_bitmap_shm(
+        &mut self,
+        surface_id: u64,
+        bitmap: UpdateBitmapShm,
+    ) -> anyhow::Result<()> {
+        self.conn.surface(surface_id).request(bitmap).await
+    }
+
+    async fn invalidate_region(
+        &mut self,
+        surface_id: u64,
+        rects: Vec<DirtyRect>,
+    ) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(InvalidateRegion { rects })
+            .await
+    }
+
+    async fn update_layer(&mut self, surface_id: u64, update: UpdateLayer) -> anyhow::Result<()> {
+        self.conn.surface(surface_id).request(update).await
+    }
+
+    async fn remove_layer(&mut self, surface_id: u64, layer: u32) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(RemoveLayer { layer })
+            .await
+    }
+
+    async fn set_hit_region(
+        &mut self,
+        surface_id: u64,
+        layer: u32,
+        region: HitRegion,
+    ) -> anyhow::Result<()> {
+        self.conn
+            .surface(surface_id)
+            .request(SetHitRegion { layer, region })
+            .await
+    }
+
+    fn windows(&self) -> Vec<WindowSnapshot> {
+        self.state
+            .borrow()
+            .windows
+            .values()
+            .map(|window| WindowSnapshot {
+                id: window.id,
+                width: window.width,
+                height: window.height,
+                info: window.info.clone(),
+            })
+            .collect()
+    }
+
+    fn surfaces(&self) -> Vec<SurfaceSnapshot> {
+        self.state
+            .borrow()
+            .surfaces
+            .values()
+            .map(|surface| SurfaceSnapshot {
+                id: surface.id,
+                width: surface.width,
+                height: surface.height,
+                info: surface.info,
+                backbuffer: surface.backbuffer,
+            })
+            .collect()
+    }
+}
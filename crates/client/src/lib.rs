@@ -30,7 +30,10 @@
 //!
 
 pub mod client;
+pub mod controller;
 mod injector;
+pub mod shm;
+pub mod state;
 
 pub use asdf_overlay_common as common;
 
@@ -0,0 +1,133 @@
+//! Named shared memory for sending CPU bitmaps to overlay.
+//!
+//! Clients without GPU access can update surfaces by writing frames into [`BitmapShm`]
+//! and sending [`BitmapShm::request`] once, instead of creating Direct3D 11 shared textures.
+
+use anyhow::Context;
+use asdf_overlay_bitmap_ring::{BitmapWriter, required_size};
+use asdf_overlay_common::request::surface::UpdateBitmapShm;
+use windows::{
+    Win32::{
+        Foundation::{
+            CloseHandle, ERROR_ALREADY_EXISTS, GetLastError, HANDLE, INVALID_HANDLE_VALUE,
+        },
+        System::Memory::{
+            CreateFileMappingW, FILE_MAP_WRITE, MEMORY_MAPPED_VIEW_ADDRESS, MapViewOfFile,
+            PAGE_READWRITE, UnmapViewOfFile,
+        },
+    },
+    core::HSTRING,
+};
+
+pub use asdf_overlay_bitmap_ring::{BitmapFormat, BitmapInfo, RingError};
+
+/// Bitmap ring buffer in named shared memory, written by this process.
+pub struct BitmapShm {
+    name: String,
+    mapping: HANDLE,
+    view: MEMORY_MAPPED_VIEW_ADDRESS,
+    writer: BitmapWriter,
+}
+
+// SAFETY: The mapping and its view are only closed on drop.
+unsafe impl Send for BitmapShm {}
+unsafe impl Sync for BitmapShm {}
+
+impl BitmapShm {
+    /// Create named shared memory holding `slot_count` frames up to `slot_capacity` bytes each.
+    ///
+    /// The name must be unique, use `Local\` or `Global\` prefix to choose its namespace.
+    /// At least two slots are recommended, so the overlay never reads a frame being written.
+    pub fn create(
+        name: impl Into<String>,
+        slot_count: u32,
+        slot_capacity: u32,
+    ) -> anyhow::Result<Self> {
+        let name = name.into();
+        let size = required_size(slot_count, slot_capacity).context("invalid ring buffer size")?;
+
+        unsafe {
+            let mapping = CreateFileMappingW(
+                INVALID_HANDLE_VALUE,
+                None,
+                PAGE_READWRITE,
+                (size as u64 >> 32) as u32,
+                size as u32,
+                &HSTRING::from(&name),
+            )
+            .with_context(|| format!("failed to create shared memory: {name}"))?;
+            if GetLastError() == ERROR_ALREADY_EXISTS {
+                _ = CloseHandle(mapping);
+                anyhow::bail!("shared memory already exists: {name}");
+            }
+
+            let view = MapViewOfFile(mapping, FILE_MAP_WRITE, 0, 0, size);
+            if view.Value.is_null() {
+                let err = windows::core::Error::from_thread();
+                _ = CloseHandle(mapping);
+                return Err(err).context("failed to map shared memory");
+            }
+
+            let writer =
+                match BitmapWriter::init(view.Value.cast(), size, slot_count, slot_capacity) {
+                    Ok(writer) => writer,
+                    Err(err) => {
+                        _ = UnmapViewOfFile(view);
+                        _ = CloseHandle(mapping);
+                        return Err(err).context("failed to initialize ring buffer");
+                    }
+                };
+
+            Ok(Self {
+                name,
+                mapping,
+                view,
+                writer,
+            })
+        }
+    }
+
+    /// Name of the shared memory.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Request for updating a surface or layer from this shared memory.
+    pub fn request(&self) -> UpdateBitmapShm {
+        UpdateBitmapShm {
+            name: Some(self.name.clone()),
+        }
+    }
+
+    /// Maximum size of a frame in bytes.
+    pub fn slot_capacity(&self) -> u32 {
+        self.writer.slot_capacity()
+    }
+
+    /// Publish a frame by copying `data`.
+    ///
+    /// Returns sequence number of the frame.
+    pub fn write(&mut self, info: BitmapInfo, data: &[u8]) -> Result<u64, RingError> {
+        self.writer.write(info, data)
+    }
+
+    /// Publish a frame by filling its data in shared memory with the closure.
+    ///
+    /// Returns sequence number of the frame.
+    pub fn write_with(
+        &mut self,
+        info: BitmapInfo,
+        f: impl FnOnce(&mut [u8]),
+    ) -> Result<u64, RingError> {
+        self.writer.write_with(info, f)
+    }
+}
+
+impl Drop for BitmapShm {
+    fn drop(&mut self) {
+        unsafe {
+            _ = UnmapViewOfFile(self.view);
+            _ = CloseHandle(self.mapping);
+        }
+    }
+}
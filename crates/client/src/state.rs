@@ -0,0 +1,128 @@
+//! Client side registry of windows and surfaces.
+//!
+//! [`OverlayState`] consumes [`IpcClientEventStream`] and keeps track of live windows and surfaces,
+//! so clients don't need to track them manually from events.
+
+use tokio::sync::{mpsc, watch};
+
+use crate::client::IpcClientEventStream;
+
+pub use asdf_overlay_common::state::{MainSurfacePreference, Registry, SurfaceEntry, WindowEntry};
+
+/// Live registry of windows and surfaces maintained from overlay events.
+///
+/// Cheap to clone. Every clone observes the same registry.
+#[derive(Debug, Clone)]
+pub struct OverlayState {
+    rx: watch::Receiver<Registry>,
+}
+
+impl OverlayState {
+    /// Start tracking windows and surfaces from the event stream.
+    ///
+    /// Every event is forwarded to the returned event stream after the registry is updated.
+    /// Tracking stops when the connection is closed.
+    pub fn new(mut events: IpcClientEventStream) -> (Self, IpcClientEventStream) {
+        let (tx, rx) = watch::channel(Registry::default());
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                tx.send_if_modified(|registry| registry.update(&event));
+                _ = event_tx.send(event);
+            }
+        });
+
+        (Self { rx }, IpcClientEventStream { inner: event_rx })
+    }
+
+    /// Borrow current snapshot of the registry.
+    ///
+    /// Holding the returned reference blocks registry updates, so keep it short.
+    #[inline]
+    pub fn borrow(&self) -> watch::Ref<'_, Registry> {
+        self.rx.borrow()
+    }
+
+    /// Get a window by id.
+    pub fn window(&self, id: u32) -> Option<WindowEntry> {
+        self.rx.borrow().windows.get(&id).cloned()
+    }
+
+    /// Get a surface by id.
+    pub fn surface(&self, id: u64) -> Option<SurfaceEntry> {
+        self.rx.borrow().surfaces.get(&id).copied()
+    }
+
+    /// Pick the most likely main surface using the preference.
+    pub fn main_surface(&self, preference: MainSurfacePreference) -> Option<SurfaceEntry> {
+        self.rx.borrow().main_surface(preference).copied()
+    }
+
+    /// Wait until windows or surfaces are changed.
+    ///
+    /// Returns `false` if the connection is closed.
+    pub async fn changed(&mut self) -> bool {
+        self.rx.changed().await.is_ok()
+    }
+
+    /// Wait for a window matching the predicate.
+    ///
+    /// Returns `None` if the connection is closed before any window matches.
+    pub async fn wait_for_window(
+        &self,
+        mut predicate: impl FnMut(&WindowEntry) -> bool,
+    ) -> Option<WindowEntry> {
+        let mut found = None;
+        self.rx
+            .clone()
+            .wait_for(|registry| {
+                found = registry.windows.values().find(|w| predicate(w)).cloned();
+                found.is_some()
+            })
+            .await
+            .ok()?;
+
+        found
+    }
+
+    /// Wait for a surface matching the predicate.
+    ///
+    /// Returns `None` if the connection is closed before any surface matches.
+    pub async fn wait_for_surface(
+        &self,
+        mut predicate: impl FnMut(&SurfaceEntry) -> bool,
+    ) -> Option<SurfaceEntry> {
+        let mut found = None;
+        self.rx
+            .clone()
+            .wait_for(|registry| {
+                found = registry.surfaces.values().find(|s| predicate(s)).copied();
+                found.is_some()
+            })
+            .await
+            .ok()?;
+
+        found
+    }
+
+    /// Wait until any surface is available and pick the main surface using the preference.
+    ///
+    /// Returns `None` if the connection is closed before any surface is added.
+    pub async fn wait_for_main_surface(
+        &self,
+        preference: MainSurfacePreference,
+    ) -> Option<SurfaceEntry> {
+        let mut found = None;
+        self.rx
+            .clone()
+            .wait_for(|registry| {
+                found = registry.main_surface(preference).copied();
+                found.is_some()
+            })
+            .await
+            .ok()?;
+
+        found
+    }
+}
@@ -0,0 +1,192 @@
+//! The [`OverlayController`] trait for controlling overlay regardless of where it runs.
+//!
+//! Logic written against [`OverlayController`] can run both in-process
+//! and in an external process connected via IPC.
+//! * In-process: `asdf-overlay-local`
+//! * IPC client: `asdf-overlay-client`
+//! * Tests: [`mock::MockController`], requires `mock` feature.
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+use core::{future::Future, time::Duration};
+
+use crate::{
+    event::{
+        surface::{
+            SurfaceInfo,
+            backbuffer::BackbufferInfo,
+            blend::Blend,
+            layout::{Layout, Transform},
+            stats::FrameStats,
+        },
+        window::WindowInfo,
+    },
+    request::{
+        surface::{DirtyRect, HitRegion, UpdateBitmapShm, UpdateLayer, UpdateSharedHandle},
+        window::ListenInput,
+    },
+};
+
+/// Snapshot of a window.
+#[derive(Debug, Clone)]
+pub struct WindowSnapshot {
+    /// Window id.
+    pub id: u32,
+
+    /// Width of the window client area.
+    pub width: u32,
+
+    /// Height of the window client area.
+    pub height: u32,
+
+    /// Window information.
+    pub info: WindowInfo,
+}
+
+/// Snapshot of a surface.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceSnapshot {
+    /// Surface id.
+    pub id: u64,
+
+    /// Width of the surface.
+    pub width: u32,
+
+    /// Height of the surface.
+    pub height: u32,
+
+    /// Surface information.
+    pub info: SurfaceInfo,
+
+    /// Backbuffer properties of the surface.
+    pub backbuffer: BackbufferInfo,
+}
+
+/// Common interface for controlling overlay.
+pub trait OverlayController {
+    /// Block input events from reaching all windows and listen all input events.
+    fn block_input(&mut self, block: bool) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Set which input events to listen for the window.
+    fn listen_input(
+        &mut self,
+        window_id: u32,
+        listen: ListenInput,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Set position of the surface overlay.
+    fn set_position(
+        &mut self,
+        surface_id: u64,
+        x: i32,
+        y: i32,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Set layout of the surface overlay.
+    fn set_layout(
+        &mut self,
+        surface_id: u64,
+        layout: Layout,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Set opacity, scale and fit mode of the surface overlay.
+    fn set_transform(
+        &mut self,
+        surface_id: u64,
+        transform: Transform,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Set blend mode and color encoding of the surface overlay.
+    fn set_blend(
+        &mut self,
+        surface_id: u64,
+        blend: Blend,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Show or hide the surface overlay, keeping its textures.
+    fn set_visible(
+        &mut self,
+        surface_id: u64,
+        visible: bool,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Enable or disable [`SurfaceEvent::Presented`] on every present of the surface.
+    ///
+    /// [`SurfaceEvent::Presented`]: asdf_overlay_event::SurfaceEvent::Presented
+    fn listen_present(
+        &mut self,
+        surface_id: u64,
+        listen: bool,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Request [`SurfaceEvent::Presented`] once after the next present of the surface.
+    ///
+    /// [`SurfaceEvent::Presented`]: asdf_overlay_event::SurfaceEvent::Presented
+    fn request_frame(&mut self, surface_id: u64)
+    -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Get frame time statistics measured from recent presents of the surface.
+    fn frame_stats(
+        &mut self,
+        surface_id: u64,
+    ) -> impl Future<Output = anyhow::Result<Option<FrameStats>>> + Send;
+
+    /// Set interval of periodic [`SurfaceEvent::Stats`] of the surface.
+    ///
+    /// [`SurfaceEvent::Stats`]: asdf_overlay_event::SurfaceEvent::Stats
+    fn listen_stats(
+        &mut self,
+        surface_id: u64,
+        interval: Option<Duration>,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Update overlay texture of the surface.
+    fn update_texture(
+        &mut self,
+        surface_id: u64,
+        handle: UpdateSharedHandle,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Update overlay texture of the surface from CPU bitmaps in named shared memory.
+    fn update_bitmap_shm(
+        &mut self,
+        surface_id: u64,
+        bitmap: UpdateBitmapShm,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Mark regions of overlay texture of the surface changed.
+    fn invalidate_region(
+        &mut self,
+        surface_id: u64,
+        rects: Vec<DirtyRect>,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Create or update an overlay layer of the surface.
+    fn update_layer(
+        &mut self,
+        surface_id: u64,
+        update: UpdateLayer,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Remove an overlay layer of the surface.
+    fn remove_layer(
+        &mut self,
+        surface_id: u64,
+        layer: u32,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Set interactive region of an overlay layer of the surface.
+    fn set_hit_region(
+        &mut self,
+        surface_id: u64,
+        layer: u32,
+        region: HitRegion,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Get snapshots of live windows.
+    fn windows(&self) -> Vec<WindowSnapshot>;
+
+    /// Get snapshots of live surfaces.
+    fn surfaces(&self) -> Vec<SurfaceSnapshot>;
+}
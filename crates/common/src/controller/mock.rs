@@ -0,0 +1,190 @@
+//! [`OverlayController`] implementation for tests.
+
+use core::time::Duration;
+
+use anyhow::bail;
+
+use crate::{
+    controller::{OverlayController, SurfaceSnapshot, WindowSnapshot},
+    event::surface::{
+        blend::Blend,
+        layout::{Layout, Transform},
+        stats::FrameStats,
+    },
+    request::{
+        surface::{DirtyRect, HitRegion, UpdateBitmapShm, UpdateLayer, UpdateSharedHandle},
+        window::ListenInput,
+    },
+};
+
+/// A call made to [`MockController`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockCall {
+    BlockInput(bool),
+    ListenInput(u32, ListenInput),
+    SetPosition(u64, i32, i32),
+    SetLayout(u64, Layout),
+    SetTransform(u64, Transform),
+    SetBlend(u64, Blend),
+    SetVisible(u64, bool),
+    ListenPresent(u64, bool),
+    RequestFrame(u64),
+    FrameStats(u64),
+    ListenStats(u64, Option<Duration>),
+    UpdateTexture(u64, UpdateSharedHandle),
+    UpdateBitmapShm(u64, UpdateBitmapShm),
+    InvalidateRegion(u64, Vec<DirtyRect>),
+    UpdateLayer(u64, UpdateLayer),
+    RemoveLayer(u64, u32),
+    SetHitRegion(u64, u32, HitRegion),
+}
+
+/// [`OverlayController`] which records calls instead of controlling overlay.
+///
+/// Like other implementations, requests to missing surfaces fail and requests to missing windows are ignored.
+/// Calls are recorded even if they fail.
+#[derive(Debug, Default, Clone)]
+pub struct MockController {
+    /// Windows returned by [`OverlayController::windows`].
+    pub windows: Vec<WindowSnapshot>,
+
+    /// Surfaces returned by [`OverlayController::surfaces`].
+    pub surfaces: Vec<SurfaceSnapshot>,
+
+    /// Statistics returned by [`OverlayController::frame_stats`].
+    pub frame_stats: Option<FrameStats>,
+
+    /// Calls made to the controller, in order.
+    pub calls: Vec<MockCall>,
+}
+
+impl MockController {
+    /// Create a new [`MockController`] with the windows and surfaces.
+    pub const fn new(windows: Vec<WindowSnapshot>, surfaces: Vec<SurfaceSnapshot>) -> Self {
+        Self {
+            windows,
+            surfaces,
+            frame_stats: None,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Take calls recorded so far.
+    pub fn take_calls(&mut self) -> Vec<MockCall> {
+        core::mem::take(&mut self.calls)
+    }
+
+    fn surface_call(&mut self, surface_id: u64, call: MockCall) -> anyhow::Result<()> {
+        self.calls.push(call);
+        if !self.surfaces.iter().any(|surface| surface.id == surface_id) {
+            bail!("Surface not found");
+        }
+
+        Ok(())
+    }
+}
+
+impl OverlayController for MockController {
+    async fn block_input(&mut self, block: bool) -> anyhow::Result<()> {
+        self.calls.push(MockCall::BlockInput(block));
+        Ok(())
+    }
+
+    async fn listen_input(&mut self, window_id: u32, listen: ListenInput) -> anyhow::Result<()> {
+        self.calls.push(MockCall::ListenInput(window_id, listen));
+        Ok(())
+    }
+
+    async fn set_position(&mut self, surface_id: u64, x: i32, y: i32) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::SetPosition(surface_id, x, y))
+    }
+
+    async fn set_layout(&mut self, surface_id: u64, layout: Layout) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::SetLayout(surface_id, layout))
+    }
+
+    async fn set_transform(&mut self, surface_id: u64, transform: Transform) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::SetTransform(surface_id, transform))
+    }
+
+    async fn set_blend(&mut self, surface_id: u64, blend: Blend) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::SetBlend(surface_id, blend))
+    }
+
+    async fn set_visible(&mut self, surface_id: u64, visible: bool) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::SetVisible(surface_id, visible))
+    }
+
+    async fn listen_present(&mut self, surface_id: u64, listen: bool) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::ListenPresent(surface_id, listen))
+    }
+
+    async fn request_frame(&mut self, surface_id: u64) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::RequestFrame(surface_id))
+    }
+
+    async fn frame_stats(&mut self, surface_id: u64) -> anyhow::Result<Option<FrameStats>> {
+        self.surface_call(surface_id, MockCall::FrameStats(surface_id))?;
+        Ok(self.frame_stats)
+    }
+
+    async fn listen_stats(
+        &mut self,
+        surface_id: u64,
+        interval: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::ListenStats(surface_id, interval))
+    }
+
+    async fn update_texture(
+        &mut self,
+        surface_id: u64,
+        handle: UpdateSharedHandle,
+    ) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::UpdateTexture(surface_id, handle))
+    }
+
+    async fn update_bitmap_shm(
+        &mut self,
+        surface_id: u64,
+        bitmap: UpdateBitmapShm,
+    ) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::UpdateBitmapShm(surface_id, bitmap))
+    }
+
+    async fn invalidate_region(
+        &mut self,
+        surface_id: u64,
+        rects: Vec<DirtyRect>,
+    ) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::InvalidateRegion(surface_id, rects))
+    }
+
+    async fn update_layer(&mut self, surface_id: u64, update: UpdateLayer) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::UpdateLayer(surface_id, update))
+    }
+
+    async fn remove_layer(&mut self, surface_id: u64, layer: u32) -> anyhow::Result<()> {
+        self.surface_call(surface_id, MockCall::RemoveLayer(surface_id, layer))
+    }
+
+    async fn set_hit_region(
+        &mut self,
+        surface_id: u64,
+        layer: u32,
+        region: HitRegion,
+    ) -> anyhow::Result<()> {
+        self.surface_call(
+            surface_id,
+            MockCall::SetHitRegion(surface_id, layer, region),
+        )
+    }
+
+    fn windows(&self) -> Vec<WindowSnapshot> {
+        self.windows.clone()
+    }
+
+    fn surfaces(&self) -> Vec<SurfaceSnapshot> {
+        self.surfaces.clone()
+    }
+}
@@ -8,7 +8,7 @@
 
 pub mod tracing;
 
-use asdf_overlay_window_event::WindowEvent;
+use asdf_overlay_window_event::{InputBlockingEndReason, WindowEvent, input::CursorInput};
 use serde::{Deserialize, Serialize};
 
 use surface::SurfaceEvent;
@@ -27,11 +27,32 @@ pub enum OverlayEvent {
     /// Events related to a specific surface.
     Surface { id: u64, event: SurfaceEvent },
 
+    /// Cursor input routed to the topmost surface layer under the cursor.
+    ///
+    /// You only receive this event if surface input routing is enabled.
+    /// Cursor inputs not over any surface are still sent as window input events.
+    SurfaceCursorInput {
+        /// Surface identifier.
+        id: u64,
+
+        /// Window identifier of the surface.
+        window_id: u32,
+
+        /// Layer identifier. `0` is the base layer.
+        layer: u32,
+
+        /// Cursor input. The position is relative to the left-top corner of the layer texture.
+        input: CursorInput,
+    },
+
     /// Input blocking is turned off or interrupted by the user or system.
     ///
     /// The user may turn off input blocking at any time,
     /// for example, by pressing Alt+F4 on Windows.
-    InputBlockingEnded,
+    InputBlockingEnded {
+        /// Why input blocking has ended.
+        reason: InputBlockingEndReason,
+    },
 
     /// A tracing from overlay system.
     Tracing(TracingEvent),
@@ -1,7 +1,9 @@
 //! Common utilities and types used across `asdf-overlay-client` and `asdf-overlay` related crates.
 //! This crate is not intended to be used directly by end users.
 
+pub mod controller;
 pub mod cursor;
 pub mod event;
 pub mod ipc;
 pub mod request;
+pub mod state;
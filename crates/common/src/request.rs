@@ -9,6 +9,9 @@ use core::fmt;
 
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
+use asdf_overlay_event::SurfaceFilter;
+use asdf_overlay_window_event::{filter::WindowFilter, input::EscapeChord};
+
 use crate::{
     cursor::Cursor,
     request::{surface::SurfaceRequest, window::WindowRequest},
@@ -23,6 +26,18 @@ pub enum Request {
     /// Set cursor when being input blocked.
     SetBlockingCursor(SetBlockingCursor),
 
+    /// Set key combination which ends input blocking.
+    SetEscapeChord(SetEscapeChord),
+
+    /// Set timeout which ends input blocking when the client stops responding.
+    SetBlockingWatchdog(SetBlockingWatchdog),
+
+    /// Set whether to route cursor inputs to surfaces.
+    SetInputRouting(SetInputRouting),
+
+    /// Set rules for ignoring windows and surfaces.
+    SetFilter(SetFilter),
+
     /// Request to a specific window.
     Window(WindowRequest),
 
@@ -85,3 +100,51 @@ pub struct SetBlockingCursor {
     pub cursor: Option<Cursor>,
 }
 impl_Requestable!(SetBlockingCursor, ());
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// Set key combination which ends input blocking when pressed
+pub struct SetEscapeChord {
+    /// Key combination to be set.
+    /// If [`None`] is given, the escape chord is disabled.
+    pub chord: Option<EscapeChord>,
+}
+impl_Requestable!(SetEscapeChord, ());
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// Set timeout which ends input blocking when the client stops responding
+///
+/// Every request received from the client resets the timeout.
+/// While input is blocked, the client should send requests periodically to keep blocking.
+pub struct SetBlockingWatchdog {
+    /// Timeout in milliseconds.
+    /// If [`None`] is given, the watchdog is disabled.
+    pub timeout_ms: Option<u32>,
+}
+impl_Requestable!(SetBlockingWatchdog, ());
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// Set whether to route cursor inputs to the topmost surface under the cursor
+pub struct SetInputRouting {
+    /// Whether to send cursor inputs over surfaces as [`OverlayEvent::SurfaceCursorInput`].
+    ///
+    /// A surface pressed by a cursor button receives every cursor input of the window until all buttons are released,
+    /// even outside of the surface.
+    ///
+    /// [`OverlayEvent::SurfaceCursorInput`]: crate::event::OverlayEvent::SurfaceCursorInput
+    pub surface: bool,
+}
+impl_Requestable!(SetInputRouting, ());
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// Set rules for ignoring irrelevant windows and surfaces
+///
+/// Ignored windows and surfaces are not tracked, so no event is sent for them.
+/// The rules only apply to windows and surfaces identified afterward.
+pub struct SetFilter {
+    /// Rules for ignoring windows.
+    pub window: WindowFilter,
+
+    /// Rules for ignoring surfaces.
+    pub surface: SurfaceFilter,
+}
+impl_Requestable!(SetFilter, ());
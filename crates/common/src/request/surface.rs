@@ -1,3 +1,8 @@
+use asdf_overlay_event::{
+    blend::Blend,
+    layout::{Layout, Transform},
+    stats::FrameStats,
+};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 /// Describes all possible kinds of surface request.
@@ -15,8 +20,47 @@ pub enum SurfaceRequestKind {
     /// Set overlay surface position.
     SetPosition(SetPosition),
 
+    /// Set overlay surface layout.
+    SetLayout(SetLayout),
+
+    /// Set overlay surface opacity, scale and fit mode.
+    SetTransform(SetTransform),
+
+    /// Set overlay surface blend mode and color encoding.
+    SetBlend(SetBlend),
+
+    /// Show or hide overlay surface.
+    SetVisible(SetVisible),
+
+    /// Enable or disable present notifications.
+    ListenPresent(ListenPresent),
+
+    /// Request a present notification after the next present.
+    RequestFrame(RequestFrame),
+
+    /// Get frame time statistics.
+    GetFrameStats(GetFrameStats),
+
+    /// Set interval of periodic frame time statistics.
+    ListenStats(ListenStats),
+
+    /// Create or update overlay layer.
+    UpdateLayer(UpdateLayer),
+
+    /// Remove overlay layer.
+    RemoveLayer(RemoveLayer),
+
     /// Set overlay shared handle.
     UpdateSharedHandle(UpdateSharedHandle),
+
+    /// Set overlay bitmap shared memory.
+    UpdateBitmapShm(UpdateBitmapShm),
+
+    /// Mark regions of overlay texture changed.
+    InvalidateRegion(InvalidateRegion),
+
+    /// Set interactive region of overlay surface.
+    SetHitRegion(SetHitRegion),
 }
 
 /// Trait implemented to sub types of [`SurfaceRequestKind`] enum.
@@ -43,6 +87,141 @@ pub struct SetPosition {
 }
 impl_SurfaceRequestable!(SetPosition, ());
 
+/// Set overlay layout relative to the surface.
+///
+/// Unlike [`SetPosition`], the layout is resolved against the current surface size on each draw,
+/// so the overlay keeps its place without waiting for resize events.
+/// Replaces position set by [`SetPosition`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetLayout {
+    /// Layout of the overlay.
+    pub layout: Layout,
+}
+impl_SurfaceRequestable!(SetLayout, ());
+
+/// Set how overlay texture is drawn in the surface.
+///
+/// Size of the transformed texture is used for resolving the layout.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetTransform {
+    /// Transform of the overlay.
+    pub transform: Transform,
+}
+impl_SurfaceRequestable!(SetTransform, ());
+
+/// Set how overlay texture colors are blended onto the surface.
+///
+/// Texture colors are converted to the encoding the surface backbuffer expects before blending.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetBlend {
+    /// Blend mode and color encoding of the overlay texture.
+    pub blend: Blend,
+}
+impl_SurfaceRequestable!(SetBlend, ());
+
+/// Show or hide every layer of the overlay surface.
+///
+/// Unlike [`UpdateSharedHandle::None`], textures are kept while hidden,
+/// so the overlay can be shown again without sending new textures.
+/// Hidden surfaces do not capture cursor inputs.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetVisible {
+    /// Whether if the overlay is drawn.
+    pub visible: bool,
+}
+impl_SurfaceRequestable!(SetVisible, ());
+
+/// Enable or disable [`SurfaceEvent::Presented`] on every present of the surface.
+///
+/// [`SurfaceEvent::Presented`]: asdf_overlay_event::SurfaceEvent::Presented
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListenPresent {
+    /// Whether if present notifications are emitted.
+    pub listen: bool,
+}
+impl_SurfaceRequestable!(ListenPresent, ());
+
+/// Request [`SurfaceEvent::Presented`] once after the next present of the surface.
+///
+/// Similar to Wayland frame callbacks, it can be used to sync overlay updates with the application.
+///
+/// [`SurfaceEvent::Presented`]: asdf_overlay_event::SurfaceEvent::Presented
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestFrame;
+impl_SurfaceRequestable!(RequestFrame, ());
+
+/// Get frame time statistics measured from recent presents of the surface.
+///
+/// Responds [`None`] if the surface has not been presented twice yet.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetFrameStats;
+impl_SurfaceRequestable!(GetFrameStats, Option<FrameStats>);
+
+/// Set interval of periodic [`SurfaceEvent::Stats`] of the surface.
+///
+/// [`SurfaceEvent::Stats`]: asdf_overlay_event::SurfaceEvent::Stats
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListenStats {
+    /// Interval in milliseconds.
+    /// If [`None`] is given, statistics are not emitted.
+    pub interval_ms: Option<u32>,
+}
+impl_SurfaceRequestable!(ListenStats, ());
+
+/// Create or update an overlay layer of the surface.
+///
+/// Each layer has its own texture, layout, transform, visibility and z index.
+/// Layers are drawn from the lowest z index to the highest, and by layer id if z indices are equal.
+/// Layer `0` is the base layer, which other surface requests apply to.
+/// Hit-testing and inputs are relative to the base layer.
+///
+/// A new layer is visible with z index `0` and has no texture.
+/// Fields left as [`None`] keep their current value.
+///
+/// ## Note
+/// * A surface can have up to 16 layers, including the base layer.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateLayer {
+    /// Layer id.
+    pub layer: u32,
+
+    /// Z index of the layer.
+    pub z_index: Option<i32>,
+
+    /// Whether if the layer is drawn.
+    pub visible: Option<bool>,
+
+    /// Layout of the layer.
+    pub layout: Option<Layout>,
+
+    /// Transform of the layer.
+    pub transform: Option<Transform>,
+
+    /// Blend mode and color encoding of the layer texture.
+    pub blend: Option<Blend>,
+
+    /// Shared handle of the layer texture.
+    pub handle: Option<UpdateSharedHandle>,
+
+    /// Bitmap shared memory of the layer texture, applied after [`UpdateLayer::handle`].
+    pub bitmap_shm: Option<UpdateBitmapShm>,
+
+    /// Changed regions of the layer texture, applied after the texture is updated.
+    /// See [`InvalidateRegion`].
+    pub invalidate: Option<Vec<DirtyRect>>,
+}
+impl_SurfaceRequestable!(UpdateLayer, ());
+
+/// Remove an overlay layer and its texture.
+///
+/// The base layer cannot be removed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoveLayer {
+    /// Layer id.
+    pub layer: u32,
+}
+impl_SurfaceRequestable!(RemoveLayer, ());
+
 /// Update overlay surface
 ///
 /// ## Note
@@ -62,3 +241,111 @@ pub enum UpdateSharedHandle {
 }
 
 impl_SurfaceRequestable!(UpdateSharedHandle, ());
+
+/// Update overlay surface from CPU bitmaps in named shared memory,
+/// for clients which cannot create Direct3D 11 shared textures.
+///
+/// The shared memory must contain a ring buffer initialized by `asdf-overlay-bitmap-ring`.
+/// The overlay uploads the latest published frame to its own texture on each present.
+///
+/// If [`None`] is given, the overlay surface will be removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateBitmapShm {
+    /// Name of the file mapping object.
+    pub name: Option<String>,
+}
+impl_SurfaceRequestable!(UpdateBitmapShm, ());
+
+/// Mark regions of overlay texture changed.
+///
+/// By default, renderers copying overlay textures into their own textures (Direct3D 9)
+/// copy whole textures on every frame, as contents of shared textures can change anytime.
+/// Once regions are invalidated, they only copy invalidated regions
+/// until another texture is committed, which saves bandwidth for mostly static overlays.
+///
+/// ## Note
+/// * After the first request, every change must be invalidated, or it will not be shown.
+/// * An empty list of rectangles only enables dirty region tracking.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidateRegion {
+    /// Changed rectangles of the texture.
+    pub rects: Vec<DirtyRect>,
+}
+impl_SurfaceRequestable!(InvalidateRegion, ());
+
+/// Describe a changed rectangle of overlay texture.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirtyRect {
+    /// X position.
+    pub x: u32,
+
+    /// Y position.
+    pub y: u32,
+
+    /// Width of the rectangle.
+    pub width: u32,
+
+    /// Height of the rectangle.
+    pub height: u32,
+}
+
+/// Set interactive region of overlay surface.
+///
+/// While input is not blocked, cursor inputs over the interactive region are captured
+/// and blocked from reaching the window. Everything else reaches the window as usual.
+///
+/// Each layer has its own interactive region, relative to the layer texture.
+/// Visible layers are hit-tested from top to bottom,
+/// so layers without interactive region at the cursor let lower layers capture it.
+///
+/// ## Note
+/// * Raw inputs and pointer messages are not hit-tested.
+/// * [`HitRegion::AlphaMask`] is only supported on the base layer.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetHitRegion {
+    /// Layer id. `0` is the base layer.
+    pub layer: u32,
+
+    /// Interactive region of the layer.
+    pub region: HitRegion,
+}
+impl_SurfaceRequestable!(SetHitRegion, ());
+
+/// Describe interactive region of overlay surface.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HitRegion {
+    /// Surface does not capture any cursor input.
+    #[default]
+    None,
+
+    /// Entire surface captures cursor inputs.
+    Full,
+
+    /// Only cursor inputs inside of the rectangles are captured.
+    Rects(Vec<HitRect>),
+
+    /// Only cursor inputs over pixels with alpha value equal or greater than `threshold` are captured.
+    ///
+    /// The mask is built from surface texture when it is set, the texture is updated or its region is invalidated.
+    /// Invalidate a region or send the request again to rebuild the mask after changing texture contents.
+    AlphaMask {
+        /// Alpha threshold.
+        threshold: u8,
+    },
+}
+
+/// Rectangle relative to overlay surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HitRect {
+    /// X position.
+    pub x: i32,
+
+    /// Y position.
+    pub y: i32,
+
+    /// Width of the rectangle.
+    pub width: u32,
+
+    /// Height of the rectangle.
+    pub height: u32,
+}
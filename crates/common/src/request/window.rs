@@ -41,5 +41,14 @@ pub struct ListenInput {
 
     /// Whether to listen keyboard related events.
     pub keyboard: bool,
+
+    /// Whether to listen relative mouse motion from raw inputs.
+    pub raw_motion: bool,
+
+    /// Whether to listen touch and pen events.
+    pub pointer: bool,
+
+    /// Whether to listen gamepad events.
+    pub gamepad: bool,
 }
 impl_WindowRequestable!(ListenInput, ());
@@ -0,0 +1,506 @@
+//! Registry of windows and surfaces tracked from overlay events.
+//!
+//! For tracking from an IPC connection, see `OverlayState` of `asdf-overlay-client`.
+
+use std::collections::HashMap;
+
+use crate::event::{
+    OverlayEvent,
+    surface::{SurfaceApis, SurfaceEvent, SurfaceInfo, backbuffer::BackbufferInfo},
+    window::{WindowEvent, WindowInfo},
+};
+
+/// Live state of a window.
+#[derive(Debug, Clone)]
+pub struct WindowEntry {
+    /// Window id.
+    pub id: u32,
+
+    /// Width of the window client area.
+    pub width: u32,
+
+    /// Height of the window client area.
+    pub height: u32,
+
+    /// Window information, updated by title and DPI changes.
+    pub info: WindowInfo,
+
+    /// Whether if the window is focused.
+    ///
+    /// Initially the focus state reported with the window, then updated on focus change.
+    pub focused: bool,
+
+    /// Whether if the window is minimized.
+    pub minimized: bool,
+}
+
+/// Live state of a surface.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceEntry {
+    /// Surface id.
+    pub id: u64,
+
+    /// Width of the surface.
+    pub width: u32,
+
+    /// Height of the surface.
+    pub height: u32,
+
+    /// Surface information.
+    pub info: SurfaceInfo,
+
+    /// Backbuffer properties of the surface.
+    pub backbuffer: BackbufferInfo,
+}
+
+impl SurfaceEntry {
+    /// Id of the window which the surface presents to, if any.
+    #[inline]
+    pub fn window_id(&self) -> Option<u32> {
+        self.info.api.window_id()
+    }
+}
+
+/// Snapshot of live windows and surfaces.
+#[derive(Debug, Default, Clone)]
+pub struct Registry {
+    /// Live windows mapped by window id.
+    pub windows: HashMap<u32, WindowEntry>,
+
+    /// Live surfaces mapped by surface id.
+    pub surfaces: HashMap<u64, SurfaceEntry>,
+}
+
+impl Registry {
+    /// Iterate surfaces presenting to the window.
+    pub fn window_surfaces(&self, window_id: u32) -> impl Iterator<Item = &SurfaceEntry> {
+        self.surfaces
+            .values()
+            .filter(move |surface| surface.window_id() == Some(window_id))
+    }
+
+    /// Pick the most likely main surface using the preference.
+    pub fn main_surface(&self, preference: MainSurfacePreference) -> Option<&SurfaceEntry> {
+        self.surfaces.values().max_by_key(|surface| {
+            let focused = preference.foreground
+                && surface
+                    .window_id()
+                    .and_then(|id| self.windows.get(&id))
+                    .is_some_and(|window| window.focused && !window.minimized);
+
+            (
+                focused,
+                preference.apis.intersects(surface.info.api.api()),
+                surface.width as u64 * surface.height as u64,
+            )
+        })
+    }
+
+    /// Apply an overlay event to the registry.
+    ///
+    /// Returns `true` if the registry changed.
+    pub fn update(&mut self, event: &OverlayEvent) -> bool {
+        match *event {
+            OverlayEvent::Window { id, ref event } => self.update_window(id, event),
+            OverlayEvent::Surface { id, ref event } => self.update_surface(id, event),
+            _ => false,
+        }
+    }
+
+    /// Apply a window event to the registry.
+    ///
+    /// Returns `true` if the registry changed.
+    pub fn update_window(&mut self, id: u32, event: &WindowEvent) -> bool {
+        if let WindowEvent::Added {
+            width,
+            height,
+            ref info,
+        } = *event
+        {
+            self.windows.insert(
+                id,
+                WindowEntry {
+                    id,
+                    width,
+                    height,
+                    info: info.clone(),
+                    focused: info.focused,
+                    minimized: false,
+                },
+            );
+            return true;
+        }
+
+        if let WindowEvent::Destroyed = event {
+            return self.windows.remove(&id).is_some();
+        }
+
+        let Some(window) = self.windows.get_mut(&id) else {
+            return false;
+        };
+
+        match *event {
+            WindowEvent::Resized { width, height } => {
+                window.width = width;
+                window.height = height;
+            }
+            WindowEvent::TitleChanged { ref title } => window.info.title = title.clone(),
+            WindowEvent::FocusChanged { focused } => {
+                window.focused = focused;
+                window.info.focused = focused;
+            }
+            WindowEvent::Minimized => window.minimized = true,
+            WindowEvent::Restored => window.minimized = false,
+            WindowEvent::DpiChanged { scale } => window.info.scale = scale,
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Apply a surface event to the registry.
+    ///
+    /// Returns `true` if the registry changed.
+    pub fn update_surface(&mut self, id: u64, event: &SurfaceEvent) -> bool {
+        match *event {
+            SurfaceEvent::Added {
+                width,
+                height,
+                info,
+                backbuffer,
+            } => {
+                self.surfaces.insert(
+                    id,
+                    SurfaceEntry {
+                        id,
+                        width,
+                        height,
+                        info,
+                        backbuffer,
+                    },
+                );
+                true
+            }
+
+            SurfaceEvent::Resized {
+                width,
+                height,
+                backbuffer,
+            } => {
+                let Some(surface) = self.surfaces.get_mut(&id) else {
+                    return false;
+                };
+
+                surface.width = width;
+                surface.height = height;
+                surface.backbuffer = backbuffer;
+                true
+            }
+
+            SurfaceEvent::Presented { .. } | SurfaceEvent::Stats { .. } => false,
+
+            SurfaceEvent::Destroyed => self.surfaces.remove(&id).is_some(),
+        }
+    }
+}
+
+/// Preference for picking the main surface.
+///
+/// Surfaces are compared in order of foreground window, preferred api and size.
+#[derive(Debug, Clone, Copy)]
+pub struct MainSurfacePreference {
+    /// Prefer surfaces presenting to the focused window.
+    pub foreground: bool,
+
+    /// Prefer surfaces using one of these rendering apis.
+    pub apis: SurfaceApis,
+}
+
+impl Default for MainSurfacePreference {
+    fn default() -> Self {
+        Self {
+            foreground: true,
+            apis: SurfaceApis::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{
+        surface::{GpuLuid, SurfaceType},
+        window::WindowStyle,
+    };
+
+    use super::*;
+
+    fn window_added(id: u32, focused: bool) -> OverlayEvent {
+        OverlayEvent::Window {
+            id,
+            event: WindowEvent::Added {
+                width: 800,
+                height: 600,
+                info: WindowInfo {
+                    title: "Window".to_string(),
+                    class_name: "Class".to_string(),
+                    style: WindowStyle::empty(),
+                    thread_id: 1,
+                    scale: 1.0,
+                    monitor: None,
+                    focused,
+                },
+            },
+        }
+    }
+
+    fn window(id: u32, event: WindowEvent) -> OverlayEvent {
+        OverlayEvent::Window { id, event }
+    }
+
+    fn surface_added(id: u64, api: SurfaceType, width: u32, height: u32) -> OverlayEvent {
+        OverlayEvent::Surface {
+            id,
+            event: SurfaceEvent::Added {
+                width,
+                height,
+                info: SurfaceInfo {
+                    api,
+                    gpu_id: GpuLuid { low: 0, high: 0 },
+                },
+                backbuffer: BackbufferInfo::default(),
+            },
+        }
+    }
+
+    fn surface(id: u64, event: SurfaceEvent) -> OverlayEvent {
+        OverlayEvent::Surface { id, event }
+    }
+
+    fn main_surface_id(registry: &Registry, preference: MainSurfacePreference) -> Option<u64> {
+        registry.main_surface(preference).map(|surface| surface.id)
+    }
+
+    #[test]
+    fn window_lifecycle() {
+        let mut registry = Registry::default();
+
+        // Events of unknown windows are ignored
+        assert!(!registry.update(&window(
+            1,
+            WindowEvent::Resized {
+                width: 1,
+                height: 1
+            }
+        )));
+        assert!(!registry.update(&window(1, WindowEvent::Destroyed)));
+
+        assert!(registry.update(&window_added(1, false)));
+        assert!(registry.update(&window(
+            1,
+            WindowEvent::Resized {
+                width: 1280,
+                height: 720
+            }
+        )));
+        assert!(registry.update(&window(1, WindowEvent::FocusChanged { focused: true })));
+        assert!(registry.update(&window(1, WindowEvent::Minimized)));
+        assert!(registry.update(&window(
+            1,
+            WindowEvent::TitleChanged {
+                title: "Renamed".to_string()
+            }
+        )));
+        assert!(registry.update(&window(1, WindowEvent::DpiChanged { scale: 1.5 })));
+
+        let entry = &registry.windows[&1];
+        assert_eq!((entry.width, entry.height), (1280, 720));
+        assert!(entry.focused && entry.info.focused);
+        assert!(entry.minimized);
+        assert_eq!(entry.info.title, "Renamed");
+        assert_eq!(entry.info.scale, 1.5);
+
+        assert!(registry.update(&window(1, WindowEvent::Restored)));
+        assert!(!registry.windows[&1].minimized);
+
+        assert!(registry.update(&window(1, WindowEvent::Destroyed)));
+        assert!(registry.windows.is_empty());
+        assert!(!registry.update(&window(1, WindowEvent::Destroyed)));
+    }
+
+    #[test]
+    fn window_readded() {
+        let mut registry = Registry::default();
+        registry.update(&window_added(1, false));
+        registry.update(&window(1, WindowEvent::Minimized));
+
+        // Reused window id starts over
+        registry.update(&window(1, WindowEvent::Destroyed));
+        assert!(registry.update(&window_added(1, true)));
+        let entry = &registry.windows[&1];
+        assert!(entry.focused);
+        assert!(!entry.minimized);
+    }
+
+    #[test]
+    fn surface_lifecycle() {
+        let mut registry = Registry::default();
+        let api = SurfaceType::Direct3D11 { window_id: Some(1) };
+
+        assert!(!registry.update(&surface(
+            1,
+            SurfaceEvent::Resized {
+                width: 1,
+                height: 1,
+                backbuffer: BackbufferInfo::default(),
+            }
+        )));
+
+        assert!(registry.update(&surface_added(1, api, 800, 600)));
+        assert!(registry.update(&surface_added(
+            2,
+            SurfaceType::Direct3D11 { window_id: None },
+            10,
+            10
+        )));
+        assert!(registry.update(&surface(
+            1,
+            SurfaceEvent::Resized {
+                width: 1920,
+                height: 1080,
+                backbuffer: BackbufferInfo {
+                    buffer_count: 3,
+                    ..Default::default()
+                },
+            }
+        )));
+        assert!(!registry.update(&surface(
+            1,
+            SurfaceEvent::Presented {
+                frame: 1,
+                timestamp: 0
+            }
+        )));
+
+        let entry = registry.surfaces[&1];
+        assert_eq!((entry.width, entry.height), (1920, 1080));
+        assert_eq!(entry.backbuffer.buffer_count, 3);
+        assert_eq!(entry.window_id(), Some(1));
+        assert_eq!(
+            registry
+                .window_surfaces(1)
+                .map(|surface| surface.id)
+                .collect::<Vec<_>>(),
+            [1]
+        );
+
+        assert!(registry.update(&surface(1, SurfaceEvent::Destroyed)));
+        assert!(!registry.update(&surface(1, SurfaceEvent::Destroyed)));
+        assert_eq!(registry.surfaces.keys().copied().collect::<Vec<_>>(), [2]);
+        assert_eq!(registry.window_surfaces(1).count(), 0);
+    }
+
+    #[test]
+    fn main_surface_empty() {
+        let registry = Registry::default();
+        assert!(
+            registry
+                .main_surface(MainSurfacePreference::default())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn main_surface_foreground() {
+        let mut registry = Registry::default();
+        registry.update(&window_added(1, false));
+        registry.update(&window_added(2, true));
+        registry.update(&surface_added(
+            1,
+            SurfaceType::Opengl { window_id: 1 },
+            1920,
+            1080,
+        ));
+        registry.update(&surface_added(
+            2,
+            SurfaceType::Vulkan { window_id: 2 },
+            800,
+            600,
+        ));
+
+        let foreground = MainSurfacePreference::default();
+        let largest = MainSurfacePreference {
+            foreground: false,
+            ..Default::default()
+        };
+        assert_eq!(main_surface_id(&registry, foreground), Some(2));
+        assert_eq!(main_surface_id(&registry, largest), Some(1));
+
+        // Minimized windows are not considered focused
+        registry.update(&window(2, WindowEvent::Minimized));
+        assert_eq!(main_surface_id(&registry, foreground), Some(1));
+        registry.update(&window(2, WindowEvent::Restored));
+
+        registry.update(&window(2, WindowEvent::FocusChanged { focused: false }));
+        registry.update(&window(1, WindowEvent::FocusChanged { focused: true }));
+        registry.update(&surface(
+            1,
+            SurfaceEvent::Resized {
+                width: 1,
+                height: 1,
+                backbuffer: BackbufferInfo::default(),
+            },
+        ));
+        assert_eq!(main_surface_id(&registry, foreground), Some(1));
+        assert_eq!(main_surface_id(&registry, largest), Some(2));
+    }
+
+    #[test]
+    fn main_surface_apis() {
+        let mut registry = Registry::default();
+        registry.update(&window_added(1, true));
+        registry.update(&surface_added(
+            1,
+            SurfaceType::Opengl { window_id: 1 },
+            1920,
+            1080,
+        ));
+        registry.update(&surface_added(
+            2,
+            SurfaceType::Direct3D12 { window_id: Some(1) },
+            800,
+            600,
+        ));
+        registry.update(&surface_added(
+            3,
+            SurfaceType::Direct3D11 { window_id: None },
+            4000,
+            4000,
+        ));
+
+        // Foreground wins over apis
+        let d3d12 = MainSurfacePreference {
+            foreground: true,
+            apis: SurfaceApis::DIRECT3D12,
+        };
+        assert_eq!(main_surface_id(&registry, d3d12), Some(2));
+
+        let d3d11 = MainSurfacePreference {
+            foreground: true,
+            apis: SurfaceApis::DIRECT3D11,
+        };
+        assert_eq!(main_surface_id(&registry, d3d11), Some(1));
+
+        // Apis win over size
+        let d3d12_anywhere = MainSurfacePreference {
+            foreground: false,
+            apis: SurfaceApis::DIRECT3D12,
+        };
+        assert_eq!(main_surface_id(&registry, d3d12_anywhere), Some(2));
+
+        let any = MainSurfacePreference {
+            foreground: false,
+            apis: SurfaceApis::empty(),
+        };
+        assert_eq!(main_surface_id(&registry, any), Some(3));
+    }
+}
@@ -1,27 +1,32 @@
 mod io;
 
-use anyhow::Context;
-use asdf_overlay::{
-    event_sink::OverlayEventSink,
-    surface::{SharedTextureHandle, Surfaces},
-};
+use asdf_overlay::{event_sink::OverlayEventSink, surface::Surfaces};
 use asdf_overlay_common::{
+    controller::OverlayController,
     event::{OverlayEvent, surface::SurfaceEvent, window::WindowEvent},
     request::{
-        BlockInput, Request, Requestable, SetBlockingCursor,
+        BlockInput, Request, Requestable, SetBlockingCursor, SetBlockingWatchdog, SetEscapeChord,
+        SetFilter, SetInputRouting,
         surface::{
-            SetPosition, SurfaceRequest, SurfaceRequestKind, SurfaceRequestable, UpdateSharedHandle,
+            GetFrameStats, InvalidateRegion, ListenPresent, ListenStats, RemoveLayer, RequestFrame,
+            SetBlend, SetHitRegion, SetLayout, SetPosition, SetTransform, SetVisible,
+            SurfaceRequest, SurfaceRequestKind, SurfaceRequestable, UpdateBitmapShm, UpdateLayer,
+            UpdateSharedHandle,
         },
         window::{ListenInput, WindowRequest, WindowRequestKind, WindowRequestable},
     },
 };
-use asdf_overlay_window::{Backends, window::ListenInputFlags};
+use asdf_overlay_local::LocalController;
+use asdf_overlay_window::Backends;
 use scopeguard::defer;
-use std::sync::Arc;
-use tokio::net::windows::named_pipe::NamedPipeServer;
+use std::{sync::Arc, time::Duration};
+use tokio::{net::windows::named_pipe::NamedPipeServer, time};
 use tracing::{Level, debug, trace};
 
-use crate::{cursors, event_sink::EventSink, ipc::io::IpcServerConn};
+use crate::{cursors, event_sink::EventSink, ipc::io::IpcServerConn, routing};
+
+/// Interval of checking the input blocking watchdog.
+const WATCHDOG_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
 /// IPC server main loop.
 #[tracing::instrument(level = Level::DEBUG, skip(backends, server))]
@@ -31,34 +36,32 @@ pub async fn run(
     server: NamedPipeServer,
 ) -> anyhow::Result<()> {
     let mut conn = IpcServerConn::new(server).await?;
+    let mut controller = LocalController::new(backends.clone());
     let emitter = conn.create_emitter();
     {
         debug!("sending initial data");
         // send existing windows
-        for id in backends.windows() {
-            backends.window(id, |state| {
-                let (width, height) = state.size();
-
-                _ = emitter.emit(OverlayEvent::Window {
-                    id,
-                    event: WindowEvent::Added { width, height },
-                });
+        for window in controller.windows() {
+            _ = emitter.emit(OverlayEvent::Window {
+                id: window.id,
+                event: WindowEvent::Added {
+                    width: window.width,
+                    height: window.height,
+                    info: window.info,
+                },
             });
         }
 
         // send existing surfaces
-        for id in Surfaces::iter() {
-            Surfaces::state(id, |state| {
-                let (width, height) = state.size();
-
-                _ = emitter.emit(OverlayEvent::Surface {
-                    id,
-                    event: SurfaceEvent::Added {
-                        width,
-                        height,
-                        info: state.info,
-                    },
-                });
+        for surface in controller.surfaces() {
+            _ = emitter.emit(OverlayEvent::Surface {
+                id: surface.id,
+                event: SurfaceEvent::Added {
+                    width: surface.width,
+                    height: surface.height,
+                    info: surface.info,
+                    backbuffer: surface.backbuffer,
+                },
             });
         }
     }
@@ -79,32 +82,40 @@ pub async fn run(
         }
     });
 
+    let watchdog = tokio::spawn({
+        let backends = backends.clone();
+
+        async move {
+            let mut interval = time::interval(WATCHDOG_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                backends.check_watchdog();
+            }
+        }
+    });
+
     defer!({
         debug!("cleanup start");
+        watchdog.abort();
         EventSink::clear();
         OverlayEventSink::clear();
         backends.reset();
         Surfaces::reset();
+        routing::set_surface_routing(false);
     });
 
     while let Ok((req_id, req)) = conn.recv().await {
         trace!("recv id: {req_id} req: {req:?}");
+        backends.feed_watchdog();
 
         match req {
             Request::Window(window) => {
-                handle_window_request(&mut conn, req_id, &backends, window)?;
+                handle_window_request(&mut conn, req_id, &mut controller, window).await?;
             }
 
             Request::BlockInput(BlockInput { block }) => {
-                conn.reply_with::<<BlockInput as Requestable>::Response>(req_id, || {
-                    if block {
-                        backends.block_input();
-                    } else {
-                        backends.unblock_input();
-                    }
-
-                    Ok(())
-                })?;
+                let res = controller.block_input(block).await;
+                conn.reply_with::<<BlockInput as Requestable>::Response>(req_id, || res)?;
             }
 
             Request::SetBlockingCursor(SetBlockingCursor { cursor }) => {
@@ -117,75 +128,158 @@ pub async fn run(
                 })?;
             }
 
+            Request::SetEscapeChord(SetEscapeChord { chord }) => {
+                conn.reply_with::<<SetEscapeChord as Requestable>::Response>(req_id, || {
+                    backends.set_escape_chord(chord);
+
+                    Ok(())
+                })?;
+            }
+
+            Request::SetBlockingWatchdog(SetBlockingWatchdog { timeout_ms }) => {
+                conn.reply_with::<<SetBlockingWatchdog as Requestable>::Response>(req_id, || {
+                    backends.set_blocking_watchdog(
+                        timeout_ms.map(|timeout_ms| Duration::from_millis(timeout_ms as _)),
+                    );
+
+                    Ok(())
+                })?;
+            }
+
+            Request::SetInputRouting(SetInputRouting { surface }) => {
+                conn.reply_with::<<SetInputRouting as Requestable>::Response>(req_id, || {
+                    routing::set_surface_routing(surface);
+
+                    Ok(())
+                })?;
+            }
+
+            Request::SetFilter(SetFilter { window, surface }) => {
+                conn.reply_with::<<SetFilter as Requestable>::Response>(req_id, || {
+                    backends.set_window_filter(window);
+                    Surfaces::set_filter(surface);
+
+                    Ok(())
+                })?;
+            }
+
             Request::Surface(surface) => {
-                handle_surface_request(&mut conn, req_id, surface)?;
+                handle_surface_request(&mut conn, req_id, &mut controller, surface).await?;
             }
         }
     }
     Ok(())
 }
 
-fn handle_window_request(
+async fn handle_window_request(
     conn: &mut IpcServerConn,
     req_id: u32,
-    backends: &Backends,
+    controller: &mut LocalController,
     req: WindowRequest,
 ) -> anyhow::Result<()> {
     match req.kind {
         WindowRequestKind::ListenInput(cmd) => {
-            conn.reply_with::<<ListenInput as WindowRequestable>::Response>(req_id, || {
-                let mut flags = ListenInputFlags::empty();
-                flags.set(ListenInputFlags::CURSOR, cmd.cursor);
-                flags.set(ListenInputFlags::KEYBOARD, cmd.keyboard);
-
-                backends.window(req.id, |state| state.set_input_flags(flags));
-
-                Ok(())
-            })?;
+            let res = controller.listen_input(req.id, cmd).await;
+            conn.reply_with::<<ListenInput as WindowRequestable>::Response>(req_id, || res)?;
         }
     }
 
     Ok(())
 }
 
-fn handle_surface_request(
+async fn handle_surface_request(
     conn: &mut IpcServerConn,
     req_id: u32,
+    controller: &mut LocalController,
     req: SurfaceRequest,
 ) -> anyhow::Result<()> {
     match req.kind {
         SurfaceRequestKind::SetPosition(cmd) => {
-            conn.reply_with::<<SetPosition as SurfaceRequestable>::Response>(req_id, || {
-                Surfaces::state(req.id, |state| state.reposition(cmd.x, cmd.y))
-                    .context("Surface not found")?;
-                Ok(())
-            })?;
+            let res = controller.set_position(req.id, cmd.x, cmd.y).await;
+            conn.reply_with::<<SetPosition as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::SetLayout(cmd) => {
+            let res = controller.set_layout(req.id, cmd.layout).await;
+            conn.reply_with::<<SetLayout as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::SetTransform(cmd) => {
+            let res = controller.set_transform(req.id, cmd.transform).await;
+            conn.reply_with::<<SetTransform as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::SetBlend(cmd) => {
+            let res = controller.set_blend(req.id, cmd.blend).await;
+            conn.reply_with::<<SetBlend as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::SetVisible(cmd) => {
+            let res = controller.set_visible(req.id, cmd.visible).await;
+            conn.reply_with::<<SetVisible as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::ListenPresent(cmd) => {
+            let res = controller.listen_present(req.id, cmd.listen).await;
+            conn.reply_with::<<ListenPresent as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::RequestFrame(_) => {
+            let res = controller.request_frame(req.id).await;
+            conn.reply_with::<<RequestFrame as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::GetFrameStats(_) => {
+            let res = controller.frame_stats(req.id).await;
+            conn.reply_with::<<GetFrameStats as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::ListenStats(cmd) => {
+            let res = controller
+                .listen_stats(
+                    req.id,
+                    cmd.interval_ms
+                        .map(|interval| Duration::from_millis(interval as _)),
+                )
+                .await;
+            conn.reply_with::<<ListenStats as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::UpdateLayer(cmd) => {
+            let res = controller.update_layer(req.id, cmd).await;
+            conn.reply_with::<<UpdateLayer as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::RemoveLayer(cmd) => {
+            let res = controller.remove_layer(req.id, cmd.layer).await;
+            conn.reply_with::<<RemoveLayer as SurfaceRequestable>::Response>(req_id, || res)?;
         }
 
         SurfaceRequestKind::UpdateSharedHandle(shared) => {
+            let res = controller.update_texture(req.id, shared).await;
             conn.reply_with::<<UpdateSharedHandle as SurfaceRequestable>::Response>(
                 req_id,
-                || {
-                    Surfaces::state(req.id, |state| {
-                        state
-                            .commit_overlay_texture(map_ipc_shtex_update(shared))
-                            .context("Failed to commit overlay texture")
-                    })
-                    .context("Surface not found")??;
-
-                    Ok(())
-                },
+                || res,
             )?;
         }
-    }
 
-    Ok(())
-}
+        SurfaceRequestKind::UpdateBitmapShm(cmd) => {
+            let res = controller.update_bitmap_shm(req.id, cmd).await;
+            conn.reply_with::<<UpdateBitmapShm as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
+
+        SurfaceRequestKind::InvalidateRegion(cmd) => {
+            let res = controller.invalidate_region(req.id, cmd.rects).await;
+            conn.reply_with::<<InvalidateRegion as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
 
-fn map_ipc_shtex_update(shared: UpdateSharedHandle) -> Option<SharedTextureHandle> {
-    match shared {
-        UpdateSharedHandle::Kmt(handle) => Some(SharedTextureHandle::Kmt(handle)),
-        UpdateSharedHandle::Nt(handle) => Some(SharedTextureHandle::Nt(handle)),
-        UpdateSharedHandle::None => None,
+        SurfaceRequestKind::SetHitRegion(cmd) => {
+            let res = controller
+                .set_hit_region(req.id, cmd.layer, cmd.region)
+                .await;
+            conn.reply_with::<<SetHitRegion as SurfaceRequestable>::Response>(req_id, || res)?;
+        }
     }
+
+    Ok(())
 }
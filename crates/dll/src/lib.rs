@@ -9,12 +9,13 @@ mod cursors;
 mod event_sink;
 mod ipc;
 mod ipc_tracing;
+mod routing;
 mod server;
 
 extern crate asdf_overlay_vulkan_layer;
 
 use anyhow::Context;
-use asdf_overlay::initialize;
+use asdf_overlay::{initialize, surface::Surfaces};
 use asdf_overlay_common::event::OverlayEvent;
 use asdf_overlay_window::Backends;
 use core::time::Duration;
@@ -50,13 +51,16 @@ async fn run(module_handle: usize, mut server: NamedPipeServer) -> anyhow::Resul
 
             |event| {
                 EventSink::emit(match event {
-                    Event::Window { id, event } => OverlayEvent::Window { id, event },
-                    Event::InputBlockingEnded => OverlayEvent::InputBlockingEnded,
+                    Event::Window { id, event } => routing::route_window_event(id, event),
+                    Event::InputBlockingEnded { reason } => {
+                        OverlayEvent::InputBlockingEnded { reason }
+                    }
                 });
             }
         })
         .context("window initialization")?,
     );
+    backends.set_hit_tester(Surfaces::hit_test);
     debug!("Window backend initialized.");
 
     loop {
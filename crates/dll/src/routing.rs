@@ -0,0 +1,135 @@
+//! Routing cursor inputs to the topmost surface layer under the cursor.
+//!
+//! A layer pressed by a cursor button captures cursor inputs of the window until every button is released,
+//! so dragging outside of the layer keeps delivering inputs to it.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use asdf_overlay::surface::Surfaces;
+use asdf_overlay_common::event::{
+    OverlayEvent,
+    window::{
+        WindowEvent,
+        input::{CursorEvent, CursorInput, CursorInputState, InputEvent, InputPosition},
+    },
+};
+use parking_lot::Mutex;
+
+static SURFACE_ROUTING: AtomicBool = AtomicBool::new(false);
+
+/// Surface layer capturing cursor inputs.
+static CAPTURE: Mutex<Option<Capture>> = Mutex::new(None);
+
+#[derive(Clone, Copy)]
+struct Capture {
+    window_id: u32,
+    surface_id: u64,
+    layer: u32,
+
+    /// Number of cursor buttons pressed since the capture started.
+    pressed: u32,
+}
+
+/// Set whether to route cursor inputs to surfaces.
+pub fn set_surface_routing(enabled: bool) {
+    SURFACE_ROUTING.store(enabled, Ordering::Relaxed);
+    *CAPTURE.lock() = None;
+}
+
+/// Map window event to [`OverlayEvent`], routing cursor inputs to the surface under the cursor if enabled.
+pub fn route_window_event(id: u32, event: WindowEvent) -> OverlayEvent {
+    if !SURFACE_ROUTING.load(Ordering::Relaxed) {
+        return OverlayEvent::Window { id, event };
+    }
+
+    let WindowEvent::Input(InputEvent::Cursor(input)) = event else {
+        return OverlayEvent::Window { id, event };
+    };
+
+    match cursor_target(id, &input) {
+        Some((surface_id, layer, (x, y))) => OverlayEvent::SurfaceCursorInput {
+            id: surface_id,
+            window_id: id,
+            layer,
+            input: CursorInput {
+                pos: InputPosition { x, y },
+                ..input
+            },
+        },
+
+        None => OverlayEvent::Window {
+            id,
+            event: WindowEvent::Input(InputEvent::Cursor(input)),
+        },
+    }
+}
+
+/// Find the surface layer receiving the cursor input and the point relative to it, updating the capture.
+fn cursor_target(window_id: u32, input: &CursorInput) -> Option<(u64, u32, (i32, i32))> {
+    let (x, y) = (input.pos.x, input.pos.y);
+    let mut capture = CAPTURE.lock();
+
+    let target = match *capture {
+        Some(Capture {
+            window_id: captured_window,
+            surface_id,
+            layer,
+            ..
+        }) if captured_window == window_id => {
+            match Surfaces::state(surface_id, |state| state.captured_point(layer, x, y)) {
+                Some(point) => point.map(|point| (surface_id, layer, point)),
+
+                // Captured surface is destroyed
+                None => {
+                    *capture = None;
+                    Surfaces::surface_at(window_id, x, y)
+                }
+            }
+        }
+
+        _ => Surfaces::surface_at(window_id, x, y),
+    };
+
+    match input.event {
+        CursorEvent::Action {
+            state: CursorInputState::Pressed { .. },
+            ..
+        } => match *capture {
+            Some(ref mut capture) if capture.window_id == window_id => {
+                capture.pressed += 1;
+            }
+
+            _ => {
+                *capture = target.map(|(surface_id, layer, _)| Capture {
+                    window_id,
+                    surface_id,
+                    layer,
+                    pressed: 1,
+                });
+            }
+        },
+
+        CursorEvent::Action {
+            state: CursorInputState::Released,
+            ..
+        } => {
+            if let Some(ref mut inner) = *capture
+                && inner.window_id == window_id
+            {
+                inner.pressed = inner.pressed.saturating_sub(1);
+                if inner.pressed == 0 {
+                    *capture = None;
+                }
+            }
+        }
+
+        // Button releases are not delivered after the cursor left the window
+        CursorEvent::Leave if capture.is_some_and(|capture| capture.window_id == window_id) => {
+            *capture = None;
+        }
+
+        _ => {}
+    }
+
+    target
+}
@@ -5,4 +5,5 @@ pub enum Event {
     Overlay(#[from] asdf_overlay_event::Event),
     Window(#[from] asdf_overlay_window_event::Event),
     RequestRepaint(#[from] RequestRepaintInfo),
+    BlockInput(bool),
 }
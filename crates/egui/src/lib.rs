@@ -5,20 +5,19 @@ pub mod prelude;
 pub mod runner;
 mod state;
 
-use std::sync::Arc;
-
 use asdf_overlay_event::SurfaceInfo;
-use asdf_overlay_window::Backends;
+use asdf_overlay_window_event::InputBlockingEndReason;
 use egui::{Context, Ui, Visuals};
+use flume::Sender;
 
-use crate::state::SurfaceState;
+use crate::{event::Event, state::SurfaceState};
 
 pub trait App {
     fn ui(&mut self, ui: &mut Ui, overlay_cx: &OverlayContext);
 
     fn logic(&mut self, _cx: &Context, _overlay_cx: &OverlayContext) {}
 
-    fn on_input_blocking_ended(&mut self) {}
+    fn on_input_blocking_ended(&mut self, _reason: InputBlockingEndReason) {}
 
     fn clear_color(&self, _visuals: &Visuals) -> [f32; 4] {
         [0.0, 0.0, 0.0, 0.0]
@@ -32,17 +31,17 @@ pub struct CreationContext {
 
 #[non_exhaustive]
 pub struct OverlayContext {
-    pub(crate) windows: Arc<Backends>,
+    pub(crate) tx: Sender<Event>,
     pub(crate) surface: SurfaceState,
 }
 
 impl OverlayContext {
     pub fn block_input(&self) {
-        self.windows.block_input();
+        _ = self.tx.send(Event::BlockInput(true));
     }
 
     pub fn unblock_input(&self) {
-        self.windows.unblock_input();
+        _ = self.tx.send(Event::BlockInput(false));
     }
 
     pub fn surface_info(&self) -> &SurfaceInfo {
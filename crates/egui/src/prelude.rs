@@ -5,4 +5,5 @@ pub use crate::impl_dll;
 pub use crate::runner::*;
 pub use crate::{App, CreationContext};
 pub use asdf_overlay_event::{GpuLuid, SurfaceInfo, SurfaceType};
+pub use asdf_overlay_window_event::InputBlockingEndReason;
 pub use egui;
@@ -3,16 +3,25 @@ use std::{sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use asdf_overlay::event_sink::OverlayEventSink;
+use asdf_overlay_common::{
+    controller::OverlayController,
+    request::window::ListenInput,
+    state::{MainSurfacePreference, Registry},
+};
 use asdf_overlay_event::SurfaceEvent;
-use asdf_overlay_window::{Backends, window::ListenInputFlags};
+use asdf_overlay_local::LocalController;
+use asdf_overlay_window::Backends;
 use asdf_overlay_window_event::{
     WindowEvent,
     input::{
-        CursorAction, CursorEvent, CursorInput, CursorInputState, Ime, InputEvent, Key,
-        KeyInputState, KeyboardInput, ScrollAxis,
+        self, CursorAction, CursorEvent, CursorInput, CursorInputState, Ime, InputEvent, KeyCode,
+        KeyInputState, KeyboardInput, PointerPhase, ScrollAxis, TouchInput,
     },
 };
-use egui::{Context, ImeEvent, Modifiers, MouseWheelUnit, PointerButton, RawInput, TouchPhase};
+use egui::{
+    Context, ImeEvent, Modifiers, MouseWheelUnit, PointerButton, RawInput, TouchDeviceId, TouchId,
+    TouchPhase,
+};
 use egui_directx11::split_output;
 use flume::{Receiver, Sender};
 
@@ -34,7 +43,7 @@ where
         }
     })
     .context("initializing windowing")?;
-    let window = Arc::new(window);
+    let controller = LocalController::new(Arc::new(window));
 
     OverlayEventSink::set({
         let tx = tx.clone();
@@ -48,12 +57,21 @@ where
     let cx = CreationContext { egui_cx };
     let app = setup_fn(&cx).await?;
 
-    Ok(inner(cx.egui_cx, window, (tx, rx), app).await?)
+    Ok(inner(cx.egui_cx, controller, (tx, rx), app).await?)
 }
 
+/// Input events listened on every window.
+const LISTEN_ALL: ListenInput = ListenInput {
+    cursor: true,
+    keyboard: true,
+    raw_motion: true,
+    pointer: true,
+    gamepad: true,
+};
+
 async fn inner(
     egui_cx: Context,
-    windows: Arc<Backends>,
+    mut controller: impl OverlayController,
     (tx, mut rx): (Sender<Event>, Receiver<Event>),
     mut app: impl App,
 ) -> anyhow::Result<()> {
@@ -64,13 +82,16 @@ async fn inner(
         }
     });
 
-    let surface = next_main_surface(&mut rx)
+    let mut registry = Registry::default();
+    let surface = next_main_surface(&mut controller, &mut rx, &mut registry)
         .await
         .context("waiting for main surface")?;
-    init_windows(&windows);
+    init_windows(&mut controller)
+        .await
+        .context("initializing windows")?;
     egui_cx.request_repaint();
 
-    let mut cx = OverlayContext { windows, surface };
+    let mut cx = OverlayContext { tx, surface };
     let mut input = RawInput {
         viewport_id: egui_cx.viewport_id(),
         screen_rect: Some(egui::Rect {
@@ -84,26 +105,39 @@ async fn inner(
 
     let start = Instant::now();
     while let Ok(event) = rx.recv_async().await {
+        update_registry(&mut registry, &event);
+
         match event {
-            Event::Overlay(event) => {
-                overlay_event(&egui_cx, &mut rx, &mut cx.surface, &mut input, event)
-                    .await
-                    .context("handling overlay event")?
-            }
+            Event::Overlay(event) => overlay_event(
+                &egui_cx,
+                &mut controller,
+                &mut rx,
+                &mut registry,
+                &mut cx.surface,
+                &mut input,
+                event,
+            )
+            .await
+            .context("handling overlay event")?,
 
             Event::Window(event) => match event {
                 asdf_overlay_window_event::Event::Window { id, event } => {
-                    window_event(&egui_cx, &cx, &mut input, id, event)
+                    window_event(&egui_cx, &mut controller, &mut input, id, event)
                         .await
                         .context("handling window event")?
                 }
 
-                asdf_overlay_window_event::Event::InputBlockingEnded => {
-                    app.on_input_blocking_ended();
+                asdf_overlay_window_event::Event::InputBlockingEnded { reason } => {
+                    app.on_input_blocking_ended(reason);
                     egui_cx.request_repaint();
                 }
             },
 
+            Event::BlockInput(block) => controller
+                .block_input(block)
+                .await
+                .context("blocking input")?,
+
             Event::RequestRepaint(info) => {
                 let cumulative_pass_nr = egui_cx.cumulative_pass_nr();
                 if info.current_cumulative_pass_nr != cumulative_pass_nr
@@ -129,32 +163,34 @@ async fn inner(
     Ok(())
 }
 
-fn init_windows(window: &Backends) {
-    for id in window.windows() {
-        window.window(id, |state| {
-            state.set_input_flags(ListenInputFlags::all());
-        });
+async fn init_windows(controller: &mut impl OverlayController) -> anyhow::Result<()> {
+    for window in controller.windows() {
+        controller.listen_input(window.id, LISTEN_ALL).await?;
     }
+
+    Ok(())
 }
 
 async fn window_event(
     egui_cx: &Context,
-    cx: &OverlayContext,
+    controller: &mut impl OverlayController,
     raw_input: &mut RawInput,
     id: u32,
     event: WindowEvent,
 ) -> anyhow::Result<()> {
     match event {
         WindowEvent::Added { .. } => {
-            cx.windows.window(id, |state| {
-                state.set_input_flags(ListenInputFlags::all());
-            });
+            controller.listen_input(id, LISTEN_ALL).await?;
         }
 
         WindowEvent::Input(event) => {
             match event {
                 InputEvent::Cursor(input) => handle_cursor_input(raw_input, input),
                 InputEvent::Keyboard(input) => handle_keyboard_input(raw_input, input),
+                InputEvent::Touch(input) => handle_touch_input(raw_input, input),
+                // Pen inputs are handled by converted cursor inputs
+                InputEvent::Pen(_) => {}
+                InputEvent::Gamepad(_) => {}
             }
             egui_cx.request_repaint();
         }
@@ -166,6 +202,7 @@ async fn window_event(
 }
 
 fn handle_cursor_input(raw_input: &mut RawInput, input: CursorInput) {
+    raw_input.modifiers = conv_modifiers(input.modifiers);
     let inputs = &mut raw_input.events;
 
     match input.event {
@@ -177,6 +214,10 @@ fn handle_cursor_input(raw_input: &mut RawInput, input: CursorInput) {
 
         CursorEvent::Leave => inputs.push(egui::Event::PointerGone),
 
+        CursorEvent::RawMotion { dx, dy } => {
+            inputs.push(egui::Event::MouseMoved((dx as f32, dy as f32).into()));
+        }
+
         CursorEvent::Action { state, action } => {
             inputs.push(egui::Event::PointerButton {
                 pos: (input.pos.x as f32, input.pos.y as f32).into(),
@@ -206,23 +247,48 @@ fn handle_cursor_input(raw_input: &mut RawInput, input: CursorInput) {
     }
 }
 
+fn handle_touch_input(raw_input: &mut RawInput, input: TouchInput) {
+    let phase = match input.phase {
+        PointerPhase::Down => TouchPhase::Start,
+        PointerPhase::Move => TouchPhase::Move,
+        PointerPhase::Up => TouchPhase::End,
+        PointerPhase::Cancel => TouchPhase::Cancel,
+        PointerPhase::Enter | PointerPhase::Leave => return,
+    };
+
+    raw_input.events.push(egui::Event::Touch {
+        device_id: TouchDeviceId(0),
+        id: TouchId(input.pointer_id as _),
+        phase,
+        pos: (input.pos.x as f32, input.pos.y as f32).into(),
+        force: input.pressure,
+    });
+}
+
 fn handle_keyboard_input(raw: &mut RawInput, input: KeyboardInput) {
     let inputs = &mut raw.events;
 
     match input {
-        KeyboardInput::Key { key, state } => {
-            let Some(key) = conv_key(key) else {
+        KeyboardInput::Key {
+            key,
+            physical_key,
+            state,
+            repeat,
+            modifiers,
+            ..
+        } => {
+            raw.modifiers = conv_modifiers(modifiers);
+            let Some(key) = conv_key(KeyCode::from(key)) else {
                 return;
             };
 
             let pressed = state == KeyInputState::Pressed;
-            update_modifiers(&mut raw.modifiers, key, pressed);
 
             inputs.push(egui::Event::Key {
                 key,
-                physical_key: Some(key),
+                physical_key: conv_key(physical_key),
                 pressed,
-                repeat: false,
+                repeat,
                 modifiers: raw.modifiers,
             });
         }
@@ -253,20 +319,22 @@ fn handle_keyboard_input(raw: &mut RawInput, input: KeyboardInput) {
     }
 }
 
-fn update_modifiers(modifiers: &mut Modifiers, key: egui::Key, pressed: bool) {
-    match key {
-        egui::Key::ShiftLeft | egui::Key::ShiftRight => modifiers.shift = pressed,
-        egui::Key::ControlLeft | egui::Key::ControlRight => modifiers.ctrl = pressed,
-        egui::Key::AltLeft | egui::Key::AltRight => modifiers.alt = pressed,
-        egui::Key::SuperLeft | egui::Key::SuperRight => modifiers.command = pressed,
-
-        _ => {}
+/// Windows keys are not mapped, egui expects `command` to follow Ctrl outside of macOS.
+fn conv_modifiers(modifiers: input::Modifiers) -> Modifiers {
+    Modifiers {
+        alt: modifiers.alt(),
+        ctrl: modifiers.ctrl(),
+        shift: modifiers.shift(),
+        mac_cmd: false,
+        command: modifiers.ctrl(),
     }
 }
 
 async fn overlay_event(
     cx: &Context,
+    controller: &mut impl OverlayController,
     rx: &mut Receiver<Event>,
+    registry: &mut Registry,
     surface: &mut SurfaceState,
     input: &mut RawInput,
     event: asdf_overlay_event::Event,
@@ -277,8 +345,8 @@ async fn overlay_event(
     }
 
     match event {
-        SurfaceEvent::Resized { width, height } => {
-            surface.resize(width, height);
+        SurfaceEvent::Resized { width, height, .. } => {
+            surface.resize(controller, width, height).await;
             input.screen_rect = Some(egui::Rect {
                 min: (0.0, 0.0).into(),
                 max: (width as f32, height as f32).into(),
@@ -288,7 +356,7 @@ async fn overlay_event(
         }
 
         SurfaceEvent::Destroyed => {
-            *surface = next_main_surface(rx)
+            *surface = next_main_surface(controller, rx, registry)
                 .await
                 .context("waiting for main surface")?;
 
@@ -300,155 +368,285 @@ async fn overlay_event(
     Ok(())
 }
 
-async fn next_main_surface(rx: &mut Receiver<Event>) -> anyhow::Result<SurfaceState> {
-    while let Ok(event) = rx.recv_async().await {
-        let Event::Overlay(asdf_overlay_event::Event::Surface { id, event }) = event else {
-            continue;
-        };
+/// Wait until any surface is available and pick the main surface.
+async fn next_main_surface(
+    controller: &mut impl OverlayController,
+    rx: &mut Receiver<Event>,
+    registry: &mut Registry,
+) -> anyhow::Result<SurfaceState> {
+    loop {
+        if let Some(entry) = registry.main_surface(MainSurfacePreference::default()) {
+            let surface = SurfaceState::new(entry.id, entry.info, entry.width, entry.height)?;
+            surface.commit(controller).await;
+            return Ok(surface);
+        }
 
-        let asdf_overlay_event::SurfaceEvent::Added {
-            width,
-            height,
-            info,
-        } = event
-        else {
-            continue;
+        let Ok(event) = rx.recv_async().await else {
+            anyhow::bail!("surface not found");
         };
-
-        return SurfaceState::new(id, info, width, height);
+        update_registry(registry, &event);
     }
-
-    anyhow::bail!("surface not found");
 }
 
-fn conv_key(key: Key) -> Option<egui::Key> {
-    Some(match key.code.get() {
-        8 => egui::Key::Backspace,
-        9 => egui::Key::Tab,
-        13 => egui::Key::Enter,
-        16 => {
-            if key.extended {
-                egui::Key::ShiftRight
-            } else {
-                egui::Key::ShiftLeft
-            }
+fn update_registry(registry: &mut Registry, event: &Event) {
+    match *event {
+        Event::Overlay(asdf_overlay_event::Event::Surface { id, ref event }) => {
+            registry.update_surface(id, event);
         }
-        17 => {
-            if key.extended {
-                egui::Key::ControlRight
-            } else {
-                egui::Key::ControlLeft
-            }
-        }
-        18 => {
-            if key.extended {
-                egui::Key::AltRight
-            } else {
-                egui::Key::AltLeft
-            }
+
+        Event::Window(asdf_overlay_window_event::Event::Window { id, ref event }) => {
+            registry.update_window(id, event);
         }
-        27 => egui::Key::Escape,
-        32 => egui::Key::Space,
-        33 => egui::Key::PageUp,
-        34 => egui::Key::PageDown,
-        35 => egui::Key::End,
-        36 => egui::Key::Home,
-        37 => egui::Key::ArrowLeft,
-        38 => egui::Key::ArrowUp,
-        39 => egui::Key::ArrowRight,
-        45 => egui::Key::Insert,
-        46 => egui::Key::Delete,
-        48 => egui::Key::Num0,
-        49 => egui::Key::Num1,
-        50 => egui::Key::Num2,
-        51 => egui::Key::Num3,
-        52 => egui::Key::Num4,
-        53 => egui::Key::Num5,
-        54 => egui::Key::Num6,
-        55 => egui::Key::Num7,
-        56 => egui::Key::Num8,
-        57 => egui::Key::Num9,
-        65 => egui::Key::A,
-        66 => egui::Key::B,
-        67 => egui::Key::C,
-        68 => egui::Key::D,
-        69 => egui::Key::E,
-        70 => egui::Key::F,
-        71 => egui::Key::G,
-        72 => egui::Key::H,
-        73 => egui::Key::I,
-        74 => egui::Key::J,
-        75 => egui::Key::K,
-        76 => egui::Key::L,
-        77 => egui::Key::M,
-        78 => egui::Key::N,
-        79 => egui::Key::O,
-        80 => egui::Key::P,
-        81 => egui::Key::Q,
-        82 => egui::Key::R,
-        83 => egui::Key::S,
-        84 => egui::Key::T,
-        85 => egui::Key::U,
-        86 => egui::Key::V,
-        87 => egui::Key::W,
-        88 => egui::Key::X,
-        89 => egui::Key::Y,
-        90 => egui::Key::Z,
-        91 => egui::Key::SuperLeft,
-        92 => egui::Key::SuperRight,
-        96 => egui::Key::Num0,
-        97 => egui::Key::Num1,
-        98 => egui::Key::Num2,
-        99 => egui::Key::Num3,
-        100 => egui::Key::Num4,
-        101 => egui::Key::Num5,
-        102 => egui::Key::Num6,
-        103 => egui::Key::Num7,
-        104 => egui::Key::Num8,
-        105 => egui::Key::Num9,
-        106 => egui::Key::Delete,
-        108 => egui::Key::Minus,
-        109 => egui::Key::Minus,
-        110 => egui::Key::Period,
-        111 => egui::Key::Slash,
-        112 => egui::Key::F1,
-        113 => egui::Key::F2,
-        114 => egui::Key::F3,
-        115 => egui::Key::F4,
-        116 => egui::Key::F5,
-        117 => egui::Key::F6,
-        118 => egui::Key::F7,
-        119 => egui::Key::F8,
-        120 => egui::Key::F9,
-        121 => egui::Key::F10,
-        122 => egui::Key::F11,
-        123 => egui::Key::F12,
-        124 => egui::Key::F13,
-        125 => egui::Key::F14,
-        126 => egui::Key::F15,
-        127 => egui::Key::F16,
-        128 => egui::Key::F17,
-        129 => egui::Key::F18,
-        130 => egui::Key::F19,
-        131 => egui::Key::F20,
-        132 => egui::Key::F21,
-        133 => egui::Key::F22,
-        134 => egui::Key::F23,
-        135 => egui::Key::F24,
-        186 => egui::Key::Semicolon,
-        187 => egui::Key::Equals,
-        188 => egui::Key::Comma,
-        189 => egui::Key::Minus,
-        190 => egui::Key::Period,
-        191 => egui::Key::Slash,
-        192 => egui::Key::Backtick,
-        219 => egui::Key::OpenCurlyBracket,
-        220 => egui::Key::Backslash,
-        221 => egui::Key::CloseCurlyBracket,
-        222 => egui::Key::Quote,
-        225 => egui::Key::AltRight,
+
+        _ => {}
+    }
+}
+
+fn conv_key(key: KeyCode) -> Option<egui::Key> {
+    Some(match key {
+        KeyCode::Escape => egui::Key::Escape,
+        KeyCode::Tab => egui::Key::Tab,
+        KeyCode::Backspace => egui::Key::Backspace,
+        KeyCode::Enter => egui::Key::Enter,
+        KeyCode::NumpadEnter => egui::Key::Enter,
+        KeyCode::Space => egui::Key::Space,
+        KeyCode::Insert => egui::Key::Insert,
+        KeyCode::Delete => egui::Key::Delete,
+        KeyCode::Home => egui::Key::Home,
+        KeyCode::End => egui::Key::End,
+        KeyCode::PageUp => egui::Key::PageUp,
+        KeyCode::PageDown => egui::Key::PageDown,
+        KeyCode::ArrowDown => egui::Key::ArrowDown,
+        KeyCode::ArrowLeft => egui::Key::ArrowLeft,
+        KeyCode::ArrowRight => egui::Key::ArrowRight,
+        KeyCode::ArrowUp => egui::Key::ArrowUp,
+        KeyCode::ShiftLeft => egui::Key::ShiftLeft,
+        KeyCode::ShiftRight => egui::Key::ShiftRight,
+        KeyCode::ControlLeft => egui::Key::ControlLeft,
+        KeyCode::ControlRight => egui::Key::ControlRight,
+        KeyCode::AltLeft => egui::Key::AltLeft,
+        KeyCode::AltRight => egui::Key::AltRight,
+        KeyCode::MetaLeft => egui::Key::SuperLeft,
+        KeyCode::MetaRight => egui::Key::SuperRight,
+        KeyCode::Comma => egui::Key::Comma,
+        KeyCode::Backslash => egui::Key::Backslash,
+        KeyCode::Slash => egui::Key::Slash,
+        KeyCode::BracketLeft => egui::Key::OpenBracket,
+        KeyCode::BracketRight => egui::Key::CloseBracket,
+        KeyCode::Backquote => egui::Key::Backtick,
+        KeyCode::Minus => egui::Key::Minus,
+        KeyCode::Period => egui::Key::Period,
+        KeyCode::Equal => egui::Key::Equals,
+        KeyCode::Semicolon => egui::Key::Semicolon,
+        KeyCode::Quote => egui::Key::Quote,
+        KeyCode::NumpadAdd => egui::Key::Plus,
+        KeyCode::NumpadSubtract => egui::Key::Minus,
+        KeyCode::NumpadDecimal => egui::Key::Period,
+        KeyCode::NumpadDivide => egui::Key::Slash,
+        KeyCode::NumpadEqual => egui::Key::Equals,
+        KeyCode::BrowserBack => egui::Key::BrowserBack,
+        KeyCode::Digit0 => egui::Key::Num0,
+        KeyCode::Digit1 => egui::Key::Num1,
+        KeyCode::Digit2 => egui::Key::Num2,
+        KeyCode::Digit3 => egui::Key::Num3,
+        KeyCode::Digit4 => egui::Key::Num4,
+        KeyCode::Digit5 => egui::Key::Num5,
+        KeyCode::Digit6 => egui::Key::Num6,
+        KeyCode::Digit7 => egui::Key::Num7,
+        KeyCode::Digit8 => egui::Key::Num8,
+        KeyCode::Digit9 => egui::Key::Num9,
+        KeyCode::Numpad0 => egui::Key::Num0,
+        KeyCode::Numpad1 => egui::Key::Num1,
+        KeyCode::Numpad2 => egui::Key::Num2,
+        KeyCode::Numpad3 => egui::Key::Num3,
+        KeyCode::Numpad4 => egui::Key::Num4,
+        KeyCode::Numpad5 => egui::Key::Num5,
+        KeyCode::Numpad6 => egui::Key::Num6,
+        KeyCode::Numpad7 => egui::Key::Num7,
+        KeyCode::Numpad8 => egui::Key::Num8,
+        KeyCode::Numpad9 => egui::Key::Num9,
+        KeyCode::KeyA => egui::Key::A,
+        KeyCode::KeyB => egui::Key::B,
+        KeyCode::KeyC => egui::Key::C,
+        KeyCode::KeyD => egui::Key::D,
+        KeyCode::KeyE => egui::Key::E,
+        KeyCode::KeyF => egui::Key::F,
+        KeyCode::KeyG => egui::Key::G,
+        KeyCode::KeyH => egui::Key::H,
+        KeyCode::KeyI => egui::Key::I,
+        KeyCode::KeyJ => egui::Key::J,
+        KeyCode::KeyK => egui::Key::K,
+        KeyCode::KeyL => egui::Key::L,
+        KeyCode::KeyM => egui::Key::M,
+        KeyCode::KeyN => egui::Key::N,
+        KeyCode::KeyO => egui::Key::O,
+        KeyCode::KeyP => egui::Key::P,
+        KeyCode::KeyQ => egui::Key::Q,
+        KeyCode::KeyR => egui::Key::R,
+        KeyCode::KeyS => egui::Key::S,
+        KeyCode::KeyT => egui::Key::T,
+        KeyCode::KeyU => egui::Key::U,
+        KeyCode::KeyV => egui::Key::V,
+        KeyCode::KeyW => egui::Key::W,
+        KeyCode::KeyX => egui::Key::X,
+        KeyCode::KeyY => egui::Key::Y,
+        KeyCode::KeyZ => egui::Key::Z,
+        KeyCode::F1 => egui::Key::F1,
+        KeyCode::F2 => egui::Key::F2,
+        KeyCode::F3 => egui::Key::F3,
+        KeyCode::F4 => egui::Key::F4,
+        KeyCode::F5 => egui::Key::F5,
+        KeyCode::F6 => egui::Key::F6,
+        KeyCode::F7 => egui::Key::F7,
+        KeyCode::F8 => egui::Key::F8,
+        KeyCode::F9 => egui::Key::F9,
+        KeyCode::F10 => egui::Key::F10,
+        KeyCode::F11 => egui::Key::F11,
+        KeyCode::F12 => egui::Key::F12,
+        KeyCode::F13 => egui::Key::F13,
+        KeyCode::F14 => egui::Key::F14,
+        KeyCode::F15 => egui::Key::F15,
+        KeyCode::F16 => egui::Key::F16,
+        KeyCode::F17 => egui::Key::F17,
+        KeyCode::F18 => egui::Key::F18,
+        KeyCode::F19 => egui::Key::F19,
+        KeyCode::F20 => egui::Key::F20,
+        KeyCode::F21 => egui::Key::F21,
+        KeyCode::F22 => egui::Key::F22,
+        KeyCode::F23 => egui::Key::F23,
+        KeyCode::F24 => egui::Key::F24,
 
         // Unknown key code
         _ => return None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use asdf_overlay_common::controller::{
+        WindowSnapshot,
+        mock::{MockCall, MockController},
+    };
+    use asdf_overlay_window_event::{
+        WindowInfo, WindowStyle,
+        input::{CursorButtons, InputPosition, Modifiers},
+    };
+
+    use super::*;
+
+    fn window(id: u32) -> WindowSnapshot {
+        WindowSnapshot {
+            id,
+            width: 800,
+            height: 600,
+            info: WindowInfo {
+                title: String::new(),
+                class_name: String::new(),
+                style: WindowStyle::empty(),
+                thread_id: 1,
+                scale: 1.0,
+                monitor: None,
+                focused: false,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn init_windows_listens_every_window() {
+        let mut controller = MockController::new(vec![window(1), window(2)], Vec::new());
+        init_windows(&mut controller).await.unwrap();
+
+        assert_eq!(
+            controller.take_calls(),
+            [
+                MockCall::ListenInput(1, LISTEN_ALL),
+                MockCall::ListenInput(2, LISTEN_ALL),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn added_window_listens_input() {
+        let egui_cx = Context::default();
+        let mut controller = MockController::default();
+        let mut input = RawInput::default();
+
+        window_event(
+            &egui_cx,
+            &mut controller,
+            &mut input,
+            3,
+            WindowEvent::Added {
+                width: 800,
+                height: 600,
+                info: window(3).info,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            controller.take_calls(),
+            [MockCall::ListenInput(3, LISTEN_ALL)]
+        );
+    }
+
+    #[tokio::test]
+    async fn input_event_does_not_control_overlay() {
+        let egui_cx = Context::default();
+        let mut controller = MockController::default();
+        let mut input = RawInput::default();
+
+        window_event(
+            &egui_cx,
+            &mut controller,
+            &mut input,
+            1,
+            WindowEvent::Input(InputEvent::Cursor(CursorInput {
+                event: CursorEvent::Move,
+                pos: InputPosition { x: 10, y: 20 },
+                modifiers: Modifiers::empty(),
+                buttons: CursorButtons::empty(),
+            })),
+        )
+        .await
+        .unwrap();
+
+        assert!(controller.take_calls().is_empty());
+        assert_eq!(
+            input.events,
+            [egui::Event::PointerMoved((10.0, 20.0).into())]
+        );
+    }
+
+    #[test]
+    fn modifiers_either_side() {
+        for modifiers in [Modifiers::LCTRL, Modifiers::RCTRL] {
+            assert_eq!(
+                conv_modifiers(modifiers),
+                egui::Modifiers {
+                    ctrl: true,
+                    command: true,
+                    ..Default::default()
+                }
+            );
+        }
+
+        assert_eq!(
+            conv_modifiers(Modifiers::RSHIFT | Modifiers::LALT),
+            egui::Modifiers {
+                alt: true,
+                shift: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn modifiers_ignore_win_and_locks() {
+        assert_eq!(
+            conv_modifiers(Modifiers::WIN | Modifiers::CAPS_LOCK | Modifiers::NUM_LOCK),
+            egui::Modifiers::NONE
+        );
+    }
+}
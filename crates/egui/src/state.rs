@@ -1,7 +1,7 @@
 use core::ptr;
 
 use anyhow::Context as _;
-use asdf_overlay::surface::{SharedTextureHandle, Surfaces};
+use asdf_overlay_common::{controller::OverlayController, request::surface::UpdateSharedHandle};
 use asdf_overlay_event::{GpuLuid, SurfaceInfo};
 use egui::Context;
 use egui_directx11::{Renderer, RendererOutput};
@@ -49,7 +49,7 @@ impl SurfaceState {
         let surface_texture = create_surface_texture(&d3d11_device, width, height)
             .context("creating surface texture")?;
 
-        let this = Self {
+        Ok(Self {
             id,
             width,
             height,
@@ -59,16 +59,24 @@ impl SurfaceState {
             d3d11_cx,
             renderer,
             surface_texture,
-        };
-
-        this.update_surface();
-        Ok(this)
+        })
     }
 
-    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+    pub(crate) async fn resize(
+        &mut self,
+        controller: &mut impl OverlayController,
+        width: u32,
+        height: u32,
+    ) {
         self.width = width;
         self.height = height;
-        self.on_resized();
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        self.surface_texture = create_surface_texture(&self.d3d11_device, self.width, self.height)
+            .expect("creating surface texture");
+
+        self.commit(controller).await;
     }
 
     pub(crate) fn render(
@@ -92,17 +100,8 @@ impl SurfaceState {
         Ok(())
     }
 
-    fn on_resized(&mut self) {
-        if self.width == 0 || self.height == 0 {
-            return;
-        }
-        self.surface_texture = create_surface_texture(&self.d3d11_device, self.width, self.height)
-            .expect("creating surface texture");
-
-        self.update_surface();
-    }
-
-    fn update_surface(&self) {
+    /// Commit the surface texture to the surface overlay.
+    pub(crate) async fn commit(&self, controller: &mut impl OverlayController) {
         let shared_handle = unsafe {
             let res = self
                 .surface_texture
@@ -113,17 +112,11 @@ impl SurfaceState {
                 .CreateSharedHandle(None, DXGI_SHARED_RESOURCE_READ.0, None)
                 .expect("creating shared texture");
 
-            SharedTextureHandle::Nt(handle.0 as _)
+            UpdateSharedHandle::Nt(handle.0 as _)
         };
 
-        if Surfaces::state(self.id, |state| {
-            if let Err(err) = state.commit_overlay_texture(Some(shared_handle)) {
-                error!("failed to commit overlay texture: {err:?}");
-            }
-        })
-        .is_none()
-        {
-            error!("failed to commit overlay texture: surface not found");
+        if let Err(err) = controller.update_texture(self.id, shared_handle).await {
+            error!("failed to commit overlay texture: {err:?}");
         }
     }
 }
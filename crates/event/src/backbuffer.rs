@@ -0,0 +1,105 @@
+//! Backbuffer properties of a surface.
+//!
+//! Clients can use these to produce overlay contents matching the application rendering,
+//! for example rendering in HDR when the surface presents HDR10 or scRGB.
+
+/// Backbuffer properties of a surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BackbufferInfo {
+    /// Pixel format of the backbuffer.
+    pub format: PixelFormat,
+
+    /// Color space the backbuffer is presented in.
+    pub color_space: ColorSpace,
+
+    /// Number of multisamples per pixel. `1` if the backbuffer is not multisampled.
+    pub sample_count: u32,
+
+    /// Number of buffers in the swapchain.
+    pub buffer_count: u32,
+
+    /// How frames are presented to the screen.
+    pub present_mode: PresentMode,
+}
+
+/// Pixel format of a backbuffer.
+///
+/// Channels are listed from the lowest bits to the highest bits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PixelFormat {
+    /// Format is unknown or not listed.
+    #[default]
+    Unknown,
+
+    /// 8 bits per channel RGBA.
+    Rgba8Unorm,
+
+    /// 8 bits per channel RGBA, sRGB encoded.
+    Rgba8UnormSrgb,
+
+    /// 8 bits per channel BGRA.
+    Bgra8Unorm,
+
+    /// 8 bits per channel BGRA, sRGB encoded.
+    Bgra8UnormSrgb,
+
+    /// 8 bits per channel BGR with unused 8 bits.
+    Bgrx8Unorm,
+
+    /// 10 bits per color channel RGB with 2 bits alpha.
+    Rgb10A2Unorm,
+
+    /// 10 bits per color channel BGR with 2 bits alpha.
+    Bgr10A2Unorm,
+
+    /// 16 bits per channel floating point RGBA.
+    Rgba16Float,
+
+    /// 5, 6, 5 bits BGR.
+    B5G6R5Unorm,
+}
+
+/// Color space a backbuffer is presented in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorSpace {
+    /// Color space is unknown or not listed.
+    #[default]
+    Unknown,
+
+    /// sRGB with BT.709 primaries. Standard dynamic range.
+    Srgb,
+
+    /// Linear scRGB with BT.709 primaries. Values above `1.0` are brighter than SDR white.
+    ScRgb,
+
+    /// HDR10. SMPTE ST 2084 (PQ) encoded with BT.2020 primaries.
+    Hdr10,
+}
+
+/// How frames are presented to the screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PresentMode {
+    /// Present mode is unknown.
+    #[default]
+    Unknown,
+
+    /// Frames are presented without waiting for vertical blank. Tearing may occur.
+    Immediate,
+
+    /// Latest frame is presented on vertical blank without blocking the application.
+    Mailbox,
+
+    /// Frames are presented on vertical blank in order.
+    Fifo {
+        /// Number of vertical blanks to wait per frame.
+        sync_interval: u32,
+    },
+
+    /// Frames are presented on vertical blank in order,
+    /// but presented immediately if the application missed a vertical blank.
+    FifoRelaxed,
+}
@@ -0,0 +1,42 @@
+//! Blending of overlay textures onto a surface.
+
+/// How an overlay texture is blended onto the surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Blend {
+    /// Blend mode of the texture.
+    pub mode: BlendMode,
+
+    /// Color encoding of the texture.
+    pub encoding: ColorEncoding,
+}
+
+/// Blend mode of an overlay texture.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    /// Texture has straight alpha. Colors are multiplied by alpha when blended.
+    #[default]
+    Straight,
+
+    /// Texture has premultiplied alpha, like contents rendered by browsers.
+    Premultiplied,
+
+    /// Texture colors multiplied by alpha are added to the surface.
+    /// Alpha of the surface is kept.
+    Additive,
+}
+
+/// Color encoding of an overlay texture.
+///
+/// Texture colors are converted to the encoding the surface backbuffer expects.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorEncoding {
+    /// Colors are sRGB encoded.
+    #[default]
+    Srgb,
+
+    /// Colors are linear.
+    Linear,
+}
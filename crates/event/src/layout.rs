@@ -0,0 +1,380 @@
+//! Layout of overlay texture in a surface.
+//!
+//! Layouts are resolved against the current surface size on each draw,
+//! so overlay keeps its place when the surface is resized.
+
+/// Point of the surface and overlay texture aligned each other.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Horizontal and vertical factor of the anchor point. `0.0` is left or top, `1.0` is right or bottom.
+    pub const fn factor(self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// Length relative to a surface dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Length {
+    /// Length in pixels.
+    Pixels(i32),
+
+    /// Length in percent of the surface dimension. `100.0` is the whole dimension.
+    Percent(f32),
+}
+
+impl Length {
+    /// Resolve the length in pixels against the surface dimension.
+    pub fn resolve(self, dimension: u32) -> i32 {
+        match self {
+            Length::Pixels(pixels) => pixels,
+            Length::Percent(percent) => (dimension as f32 * percent / 100.0).round() as i32,
+        }
+    }
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Length::Pixels(0)
+    }
+}
+
+/// Describe where and how large overlay texture is drawn in a surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout {
+    /// Anchor point of the surface and overlay texture.
+    pub anchor: Anchor,
+
+    /// Horizontal offset from the anchor point. Positive values move right.
+    pub x: Length,
+
+    /// Vertical offset from the anchor point. Positive values move down.
+    pub y: Length,
+
+    /// Maximum width of overlay texture.
+    ///
+    /// Overlay texture larger than maximum size is scaled down, keeping its aspect ratio.
+    pub max_width: Option<Length>,
+
+    /// Maximum height of overlay texture.
+    ///
+    /// Overlay texture larger than maximum size is scaled down, keeping its aspect ratio.
+    pub max_height: Option<Length>,
+}
+
+impl Layout {
+    /// Layout placing overlay texture at the position relative to the left-top corner of the surface.
+    pub const fn absolute(x: i32, y: i32) -> Self {
+        Self {
+            anchor: Anchor::TopLeft,
+            x: Length::Pixels(x),
+            y: Length::Pixels(y),
+            max_width: None,
+            max_height: None,
+        }
+    }
+
+    /// Resolve placement of overlay texture against the surface size and its drawn size.
+    pub fn resolve(&self, surface: (u32, u32), texture: (u32, u32)) -> Placement {
+        let scale = {
+            let fit = |max: Option<Length>, dimension: u32, size: u32| {
+                max.map_or(1.0, |max| {
+                    max.resolve(dimension).max(0) as f32 / size.max(1) as f32
+                })
+            };
+
+            fit(self.max_width, surface.0, texture.0)
+                .min(fit(self.max_height, surface.1, texture.1))
+                .min(1.0)
+        };
+
+        let width = (texture.0 as f32 * scale).round() as u32;
+        let height = (texture.1 as f32 * scale).round() as u32;
+
+        let (fx, fy) = self.anchor.factor();
+        Placement {
+            x: ((surface.0 as f32 - width as f32) * fx).round() as i32 + self.x.resolve(surface.0),
+            y: ((surface.1 as f32 - height as f32) * fy).round() as i32 + self.y.resolve(surface.1),
+            width,
+            height,
+        }
+    }
+}
+
+/// Describe how overlay texture is sized to a surface before applying scale.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FitMode {
+    /// Keep the texture size.
+    #[default]
+    None,
+
+    /// Fit the texture inside the surface, keeping its aspect ratio.
+    Contain,
+
+    /// Cover the whole surface, keeping its aspect ratio. The texture may overflow the surface.
+    Cover,
+
+    /// Stretch the texture to the surface size.
+    Stretch,
+}
+
+/// Describe how overlay texture is drawn in a surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// Opacity of overlay texture. `0.0` is fully transparent and `1.0` is opaque.
+    pub opacity: f32,
+
+    /// Horizontal scale applied after fitting.
+    pub scale_x: f32,
+
+    /// Vertical scale applied after fitting.
+    pub scale_y: f32,
+
+    /// How overlay texture is sized to the surface.
+    pub fit: FitMode,
+}
+
+impl Transform {
+    /// Resolve drawn size of overlay texture against the surface size and texture size.
+    pub fn size(&self, surface: (u32, u32), texture: (u32, u32)) -> (u32, u32) {
+        let (width, height) = match self.fit {
+            FitMode::None => (texture.0 as f32, texture.1 as f32),
+            FitMode::Stretch => (surface.0 as f32, surface.1 as f32),
+            FitMode::Contain | FitMode::Cover => {
+                let scale_x = surface.0 as f32 / texture.0.max(1) as f32;
+                let scale_y = surface.1 as f32 / texture.1.max(1) as f32;
+                let scale = if self.fit == FitMode::Contain {
+                    scale_x.min(scale_y)
+                } else {
+                    scale_x.max(scale_y)
+                };
+
+                (texture.0 as f32 * scale, texture.1 as f32 * scale)
+            }
+        };
+
+        (
+            (width * self.scale_x.max(0.0)).round() as u32,
+            (height * self.scale_y.max(0.0)).round() as u32,
+        )
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            fit: FitMode::None,
+        }
+    }
+}
+
+/// Resolved placement of overlay texture relative to the left-top corner of the surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placement {
+    /// X position.
+    pub x: i32,
+
+    /// Y position.
+    pub y: i32,
+
+    /// Width of drawn overlay texture.
+    pub width: u32,
+
+    /// Height of drawn overlay texture.
+    pub height: u32,
+}
+
+impl Placement {
+    /// Position of the placement.
+    #[inline]
+    pub const fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// Size of the placement.
+    #[inline]
+    pub const fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn placement(x: i32, y: i32, width: u32, height: u32) -> Placement {
+        Placement {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn anchors() {
+        let layout = |anchor| Layout {
+            anchor,
+            ..Layout::default()
+        };
+        let surface = (800, 600);
+        let texture = (200, 100);
+
+        let cases = [
+            (Anchor::TopLeft, (0, 0)),
+            (Anchor::Top, (300, 0)),
+            (Anchor::TopRight, (600, 0)),
+            (Anchor::Left, (0, 250)),
+            (Anchor::Center, (300, 250)),
+            (Anchor::Right, (600, 250)),
+            (Anchor::BottomLeft, (0, 500)),
+            (Anchor::Bottom, (300, 500)),
+            (Anchor::BottomRight, (600, 500)),
+        ];
+        for (anchor, (x, y)) in cases {
+            assert_eq!(
+                layout(anchor).resolve(surface, texture),
+                placement(x, y, 200, 100),
+                "{anchor:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn offsets() {
+        let layout = Layout {
+            anchor: Anchor::BottomRight,
+            x: Length::Pixels(-10),
+            y: Length::Percent(-10.0),
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((800, 600), (200, 100)),
+            placement(590, 440, 200, 100)
+        );
+
+        assert_eq!(
+            Layout::absolute(12, -34).resolve((800, 600), (200, 100)),
+            placement(12, -34, 200, 100)
+        );
+    }
+
+    #[test]
+    fn lengths() {
+        assert_eq!(Length::Pixels(-5).resolve(1000), -5);
+        assert_eq!(Length::Percent(25.0).resolve(1000), 250);
+        assert_eq!(Length::Percent(33.3).resolve(100), 33);
+        assert_eq!(Length::Percent(100.0).resolve(0), 0);
+    }
+
+    #[test]
+    fn max_size_scales_down_keeping_aspect_ratio() {
+        let layout = Layout {
+            max_width: Some(Length::Percent(50.0)),
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((800, 600), (800, 200)),
+            placement(0, 0, 400, 100)
+        );
+
+        // Smaller of both limits applies
+        let layout = Layout {
+            max_width: Some(Length::Pixels(400)),
+            max_height: Some(Length::Pixels(50)),
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((800, 600), (800, 200)),
+            placement(0, 0, 200, 50)
+        );
+    }
+
+    #[test]
+    fn max_size_does_not_scale_up() {
+        let layout = Layout {
+            max_width: Some(Length::Percent(100.0)),
+            max_height: Some(Length::Percent(100.0)),
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((800, 600), (200, 100)),
+            placement(0, 0, 200, 100)
+        );
+    }
+
+    #[test]
+    fn negative_max_size_clamps_to_zero() {
+        let layout = Layout {
+            max_width: Some(Length::Pixels(-100)),
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((800, 600), (200, 100)),
+            placement(0, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn zero_size_surface() {
+        let layout = Layout {
+            anchor: Anchor::Center,
+            max_width: Some(Length::Percent(50.0)),
+            ..Layout::default()
+        };
+        assert_eq!(layout.resolve((0, 0), (200, 100)), placement(0, 0, 0, 0));
+
+        // Texture overflowing the surface is placed off the anchor
+        let layout = Layout {
+            anchor: Anchor::BottomRight,
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((0, 0), (200, 100)),
+            placement(-200, -100, 200, 100)
+        );
+    }
+
+    #[test]
+    fn zero_size_texture() {
+        let layout = Layout {
+            anchor: Anchor::Center,
+            max_width: Some(Length::Pixels(100)),
+            ..Layout::default()
+        };
+        assert_eq!(
+            layout.resolve((800, 600), (0, 0)),
+            placement(400, 300, 0, 0)
+        );
+    }
+}
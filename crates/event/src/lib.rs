@@ -1,3 +1,8 @@
+pub mod backbuffer;
+pub mod blend;
+pub mod layout;
+pub mod stats;
+
 /// Describe a event.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -23,13 +28,38 @@ pub enum SurfaceEvent {
 
         /// Surface information
         info: SurfaceInfo,
+
+        /// Backbuffer properties of the surface
+        backbuffer: backbuffer::BackbufferInfo,
     },
+    /// Surface is resized or its backbuffer properties changed.
     Resized {
         // New width of the surface
         width: u32,
 
         // New height of the surface
         height: u32,
+
+        /// New backbuffer properties of the surface
+        backbuffer: backbuffer::BackbufferInfo,
+    },
+    /// A frame of the surface was presented.
+    ///
+    /// Only emitted while present notifications are enabled on the surface,
+    /// or once after a frame callback was requested.
+    Presented {
+        /// Number of frames presented since the surface was added, starting from `1`.
+        frame: u64,
+
+        /// Time of the present in microseconds since the UNIX epoch.
+        timestamp: u64,
+    },
+    /// Periodic frame time statistics of the surface.
+    ///
+    /// Only emitted while periodic statistics are enabled on the surface.
+    Stats {
+        /// Frame time statistics measured from recent presents.
+        stats: stats::FrameStats,
     },
     Destroyed,
 }
@@ -100,6 +130,17 @@ pub enum SurfaceType {
 }
 
 impl SurfaceType {
+    /// Rendering api of the surface.
+    pub fn api(self) -> SurfaceApis {
+        match self {
+            SurfaceType::Opengl { .. } => SurfaceApis::OPENGL,
+            SurfaceType::Direct3D9 { .. } => SurfaceApis::DIRECT3D9,
+            SurfaceType::Direct3D11 { .. } => SurfaceApis::DIRECT3D11,
+            SurfaceType::Direct3D12 { .. } => SurfaceApis::DIRECT3D12,
+            SurfaceType::Vulkan { .. } => SurfaceApis::VULKAN,
+        }
+    }
+
     pub fn window_id(self) -> Option<u32> {
         match self {
             SurfaceType::Opengl { window_id } => Some(window_id),
@@ -110,3 +151,112 @@ impl SurfaceType {
         }
     }
 }
+
+bitflags::bitflags! {
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    /// Set of surface rendering apis.
+    pub struct SurfaceApis: u8 {
+        /// OpenGL.
+        const OPENGL = 1;
+
+        /// Direct3D9.
+        const DIRECT3D9 = 1 << 1;
+
+        /// Direct3D11.
+        const DIRECT3D11 = 1 << 2;
+
+        /// Direct3D12.
+        const DIRECT3D12 = 1 << 3;
+
+        /// Vulkan.
+        const VULKAN = 1 << 4;
+    }
+}
+
+/// Describe rules for ignoring surfaces.
+///
+/// Ignored surfaces are not tracked, so no overlay is rendered and no event is sent for them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurfaceFilter {
+    /// Rendering apis to ignore.
+    pub ignore_apis: SurfaceApis,
+
+    /// Minimum surface width.
+    pub min_width: u32,
+
+    /// Minimum surface height.
+    pub min_height: u32,
+}
+
+impl SurfaceFilter {
+    /// Check if the surface should be ignored.
+    pub fn ignores(&self, ty: SurfaceType, width: u32, height: u32) -> bool {
+        self.ignore_apis.intersects(ty.api()) || width < self.min_width || height < self.min_height
+    }
+}
+
+#[cfg(feature = "serde")]
+const _: () = {
+    use serde::{Deserialize, Serialize};
+
+    impl Serialize for SurfaceApis {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_u8(self.bits())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SurfaceApis {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            Ok(SurfaceApis::from_bits_retain(u8::deserialize(
+                deserializer,
+            )?))
+        }
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_filter_default_ignores_nothing() {
+        let filter = SurfaceFilter::default();
+        assert!(!filter.ignores(SurfaceType::Opengl { window_id: 1 }, 0, 0));
+        assert!(!filter.ignores(SurfaceType::Direct3D11 { window_id: None }, 0, 0));
+    }
+
+    #[test]
+    fn surface_filter_ignores_apis() {
+        let filter = SurfaceFilter {
+            ignore_apis: SurfaceApis::OPENGL | SurfaceApis::VULKAN,
+            ..Default::default()
+        };
+
+        assert!(filter.ignores(SurfaceType::Opengl { window_id: 1 }, 100, 100));
+        assert!(filter.ignores(SurfaceType::Vulkan { window_id: 1 }, 100, 100));
+        assert!(!filter.ignores(SurfaceType::Direct3D9 { window_id: 1 }, 100, 100));
+        assert!(!filter.ignores(SurfaceType::Direct3D11 { window_id: None }, 100, 100));
+        assert!(!filter.ignores(SurfaceType::Direct3D12 { window_id: Some(1) }, 100, 100));
+    }
+
+    #[test]
+    fn surface_filter_ignores_small() {
+        let filter = SurfaceFilter {
+            min_width: 100,
+            min_height: 50,
+            ..Default::default()
+        };
+        let ty = SurfaceType::Direct3D11 { window_id: Some(1) };
+
+        assert!(!filter.ignores(ty, 100, 50));
+        assert!(filter.ignores(ty, 99, 50));
+        assert!(filter.ignores(ty, 100, 49));
+    }
+}
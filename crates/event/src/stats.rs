@@ -0,0 +1,24 @@
+//! Frame time statistics of a surface.
+
+/// Frame time statistics measured from recent presents of a surface.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameStats {
+    /// Number of present intervals the statistics are measured from.
+    pub samples: u32,
+
+    /// Average frames per second.
+    pub fps: f32,
+
+    /// Average frame time in milliseconds.
+    pub frame_time_ms: f32,
+
+    /// Average frames per second of the slowest 1% frames.
+    pub low_1_percent_fps: f32,
+
+    /// Average frames per second of the slowest 0.1% frames.
+    pub low_0_1_percent_fps: f32,
+
+    /// Standard deviation of present intervals in milliseconds.
+    pub jitter_ms: f32,
+}
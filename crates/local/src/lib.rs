@@ -0,0 +1,283 @@
+//! In-process [`OverlayController`] implementation.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context;
+use asdf_overlay::surface::{SharedTextureHandle, Surfaces, dirty, hit_test};
+use asdf_overlay_common::{
+    controller::{OverlayController, SurfaceSnapshot, WindowSnapshot},
+    event::{
+        surface::{
+            blend::Blend,
+            layout::{Layout, Transform},
+            stats::FrameStats,
+        },
+        window::InputBlockingEndReason,
+    },
+    request::{
+        surface::{DirtyRect, HitRegion, UpdateBitmapShm, UpdateLayer, UpdateSharedHandle},
+        window::ListenInput,
+    },
+};
+
+use asdf_overlay_window::{Backends, window::ListenInputFlags};
+
+/// [`OverlayController`] which controls overlay in the current process.
+#[derive(Clone)]
+pub struct LocalController {
+    backends: Arc<Backends>,
+}
+
+impl LocalController {
+    /// Create a new [`LocalController`] using initialized window backends.
+    pub const fn new(backends: Arc<Backends>) -> Self {
+        Self { backends }
+    }
+
+    /// Window backends of the controller.
+    #[inline]
+    pub fn backends(&self) -> &Arc<Backends> {
+        &self.backends
+    }
+}
+
+impl OverlayController for LocalController {
+    async fn block_input(&mut self, block: bool) -> anyhow::Result<()> {
+        if block {
+            self.backends.block_input();
+        } else {
+            self.backends
+                .unblock_input(InputBlockingEndReason::ClientRequest);
+        }
+
+        Ok(())
+    }
+
+    async fn listen_input(&mut self, window_id: u32, listen: ListenInput) -> anyhow::Result<()> {
+        let mut flags = ListenInputFlags::empty();
+        flags.set(ListenInputFlags::CURSOR, listen.cursor);
+        flags.set(ListenInputFlags::KEYBOARD, listen.keyboard);
+        flags.set(ListenInputFlags::RAW_MOTION, listen.raw_motion);
+        flags.set(ListenInputFlags::POINTER, listen.pointer);
+        flags.set(ListenInputFlags::GAMEPAD, listen.gamepad);
+
+        // Same as IPC server, requests to missing windows are ignored
+        self.backends
+            .window(window_id, |state| state.set_input_flags(flags));
+        Ok(())
+    }
+
+    async fn set_position(&mut self, surface_id: u64, x: i32, y: i32) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.reposition(x, y)).context("Surface not found")
+    }
+
+    async fn set_layout(&mut self, surface_id: u64, layout: Layout) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.set_layout(layout)).context("Surface not found")
+    }
+
+    async fn set_transform(&mut self, surface_id: u64, transform: Transform) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.set_transform(transform))
+            .context("Surface not found")
+    }
+
+    async fn set_blend(&mut self, surface_id: u64, blend: Blend) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.set_blend(blend)).context("Surface not found")
+    }
+
+    async fn set_visible(&mut self, surface_id: u64, visible: bool) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.set_visible(visible)).context("Surface not found")
+    }
+
+    async fn listen_present(&mut self, surface_id: u64, listen: bool) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.set_listen_present(listen))
+            .context("Surface not found")
+    }
+
+    async fn request_frame(&mut self, surface_id: u64) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.request_frame()).context("Surface not found")
+    }
+
+    async fn frame_stats(&mut self, surface_id: u64) -> anyhow::Result<Option<FrameStats>> {
+        Surfaces::state(surface_id, |state| state.frame_stats()).context("Surface not found")
+    }
+
+    async fn listen_stats(
+        &mut self,
+        surface_id: u64,
+        interval: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.set_stats_interval(interval))
+            .context("Surface not found")
+    }
+
+    async fn update_texture(
+        &mut self,
+        surface_id: u64,
+        handle: UpdateSharedHandle,
+    ) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| {
+            state
+                .commit_overlay_texture(map_shared_handle(handle))
+                .context("Failed to commit overlay texture")
+        })
+        .context("Surface not found")?
+    }
+
+    async fn update_bitmap_shm(
+        &mut self,
+        surface_id: u64,
+        bitmap: UpdateBitmapShm,
+    ) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| {
+            state
+                .commit_overlay_bitmap_shm(bitmap.name.as_deref())
+                .context("Failed to commit overlay bitmap")
+        })
+        .context("Surface not found")?
+    }
+
+    async fn invalidate_region(
+        &mut self,
+        surface_id: u64,
+        rects: Vec<DirtyRect>,
+    ) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| {
+            state.invalidate_region(&map_dirty_rects(rects))
+        })
+        .context("Surface not found")
+    }
+
+    async fn update_layer(&mut self, surface_id: u64, update: UpdateLayer) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| {
+            state.update_layer(update.layer, |layer| {
+                if let Some(z_index) = update.z_index {
+                    layer.z_index = z_index;
+                }
+                if let Some(visible) = update.visible {
+                    layer.visible = visible;
+                }
+                if let Some(layout) = update.layout {
+                    layer.layout = layout;
+                }
+                if let Some(transform) = update.transform {
+                    layer.transform = transform;
+                }
+                if let Some(blend) = update.blend {
+                    layer.blend = blend;
+                }
+            })?;
+
+            if let Some(handle) = update.handle {
+                state
+                    .commit_layer_texture(update.layer, map_shared_handle(handle))
+                    .context("Failed to commit overlay texture")?;
+            }
+            if let Some(bitmap) = update.bitmap_shm {
+                state
+                    .commit_layer_bitmap_shm(update.layer, bitmap.name.as_deref())
+                    .context("Failed to commit overlay bitmap")?;
+            }
+            if let Some(rects) = update.invalidate {
+                state.invalidate_layer_region(update.layer, &map_dirty_rects(rects))?;
+            }
+
+            Ok(())
+        })
+        .context("Surface not found")?
+    }
+
+    async fn remove_layer(&mut self, surface_id: u64, layer: u32) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| state.remove_layer(layer))
+            .context("Surface not found")?
+    }
+
+    async fn set_hit_region(
+        &mut self,
+        surface_id: u64,
+        layer: u32,
+        region: HitRegion,
+    ) -> anyhow::Result<()> {
+        Surfaces::state(surface_id, |state| {
+            state.set_layer_hit_region(layer, map_hit_region(region))
+        })
+        .context("Surface not found")?
+    }
+
+    fn windows(&self) -> Vec<WindowSnapshot> {
+        self.backends
+            .windows()
+            .filter_map(|id| {
+                self.backends.window(id, |state| {
+                    let (width, height) = state.size();
+
+                    WindowSnapshot {
+                        id,
+                        width,
+                        height,
+                        info: state.info(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn surfaces(&self) -> Vec<SurfaceSnapshot> {
+        Surfaces::iter()
+            .filter_map(|id| {
+                Surfaces::state(id, |state| {
+                    let (width, height) = state.size();
+
+                    SurfaceSnapshot {
+                        id,
+                        width,
+                        height,
+                        info: state.info,
+                        backbuffer: state.backbuffer(),
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+fn map_shared_handle(handle: UpdateSharedHandle) -> Option<SharedTextureHandle> {
+    match handle {
+        UpdateSharedHandle::Kmt(handle) => Some(SharedTextureHandle::Kmt(handle)),
+        UpdateSharedHandle::Nt(handle) => Some(SharedTextureHandle::Nt(handle)),
+        UpdateSharedHandle::None => None,
+    }
+}
+
+fn map_hit_region(region: HitRegion) -> hit_test::HitRegion {
+    match region {
+        HitRegion::None => hit_test::HitRegion::None,
+        HitRegion::Full => hit_test::HitRegion::Full,
+        HitRegion::Rects(rects) => hit_test::HitRegion::Rects(
+            rects
+                .into_iter()
+                .map(|rect| hit_test::HitRect {
+                    x: rect.x,
+                    y: rect.y,
+                    width: rect.width,
+                    height: rect.height,
+                })
+                .collect(),
+        ),
+        HitRegion::AlphaMask { threshold } => hit_test::HitRegion::AlphaMask {
+            threshold,
+            mask: None,
+        },
+    }
+}
+
+fn map_dirty_rects(rects: Vec<DirtyRect>) -> Vec<dirty::DirtyRect> {
+    rects
+        .into_iter()
+        .map(|rect| dirty::DirtyRect {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        })
+        .collect()
+}
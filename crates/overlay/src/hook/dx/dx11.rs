@@ -1,5 +1,5 @@
 use anyhow::Context;
-use asdf_overlay_event::{SurfaceInfo, SurfaceType};
+use asdf_overlay_event::{SurfaceInfo, SurfaceType, backbuffer::PresentMode};
 use dashmap::Entry;
 use once_cell::sync::Lazy;
 use scopeguard::defer;
@@ -18,7 +18,7 @@ use windows::{
 };
 
 use crate::{
-    hook::dx::dxgi::callback::register_swapchain_destruction_callback,
+    hook::dx::dxgi::{self, callback::register_swapchain_destruction_callback},
     interop::DxInterop,
     renderer::dx11::Dx11Renderer,
     surface::{SurfaceState, Surfaces},
@@ -85,19 +85,18 @@ pub fn draw_overlay(
     device: &ID3D11Device1,
     swapchain: &IDXGISwapChain1,
 ) -> anyhow::Result<()> {
-    let Some(size) = state.texture_size() else {
-        return Ok(());
-    };
-
-    let position = state.position();
     let screen = state.size();
     with_or_init_renderer_data(swapchain, move |data| {
         trace!("Using Direct3D11 renderer");
 
-        if state.texture.take_update() {
-            data.renderer
-                .update_texture(device, state.texture.get().as_ref())
-                .context("renderer texture update")?;
+        data.renderer.retain_textures(|id| state.contains_layer(id));
+        state
+            .take_texture_updates(|id, surface| data.renderer.update_texture(id, device, surface))
+            .context("renderer texture update")?;
+
+        let layers = state.layer_draws();
+        if layers.is_empty() {
+            return Ok(());
         }
 
         let cx = unsafe { device.GetImmediateContext1()? };
@@ -121,32 +120,39 @@ pub fn draw_overlay(
         unsafe { cx.OMSetRenderTargets(Some(&[Some(rtv)]), None) };
         defer!(unsafe { cx.OMSetRenderTargets(None, None) });
         data.renderer
-            .draw(device, &cx, position, size, screen)
+            .draw(device, &cx, screen, &layers)
             .context("renderer draw")
     })
 }
 
+/// Type and size of the swapchain surface.
+pub(super) fn identify(swapchain: &IDXGISwapChain) -> anyhow::Result<(SurfaceType, (u32, u32))> {
+    let desc = unsafe { swapchain.GetDesc() }?;
+    Ok((
+        SurfaceType::Direct3D11 {
+            window_id: dxgi::output_window_id(&desc),
+        },
+        (desc.BufferDesc.Width, desc.BufferDesc.Height),
+    ))
+}
+
 pub(super) fn setup_fn(
     device: &ID3D11Device,
     swapchain: &IDXGISwapChain,
+    present_mode: PresentMode,
+    api: SurfaceType,
+    size: (u32, u32),
 ) -> anyhow::Result<SurfaceState> {
     let adapter = unsafe { device.cast::<IDXGIDevice>().unwrap().GetAdapter().ok() };
     let desc = unsafe { swapchain.GetDesc() }?;
-    let window_id = if desc.OutputWindow.is_invalid() {
-        None
-    } else {
-        Some(desc.OutputWindow.0 as u32)
-    };
 
     let interop = DxInterop::new(adapter.as_ref())?;
     let gpu_id = interop.gpu_id;
     SurfaceState::new(
         interop,
-        (desc.BufferDesc.Width, desc.BufferDesc.Height),
-        SurfaceInfo {
-            api: SurfaceType::Direct3D11 { window_id },
-            gpu_id,
-        },
+        size,
+        dxgi::backbuffer_info(swapchain, &desc, present_mode),
+        SurfaceInfo { api, gpu_id },
     )
 }
 
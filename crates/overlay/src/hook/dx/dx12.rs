@@ -1,7 +1,7 @@
 mod rtv;
 mod util;
 
-use asdf_overlay_event::{SurfaceInfo, SurfaceType};
+use asdf_overlay_event::{SurfaceInfo, SurfaceType, backbuffer::PresentMode};
 use parking_lot::Once;
 pub use util::original_execute_command_lists;
 
@@ -25,7 +25,8 @@ use windows::{
 
 use crate::{
     hook::dx::{
-        dx12::rtv::RtvDescriptors, dxgi::callback::register_swapchain_destruction_callback,
+        dx12::rtv::RtvDescriptors,
+        dxgi::{self, callback::register_swapchain_destruction_callback},
     },
     interop::DxInterop,
     renderer::dx12::Dx12Renderer,
@@ -93,18 +94,17 @@ pub fn draw_overlay(
         return Ok(());
     };
 
-    let Some(size) = state.texture_size() else {
-        return Ok(());
-    };
-
-    let position = state.position();
     let screen = state.size();
     with_or_init_renderer_data(swapchain, move |data| {
         trace!("Using Direct3D12 renderer");
-        if state.texture.take_update() {
-            data.renderer
-                .update_texture(device, state.texture.get().as_ref())
-                .context("updating renderer texture")?;
+        data.renderer.retain_textures(|id| state.contains_layer(id));
+        state
+            .take_texture_updates(|id, surface| data.renderer.update_texture(id, device, surface))
+            .context("updating renderer texture")?;
+
+        let layers = state.layer_draws();
+        if layers.is_empty() {
+            return Ok(());
         }
 
         let backbuffer_index = unsafe { swapchain.GetCurrentBackBufferIndex() };
@@ -116,18 +116,31 @@ pub fn draw_overlay(
                     backbuffer_index,
                     desc,
                     &queue,
-                    position,
-                    size,
                     screen,
+                    &layers,
                 )
             })
             .context("renderer draw")
     })
 }
 
+/// Type and size of the swapchain surface.
+pub(super) fn identify(swapchain: &IDXGISwapChain) -> anyhow::Result<(SurfaceType, (u32, u32))> {
+    let desc = unsafe { swapchain.GetDesc() }?;
+    Ok((
+        SurfaceType::Direct3D12 {
+            window_id: dxgi::output_window_id(&desc),
+        },
+        (desc.BufferDesc.Width, desc.BufferDesc.Height),
+    ))
+}
+
 pub(super) fn setup_fn(
     device: &ID3D12Device,
     swapchain: &IDXGISwapChain,
+    present_mode: PresentMode,
+    api: SurfaceType,
+    size: (u32, u32),
 ) -> anyhow::Result<SurfaceState> {
     static ONCE: Once = Once::new();
     ONCE.call_once(|| {
@@ -138,19 +151,12 @@ pub(super) fn setup_fn(
 
     let desc = unsafe { swapchain.GetDesc() }?;
     let interop = DxInterop::new(get_dxgi_adapter(device).as_ref())?;
-    let window_id = if desc.OutputWindow.is_invalid() {
-        None
-    } else {
-        Some(desc.OutputWindow.0 as u32)
-    };
     let gpu_id = interop.gpu_id;
     SurfaceState::new(
         interop,
-        (desc.BufferDesc.Width, desc.BufferDesc.Height),
-        SurfaceInfo {
-            api: SurfaceType::Direct3D12 { window_id },
-            gpu_id,
-        },
+        size,
+        dxgi::backbuffer_info(swapchain, &desc, present_mode),
+        SurfaceInfo { api, gpu_id },
     )
 }
 
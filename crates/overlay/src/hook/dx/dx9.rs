@@ -2,9 +2,12 @@ use core::{ffi::c_void, ptr};
 use std::thread;
 
 use anyhow::Context;
-use asdf_overlay_event::{Event, SurfaceEvent, SurfaceInfo, SurfaceType};
+use asdf_overlay_event::{
+    Event, SurfaceEvent, SurfaceInfo, SurfaceType,
+    backbuffer::{BackbufferInfo, ColorSpace, PixelFormat, PresentMode},
+};
 use asdf_overlay_hook::DetourHook;
-use dashmap::Entry;
+use dashmap::{Entry, try_result::TryResult};
 use once_cell::sync::{Lazy, OnceCell};
 use tracing::{Level, debug, error, info, trace};
 use windows::{
@@ -14,8 +17,12 @@ use windows::{
             Direct3D9::{
                 D3D_SDK_VERSION, D3DADAPTER_DEFAULT, D3DBACKBUFFER_TYPE_MONO,
                 D3DCREATE_HARDWARE_VERTEXPROCESSING, D3DDEVICE_CREATION_PARAMETERS, D3DDEVTYPE_HAL,
-                D3DDISPLAYMODEEX, D3DPRESENT_PARAMETERS, D3DSURFACE_DESC, D3DSWAPEFFECT_DISCARD,
-                Direct3DCreate9Ex, IDirect3D9Ex, IDirect3DDevice9, IDirect3DSwapChain9,
+                D3DDISPLAYMODEEX, D3DFMT_A2R10G10B10, D3DFMT_A8R8G8B8, D3DFMT_A16B16G16R16F,
+                D3DFMT_R5G6B5, D3DFMT_X8R8G8B8, D3DFORMAT, D3DPRESENT_INTERVAL_DEFAULT,
+                D3DPRESENT_INTERVAL_FOUR, D3DPRESENT_INTERVAL_IMMEDIATE, D3DPRESENT_INTERVAL_ONE,
+                D3DPRESENT_INTERVAL_THREE, D3DPRESENT_INTERVAL_TWO, D3DPRESENT_PARAMETERS,
+                D3DSURFACE_DESC, D3DSWAPEFFECT_DISCARD, Direct3DCreate9Ex, IDirect3D9Ex,
+                IDirect3DDevice9, IDirect3DSwapChain9,
             },
             Dxgi::{CreateDXGIFactory1, IDXGIAdapter, IDXGIFactory1},
             Gdi::RGNDATA,
@@ -112,8 +119,12 @@ extern "system" fn hooked_release(this: *mut c_void) -> u32 {
 
     let count = unsafe { HOOK.release.wait().original_fn()(this) };
 
-    // renderer includes refs from IDirect3DVertexBuffer9, IDirect3DStateBlock9 and optionally texture.
-    if count == 2 || count == 3 {
+    // renderer includes refs from IDirect3DVertexBuffer9, IDirect3DStateBlock9 and layer textures.
+    let renderer_refs = match RENDERERS.try_get(&(this as usize)) {
+        TryResult::Present(renderer) => 2 + renderer.texture_count() as u32,
+        _ => return count,
+    };
+    if count == renderer_refs {
         reset_renderer(this as _);
         cleanup_renderer(this as _);
     }
@@ -156,7 +167,8 @@ fn draw_overlay(device: &IDirect3DDevice9, swapchain: &IDirect3DSwapChain9) -> a
 
     Surfaces::with(
         id,
-        || setup_fn(device, swapchain),
+        || identify(device, swapchain),
+        |api, size| setup_fn(device, swapchain, api, size),
         |state| {
             match state.info.api {
                 SurfaceType::Direct3D9 { .. } => {}
@@ -166,35 +178,48 @@ fn draw_overlay(device: &IDirect3DDevice9, swapchain: &IDirect3DSwapChain9) -> a
                 }
             }
 
-            let position = state.position();
             let screen = state.size();
             with_or_init_renderer(device, |renderer| {
                 trace!("Using Direct3D9 renderer");
 
-                let surface_lock = state.texture.get();
-                let Some(surface) = surface_lock.as_ref() else {
-                    return Ok(());
-                };
-
                 let interop = &state.interop;
-                renderer
-                    .update_texture(device, surface, &interop.device, &interop.cx.lock())
+                renderer.retain_textures(|id| state.contains_layer(id));
+                state
+                    .for_each_texture(|id, surface, dirty| {
+                        renderer.update_texture(
+                            id,
+                            device,
+                            surface,
+                            dirty,
+                            &interop.device,
+                            &interop.cx.lock(),
+                        )
+                    })
                     .context("updating renderer texture")?;
 
+                let layers = state.layer_draws();
+                if layers.is_empty() {
+                    return Ok(());
+                }
+
                 unsafe { device.BeginScene() }.context("BeginScene failed")?;
-                renderer.draw(device, position, screen)?;
+                renderer.draw(device, screen, &layers)?;
                 unsafe { device.EndScene() }.context("EndScene failed")?;
 
                 Ok(())
             })
         },
-    )
+    )?;
+
+    Ok(())
 }
 
 fn present(device: &IDirect3DDevice9, swapchain: &IDirect3DSwapChain9) {
     if let Err(err) = draw_overlay(device, swapchain) {
         error!("Failed to draw Direct3D9 overlay. err: {:?}", err);
     }
+
+    Surfaces::presented(device.as_raw() as u64);
 }
 
 fn post_reset(device: &IDirect3DDevice9) {
@@ -207,13 +232,19 @@ fn post_reset(device: &IDirect3DDevice9) {
         let mut desc = D3DSURFACE_DESC::default();
         unsafe { back_buffer.GetDesc(&mut desc) }.unwrap();
 
-        state.texture.invalidate();
+        let mut present_params = D3DPRESENT_PARAMETERS::default();
+        unsafe { default_swapchain.GetPresentParameters(&mut present_params) }.unwrap();
+        let backbuffer = backbuffer_info(&present_params);
+
+        state.invalidate_textures();
         state.resize(desc.Width, desc.Height);
+        state.set_backbuffer(backbuffer);
         OverlayEventSink::emit(Event::Surface {
             id: device.as_raw() as _,
             event: SurfaceEvent::Resized {
                 width: desc.Width,
                 height: desc.Height,
+                backbuffer,
             },
         });
     });
@@ -235,14 +266,15 @@ fn reset_renderer(device: usize) {
     };
 
     Surfaces::state(device as _, |state| {
-        state.texture.invalidate();
+        state.invalidate_textures();
     });
 }
 
-fn setup_fn(
+/// Type and size of the swapchain surface.
+fn identify(
     device: &IDirect3DDevice9,
     swapchain: &IDirect3DSwapChain9,
-) -> anyhow::Result<SurfaceState> {
+) -> anyhow::Result<(SurfaceType, (u32, u32))> {
     let back_buffer = unsafe { swapchain.GetBackBuffer(0, D3DBACKBUFFER_TYPE_MONO) }
         .context("failed to get back buffer")?;
 
@@ -262,18 +294,66 @@ fn setup_fn(
         creation_params.hFocusWindow.0 as u32
     };
 
+    Ok((
+        SurfaceType::Direct3D9 { window_id },
+        (desc.Width, desc.Height),
+    ))
+}
+
+fn setup_fn(
+    device: &IDirect3DDevice9,
+    swapchain: &IDirect3DSwapChain9,
+    api: SurfaceType,
+    size: (u32, u32),
+) -> anyhow::Result<SurfaceState> {
+    let mut present_params = D3DPRESENT_PARAMETERS::default();
+    unsafe { swapchain.GetPresentParameters(&mut present_params) }?;
+
     let interop = DxInterop::new(get_dxgi_adapter(device).as_ref())?;
     let gpu_id = interop.gpu_id;
     SurfaceState::new(
         interop,
-        (desc.Width, desc.Height),
-        SurfaceInfo {
-            api: SurfaceType::Direct3D9 { window_id },
-            gpu_id,
-        },
+        size,
+        backbuffer_info(&present_params),
+        SurfaceInfo { api, gpu_id },
     )
 }
 
+/// Backbuffer properties of the swapchain.
+fn backbuffer_info(params: &D3DPRESENT_PARAMETERS) -> BackbufferInfo {
+    let present_mode = match params.PresentationInterval as i32 {
+        D3DPRESENT_INTERVAL_IMMEDIATE => PresentMode::Immediate,
+        D3DPRESENT_INTERVAL_DEFAULT | D3DPRESENT_INTERVAL_ONE => {
+            PresentMode::Fifo { sync_interval: 1 }
+        }
+        D3DPRESENT_INTERVAL_TWO => PresentMode::Fifo { sync_interval: 2 },
+        D3DPRESENT_INTERVAL_THREE => PresentMode::Fifo { sync_interval: 3 },
+        D3DPRESENT_INTERVAL_FOUR => PresentMode::Fifo { sync_interval: 4 },
+        _ => PresentMode::Unknown,
+    };
+
+    BackbufferInfo {
+        format: map_d3d9_format(params.BackBufferFormat),
+        // Direct3D9 does not support HDR presentation
+        color_space: ColorSpace::Srgb,
+        // non-maskable multisampling reports 1
+        sample_count: params.MultiSampleType.0.max(1) as u32,
+        buffer_count: params.BackBufferCount.max(1),
+        present_mode,
+    }
+}
+
+fn map_d3d9_format(format: D3DFORMAT) -> PixelFormat {
+    match format {
+        D3DFMT_A8R8G8B8 => PixelFormat::Bgra8Unorm,
+        D3DFMT_X8R8G8B8 => PixelFormat::Bgrx8Unorm,
+        D3DFMT_A2R10G10B10 => PixelFormat::Bgr10A2Unorm,
+        D3DFMT_A16B16G16R16F => PixelFormat::Rgba16Float,
+        D3DFMT_R5G6B5 => PixelFormat::B5G6R5Unorm,
+        _ => PixelFormat::Unknown,
+    }
+}
+
 fn get_dxgi_adapter(device: &IDirect3DDevice9) -> Option<IDXGIAdapter> {
     let d3d9ex = unsafe { device.GetDirect3D() }
         .ok()?
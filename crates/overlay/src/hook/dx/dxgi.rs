@@ -3,9 +3,12 @@ pub mod callback;
 use core::{ffi::c_void, ptr};
 
 use anyhow::Context;
-use asdf_overlay_event::{Event, SurfaceEvent};
+use asdf_overlay_event::{
+    Event, SurfaceEvent,
+    backbuffer::{BackbufferInfo, ColorSpace, PixelFormat, PresentMode},
+};
 use asdf_overlay_hook::DetourHook;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use tracing::{Level, debug, error, trace};
 use windows::{
     Win32::{
@@ -18,11 +21,20 @@ use windows::{
             Direct3D12::ID3D12Device,
             Dxgi::{
                 Common::{
-                    DXGI_FORMAT, DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_MODE_DESC, DXGI_SAMPLE_DESC,
+                    DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709,
+                    DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709,
+                    DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020, DXGI_COLOR_SPACE_TYPE, DXGI_FORMAT,
+                    DXGI_FORMAT_B5G6R5_UNORM, DXGI_FORMAT_B8G8R8A8_UNORM,
+                    DXGI_FORMAT_B8G8R8A8_UNORM_SRGB, DXGI_FORMAT_B8G8R8X8_UNORM,
+                    DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+                    DXGI_FORMAT_R10G10B10A2_UNORM, DXGI_FORMAT_R16G16B16A16_FLOAT, DXGI_MODE_DESC,
+                    DXGI_SAMPLE_DESC,
                 },
-                CreateDXGIFactory1, DXGI_PRESENT, DXGI_PRESENT_PARAMETERS, DXGI_PRESENT_TEST,
-                DXGI_SWAP_CHAIN_DESC, DXGI_SWAP_EFFECT_DISCARD, DXGI_USAGE_RENDER_TARGET_OUTPUT,
-                IDXGIFactory1, IDXGISwapChain, IDXGISwapChain1, IDXGISwapChain3,
+                CreateDXGIFactory1, DXGI_PRESENT, DXGI_PRESENT_ALLOW_TEARING,
+                DXGI_PRESENT_PARAMETERS, DXGI_PRESENT_TEST, DXGI_SWAP_CHAIN_DESC,
+                DXGI_SWAP_EFFECT_DISCARD, DXGI_SWAP_EFFECT_FLIP_DISCARD,
+                DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL, DXGI_USAGE_RENDER_TARGET_OUTPUT, IDXGIFactory1,
+                IDXGISwapChain, IDXGISwapChain1, IDXGISwapChain3,
             },
         },
     },
@@ -31,12 +43,92 @@ use windows::{
 
 use crate::{
     event_sink::OverlayEventSink,
-    hook::dx::{dx11, dx12},
+    hook::dx::{dx11, dx12, dxgi::callback::register_swapchain_destruction_callback},
     surface::Surfaces,
+    types::IntDashMap,
 };
 
+/// Color spaces set by `IDXGISwapChain3::SetColorSpace1`.
+// swapchain -> color space
+static COLOR_SPACES: Lazy<IntDashMap<u64, DXGI_COLOR_SPACE_TYPE>> = Lazy::new(IntDashMap::default);
+
+/// Backbuffer properties of the swapchain.
+pub(super) fn backbuffer_info(
+    swapchain: &IDXGISwapChain,
+    desc: &DXGI_SWAP_CHAIN_DESC,
+    present_mode: PresentMode,
+) -> BackbufferInfo {
+    let format = map_dxgi_format(desc.BufferDesc.Format);
+    let color_space = match COLOR_SPACES.get(&(swapchain.as_raw() as u64)) {
+        Some(color_space) => map_dxgi_color_space(*color_space),
+        // FP16 swapchains are presented in scRGB unless color space is set
+        None if format == PixelFormat::Rgba16Float => ColorSpace::ScRgb,
+        None => ColorSpace::Srgb,
+    };
+
+    BackbufferInfo {
+        format,
+        color_space,
+        sample_count: desc.SampleDesc.Count,
+        buffer_count: desc.BufferCount,
+        present_mode,
+    }
+}
+
+fn present_mode(
+    desc: &DXGI_SWAP_CHAIN_DESC,
+    sync_interval: u32,
+    flags: DXGI_PRESENT,
+) -> PresentMode {
+    if sync_interval > 0 {
+        return PresentMode::Fifo { sync_interval };
+    }
+
+    let flip_model = matches!(
+        desc.SwapEffect,
+        DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL | DXGI_SWAP_EFFECT_FLIP_DISCARD
+    );
+    if flip_model && !flags.contains(DXGI_PRESENT_ALLOW_TEARING) {
+        PresentMode::Mailbox
+    } else {
+        PresentMode::Immediate
+    }
+}
+
+fn map_dxgi_format(format: DXGI_FORMAT) -> PixelFormat {
+    match format {
+        DXGI_FORMAT_R8G8B8A8_UNORM => PixelFormat::Rgba8Unorm,
+        DXGI_FORMAT_R8G8B8A8_UNORM_SRGB => PixelFormat::Rgba8UnormSrgb,
+        DXGI_FORMAT_B8G8R8A8_UNORM => PixelFormat::Bgra8Unorm,
+        DXGI_FORMAT_B8G8R8A8_UNORM_SRGB => PixelFormat::Bgra8UnormSrgb,
+        DXGI_FORMAT_B8G8R8X8_UNORM => PixelFormat::Bgrx8Unorm,
+        DXGI_FORMAT_R10G10B10A2_UNORM => PixelFormat::Rgb10A2Unorm,
+        DXGI_FORMAT_R16G16B16A16_FLOAT => PixelFormat::Rgba16Float,
+        DXGI_FORMAT_B5G6R5_UNORM => PixelFormat::B5G6R5Unorm,
+        _ => PixelFormat::Unknown,
+    }
+}
+
+/// Id of the window the swapchain presents to, if any.
+pub(super) fn output_window_id(desc: &DXGI_SWAP_CHAIN_DESC) -> Option<u32> {
+    if desc.OutputWindow.is_invalid() {
+        None
+    } else {
+        Some(desc.OutputWindow.0 as u32)
+    }
+}
+
+fn map_dxgi_color_space(color_space: DXGI_COLOR_SPACE_TYPE) -> ColorSpace {
+    match color_space {
+        DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709 => ColorSpace::Srgb,
+        DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709 => ColorSpace::ScRgb,
+        DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020 => ColorSpace::Hdr10,
+        _ => ColorSpace::Unknown,
+    }
+}
+
 #[tracing::instrument(level = Level::TRACE)]
-fn draw_overlay(swapchain: &IDXGISwapChain) -> anyhow::Result<()> {
+fn draw_overlay(swapchain: &IDXGISwapChain, present_mode: PresentMode) -> anyhow::Result<()> {
     // use swapchain pointer as unique identifier
     let id = swapchain.as_raw() as u64;
 
@@ -47,7 +139,8 @@ fn draw_overlay(swapchain: &IDXGISwapChain) -> anyhow::Result<()> {
 
         Surfaces::with(
             id,
-            || dx12::setup_fn(&device, &swapchain),
+            || dx12::identify(&swapchain),
+            |api, size| dx12::setup_fn(&device, &swapchain, present_mode, api, size),
             |backend| dx12::draw_overlay(backend, &device, &swapchain),
         )
         .context("Direct3D12 overlay error")?;
@@ -61,7 +154,8 @@ fn draw_overlay(swapchain: &IDXGISwapChain) -> anyhow::Result<()> {
 
         Surfaces::with(
             id,
-            || dx11::setup_fn(&device, &swapchain),
+            || dx11::identify(&swapchain),
+            |api, size| dx11::setup_fn(&device, &swapchain, present_mode, api, size),
             |backend| dx11::draw_overlay(backend, &device, &swapchain),
         )
         .context("Direct3D11 overlay error")?;
@@ -73,10 +167,16 @@ fn draw_overlay(swapchain: &IDXGISwapChain) -> anyhow::Result<()> {
 }
 
 #[inline]
-fn present(swapchain: &IDXGISwapChain) {
-    if let Err(e) = draw_overlay(swapchain) {
+fn present(swapchain: &IDXGISwapChain, sync_interval: u32, flags: DXGI_PRESENT) {
+    let desc = unsafe { swapchain.GetDesc() }.unwrap_or_default();
+    let present_mode = present_mode(&desc, sync_interval, flags);
+    if let Err(e) = draw_overlay(swapchain, present_mode) {
         error!("Failed to draw overlay: {:?}", e);
     }
+
+    let id = swapchain.as_raw() as u64;
+    Surfaces::update_backbuffer(id, |backbuffer| backbuffer.present_mode = present_mode);
+    Surfaces::presented(id);
 }
 
 #[tracing::instrument(level = Level::TRACE)]
@@ -89,7 +189,7 @@ extern "system" fn hooked_present(
 
     if !flags.contains(DXGI_PRESENT_TEST) && OverlayEventSink::connected() {
         let swapchain = unsafe { IDXGISwapChain::from_raw_borrowed(&this).unwrap() };
-        present(swapchain);
+        present(swapchain, sync_interval, flags);
     }
 
     unsafe { HOOK.present.wait().original_fn()(this, sync_interval, flags) }
@@ -106,7 +206,7 @@ extern "system" fn hooked_present1(
 
     if !flags.contains(DXGI_PRESENT_TEST) && OverlayEventSink::connected() {
         let swapchain = unsafe { IDXGISwapChain1::from_raw_borrowed(&this).unwrap() };
-        present(swapchain);
+        present(swapchain, sync_interval, flags);
     }
 
     unsafe { HOOK.present1.wait().original_fn()(this, sync_interval, flags, present_params) }
@@ -123,14 +223,46 @@ fn post_resize_swapchain(swapchain: &IDXGISwapChain) {
 
     let id = swapchain.as_raw() as u64;
     Surfaces::state(id, |state| {
+        let backbuffer = backbuffer_info(swapchain, &desc, state.backbuffer().present_mode);
         state.resize(width, height);
+        state.set_backbuffer(backbuffer);
         OverlayEventSink::emit(Event::Surface {
             id,
-            event: SurfaceEvent::Resized { width, height },
+            event: SurfaceEvent::Resized {
+                width,
+                height,
+                backbuffer,
+            },
         });
     });
 }
 
+#[tracing::instrument(level = Level::TRACE)]
+extern "system" fn hooked_set_color_space1(
+    this: *mut c_void,
+    color_space: DXGI_COLOR_SPACE_TYPE,
+) -> HRESULT {
+    trace!("SetColorSpace1 called");
+
+    let res = unsafe { HOOK.set_color_space1.wait().original_fn()(this, color_space) };
+    if res.is_err() {
+        return res;
+    }
+
+    let swapchain = unsafe { IDXGISwapChain::from_raw_borrowed(&this).unwrap() };
+    let id = this as u64;
+    if COLOR_SPACES.insert(id, color_space).is_none() {
+        register_swapchain_destruction_callback(swapchain, |swapchain| {
+            COLOR_SPACES.remove(&(swapchain as u64));
+        });
+    }
+
+    Surfaces::update_backbuffer(id, |backbuffer| {
+        backbuffer.color_space = map_dxgi_color_space(color_space);
+    });
+    res
+}
+
 #[tracing::instrument(level = Level::TRACE)]
 extern "system" fn hooked_resize_buffers(
     this: *mut c_void,
@@ -192,6 +324,9 @@ extern "system" fn hooked_resize_buffers1(
     res
 }
 
+pub type SetColorSpace1Fn =
+    unsafe extern "system" fn(*mut c_void, DXGI_COLOR_SPACE_TYPE) -> HRESULT;
+
 pub type PresentFn = unsafe extern "system" fn(*mut c_void, u32, DXGI_PRESENT) -> HRESULT;
 pub type Present1Fn = unsafe extern "system" fn(
     *mut c_void,
@@ -218,6 +353,7 @@ struct Hook {
     present1: OnceCell<DetourHook<Present1Fn>>,
     resize_buffers: OnceCell<DetourHook<ResizeBuffersFn>>,
     resize_buffers1: OnceCell<DetourHook<ResizeBuffers1Fn>>,
+    set_color_space1: OnceCell<DetourHook<SetColorSpace1Fn>>,
 }
 
 static HOOK: Hook = Hook {
@@ -225,6 +361,7 @@ static HOOK: Hook = Hook {
     present1: OnceCell::new(),
     resize_buffers: OnceCell::new(),
     resize_buffers1: OnceCell::new(),
+    set_color_space1: OnceCell::new(),
 };
 
 pub fn hook(dummy_hwnd: HWND) -> anyhow::Result<()> {
@@ -242,6 +379,13 @@ pub fn hook(dummy_hwnd: HWND) -> anyhow::Result<()> {
         })?;
     }
 
+    if let Some(set_color_space1) = dxgi_functions.set_color_space1 {
+        debug!("hooking IDXGISwapChain3::SetColorSpace1");
+        HOOK.set_color_space1.get_or_try_init(|| unsafe {
+            DetourHook::attach(set_color_space1, hooked_set_color_space1 as _)
+        })?;
+    }
+
     debug!("hooking IDXGISwapChain::Present");
     HOOK.present.get_or_try_init(|| unsafe {
         DetourHook::attach(dxgi_functions.present, hooked_present as _)
@@ -260,6 +404,7 @@ pub struct DxgiFunctions {
     pub present1: Option<Present1Fn>,
     pub resize_buffers: ResizeBuffersFn,
     pub resize_buffers1: Option<ResizeBuffers1Fn>,
+    pub set_color_space1: Option<SetColorSpace1Fn>,
 }
 
 /// Get pointer to dxgi functions
@@ -314,8 +459,9 @@ pub fn get_addr(dummy_hwnd: HWND) -> anyhow::Result<DxgiFunctions> {
             present1
         });
 
-        let resize_buffers1 = swapchain.cast::<IDXGISwapChain3>().ok().map(|swapchain3| {
-            let resize_buffers1 = Interface::vtable(&swapchain3).ResizeBuffers1;
+        let swapchain3 = swapchain.cast::<IDXGISwapChain3>().ok();
+        let resize_buffers1 = swapchain3.as_ref().map(|swapchain3| {
+            let resize_buffers1 = Interface::vtable(swapchain3).ResizeBuffers1;
             debug!(
                 "IDXGISwapChain3::ResizeBuffers1 found: {:p}",
                 resize_buffers1
@@ -323,11 +469,21 @@ pub fn get_addr(dummy_hwnd: HWND) -> anyhow::Result<DxgiFunctions> {
             resize_buffers1
         });
 
+        let set_color_space1 = swapchain3.as_ref().map(|swapchain3| {
+            let set_color_space1 = Interface::vtable(swapchain3).SetColorSpace1;
+            debug!(
+                "IDXGISwapChain3::SetColorSpace1 found: {:p}",
+                set_color_space1
+            );
+            set_color_space1
+        });
+
         Ok(DxgiFunctions {
             present,
             resize_buffers,
             present1,
             resize_buffers1,
+            set_color_space1,
         })
     }
 }
@@ -5,7 +5,10 @@ use core::{ffi::c_void, mem};
 use std::ffi::CString;
 
 use anyhow::Context;
-use asdf_overlay_event::{SurfaceInfo, SurfaceType};
+use asdf_overlay_event::{
+    SurfaceInfo, SurfaceType,
+    backbuffer::{BackbufferInfo, ColorSpace, PixelFormat, PresentMode},
+};
 use asdf_overlay_hook::DetourHook;
 use once_cell::sync::{Lazy, OnceCell};
 use scopeguard::defer;
@@ -17,7 +20,9 @@ use windows::{
             Dxgi::{CreateDXGIFactory1, IDXGIAdapter, IDXGIFactory1},
             Gdi::{GetDC, HDC, ReleaseDC, WindowFromDC},
             OpenGL::{
-                HGLRC, wglGetCurrentContext, wglGetCurrentDC, wglGetProcAddress, wglMakeCurrent,
+                DescribePixelFormat, GetPixelFormat, HGLRC, PFD_DOUBLEBUFFER,
+                PIXELFORMATDESCRIPTOR, wglGetCurrentContext, wglGetCurrentDC, wglGetProcAddress,
+                wglMakeCurrent,
             },
         },
         System::LibraryLoader::{GetModuleHandleA, GetProcAddress},
@@ -135,21 +140,20 @@ fn draw_overlay(hdc: HDC) {
                 }
             };
 
-            let Some(surface_size) = state.texture_size() else {
-                return Ok(());
-            };
-
-            let position = state.position();
             let screen = state.size();
-            if state.texture.take_update() {
-                renderer
-                    .update_texture(&state.interop.device, state.texture.get().as_ref())
-                    .context("renderer texture update")?;
+            renderer.retain_textures(|id| state.contains_layer(id));
+            state
+                .take_texture_updates(|id, surface| {
+                    renderer.update_texture(id, &state.interop.device, surface)
+                })
+                .context("renderer texture update")?;
+
+            let layers = state.layer_draws();
+            if layers.is_empty() {
+                return Ok(());
             }
 
-            renderer
-                .draw(position, surface_size, screen)
-                .context("renderer draw")
+            renderer.draw(screen, &layers).context("renderer draw")
         })
     }
 
@@ -179,30 +183,90 @@ fn draw_overlay(hdc: HDC) {
 
     if let Err(err) = Surfaces::with(
         key,
-        || setup_fn(unsafe { WindowFromDC(hdc) }),
+        || {
+            Ok((
+                SurfaceType::Opengl {
+                    window_id: hwnd.0 as _,
+                },
+                get_client_size(hwnd).unwrap_or_default(),
+            ))
+        },
+        |api, size| setup_fn(hdc, api, size),
         |backend| inner(backend, &mut data.renderer),
     ) {
         error!("Failed to draw opengl overlay. err: {:?}", err);
     }
+
+    let present_mode = swap_present_mode();
+    Surfaces::update_backbuffer(key, |backbuffer| backbuffer.present_mode = present_mode);
+
+    Surfaces::presented(key);
 }
 
-fn setup_fn(hwnd: HWND) -> anyhow::Result<SurfaceState> {
-    let size = get_client_size(hwnd).unwrap_or_default();
+fn setup_fn(hdc: HDC, api: SurfaceType, size: (u32, u32)) -> anyhow::Result<SurfaceState> {
     let interop = DxInterop::new(get_dxgi_adapter().as_ref())?;
     let gpu_id = interop.gpu_id;
 
     SurfaceState::new(
         interop,
         size,
-        SurfaceInfo {
-            api: SurfaceType::Opengl {
-                window_id: hwnd.0 as _,
-            },
-            gpu_id,
-        },
+        backbuffer_info(hdc),
+        SurfaceInfo { api, gpu_id },
     )
 }
 
+/// Backbuffer properties of the default framebuffer.
+fn backbuffer_info(hdc: HDC) -> BackbufferInfo {
+    let mut pfd = PIXELFORMATDESCRIPTOR::default();
+    unsafe {
+        DescribePixelFormat(
+            hdc,
+            GetPixelFormat(hdc),
+            mem::size_of::<PIXELFORMATDESCRIPTOR>() as _,
+            Some(&mut pfd),
+        );
+    }
+
+    let format = match (pfd.cRedBits, pfd.cGreenBits, pfd.cBlueBits, pfd.cAlphaBits) {
+        (8, 8, 8, 8) => PixelFormat::Bgra8Unorm,
+        (8, 8, 8, 0) => PixelFormat::Bgrx8Unorm,
+        (5, 6, 5, 0) => PixelFormat::B5G6R5Unorm,
+        _ => PixelFormat::Unknown,
+    };
+
+    // Default framebuffer is bound while swapping buffers
+    let mut samples = 0;
+    unsafe { gl::GetIntegerv(gl::SAMPLES, &mut samples) };
+
+    BackbufferInfo {
+        format,
+        color_space: ColorSpace::Srgb,
+        sample_count: samples.max(1) as _,
+        buffer_count: if pfd.dwFlags.contains(PFD_DOUBLEBUFFER) {
+            2
+        } else {
+            1
+        },
+        present_mode: swap_present_mode(),
+    }
+}
+
+/// Present mode from swap interval of the current context.
+fn swap_present_mode() -> PresentMode {
+    if !wgl::GetSwapIntervalEXT::is_loaded() {
+        return PresentMode::Unknown;
+    }
+
+    match unsafe { wgl::GetSwapIntervalEXT() } {
+        0 => PresentMode::Immediate,
+        // negative interval enables adaptive vsync
+        interval if interval < 0 => PresentMode::FifoRelaxed,
+        interval => PresentMode::Fifo {
+            sync_interval: interval as _,
+        },
+    }
+}
+
 fn setup_gl_data(hwnd: HWND) -> GlData {
     proc::install(hwnd);
 
@@ -49,7 +49,11 @@ fn proc(id: u64, msg: u32, lparam: LPARAM) {
         state.resize(width, height);
         OverlayEventSink::emit(Event::Surface {
             id,
-            event: SurfaceEvent::Resized { width, height },
+            event: SurfaceEvent::Resized {
+                width,
+                height,
+                backbuffer: state.backbuffer(),
+            },
         });
     });
 }
@@ -7,6 +7,13 @@ struct vs_out
 cbuffer OverlayBuffer : register(b0)
 {
 	float4 rect;
+	// opacity, color scale, unpremultiply
+	float4 params;
+	// transfer function, x <= t ? x * a : pow(x * b + c, g) * d + e
+	// t, a, b, c
+	float4 transfer0;
+	// g, d, e
+	float4 transfer1;
 }
 
 vs_out vs_main(uint index: SV_VertexID)
@@ -29,7 +36,19 @@ vs_out vs_main(uint index: SV_VertexID)
 Texture2D overlay : register(t0);
 SamplerState overlaySampler: register(s0);
 
+float3 transfer(float3 x)
+{
+	x = max(x, 0.0);
+	float3 curve = pow(x * transfer0.z + transfer0.w, transfer1.x) * transfer1.y + transfer1.z;
+	return x <= transfer0.x ? x * transfer0.y : curve;
+}
+
 float4 ps_main(vs_out input) : SV_TARGET
 {
-	return overlay.Sample(overlaySampler, input.texCoord);
+	float4 color = overlay.Sample(overlaySampler, input.texCoord);
+	float k = params.z != 0.0 && color.a > 0.0 ? color.a : 1.0;
+	color.rgb = transfer(color.rgb / k) * k * params.y;
+	color.a *= params.x;
+
+	return color;
 }
@@ -1,6 +1,7 @@
 use core::mem;
 
 use anyhow::Context;
+use asdf_overlay_event::blend::BlendMode;
 use tracing::Level;
 use windows::{
     Win32::{
@@ -16,7 +17,7 @@ use windows::{
 
 use crate::{
     renderer::dx::shaders,
-    surface::{SharedTextureHandle, texture::OverlaySurface},
+    surface::{SharedTextureHandle, blend::BLEND_MODES, layer::LayerDraw, texture::OverlaySurface},
     util::with_keyed_mutex,
 };
 
@@ -33,13 +34,49 @@ const SAMPLER_DESC: D3D11_SAMPLER_DESC = D3D11_SAMPLER_DESC {
     MaxLOD: D3D11_FLOAT32_MAX,
 };
 
+const fn blend_desc(mode: BlendMode) -> D3D11_RENDER_TARGET_BLEND_DESC {
+    let (src, dest, src_alpha, dest_alpha) = match mode {
+        BlendMode::Straight => (
+            D3D11_BLEND_SRC_ALPHA,
+            D3D11_BLEND_INV_SRC_ALPHA,
+            D3D11_BLEND_ONE,
+            D3D11_BLEND_INV_SRC_ALPHA,
+        ),
+        BlendMode::Premultiplied => (
+            D3D11_BLEND_ONE,
+            D3D11_BLEND_INV_SRC_ALPHA,
+            D3D11_BLEND_ONE,
+            D3D11_BLEND_INV_SRC_ALPHA,
+        ),
+        BlendMode::Additive => (
+            D3D11_BLEND_SRC_ALPHA,
+            D3D11_BLEND_ONE,
+            D3D11_BLEND_ZERO,
+            D3D11_BLEND_ONE,
+        ),
+    };
+
+    D3D11_RENDER_TARGET_BLEND_DESC {
+        BlendEnable: BOOL(1),
+        SrcBlend: src,
+        DestBlend: dest,
+        BlendOp: D3D11_BLEND_OP_ADD,
+        SrcBlendAlpha: src_alpha,
+        DestBlendAlpha: dest_alpha,
+        BlendOpAlpha: D3D11_BLEND_OP_ADD,
+        RenderTargetWriteMask: D3D11_COLOR_WRITE_ENABLE_ALL.0 as _,
+    }
+}
+
 pub struct Dx11Renderer {
     constant_buffer: ID3D11Buffer,
-    texture: Option<(ID3D11ShaderResourceView, Option<IDXGIKeyedMutex>)>,
+    /// Layer textures keyed by layer id.
+    textures: Vec<(u32, (ID3D11ShaderResourceView, Option<IDXGIKeyedMutex>))>,
 
     vertex_shader: ID3D11VertexShader,
     pixel_shader: ID3D11PixelShader,
-    blend_state: ID3D11BlendState,
+    /// Blend states indexed by blend mode.
+    blend_states: [ID3D11BlendState; 3],
     sampler_state: ID3D11SamplerState,
 }
 
@@ -63,7 +100,7 @@ impl Dx11Renderer {
             device
                 .CreateBuffer(
                     &D3D11_BUFFER_DESC {
-                        ByteWidth: mem::size_of::<[f32; 4]>() as _,
+                        ByteWidth: mem::size_of::<[f32; 16]>() as _,
                         Usage: D3D11_USAGE_DYNAMIC,
                         BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as _,
                         CPUAccessFlags: D3D11_CPU_ACCESS_WRITE.0 as _,
@@ -76,27 +113,22 @@ impl Dx11Renderer {
                 .context("cannot create constant buffer")?;
             let constant_buffer = constant_buffer.unwrap();
 
-            let mut blend_state = None;
-            device
-                .CreateBlendState(
-                    &D3D11_BLEND_DESC {
-                        AlphaToCoverageEnable: BOOL(0),
-                        IndependentBlendEnable: BOOL(0),
-                        RenderTarget: [D3D11_RENDER_TARGET_BLEND_DESC {
-                            BlendEnable: BOOL(1),
-                            SrcBlend: D3D11_BLEND_SRC_ALPHA,
-                            DestBlend: D3D11_BLEND_INV_SRC_ALPHA,
-                            BlendOp: D3D11_BLEND_OP_ADD,
-                            SrcBlendAlpha: D3D11_BLEND_ONE,
-                            DestBlendAlpha: D3D11_BLEND_INV_SRC_ALPHA,
-                            BlendOpAlpha: D3D11_BLEND_OP_ADD,
-                            RenderTargetWriteMask: D3D11_COLOR_WRITE_ENABLE_ALL.0 as _,
-                        }; 8],
-                    },
-                    Some(&mut blend_state),
-                )
-                .context("cannot create blend state")?;
-            let blend_state = blend_state.unwrap();
+            let mut blend_states = Vec::with_capacity(BLEND_MODES.len());
+            for mode in BLEND_MODES {
+                let mut blend_state = None;
+                device
+                    .CreateBlendState(
+                        &D3D11_BLEND_DESC {
+                            AlphaToCoverageEnable: BOOL(0),
+                            IndependentBlendEnable: BOOL(0),
+                            RenderTarget: [blend_desc(mode); 8],
+                        },
+                        Some(&mut blend_state),
+                    )
+                    .context("cannot create blend state")?;
+                blend_states.push(blend_state.unwrap());
+            }
+            let blend_states = blend_states.try_into().unwrap();
 
             let mut sampler_state = None;
             device
@@ -106,11 +138,11 @@ impl Dx11Renderer {
 
             Ok(Self {
                 constant_buffer,
-                texture: None,
+                textures: Vec::new(),
 
                 vertex_shader,
                 pixel_shader,
-                blend_state,
+                blend_states,
                 sampler_state,
             })
         }
@@ -118,57 +150,39 @@ impl Dx11Renderer {
 
     pub fn update_texture(
         &mut self,
+        id: u32,
         device: &ID3D11Device,
         surface: Option<&OverlaySurface>,
     ) -> anyhow::Result<()> {
-        self.texture = None;
+        self.textures.retain(|(layer, _)| *layer != id);
         let Some(surface) = surface else {
             return Ok(());
         };
 
-        self.texture = open_shared_texture(device, surface.shared_handle())?;
+        if let Some(texture) = open_shared_texture(device, surface.shared_handle())? {
+            self.textures.push((id, texture));
+        }
         Ok(())
     }
 
+    /// Drop textures of layers not matching the predicate.
+    pub fn retain_textures(&mut self, mut f: impl FnMut(u32) -> bool) {
+        self.textures.retain(|(id, _)| f(*id));
+    }
+
     #[tracing::instrument(level = Level::TRACE, skip(self))]
     pub fn draw(
         &mut self,
         device: &ID3D11Device,
         cx: &ID3D11DeviceContext,
-        position: (i32, i32),
-        size: (u32, u32),
         screen: (u32, u32),
+        layers: &[LayerDraw],
     ) -> anyhow::Result<()> {
         if screen.0 == 0 || screen.1 == 0 {
             return Ok(());
         }
 
-        let Some((view, mutex)) = &self.texture else {
-            return Ok(());
-        };
-
-        let rect = [
-            (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
-            -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
-            (size.0 as f32 / screen.0 as f32) * 2.0,
-            -(size.1 as f32 / screen.1 as f32) * 2.0,
-        ];
-
         unsafe {
-            {
-                let mut mapped_cbuffer = D3D11_MAPPED_SUBRESOURCE::default();
-                cx.Map(
-                    &self.constant_buffer,
-                    0,
-                    D3D11_MAP_WRITE_DISCARD,
-                    0,
-                    Some(&mut mapped_cbuffer),
-                )?;
-                mapped_cbuffer.pData.cast::<[f32; 4]>().write(rect);
-                cx.Unmap(&self.constant_buffer, 0);
-            }
-
-            cx.OMSetBlendState(&self.blend_state, None, 0x00ffffff);
             cx.RSSetViewports(Some(&[D3D11_VIEWPORT {
                 TopLeftX: 0.0,
                 TopLeftY: 0.0,
@@ -184,13 +198,44 @@ impl Dx11Renderer {
             cx.PSSetSamplers(0, Some(&[Some(self.sampler_state.clone())]));
 
             cx.VSSetConstantBuffers(0, Some(&[Some(self.constant_buffer.clone())]));
+            cx.PSSetConstantBuffers(0, Some(&[Some(self.constant_buffer.clone())]));
 
             cx.IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLESTRIP);
 
-            with_keyed_mutex(mutex.as_ref(), || {
-                cx.PSSetShaderResources(0, Some(&[Some(view.clone())]));
-                cx.Draw(4, 0);
-            })?;
+            for layer in layers {
+                let Some((_, (view, mutex))) = self.textures.iter().find(|(id, _)| *id == layer.id)
+                else {
+                    continue;
+                };
+
+                let (position, size) = (layer.placement.position(), layer.placement.size());
+                // Overlay rect followed by pixel shader constants
+                let mut constants = [0.0; 16];
+                constants[..4].copy_from_slice(&[
+                    (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
+                    -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
+                    (size.0 as f32 / screen.0 as f32) * 2.0,
+                    -(size.1 as f32 / screen.1 as f32) * 2.0,
+                ]);
+                constants[4..].copy_from_slice(&layer.shader_constants().as_array());
+
+                let mut mapped_cbuffer = D3D11_MAPPED_SUBRESOURCE::default();
+                cx.Map(
+                    &self.constant_buffer,
+                    0,
+                    D3D11_MAP_WRITE_DISCARD,
+                    0,
+                    Some(&mut mapped_cbuffer),
+                )?;
+                mapped_cbuffer.pData.cast::<[f32; 16]>().write(constants);
+                cx.Unmap(&self.constant_buffer, 0);
+
+                cx.OMSetBlendState(&self.blend_states[layer.blend as usize], None, 0x00ffffff);
+                with_keyed_mutex(mutex.as_ref(), || {
+                    cx.PSSetShaderResources(0, Some(&[Some(view.clone())]));
+                    cx.Draw(4, 0);
+                })?;
+            }
         }
 
         Ok(())
@@ -2,6 +2,7 @@ pub mod queue;
 mod sync;
 
 use anyhow::Context;
+use asdf_overlay_event::blend::BlendMode;
 use core::{
     mem::ManuallyDrop,
     slice::{self},
@@ -24,22 +25,45 @@ use windows::{
 use crate::{
     hook::util::original_execute_command_lists,
     renderer::{dx::shaders, dx12::queue::ID3D12CompatibilityQueue},
-    surface::{SharedTextureHandle, texture::OverlaySurface},
+    surface::{blend::BLEND_MODES, layer::LayerDraw, texture::OverlaySurface},
     util::wrap_com_manually_drop,
 };
 
-const RENDER_TARGET_BLEND_DESC: D3D12_RENDER_TARGET_BLEND_DESC = D3D12_RENDER_TARGET_BLEND_DESC {
-    BlendEnable: BOOL(1),
-    SrcBlend: D3D12_BLEND_SRC_ALPHA,
-    DestBlend: D3D12_BLEND_INV_SRC_ALPHA,
-    BlendOp: D3D12_BLEND_OP_ADD,
-    SrcBlendAlpha: D3D12_BLEND_ONE,
-    DestBlendAlpha: D3D12_BLEND_INV_SRC_ALPHA,
-    BlendOpAlpha: D3D12_BLEND_OP_ADD,
-    RenderTargetWriteMask: D3D12_COLOR_WRITE_ENABLE_ALL.0 as _,
-    LogicOpEnable: BOOL(0),
-    LogicOp: D3D12_LOGIC_OP_NOOP,
-};
+const fn render_target_blend_desc(mode: BlendMode) -> D3D12_RENDER_TARGET_BLEND_DESC {
+    let (src, dest, src_alpha, dest_alpha) = match mode {
+        BlendMode::Straight => (
+            D3D12_BLEND_SRC_ALPHA,
+            D3D12_BLEND_INV_SRC_ALPHA,
+            D3D12_BLEND_ONE,
+            D3D12_BLEND_INV_SRC_ALPHA,
+        ),
+        BlendMode::Premultiplied => (
+            D3D12_BLEND_ONE,
+            D3D12_BLEND_INV_SRC_ALPHA,
+            D3D12_BLEND_ONE,
+            D3D12_BLEND_INV_SRC_ALPHA,
+        ),
+        BlendMode::Additive => (
+            D3D12_BLEND_SRC_ALPHA,
+            D3D12_BLEND_ONE,
+            D3D12_BLEND_ZERO,
+            D3D12_BLEND_ONE,
+        ),
+    };
+
+    D3D12_RENDER_TARGET_BLEND_DESC {
+        BlendEnable: BOOL(1),
+        SrcBlend: src,
+        DestBlend: dest,
+        BlendOp: D3D12_BLEND_OP_ADD,
+        SrcBlendAlpha: src_alpha,
+        DestBlendAlpha: dest_alpha,
+        BlendOpAlpha: D3D12_BLEND_OP_ADD,
+        RenderTargetWriteMask: D3D12_COLOR_WRITE_ENABLE_ALL.0 as _,
+        LogicOpEnable: BOOL(0),
+        LogicOp: D3D12_LOGIC_OP_NOOP,
+    }
+}
 
 const SAMPLER: D3D12_STATIC_SAMPLER_DESC = D3D12_STATIC_SAMPLER_DESC {
     Filter: D3D12_FILTER_MIN_MAG_MIP_POINT,
@@ -68,10 +92,10 @@ fn root_sig() -> D3D12_ROOT_SIGNATURE_DESC {
                     Constants: D3D12_ROOT_CONSTANTS {
                         ShaderRegister: 0,
                         RegisterSpace: 0,
-                        Num32BitValues: 4,
+                        Num32BitValues: 16,
                     },
                 },
-                ShaderVisibility: D3D12_SHADER_VISIBILITY_VERTEX,
+                ShaderVisibility: D3D12_SHADER_VISIBILITY_ALL,
             },
             D3D12_ROOT_PARAMETER {
                 ParameterType: D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE,
@@ -119,9 +143,10 @@ const MAX_RENDER_TARGETS: usize = D3D12_SIMULTANEOUS_RENDER_TARGET_COUNT as _;
 pub struct Dx12Renderer {
     sig: ID3D12RootSignature,
 
-    pipeline: ID3D12PipelineState,
-    texture: Option<(ID3D12Resource, bool)>,
-    texture_descriptor: ID3D12DescriptorHeap,
+    /// Pipelines indexed by blend mode.
+    pipelines: [ID3D12PipelineState; 3],
+    /// Layer textures keyed by layer id.
+    textures: Vec<(u32, Dx12Texture)>,
 
     command_list: [(ID3D12GraphicsCommandList, ID3D12CommandAllocator); MAX_RENDER_TARGETS],
     fence: RendererFence,
@@ -151,11 +176,6 @@ impl Dx12Renderer {
                     pShaderBytecode: shaders::PIXEL_SHADER.as_ptr().cast(),
                     BytecodeLength: shaders::PIXEL_SHADER.len(),
                 },
-                BlendState: D3D12_BLEND_DESC {
-                    AlphaToCoverageEnable: BOOL(0),
-                    IndependentBlendEnable: BOOL(0),
-                    RenderTarget: [RENDER_TARGET_BLEND_DESC; 8],
-                },
                 RasterizerState: RASTERIZER_STATE,
                 PrimitiveTopologyType: D3D12_PRIMITIVE_TOPOLOGY_TYPE_TRIANGLE,
                 NumRenderTargets: 1,
@@ -168,8 +188,14 @@ impl Dx12Renderer {
             };
             pipeline_desc.RTVFormats[0] = swapchain_desc.BufferDesc.Format;
 
-            let pipeline =
-                device.CreateGraphicsPipelineState::<ID3D12PipelineState>(&pipeline_desc)?;
+            let pipelines = array_util::try_from_fn(|i| {
+                pipeline_desc.BlendState = D3D12_BLEND_DESC {
+                    AlphaToCoverageEnable: BOOL(0),
+                    IndependentBlendEnable: BOOL(0),
+                    RenderTarget: [render_target_blend_desc(BLEND_MODES[i]); 8],
+                };
+                device.CreateGraphicsPipelineState::<ID3D12PipelineState>(&pipeline_desc)
+            })?;
 
             let command_list = array_util::try_from_fn(|_| {
                 let command_alloc = device.CreateCommandAllocator::<ID3D12CommandAllocator>(
@@ -187,21 +213,11 @@ impl Dx12Renderer {
                 Ok::<_, anyhow::Error>((command_list, command_alloc))
             })?;
 
-            let texture_descriptor = device.CreateDescriptorHeap::<ID3D12DescriptorHeap>(
-                &D3D12_DESCRIPTOR_HEAP_DESC {
-                    NumDescriptors: 1,
-                    Type: D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
-                    Flags: D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE,
-                    ..Default::default()
-                },
-            )?;
-
             Ok(Self {
                 sig,
 
-                pipeline,
-                texture: None,
-                texture_descriptor,
+                pipelines,
+                textures: Vec::new(),
 
                 command_list,
                 fence: RendererFence::new(device)?,
@@ -211,26 +227,34 @@ impl Dx12Renderer {
 
     pub fn update_texture(
         &mut self,
+        id: u32,
         device: &ID3D12Device,
         surface: Option<&OverlaySurface>,
     ) -> anyhow::Result<()> {
         _ = self.fence.wait_pending();
-        self.texture = None;
+        self.textures.retain(|(layer, _)| *layer != id);
         let Some(surface) = surface else {
             return Ok(());
         };
 
-        let Some(resource) =
-            create_texture(device, &self.texture_descriptor, surface.shared_handle())?
-        else {
+        let Some(texture) = create_texture(device, surface)? else {
             return Ok(());
         };
 
-        let mutex = surface.mutex().is_some();
-        self.texture = Some((resource, mutex));
+        self.textures.push((id, texture));
         Ok(())
     }
 
+    /// Drop textures of layers not matching the predicate.
+    pub fn retain_textures(&mut self, mut f: impl FnMut(u32) -> bool) {
+        if self.textures.iter().all(|(id, _)| f(*id)) {
+            return;
+        }
+
+        _ = self.fence.wait_pending();
+        self.textures.retain(|(id, _)| f(*id));
+    }
+
     #[tracing::instrument(level = Level::TRACE, skip(self))]
     #[allow(clippy::too_many_arguments)]
     pub fn draw(
@@ -240,24 +264,23 @@ impl Dx12Renderer {
         backbuffer_index: u32,
         render_target: D3D12_CPU_DESCRIPTOR_HANDLE,
         queue: &ID3D12CommandQueue,
-        position: (i32, i32),
-        size: (u32, u32),
         screen: (u32, u32),
+        layers: &[LayerDraw],
     ) -> anyhow::Result<()> {
         if screen.0 == 0 || screen.1 == 0 {
             return Ok(());
         }
 
-        let Some((ref texture, mutex)) = self.texture else {
+        let textures = layers
+            .iter()
+            .filter_map(|layer| {
+                let (_, texture) = self.textures.iter().find(|(id, _)| *id == layer.id)?;
+                Some((layer, texture))
+            })
+            .collect::<Vec<_>>();
+        if textures.is_empty() {
             return Ok(());
-        };
-
-        let rect: [f32; 4] = [
-            (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
-            -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
-            (size.0 as f32 / screen.0 as f32) * 2.0,
-            -(size.1 as f32 / screen.1 as f32) * 2.0,
-        ];
+        }
 
         unsafe {
             let backbuffer = swapchain.GetBuffer::<ID3D12Resource>(backbuffer_index)?;
@@ -265,16 +288,9 @@ impl Dx12Renderer {
                 self.command_list[backbuffer_index as usize];
 
             command_alloc.Reset()?;
-            command_list.Reset(command_alloc, &self.pipeline)?;
+            command_list.Reset(command_alloc, None)?;
 
             command_list.SetGraphicsRootSignature(&self.sig);
-            command_list.SetGraphicsRoot32BitConstants(0, 4, rect.as_ptr().cast(), 0);
-
-            command_list.SetDescriptorHeaps(&[Some(self.texture_descriptor.clone())]);
-            command_list.SetGraphicsRootDescriptorTable(
-                1,
-                self.texture_descriptor.GetGPUDescriptorHandleForHeapStart(),
-            );
 
             command_list.RSSetViewports(&[D3D12_VIEWPORT {
                 TopLeftX: 0.0,
@@ -299,7 +315,27 @@ impl Dx12Renderer {
             )]);
 
             command_list.OMSetRenderTargets(1, Some(&render_target), true, None);
-            command_list.DrawInstanced(4, 1, 0, 0);
+            for &(layer, texture) in &textures {
+                let (position, size) = (layer.placement.position(), layer.placement.size());
+                // Overlay rect followed by pixel shader constants
+                let mut constants = [0.0_f32; 16];
+                constants[..4].copy_from_slice(&[
+                    (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
+                    -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
+                    (size.0 as f32 / screen.0 as f32) * 2.0,
+                    -(size.1 as f32 / screen.1 as f32) * 2.0,
+                ]);
+                constants[4..].copy_from_slice(&layer.shader_constants().as_array());
+                command_list.SetPipelineState(&self.pipelines[layer.blend as usize]);
+                command_list.SetGraphicsRoot32BitConstants(0, 16, constants.as_ptr().cast(), 0);
+
+                command_list.SetDescriptorHeaps(&[Some(texture.descriptor.clone())]);
+                command_list.SetGraphicsRootDescriptorTable(
+                    1,
+                    texture.descriptor.GetGPUDescriptorHandleForHeapStart(),
+                );
+                command_list.DrawInstanced(4, 1, 0, 0);
+            }
 
             command_list.ResourceBarrier(&[transition(
                 &backbuffer,
@@ -309,7 +345,12 @@ impl Dx12Renderer {
 
             command_list.Close()?;
 
-            with_keyed_mutex(queue, if mutex { Some(texture) } else { None }, || {
+            let mutexes = textures
+                .iter()
+                .filter(|(_, texture)| texture.mutex)
+                .map(|(_, texture)| -> &ID3D12Object { &texture.resource })
+                .collect::<Vec<_>>();
+            with_keyed_mutexes(queue, &mutexes, || {
                 original_execute_command_lists(queue, &[Some(command_list.clone().into())]);
             })?;
         }
@@ -328,37 +369,56 @@ impl Drop for Dx12Renderer {
 unsafe impl Send for Dx12Renderer {}
 unsafe impl Sync for Dx12Renderer {}
 
+/// Overlay layer texture with its shader resource view.
+struct Dx12Texture {
+    resource: ID3D12Resource,
+    descriptor: ID3D12DescriptorHeap,
+    mutex: bool,
+}
+
 #[inline]
-fn with_keyed_mutex<R>(
+fn with_keyed_mutexes<R>(
     queue: &ID3D12CommandQueue,
-    object: Option<&ID3D12Object>,
+    objects: &[&ID3D12Object],
     f: impl FnOnce() -> R,
 ) -> windows::core::Result<R> {
-    let Some(object) = object else {
+    if objects.is_empty() {
         return Ok(f());
-    };
+    }
 
     let Ok(queue) = queue.cast::<ID3D12CompatibilityQueue>() else {
         return Ok(f());
     };
 
+    acquire_keyed_mutexes(&queue, objects, f)
+}
+
+fn acquire_keyed_mutexes<R>(
+    queue: &ID3D12CompatibilityQueue,
+    objects: &[&ID3D12Object],
+    f: impl FnOnce() -> R,
+) -> windows::core::Result<R> {
+    let [object, rest @ ..] = objects else {
+        return Ok(f());
+    };
+
     unsafe {
         queue
-            .AcquireKeyedMutex(object, 0, u32::MAX, 0 as _, 0)
+            .AcquireKeyedMutex(*object, 0, u32::MAX, 0 as _, 0)
             .ok()?;
         defer!({
-            _ = queue.ReleaseKeyedMutex(object, 0, 0 as _, 0);
+            _ = queue.ReleaseKeyedMutex(*object, 0, 0 as _, 0);
         });
 
-        Ok(f())
+        acquire_keyed_mutexes(queue, rest, f)
     }
 }
 
 fn create_texture(
     device: &ID3D12Device,
-    texture_descriptor: &ID3D12DescriptorHeap,
-    handle: SharedTextureHandle,
-) -> anyhow::Result<Option<ID3D12Resource>> {
+    surface: &OverlaySurface,
+) -> anyhow::Result<Option<Dx12Texture>> {
+    let handle = surface.shared_handle();
     let mut texture = None;
     unsafe {
         device
@@ -372,6 +432,15 @@ fn create_texture(
         return Ok(None);
     }
 
+    let descriptor = unsafe {
+        device.CreateDescriptorHeap::<ID3D12DescriptorHeap>(&D3D12_DESCRIPTOR_HEAP_DESC {
+            NumDescriptors: 1,
+            Type: D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+            Flags: D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE,
+            ..Default::default()
+        })?
+    };
+
     unsafe {
         device.CreateShaderResourceView(
             &texture,
@@ -386,11 +455,15 @@ fn create_texture(
                     },
                 },
             }),
-            texture_descriptor.GetCPUDescriptorHandleForHeapStart(),
+            descriptor.GetCPUDescriptorHandleForHeapStart(),
         );
     }
 
-    Ok(Some(texture))
+    Ok(Some(Dx12Texture {
+        resource: texture,
+        descriptor,
+        mutex: surface.mutex().is_some(),
+    }))
 }
 
 unsafe fn transition(
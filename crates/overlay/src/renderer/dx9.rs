@@ -4,15 +4,17 @@ use core::{
 };
 
 use anyhow::Context;
+use asdf_overlay_event::blend::BlendMode;
 use scopeguard::defer;
 use tracing::Level;
 use windows::Win32::{
-    Foundation::HANDLE,
+    Foundation::{HANDLE, RECT},
     Graphics::{
         Direct3D9::*,
         Direct3D11::{
-            D3D11_CPU_ACCESS_READ, D3D11_MAP_READ, D3D11_MAPPED_SUBRESOURCE, D3D11_TEXTURE2D_DESC,
-            D3D11_USAGE_STAGING, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D,
+            D3D11_BOX, D3D11_CPU_ACCESS_READ, D3D11_MAP_READ, D3D11_MAPPED_SUBRESOURCE,
+            D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING, ID3D11Device, ID3D11DeviceContext,
+            ID3D11Texture2D,
         },
         Dxgi::Common::{
             DXGI_FORMAT, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R8G8B8A8_UNORM,
@@ -22,7 +24,15 @@ use windows::Win32::{
     },
 };
 
-use crate::{surface::texture::OverlaySurface, util::with_keyed_mutex};
+use crate::{
+    surface::{
+        blend::Transfer,
+        dirty::{DirtyRect, DirtyRegion},
+        layer::LayerDraw,
+        texture::OverlaySurface,
+    },
+    util::with_keyed_mutex,
+};
 
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -47,9 +57,8 @@ impl Vertex {
 }
 
 pub struct Dx9Renderer {
-    size: (u32, u32),
-
-    texture: Option<Dx9Texture>,
+    /// Layer textures keyed by layer id.
+    textures: Vec<LayerTexture>,
     vertex_buffer: IDirect3DVertexBuffer9,
     state_block: IDirect3DStateBlock9,
 }
@@ -71,41 +80,52 @@ impl Dx9Renderer {
             let state_block = device.CreateStateBlock(D3DSBT_ALL)?;
 
             Ok(Self {
-                size: (0, 0),
-
-                texture: None,
+                textures: Vec::new(),
                 vertex_buffer,
                 state_block,
             })
         }
     }
 
+    /// Number of textures holding reference to the device.
     #[inline]
-    pub fn reset_texture(&mut self) {
-        self.texture.take();
+    pub fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    /// Drop textures of layers not matching the predicate.
+    pub fn retain_textures(&mut self, mut f: impl FnMut(u32) -> bool) {
+        self.textures.retain(|texture| f(texture.id));
     }
 
+    /// Copy changed regions of the surface texture to the layer texture.
+    /// Whole texture is copied if the layer texture is created.
     pub fn update_texture(
         &mut self,
+        id: u32,
         device: &IDirect3DDevice9,
         surface: &OverlaySurface,
+        dirty: DirtyRegion,
         d3d11_device: &ID3D11Device,
         d3d11_cx: &ID3D11DeviceContext,
     ) -> anyhow::Result<()> {
-        if self.size != surface.size() {
-            self.reset_texture();
-        }
-
         let size = surface.size();
         let src_texture = surface.texture();
         let mutex = surface.mutex();
         let format = surface.format();
 
-        let texture = match self.texture {
-            Some(ref mut texture) => texture,
-            None => {
-                self.size = size;
-                self.texture.insert(
+        let (index, dirty) = match self.textures.iter().position(|texture| texture.id == id) {
+            Some(index)
+                if self.textures[index].size == size && self.textures[index].format == format =>
+            {
+                (index, dirty)
+            }
+            index => {
+                if let Some(index) = index {
+                    self.textures.remove(index);
+                }
+
+                let texture =
                     if let Ok((texture, handle)) = create_shared_texture(device, size, format) {
                         let mut shared_texture = None;
                         unsafe {
@@ -119,44 +139,87 @@ impl Dx9Renderer {
                         let (texture, staging) =
                             create_fallback_texture(device, d3d11_device, format, size)?;
                         Dx9Texture::Fallback(texture, staging)
-                    },
-                )
+                    };
+
+                self.textures.push(LayerTexture {
+                    id,
+                    size,
+                    format,
+                    texture,
+                });
+                (self.textures.len() - 1, DirtyRegion::Full)
             }
         };
 
-        match *texture {
+        let rects = match dirty {
+            DirtyRegion::Full => vec![DirtyRect {
+                x: 0,
+                y: 0,
+                width: size.0,
+                height: size.1,
+            }],
+            DirtyRegion::Rects(rects) => rects
+                .into_iter()
+                .filter_map(|rect| rect.clip(size))
+                .collect(),
+        };
+        if rects.is_empty() {
+            return Ok(());
+        }
+
+        match self.textures[index].texture {
             Dx9Texture::SharedTexture(_, ref d3d11_texture) => {
                 with_keyed_mutex(mutex, || unsafe {
-                    d3d11_cx.CopyResource(d3d11_texture, src_texture);
+                    copy_rects(d3d11_cx, d3d11_texture, src_texture, &rects);
                     d3d11_cx.Flush();
                 })?;
             }
 
             Dx9Texture::Fallback(ref texture, ref staging) => {
                 with_keyed_mutex(mutex, || {
-                    unsafe { d3d11_cx.CopyResource(staging, src_texture) };
+                    copy_rects(d3d11_cx, staging, src_texture, &rects);
                 })?;
 
-                let mut rect = D3DLOCKED_RECT::default();
+                let pixel_size = dxgi_pixel_size(format);
                 unsafe {
                     let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
                     d3d11_cx.Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))?;
-                    texture.LockRect(0, &mut rect, ptr::null(), D3DLOCK_DISCARD as _)?;
                     defer!({
                         d3d11_cx.Unmap(staging, 0);
-                        _ = texture.UnlockRect(0);
                     });
 
-                    for y in 0..size.1 as isize {
-                        let line_size = size.0 as usize * dxgi_pixel_size(format);
-                        let src_offset = y * mapped.RowPitch as isize;
-                        let dest_offset = y * rect.Pitch as isize;
-
-                        copy_nonoverlapping(
-                            mapped.pData.cast::<u8>().byte_offset(src_offset),
-                            rect.pBits.cast::<u8>().byte_offset(dest_offset),
-                            line_size,
-                        );
+                    for rect in &rects {
+                        let lock_rect = RECT {
+                            left: rect.x as _,
+                            top: rect.y as _,
+                            right: (rect.x + rect.width) as _,
+                            bottom: (rect.y + rect.height) as _,
+                        };
+                        // Previous contents can be discarded if the whole texture is overwritten
+                        let (lock_rect, flags) = if (rect.width, rect.height) == size {
+                            (ptr::null(), D3DLOCK_DISCARD as _)
+                        } else {
+                            (&raw const lock_rect, 0)
+                        };
+
+                        let mut locked = D3DLOCKED_RECT::default();
+                        texture.LockRect(0, &mut locked, lock_rect, flags)?;
+                        defer!({
+                            _ = texture.UnlockRect(0);
+                        });
+
+                        let line_size = rect.width as usize * pixel_size;
+                        for y in 0..rect.height as usize {
+                            let src_offset = (rect.y as usize + y) * mapped.RowPitch as usize
+                                + rect.x as usize * pixel_size;
+                            let dest_offset = y * locked.Pitch as usize;
+
+                            copy_nonoverlapping(
+                                mapped.pData.cast::<u8>().add(src_offset),
+                                locked.pBits.cast::<u8>().add(dest_offset),
+                                line_size,
+                            );
+                        }
                     }
                 }
             }
@@ -169,36 +232,13 @@ impl Dx9Renderer {
     pub fn draw(
         &mut self,
         device: &IDirect3DDevice9,
-        position: (i32, i32),
         screen: (u32, u32),
+        layers: &[LayerDraw],
     ) -> anyhow::Result<()> {
         if screen.0 == 0 || screen.1 == 0 {
             return Ok(());
         }
 
-        let texture = match self.texture {
-            Some(Dx9Texture::SharedTexture(ref texture, _)) => texture,
-            Some(Dx9Texture::Fallback(ref texture, _)) => texture,
-            None => return Ok(()),
-        };
-
-        let vertices = {
-            let pos = (
-                (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
-                -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
-            );
-            let size = (
-                (self.size.0 as f32 / screen.0 as f32) * 2.0,
-                -(self.size.1 as f32 / screen.1 as f32) * 2.0,
-            );
-            [
-                Vertex::new((pos.0, pos.1 + size.1), (0.0, 1.0)), // bottom left
-                Vertex::new(pos, (0.0, 0.0)),                     // top left
-                Vertex::new((pos.0 + size.0, pos.1 + size.1), (1.0, 1.0)), // bottom right
-                Vertex::new((pos.0 + size.0, pos.1), (1.0, 0.0)), // top right
-            ]
-        };
-
         unsafe {
             let state_block = &self.state_block;
             state_block.Capture()?;
@@ -206,16 +246,6 @@ impl Dx9Renderer {
                 _ = state_block.Apply();
             });
 
-            let mut buf = ptr::null_mut();
-            self.vertex_buffer.Lock(
-                0,
-                mem::size_of::<[Vertex; 4]>() as _,
-                &mut buf,
-                D3DLOCK_DISCARD as _,
-            )?;
-            buf.cast::<[Vertex; 4]>().write(vertices);
-            self.vertex_buffer.Unlock()?;
-
             device.SetViewport(&D3DVIEWPORT9 {
                 X: 0,
                 Y: 0,
@@ -232,15 +262,9 @@ impl Dx9Renderer {
             device.SetRenderState(D3DRS_ALPHATESTENABLE, 0)?;
             device.SetRenderState(D3DRS_CULLMODE, D3DCULL_NONE.0 as _)?;
             device.SetRenderState(D3DRS_ZENABLE, 0)?;
-            // disable srgb gamma correction enabled in some games
-            device.SetRenderState(D3DRS_SRGBWRITEENABLE, 0)?;
             device.SetRenderState(D3DRS_BLENDOP, D3DBLENDOP_ADD.0 as _)?;
             device.SetRenderState(D3DRS_ALPHABLENDENABLE, 1)?;
-            device.SetRenderState(D3DRS_SRCBLEND, D3DBLEND_SRCALPHA.0 as _)?;
-            device.SetRenderState(D3DRS_DESTBLEND, D3DBLEND_INVSRCALPHA.0 as _)?;
             device.SetRenderState(D3DRS_SEPARATEALPHABLENDENABLE, 1)?;
-            device.SetRenderState(D3DRS_SRCBLENDALPHA, D3DBLEND_ONE.0 as _)?;
-            device.SetRenderState(D3DRS_DESTBLENDALPHA, D3DBLEND_INVSRCALPHA.0 as _)?;
             device.SetRenderState(D3DRS_SCISSORTESTENABLE, 0)?;
             device.SetRenderState(D3DRS_FOGENABLE, 0)?;
             device.SetRenderState(D3DRS_RANGEFOGENABLE, 0)?;
@@ -248,12 +272,13 @@ impl Dx9Renderer {
             device.SetRenderState(D3DRS_STENCILENABLE, 0)?;
             device.SetRenderState(D3DRS_CLIPPING, 0)?;
             device.SetRenderState(D3DRS_LIGHTING, 0)?;
+            // Modulate texture with opacity and color scale
             device.SetTextureStageState(0, D3DTSS_COLOROP, D3DTOP_MODULATE.0 as _)?;
             device.SetTextureStageState(0, D3DTSS_COLORARG1, D3DTA_TEXTURE)?;
-            device.SetTextureStageState(0, D3DTSS_COLORARG2, D3DTA_DIFFUSE)?;
+            device.SetTextureStageState(0, D3DTSS_COLORARG2, D3DTA_TFACTOR)?;
             device.SetTextureStageState(0, D3DTSS_ALPHAOP, D3DTOP_MODULATE.0 as _)?;
             device.SetTextureStageState(0, D3DTSS_ALPHAARG1, D3DTA_TEXTURE)?;
-            device.SetTextureStageState(0, D3DTSS_ALPHAARG2, D3DTA_DIFFUSE)?;
+            device.SetTextureStageState(0, D3DTSS_ALPHAARG2, D3DTA_TFACTOR)?;
             device.SetTextureStageState(1, D3DTSS_COLOROP, D3DTOP_DISABLE.0 as _)?;
             device.SetTextureStageState(1, D3DTSS_ALPHAOP, D3DTOP_DISABLE.0 as _)?;
             device.SetSamplerState(0, D3DSAMP_MINFILTER, D3DTEXF_NONE.0 as _)?;
@@ -263,17 +288,115 @@ impl Dx9Renderer {
 
             device.SetStreamSource(0, &self.vertex_buffer, 0, mem::size_of::<Vertex>() as u32)?;
             device.SetFVF(Vertex::FVF)?;
-            device.SetTexture(0, texture)?;
-            device.DrawPrimitive(D3DPT_TRIANGLESTRIP, 0, 2)?;
+
+            for layer in layers {
+                let texture = match self.textures.iter().find(|texture| texture.id == layer.id) {
+                    Some(LayerTexture {
+                        texture: Dx9Texture::SharedTexture(texture, _),
+                        ..
+                    }) => texture,
+                    Some(LayerTexture {
+                        texture: Dx9Texture::Fallback(texture, _),
+                        ..
+                    }) => texture,
+                    None => continue,
+                };
+
+                let (position, size) = (layer.placement.position(), layer.placement.size());
+                let vertices = {
+                    let pos = (
+                        (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
+                        -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
+                    );
+                    let size = (
+                        (size.0 as f32 / screen.0 as f32) * 2.0,
+                        -(size.1 as f32 / screen.1 as f32) * 2.0,
+                    );
+                    [
+                        Vertex::new((pos.0, pos.1 + size.1), (0.0, 1.0)), // bottom left
+                        Vertex::new(pos, (0.0, 0.0)),                     // top left
+                        Vertex::new((pos.0 + size.0, pos.1 + size.1), (1.0, 1.0)), // bottom right
+                        Vertex::new((pos.0 + size.0, pos.1), (1.0, 0.0)), // top right
+                    ]
+                };
+
+                let mut buf = ptr::null_mut();
+                self.vertex_buffer.Lock(
+                    0,
+                    mem::size_of::<[Vertex; 4]>() as _,
+                    &mut buf,
+                    D3DLOCK_DISCARD as _,
+                )?;
+                buf.cast::<[Vertex; 4]>().write(vertices);
+                self.vertex_buffer.Unlock()?;
+
+                let constants = layer.shader_constants();
+                let color_scale = (constants.color_scale * 255.0).round() as u32;
+                device.SetRenderState(
+                    D3DRS_TEXTUREFACTOR,
+                    ((constants.opacity * 255.0).round() as u32) << 24
+                        | color_scale << 16
+                        | color_scale << 8
+                        | color_scale,
+                )?;
+                set_blend_mode(device, layer.blend)?;
+                // Fixed function pipeline converts with sRGB curves on texture read and write.
+                // Premultiplied colors are converted without unpremultiplying.
+                device.SetSamplerState(
+                    0,
+                    D3DSAMP_SRGBTEXTURE,
+                    (layer.transfer == Transfer::SrgbToLinear) as _,
+                )?;
+                // also disables srgb gamma correction enabled in some games
+                device.SetRenderState(
+                    D3DRS_SRGBWRITEENABLE,
+                    (layer.transfer == Transfer::LinearToSrgb) as _,
+                )?;
+                device.SetTexture(0, texture)?;
+                device.DrawPrimitive(D3DPT_TRIANGLESTRIP, 0, 2)?;
+            }
 
             Ok(())
         }
     }
 }
 
+fn set_blend_mode(device: &IDirect3DDevice9, mode: BlendMode) -> anyhow::Result<()> {
+    let (src, dest, src_alpha, dest_alpha) = match mode {
+        BlendMode::Straight => (
+            D3DBLEND_SRCALPHA,
+            D3DBLEND_INVSRCALPHA,
+            D3DBLEND_ONE,
+            D3DBLEND_INVSRCALPHA,
+        ),
+        BlendMode::Premultiplied => (
+            D3DBLEND_ONE,
+            D3DBLEND_INVSRCALPHA,
+            D3DBLEND_ONE,
+            D3DBLEND_INVSRCALPHA,
+        ),
+        BlendMode::Additive => (D3DBLEND_SRCALPHA, D3DBLEND_ONE, D3DBLEND_ZERO, D3DBLEND_ONE),
+    };
+
+    unsafe {
+        device.SetRenderState(D3DRS_SRCBLEND, src.0 as _)?;
+        device.SetRenderState(D3DRS_DESTBLEND, dest.0 as _)?;
+        device.SetRenderState(D3DRS_SRCBLENDALPHA, src_alpha.0 as _)?;
+        device.SetRenderState(D3DRS_DESTBLENDALPHA, dest_alpha.0 as _)?;
+    }
+    Ok(())
+}
+
 unsafe impl Send for Dx9Renderer {}
 unsafe impl Sync for Dx9Renderer {}
 
+struct LayerTexture {
+    id: u32,
+    size: (u32, u32),
+    format: DXGI_FORMAT,
+    texture: Dx9Texture,
+}
+
 enum Dx9Texture {
     SharedTexture(IDirect3DTexture9, ID3D11Texture2D),
     Fallback(IDirect3DTexture9, ID3D11Texture2D),
@@ -354,6 +477,36 @@ fn create_fallback_texture(
     Ok((texture.unwrap(), staging.unwrap()))
 }
 
+/// Copy the rectangles of `src` to the same position of `dst`.
+fn copy_rects(
+    cx: &ID3D11DeviceContext,
+    dst: &ID3D11Texture2D,
+    src: &ID3D11Texture2D,
+    rects: &[DirtyRect],
+) {
+    for rect in rects {
+        unsafe {
+            cx.CopySubresourceRegion(
+                dst,
+                0,
+                rect.x,
+                rect.y,
+                0,
+                src,
+                0,
+                Some(&D3D11_BOX {
+                    left: rect.x,
+                    top: rect.y,
+                    front: 0,
+                    right: rect.x + rect.width,
+                    bottom: rect.y + rect.height,
+                    back: 1,
+                }),
+            );
+        }
+    }
+}
+
 fn map_dxgi_to_dx9(format: DXGI_FORMAT) -> Option<D3DFORMAT> {
     match format {
         DXGI_FORMAT_R8G8B8A8_UNORM => Some(D3DFMT_A8B8G8R8),
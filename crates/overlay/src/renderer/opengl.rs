@@ -5,10 +5,11 @@ use crate::{
         self,
         types::{GLint, GLuint},
     },
-    surface::{SharedTextureHandle, texture::OverlaySurface},
+    surface::{SharedTextureHandle, layer::LayerDraw, texture::OverlaySurface},
     wgl,
 };
 use anyhow::{Context, bail};
+use asdf_overlay_event::blend::BlendMode;
 use scopeguard::{ScopeGuard, defer};
 use tracing::{Level, trace};
 use windows::{
@@ -27,10 +28,14 @@ static VERTEX_SHADER: &str = include_str!("opengl/shaders/texture.vert");
 static FRAGMENT_SHADER: &str = include_str!("opengl/shaders/texture.frag");
 
 pub struct OpenglRenderer {
-    interop: Option<GlInteropTexture>,
+    /// Layer textures keyed by layer id.
+    textures: Vec<(u32, GlInteropTexture)>,
     vao: GLuint,
     program: GLuint,
     rect_loc: GLint,
+    params_loc: GLint,
+    transfer0_loc: GLint,
+    transfer1_loc: GLint,
     tex_loc: GLint,
 }
 
@@ -64,16 +69,22 @@ impl OpenglRenderer {
             gl::LinkProgram(program);
 
             let rect_loc = gl::GetUniformLocation(program, b"rect\0" as *const _ as _);
+            let params_loc = gl::GetUniformLocation(program, b"params\0" as *const _ as _);
+            let transfer0_loc = gl::GetUniformLocation(program, b"transfer0\0" as *const _ as _);
+            let transfer1_loc = gl::GetUniformLocation(program, b"transfer1\0" as *const _ as _);
             let tex_loc = gl::GetUniformLocation(program, b"tex\0" as *const _ as _);
 
             gl::DeleteShader(vert_shader);
             gl::DeleteShader(frag_shader);
 
             Ok(Self {
-                interop: None,
+                textures: Vec::new(),
                 vao,
                 program,
                 rect_loc,
+                params_loc,
+                transfer0_loc,
+                transfer1_loc,
                 tex_loc,
             })
         }
@@ -81,10 +92,11 @@ impl OpenglRenderer {
 
     pub fn update_texture(
         &mut self,
+        id: u32,
         device: &ID3D11Device,
         surface: Option<&OverlaySurface>,
     ) -> anyhow::Result<()> {
-        self.interop.take();
+        self.textures.retain(|(layer, _)| *layer != id);
         let Some(surface) = surface else {
             return Ok(());
         };
@@ -94,42 +106,27 @@ impl OpenglRenderer {
             return Ok(());
         }
 
-        self.interop = Some(GlInteropTexture::new(device, surface)?);
+        self.textures
+            .push((id, GlInteropTexture::new(device, surface)?));
         Ok(())
     }
 
+    /// Drop textures of layers not matching the predicate.
+    pub fn retain_textures(&mut self, mut f: impl FnMut(u32) -> bool) {
+        self.textures.retain(|(id, _)| f(*id));
+    }
+
     #[tracing::instrument(level = Level::TRACE, skip(self))]
-    pub fn draw(
-        &mut self,
-        position: (i32, i32),
-        size: (u32, u32),
-        screen: (u32, u32),
-    ) -> anyhow::Result<()> {
+    pub fn draw(&mut self, screen: (u32, u32), layers: &[LayerDraw]) -> anyhow::Result<()> {
         if screen.0 == 0 || screen.1 == 0 {
             return Ok(());
         }
 
-        let Some(ref texture) = self.interop else {
-            return Ok(());
-        };
-
-        let rect: [f32; 4] = [
-            (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
-            -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
-            (size.0 as f32 / screen.0 as f32) * 2.0,
-            -(size.1 as f32 / screen.1 as f32) * 2.0,
-        ];
         unsafe {
             gl::Viewport(0, 0, screen.0 as _, screen.1 as _);
 
             gl::Enable(gl::BLEND);
             gl::BlendEquation(gl::FUNC_ADD);
-            gl::BlendFuncSeparate(
-                gl::SRC_ALPHA,
-                gl::ONE_MINUS_SRC_ALPHA,
-                gl::ONE,
-                gl::ONE_MINUS_SRC_ALPHA,
-            );
             gl::Disable(gl::CULL_FACE);
             gl::Disable(gl::DEPTH_TEST);
             gl::Disable(gl::STENCIL_TEST);
@@ -137,23 +134,65 @@ impl OpenglRenderer {
 
             gl::BindVertexArray(self.vao);
             gl::UseProgram(self.program);
-            gl::Uniform4f(self.rect_loc, rect[0], rect[1], rect[2], rect[3]);
             gl::Uniform1i(self.tex_loc, 0);
-
             gl::ActiveTexture(gl::TEXTURE0);
-            texture.bind(gl::TEXTURE_2D, || {
-                gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
-            });
+
+            for layer in layers {
+                let Some((_, texture)) = self.textures.iter().find(|(id, _)| *id == layer.id)
+                else {
+                    continue;
+                };
+
+                let (position, size) = (layer.placement.position(), layer.placement.size());
+                let rect: [f32; 4] = [
+                    (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
+                    -(position.1 as f32 / screen.1 as f32) * 2.0 + 1.0,
+                    (size.0 as f32 / screen.0 as f32) * 2.0,
+                    -(size.1 as f32 / screen.1 as f32) * 2.0,
+                ];
+                gl::Uniform4f(self.rect_loc, rect[0], rect[1], rect[2], rect[3]);
+                let constants = layer.shader_constants().as_array();
+                gl::Uniform4fv(self.params_loc, 1, constants[0..4].as_ptr());
+                gl::Uniform4fv(self.transfer0_loc, 1, constants[4..8].as_ptr());
+                gl::Uniform4fv(self.transfer1_loc, 1, constants[8..12].as_ptr());
+                set_blend_mode(layer.blend);
+
+                texture.bind(gl::TEXTURE_2D, || {
+                    gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+                });
+            }
         }
 
         Ok(())
     }
 }
 
+fn set_blend_mode(mode: BlendMode) {
+    let (src, dest, src_alpha, dest_alpha) = match mode {
+        BlendMode::Straight => (
+            gl::SRC_ALPHA,
+            gl::ONE_MINUS_SRC_ALPHA,
+            gl::ONE,
+            gl::ONE_MINUS_SRC_ALPHA,
+        ),
+        BlendMode::Premultiplied => (
+            gl::ONE,
+            gl::ONE_MINUS_SRC_ALPHA,
+            gl::ONE,
+            gl::ONE_MINUS_SRC_ALPHA,
+        ),
+        BlendMode::Additive => (gl::SRC_ALPHA, gl::ONE, gl::ZERO, gl::ONE),
+    };
+
+    unsafe {
+        gl::BlendFuncSeparate(src, dest, src_alpha, dest_alpha);
+    }
+}
+
 impl Drop for OpenglRenderer {
     #[tracing::instrument(level = Level::TRACE, skip(self))]
     fn drop(&mut self) {
-        self.interop.take();
+        self.textures.clear();
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteProgram(self.program);
@@ -4,8 +4,26 @@ out vec4 FragColor;
 in vec2 TexCoord;
 
 uniform sampler2D tex;
+// opacity, color scale, unpremultiply
+uniform vec4 params;
+// transfer function, x <= t ? x * a : pow(x * b + c, g) * d + e
+// t, a, b, c
+uniform vec4 transfer0;
+// g, d, e
+uniform vec4 transfer1;
+
+vec3 transfer(vec3 x)
+{
+    x = max(x, 0.0);
+    vec3 curve = pow(x * transfer0.z + transfer0.w, vec3(transfer1.x)) * transfer1.y + transfer1.z;
+    return mix(curve, x * transfer0.y, lessThanEqual(x, vec3(transfer0.x)));
+}
 
 void main()
 {
-    FragColor = texture(tex, TexCoord);
+    vec4 color = texture(tex, TexCoord);
+    float k = params.z != 0.0 && color.a > 0.0 ? color.a : 1.0;
+    color.rgb = transfer(color.rgb / k) * k * params.y;
+    color.a *= params.x;
+    FragColor = color;
 }
@@ -2,26 +2,62 @@
 //! You can access states for specific window using [`Backends::with_backend`].
 //! This allows you to interact with the overlay state of a window, including its layout and rendering data.
 
+mod bitmap_shm;
+pub mod blend;
+pub mod dirty;
+pub mod frame_stats;
+pub mod hit_test;
+pub mod layer;
 pub mod texture;
 
-use core::sync::atomic::{AtomicI32, AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::Context;
-use asdf_overlay_event::{Event, SurfaceEvent, SurfaceInfo};
+use asdf_overlay_event::{
+    Event, SurfaceEvent, SurfaceFilter, SurfaceInfo, SurfaceType,
+    backbuffer::BackbufferInfo,
+    blend::Blend,
+    layout::{Layout, Placement, Transform},
+    stats::FrameStats,
+};
 use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use tracing::{debug, warn};
 
 use crate::{
-    event_sink::OverlayEventSink, interop::DxInterop, surface::texture::OverlayTextureSlot,
+    event_sink::OverlayEventSink,
+    interop::DxInterop,
+    surface::{
+        blend::Transfer,
+        dirty::{DirtyRect, DirtyRegion},
+        frame_stats::FrameTimes,
+        hit_test::{HitMask, HitRegion},
+        layer::{BASE_LAYER, Layer, LayerDraw, Layers, MAX_LAYERS},
+        texture::{AlphaReadback, OverlaySurface, OverlayTextureSlot},
+    },
     types::IntDashMap,
 };
 
+/// Minimum interval of rebuilding the alpha hit mask from bitmap frames.
+const BITMAP_HIT_MASK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Creation order of the next surface.
+static NEXT_ORDER: AtomicU64 = AtomicU64::new(0);
+
 static SURFACES: Lazy<Surfaces> = Lazy::new(|| Surfaces {
     map: IntDashMap::default(),
+    ignored: IntDashMap::default(),
+    filter: RwLock::new(SurfaceFilter::default()),
 });
 
 /// Global store for surface states.
 pub struct Surfaces {
     map: IntDashMap<u64, SurfaceState>,
+    /// Surfaces ignored by the surface filter, with their size when filtered.
+    /// Surfaces are filtered again when resized.
+    ignored: IntDashMap<u64, (u32, u32)>,
+    filter: RwLock<SurfaceFilter>,
 }
 
 impl Surfaces {
@@ -43,43 +79,156 @@ impl Surfaces {
         for state in SURFACES.map.iter() {
             state.reset();
         }
+
+        Self::set_filter(SurfaceFilter::default());
+    }
+
+    /// Set rules for ignoring surfaces.
+    ///
+    /// The rules only apply to surfaces identified afterward.
+    pub fn set_filter(filter: SurfaceFilter) {
+        *SURFACES.filter.write() = filter;
+        SURFACES.ignored.clear();
+    }
+
+    /// Find the topmost surface on the window containing the point.
+    /// The point is relative to the window client area.
+    ///
+    /// Returns surface id, layer id and the point relative to the layer texture.
+    /// If multiple surfaces contain the point, the most recently created surface is considered topmost.
+    pub fn surface_at(window_id: u32, x: i32, y: i32) -> Option<(u64, u32, (i32, i32))> {
+        SURFACES
+            .map
+            .iter()
+            .filter(|state| state.info.api.window_id() == Some(window_id))
+            .filter_map(|state| Some((state.order, *state.key(), state.surface_point(x, y)?)))
+            .max_by_key(|(order, ..)| *order)
+            .map(|(_, id, (layer, point))| (id, layer, point))
+    }
+
+    /// Check if any surface on the window has interactive region at the point.
+    /// The point is relative to the window client area.
+    pub fn hit_test(window_id: u32, x: i32, y: i32) -> bool {
+        SURFACES
+            .map
+            .iter()
+            .any(|state| state.info.api.window_id() == Some(window_id) && state.hit_test(x, y))
     }
 
+    /// Run closure with the surface, initializing it with `setup_fn` if it is new.
+    ///
+    /// New surfaces are checked against the surface filter using type and size returned by `identify`,
+    /// before `setup_fn` creates resources for them.
+    /// Returns [`None`] without running the closure if the surface is ignored by the surface filter.
     #[doc(hidden)]
     pub fn with<R>(
         id: u64,
-        setup_fn: impl FnOnce() -> anyhow::Result<SurfaceState>,
+        identify: impl FnOnce() -> anyhow::Result<(SurfaceType, (u32, u32))>,
+        setup_fn: impl FnOnce(SurfaceType, (u32, u32)) -> anyhow::Result<SurfaceState>,
         f: impl FnOnce(&SurfaceState) -> anyhow::Result<R>,
-    ) -> anyhow::Result<R> {
+    ) -> anyhow::Result<Option<R>> {
         if let Some(backend) = SURFACES.map.get(&id) {
-            return f(&backend);
+            return f(&backend).map(Some);
+        }
+
+        let (api, size) = identify().context("failed to identify surface")?;
+        if SURFACES
+            .ignored
+            .get(&id)
+            .is_some_and(|ignored| *ignored == size)
+        {
+            return Ok(None);
+        }
+
+        if SURFACES.filter.read().ignores(api, size.0, size.1) {
+            debug!("ignoring surface: {id}");
+            SURFACES.ignored.insert(id, size);
+            return Ok(None);
         }
+        SURFACES.ignored.remove(&id);
+
+        let state = setup_fn(api, size).context("failed to setup surface state")?;
+        let (width, height) = state.size();
 
         let backend = SURFACES
             .map
             .entry(id)
-            .or_try_insert_with(|| {
-                let state = setup_fn().context("failed to setup surface state")?;
-
-                let (width, height) = state.size();
+            .or_insert_with(|| {
                 OverlayEventSink::emit(Event::Surface {
                     id,
                     event: SurfaceEvent::Added {
                         width,
                         height,
                         info: state.info,
+                        backbuffer: state.backbuffer(),
                     },
                 });
 
-                Ok::<_, anyhow::Error>(state)
-            })?
+                state
+            })
             .downgrade();
 
-        f(backend.value())
+        f(backend.value()).map(Some)
+    }
+
+    /// Update backbuffer properties of the surface.
+    /// Emits [`SurfaceEvent::Resized`] if the properties changed.
+    #[doc(hidden)]
+    pub fn update_backbuffer(id: u64, f: impl FnOnce(&mut BackbufferInfo)) {
+        let Some(state) = SURFACES.map.get(&id) else {
+            return;
+        };
+
+        let mut backbuffer = state.backbuffer();
+        f(&mut backbuffer);
+        if !state.set_backbuffer(backbuffer) {
+            return;
+        }
+
+        let (width, height) = state.size();
+        OverlayEventSink::emit(Event::Surface {
+            id,
+            event: SurfaceEvent::Resized {
+                width,
+                height,
+                backbuffer,
+            },
+        });
+    }
+
+    /// Count a presented frame of the surface and record its frame time.
+    /// Emits [`SurfaceEvent::Presented`] and [`SurfaceEvent::Stats`] if requested.
+    #[doc(hidden)]
+    pub fn presented(id: u64) {
+        let Some((frame, stats)) = SURFACES.map.get(&id).map(|state| state.present()) else {
+            return;
+        };
+
+        if let Some(frame) = frame {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_micros() as u64)
+                .unwrap_or_default();
+            OverlayEventSink::emit(Event::Surface {
+                id,
+                event: SurfaceEvent::Presented { frame, timestamp },
+            });
+        }
+
+        if let Some(stats) = stats {
+            OverlayEventSink::emit(Event::Surface {
+                id,
+                event: SurfaceEvent::Stats { stats },
+            });
+        }
     }
 
     #[doc(hidden)]
     pub fn cleanup_state(id: u64) {
+        if SURFACES.ignored.remove(&id).is_some() {
+            return;
+        }
+
         SURFACES.map.remove(&id);
 
         OverlayEventSink::emit(Event::Surface {
@@ -92,32 +241,65 @@ impl Surfaces {
 /// Data associated to a specific window for overlay rendering.
 #[non_exhaustive]
 pub struct SurfaceState {
-    position: (AtomicI32, AtomicI32),
+    /// Creation order of the surface. Surfaces created later are considered on top.
+    order: u64,
+
     size: (AtomicU32, AtomicU32),
+    backbuffer: RwLock<BackbufferInfo>,
+    visible: AtomicBool,
+
+    frame: AtomicU64,
+    listen_present: AtomicBool,
+    frame_requested: AtomicBool,
+    frame_times: Mutex<FrameTimes>,
 
     pub interop: DxInterop,
     pub info: SurfaceInfo,
 
-    #[doc(hidden)]
-    pub texture: OverlayTextureSlot,
+    layers: RwLock<Layers<OverlayTextureSlot>>,
+
+    /// Alpha hit masks are built under this lock, so hit-testing does not wait for the readback.
+    hit_mask: Mutex<HitMaskBuilder>,
 }
 
-impl SurfaceState {
-    pub fn new(interop: DxInterop, size: (u32, u32), info: SurfaceInfo) -> anyhow::Result<Self> {
-        let surface = OverlayTextureSlot::new();
+#[derive(Default)]
+struct HitMaskBuilder {
+    readback: AlphaReadback,
+
+    /// Whether the mask should be rebuilt from the base layer texture.
+    requested: bool,
 
+    /// When the mask was last built from a bitmap frame.
+    bitmap_built: Option<Instant>,
+}
+
+impl SurfaceState {
+    pub fn new(
+        interop: DxInterop,
+        size: (u32, u32),
+        backbuffer: BackbufferInfo,
+        info: SurfaceInfo,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
-            position: (AtomicI32::new(0), AtomicI32::new(0)),
+            order: NEXT_ORDER.fetch_add(1, Ordering::Relaxed),
             size: (AtomicU32::new(size.0), AtomicU32::new(size.1)),
+            backbuffer: RwLock::new(backbuffer),
+            visible: AtomicBool::new(true),
+            frame: AtomicU64::new(0),
+            listen_present: AtomicBool::new(false),
+            frame_requested: AtomicBool::new(false),
+            frame_times: Mutex::new(FrameTimes::default()),
             interop,
             info,
-            texture: surface,
+            layers: RwLock::new(Layers::new(OverlayTextureSlot::new())),
+            hit_mask: Mutex::new(HitMaskBuilder::default()),
         })
     }
 
+    /// Size of the base layer texture.
     #[doc(hidden)]
     pub fn texture_size(&self) -> Option<(u32, u32)> {
-        self.texture.get().as_ref().map(|surface| surface.size())
+        layer_texture_size(self.layers.read().base())
     }
 
     pub fn size(&self) -> (u32, u32) {
@@ -133,33 +315,522 @@ impl SurfaceState {
         self.size.1.store(height, Ordering::Relaxed);
     }
 
-    pub fn position(&self) -> (i32, i32) {
-        (
-            self.position.0.load(Ordering::Relaxed),
-            self.position.1.load(Ordering::Relaxed),
-        )
+    /// Backbuffer properties of the surface.
+    pub fn backbuffer(&self) -> BackbufferInfo {
+        *self.backbuffer.read()
+    }
+
+    /// Set backbuffer properties of the surface.
+    /// Returns `true` if the properties changed.
+    pub fn set_backbuffer(&self, backbuffer: BackbufferInfo) -> bool {
+        let mut current = self.backbuffer.write();
+        if *current == backbuffer {
+            return false;
+        }
+
+        *current = backbuffer;
+        true
+    }
+
+    #[inline]
+    pub fn visible(&self) -> bool {
+        self.visible.load(Ordering::Relaxed)
+    }
+
+    /// Show or hide every layer of the surface.
+    ///
+    /// Layer textures are kept while hidden, so showing again does not need new textures.
+    /// Hidden surfaces do not capture cursor inputs.
+    pub fn set_visible(&self, visible: bool) {
+        self.visible.store(visible, Ordering::Relaxed);
+    }
+
+    /// Number of frames presented since the surface was added.
+    pub fn frame(&self) -> u64 {
+        self.frame.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable [`SurfaceEvent::Presented`] on every present.
+    pub fn set_listen_present(&self, listen: bool) {
+        self.listen_present.store(listen, Ordering::Relaxed);
+    }
+
+    /// Request [`SurfaceEvent::Presented`] once after the next present.
+    pub fn request_frame(&self) {
+        self.frame_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Frame time statistics measured from recent presents.
+    ///
+    /// Returns [`None`] if the surface has not been presented twice yet.
+    pub fn frame_stats(&self) -> Option<FrameStats> {
+        self.frame_times.lock().stats()
+    }
+
+    /// Set interval of periodic [`SurfaceEvent::Stats`].
+    /// If [`None`] is given, statistics are not emitted.
+    pub fn set_stats_interval(&self, interval: Option<Duration>) {
+        self.frame_times.lock().set_report_interval(interval);
+    }
+
+    /// Count a presented frame and record its frame time.
+    /// Returns the frame number if it should be notified, and statistics if a report is due.
+    fn present(&self) -> (Option<u64>, Option<FrameStats>) {
+        let stats = self.frame_times.lock().record(Instant::now());
+
+        let frame = self.frame.fetch_add(1, Ordering::Relaxed) + 1;
+        let requested = self.frame_requested.swap(false, Ordering::Relaxed);
+        let notify = requested || self.listen_present.load(Ordering::Relaxed);
+        (notify.then_some(frame), stats)
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layers.read().base().layout
     }
 
+    /// Set layout of the base layer.
+    /// The layout is resolved against the current surface size on each draw.
+    pub fn set_layout(&self, layout: Layout) {
+        self.update_base(|layer| layer.layout = layout);
+    }
+
+    /// Place the overlay texture at the position relative to the window client area.
     pub fn reposition(&self, x: i32, y: i32) {
-        self.position.0.store(x, Ordering::Relaxed);
-        self.position.1.store(y, Ordering::Relaxed);
+        self.set_layout(Layout::absolute(x, y));
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.layers.read().base().transform
+    }
+
+    /// Set opacity, scale and fit mode of the base layer.
+    pub fn set_transform(&self, transform: Transform) {
+        self.update_base(|layer| layer.transform = transform);
+    }
+
+    pub fn blend(&self) -> Blend {
+        self.layers.read().base().blend
     }
 
+    /// Set blend mode and color encoding of the base layer.
+    pub fn set_blend(&self, blend: Blend) {
+        self.update_base(|layer| layer.blend = blend);
+    }
+
+    /// Resolve placement of the base layer texture using current surface size.
+    ///
+    /// Returns [`None`] if there is no texture.
+    pub fn placement(&self) -> Option<Placement> {
+        let layers = self.layers.read();
+        let base = layers.base();
+        Some(base.resolve(self.size(), layer_texture_size(base)?))
+    }
+
+    /// Commit texture of the base layer.
     pub fn commit_overlay_texture(
         &self,
         handle: Option<SharedTextureHandle>,
     ) -> anyhow::Result<()> {
-        self.texture.update(&self.interop.device, handle)
+        self.commit_layer_texture(BASE_LAYER, handle)
+    }
+
+    fn update_base(&self, f: impl FnOnce(&mut Layer<OverlayTextureSlot>)) {
+        self.layers
+            .write()
+            .update(BASE_LAYER, OverlayTextureSlot::new, f)
+            .expect("base layer must exist");
+    }
+
+    /// Run closure with the layer, creating it if it doesn't exist.
+    /// Layers are reordered by z index after the closure returns.
+    ///
+    /// Fails if the layer doesn't exist and the surface already has [`MAX_LAYERS`] layers.
+    pub fn update_layer<R>(
+        &self,
+        id: u32,
+        f: impl FnOnce(&mut Layer<OverlayTextureSlot>) -> R,
+    ) -> anyhow::Result<R> {
+        self.layers
+            .write()
+            .update(id, OverlayTextureSlot::new, f)
+            .with_context(|| format!("surface cannot have more than {MAX_LAYERS} layers"))
+    }
+
+    /// Commit texture of the layer, creating the layer if it doesn't exist.
+    pub fn commit_layer_texture(
+        &self,
+        id: u32,
+        handle: Option<SharedTextureHandle>,
+    ) -> anyhow::Result<()> {
+        // Create the layer first, opening texture doesn't need the write lock.
+        self.update_layer(id, |_| {})?;
+        self.layers
+            .read()
+            .get(id)
+            .context("layer removed while committing texture")?
+            .texture()
+            .update(&self.interop.device, handle)?;
+
+        if id == BASE_LAYER {
+            self.update_hit_mask();
+        }
+        Ok(())
+    }
+
+    /// Upload texture of the base layer from the bitmap ring buffer in the named shared memory.
+    /// If [`None`] is given, the texture is removed.
+    pub fn commit_overlay_bitmap_shm(&self, name: Option<&str>) -> anyhow::Result<()> {
+        self.commit_layer_bitmap_shm(BASE_LAYER, name)
+    }
+
+    /// Upload texture of the layer from the bitmap ring buffer in the named shared memory,
+    /// creating the layer if it doesn't exist.
+    ///
+    /// New frames are uploaded when renderers take texture updates.
+    pub fn commit_layer_bitmap_shm(&self, id: u32, name: Option<&str>) -> anyhow::Result<()> {
+        self.update_layer(id, |_| {})?;
+        self.layers
+            .read()
+            .get(id)
+            .context("layer removed while committing texture")?
+            .texture()
+            .update_bitmap_shm(name)?;
+
+        if id == BASE_LAYER {
+            self.update_hit_mask();
+        }
+        Ok(())
+    }
+
+    /// Mark regions of the base layer texture changed.
+    ///
+    /// Renderers copying textures copy whole textures on every frame by default.
+    /// Once regions are invalidated, they only copy invalidated regions until another texture is committed,
+    /// so clients must invalidate every change afterward.
+    pub fn invalidate_region(&self, rects: &[DirtyRect]) {
+        self.layers.read().base().texture().invalidate_rects(rects);
+        self.update_hit_mask();
+    }
+
+    /// Mark regions of the layer texture changed, like [`SurfaceState::invalidate_region`].
+    pub fn invalidate_layer_region(&self, id: u32, rects: &[DirtyRect]) -> anyhow::Result<()> {
+        self.layers
+            .read()
+            .get(id)
+            .context("layer not found")?
+            .texture()
+            .invalidate_rects(rects);
+
+        if id == BASE_LAYER {
+            self.update_hit_mask();
+        }
+        Ok(())
+    }
+
+    /// Remove the layer and its texture.
+    ///
+    /// The base layer cannot be removed.
+    pub fn remove_layer(&self, id: u32) -> anyhow::Result<()> {
+        if id == BASE_LAYER {
+            anyhow::bail!("base layer cannot be removed");
+        }
+
+        self.layers.write().remove(id).context("layer not found")?;
+        Ok(())
+    }
+
+    /// Check if the layer exists.
+    pub fn contains_layer(&self, id: u32) -> bool {
+        self.layers.read().contains(id)
+    }
+
+    /// Resolve visible layers having texture from bottom to top, using current surface size.
+    ///
+    /// Returns nothing if the surface is hidden.
+    pub fn layer_draws(&self) -> Vec<LayerDraw> {
+        if !self.visible() {
+            return Vec::new();
+        }
+
+        let surface = self.size();
+        let backbuffer = self.backbuffer();
+        self.layers
+            .read()
+            .visible()
+            .filter_map(|(id, layer)| {
+                Some(LayerDraw {
+                    id,
+                    placement: layer.resolve(surface, layer_texture_size(layer)?),
+                    opacity: layer.transform.opacity.clamp(0.0, 1.0),
+                    blend: layer.blend.mode,
+                    transfer: Transfer::new(layer.blend.encoding, &backbuffer),
+                })
+            })
+            .collect()
+    }
+
+    /// Run closure with each layer texture updated since the last call.
+    ///
+    /// The closure receives [`None`] if texture of the layer is removed.
+    #[doc(hidden)]
+    pub fn take_texture_updates(
+        &self,
+        mut f: impl FnMut(u32, Option<&OverlaySurface>) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.poll_bitmaps();
+        self.poll_hit_mask();
+        for (id, layer) in self.layers.read().iter() {
+            if layer.texture().take_update() {
+                f(id, layer.texture().get().as_ref())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run closure with each layer texture and its region changed since the last call.
+    ///
+    /// If the closure fails, the whole texture is passed again on the next call.
+    #[doc(hidden)]
+    pub fn for_each_texture(
+        &self,
+        mut f: impl FnMut(u32, &OverlaySurface, DirtyRegion) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.poll_bitmaps();
+        self.poll_hit_mask();
+        for (id, layer) in self.layers.read().iter() {
+            if let Some(ref surface) = *layer.texture().get() {
+                f(id, surface, layer.texture().take_dirty())
+                    // Taken region may be copied partially
+                    .inspect_err(|_| layer.texture().mark_dirty())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upload new frames of bitmap ring buffers to layer textures.
+    fn poll_bitmaps(&self) {
+        for (id, layer) in self.layers.read().iter() {
+            if let Err(err) = layer.texture().poll_bitmap(&self.interop) {
+                warn!("failed to upload bitmap of layer {id}. err: {err:?}");
+            }
+        }
+    }
+
+    /// Mark every layer texture updated, so renderers recreate their textures.
+    #[doc(hidden)]
+    pub fn invalidate_textures(&self) {
+        for (_, layer) in self.layers.read().iter() {
+            layer.texture().invalidate();
+        }
+    }
+
+    /// Set interactive region of the base layer.
+    pub fn set_hit_region(&self, region: HitRegion) -> anyhow::Result<()> {
+        self.set_layer_hit_region(BASE_LAYER, region)
+    }
+
+    /// Set interactive region of the layer.
+    ///
+    /// Alpha mask regions are only supported on the base layer.
+    /// Alpha mask is built from the current base layer texture,
+    /// and rebuilt each time the texture is committed or invalidated.
+    /// Textures are read back on later frames, so the mask can lag behind by a few frames.
+    /// Frames of a bitmap ring buffer rebuild the mask from the bitmap while hit-testing,
+    /// at most every 100 milliseconds.
+    pub fn set_layer_hit_region(&self, id: u32, region: HitRegion) -> anyhow::Result<()> {
+        if id != BASE_LAYER && matches!(region, HitRegion::AlphaMask { .. }) {
+            anyhow::bail!("alpha mask hit region is only supported on the base layer");
+        }
+
+        {
+            let mut layers = self.layers.write();
+            if !layers.contains(id) {
+                anyhow::bail!("layer not found");
+            }
+            layers.update(id, OverlayTextureSlot::new, |layer| {
+                layer.hit_region = region
+            });
+        }
+
+        self.update_hit_mask();
+        Ok(())
+    }
+
+    /// Find the topmost visible layer drawn over the point relative to the window client area.
+    ///
+    /// Returns layer id and the point relative to the layer texture.
+    /// Returns [`None`] if no layer texture contains the point or the surface is hidden.
+    pub fn surface_point(&self, x: i32, y: i32) -> Option<(u32, (i32, i32))> {
+        if !self.visible() {
+            return None;
+        }
+
+        self.layers
+            .read()
+            .at(self.size(), x, y, layer_texture_size)
+            .next()
+            .map(|(id, _, point)| (id, point))
+    }
+
+    /// Convert the point relative to the window client area to the point relative to the layer texture,
+    /// even if the point is outside of the texture.
+    ///
+    /// Used for cursor inputs captured by the layer.
+    /// Returns [`None`] if the layer doesn't exist or has no texture.
+    pub fn captured_point(&self, layer: u32, x: i32, y: i32) -> Option<(i32, i32)> {
+        let layers = self.layers.read();
+        let layer = layers.get(layer)?;
+        layer.texture_point(self.size(), layer_texture_size(layer)?, x, y)
+    }
+
+    /// Check if the point has interactive region.
+    /// The point is relative to the window client area.
+    ///
+    /// Visible layers are tested from top to bottom, so layers without interactive region at the point
+    /// let lower layers capture it.
+    pub fn hit_test(&self, x: i32, y: i32) -> bool {
+        if !self.visible() {
+            return false;
+        }
+
+        self.update_bitmap_hit_mask();
+        self.layers
+            .read()
+            .at(self.size(), x, y, layer_texture_size)
+            .any(|(_, layer, (x, y))| layer.hit_region.contains(x, y))
+    }
+
+    /// Threshold of the alpha mask hit region, if set.
+    fn alpha_threshold(&self) -> Option<u8> {
+        match self.layers.read().base().hit_region {
+            HitRegion::AlphaMask { threshold, .. } => Some(threshold),
+            _ => None,
+        }
+    }
+
+    /// Replace the alpha hit mask, if the region is still an alpha mask of `threshold`.
+    fn set_hit_mask(&self, threshold: u8, new_mask: Option<HitMask>) {
+        self.update_base(|layer| {
+            if let HitRegion::AlphaMask {
+                threshold: current,
+                ref mut mask,
+            } = layer.hit_region
+                && current == threshold
+            {
+                *mask = new_mask;
+            }
+        });
+    }
+
+    /// Request rebuilding the alpha hit mask from the base layer texture.
+    fn update_hit_mask(&self) {
+        let Some(threshold) = self.alpha_threshold() else {
+            return;
+        };
+
+        if self.layers.read().base().texture().get().is_none() {
+            self.set_hit_mask(threshold, None);
+            return;
+        }
+        self.hit_mask.lock().requested = true;
+    }
+
+    /// Build the alpha hit mask if the texture read back is done, and start a requested readback.
+    fn poll_hit_mask(&self) {
+        let Some(threshold) = self.alpha_threshold() else {
+            return;
+        };
+
+        let mut builder = self.hit_mask.lock();
+        match builder.readback.try_alpha_mask(&self.interop, threshold) {
+            Ok(Some(mask)) => self.set_hit_mask(threshold, Some(mask)),
+            Ok(None) => {}
+            Err(err) => {
+                warn!("failed to build alpha hit mask. err: {err:?}");
+                self.set_hit_mask(threshold, None);
+            }
+        }
+
+        if !builder.requested || builder.readback.copying() {
+            return;
+        }
+        builder.requested = false;
+
+        let layers = self.layers.read();
+        let res = match *layers.base().texture().get() {
+            Some(ref surface) => builder.readback.copy(&self.interop, surface),
+            None => Ok(()),
+        };
+        drop(layers);
+
+        if let Err(err) = res {
+            warn!("failed to read back surface texture. err: {err:?}");
+            self.set_hit_mask(threshold, None);
+        }
+    }
+
+    /// Rebuild the alpha hit mask from the last bitmap frame of the base layer.
+    ///
+    /// The mask is built while hit-testing instead of uploading frames, so presents are not delayed.
+    /// Skipped if the mask was built less than [`BITMAP_HIT_MASK_INTERVAL`] ago,
+    /// or is being built from the texture.
+    fn update_bitmap_hit_mask(&self) {
+        let Some(threshold) = self.alpha_threshold() else {
+            return;
+        };
+
+        let Some(mut builder) = self.hit_mask.try_lock() else {
+            return;
+        };
+        let now = Instant::now();
+        if builder
+            .bitmap_built
+            .is_some_and(|built| now - built < BITMAP_HIT_MASK_INTERVAL)
+        {
+            return;
+        }
+
+        let mut mask = None;
+        self.layers
+            .read()
+            .base()
+            .texture()
+            .consume_bitmap(|info, data| {
+                mask = Some(HitMask::from_alpha(
+                    data,
+                    (info.width, info.height),
+                    info.stride as usize,
+                    info.format.pixel_size(),
+                    info.format.alpha_offset(),
+                    threshold,
+                ));
+                true
+            });
+
+        if let Some(mask) = mask {
+            builder.bitmap_built = Some(now);
+            self.set_hit_mask(threshold, Some(mask));
+        }
     }
 
     /// Reset the surface state to its initial state.
-    /// This will reset the position to (0, 0) and remove the overlay texture
+    /// This will remove additional layers, reset the base layer and remove interactive region.
     pub fn reset(&self) {
-        self.reposition(0, 0);
+        self.set_visible(true);
+        self.set_listen_present(false);
+        self.frame_requested.store(false, Ordering::Relaxed);
+        self.set_stats_interval(None);
+        self.layers.write().reset();
         _ = self.commit_overlay_texture(None);
     }
 }
 
+fn layer_texture_size(layer: &Layer<OverlayTextureSlot>) -> Option<(u32, u32)> {
+    layer.texture().get().as_ref().map(|surface| surface.size())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SharedTextureHandle {
     /// KMT handle.
@@ -0,0 +1,119 @@
+//! Bitmap ring buffers in named shared memory.
+//!
+//! Clients without GPU access write frames with `asdf-overlay-bitmap-ring` protocol,
+//! and the overlay uploads the latest frame to a texture it owns.
+
+use anyhow::Context;
+use asdf_overlay_bitmap_ring::{BitmapInfo, BitmapReader, Frame};
+use scopeguard::defer;
+use windows::{
+    Win32::{
+        Foundation::CloseHandle,
+        System::Memory::{
+            FILE_MAP_READ, MEMORY_BASIC_INFORMATION, MEMORY_MAPPED_VIEW_ADDRESS, MapViewOfFile,
+            OpenFileMappingW, UnmapViewOfFile, VirtualQuery,
+        },
+    },
+    core::HSTRING,
+};
+
+/// Read only view of a bitmap ring buffer.
+pub(crate) struct BitmapShm {
+    view: MEMORY_MAPPED_VIEW_ADDRESS,
+    reader: BitmapReader,
+
+    /// Sequence number of the last read or skipped frame.
+    seq: u64,
+
+    /// Last read frame and its data.
+    frame: Option<BitmapInfo>,
+    buf: Vec<u8>,
+
+    /// Whether the last frame is not consumed yet.
+    pending: bool,
+}
+
+// SAFETY: The view is only unmapped on drop.
+unsafe impl Send for BitmapShm {}
+
+impl BitmapShm {
+    /// Open the bitmap ring buffer in the named shared memory.
+    pub fn open(name: &str) -> anyhow::Result<Self> {
+        unsafe {
+            let mapping = OpenFileMappingW(FILE_MAP_READ.0, false, &HSTRING::from(name))
+                .with_context(|| format!("failed to open shared memory: {name}"))?;
+            // View keeps the mapping alive
+            defer!({
+                _ = CloseHandle(mapping);
+            });
+
+            let view = MapViewOfFile(mapping, FILE_MAP_READ, 0, 0, 0);
+            if view.Value.is_null() {
+                return Err(windows::core::Error::from_thread())
+                    .context("failed to map shared memory");
+            }
+
+            let mut info = MEMORY_BASIC_INFORMATION::default();
+            VirtualQuery(
+                Some(view.Value),
+                &mut info,
+                size_of::<MEMORY_BASIC_INFORMATION>(),
+            );
+
+            match BitmapReader::open(view.Value.cast(), info.RegionSize) {
+                Ok(reader) => Ok(Self {
+                    view,
+                    reader,
+                    seq: 0,
+                    frame: None,
+                    buf: Vec::new(),
+                    pending: false,
+                }),
+                Err(err) => {
+                    _ = UnmapViewOfFile(view);
+                    Err(err).context("invalid bitmap ring buffer")
+                }
+            }
+        }
+    }
+
+    /// Read the latest frame, if there is a new one.
+    pub fn poll(&mut self) -> anyhow::Result<Option<(&BitmapInfo, &[u8])>> {
+        let Frame { seq, info } = match self.reader.read_latest(self.seq, &mut self.buf) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                // Skip the frame, so the error is reported once
+                self.seq = self.reader.published();
+                self.frame = None;
+                self.pending = false;
+                return Err(err)
+                    .with_context(|| format!("failed to read bitmap frame {}", self.seq));
+            }
+        };
+
+        self.seq = seq;
+        self.pending = true;
+        Ok(Some((self.frame.insert(info), &self.buf)))
+    }
+
+    /// Run closure with the last frame if it is not consumed yet.
+    /// The frame is consumed if the closure returns `true`.
+    pub fn consume(&mut self, f: impl FnOnce(&BitmapInfo, &[u8]) -> bool) {
+        if !self.pending {
+            return;
+        }
+
+        if let Some(ref info) = self.frame {
+            self.pending = !f(info, &self.buf);
+        }
+    }
+}
+
+impl Drop for BitmapShm {
+    fn drop(&mut self) {
+        unsafe {
+            _ = UnmapViewOfFile(self.view);
+        }
+    }
+}
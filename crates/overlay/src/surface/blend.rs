@@ -0,0 +1,467 @@
+//! Blend math of overlay textures.
+//!
+//! Every renderer shades texels with the same [`ShaderConstants`] and configures its blend state
+//! for the [`BlendMode`], so [`shade`] and [`blend`] are CPU reference implementations of renderers.
+//!
+//! Shaders convert texture colors with a piecewise transfer function
+//! `x <= t ? x * a : pow(x * b + c, g) * d + e`, which can express sRGB encoding, decoding and identity.
+
+use asdf_overlay_event::{
+    backbuffer::{BackbufferInfo, ColorSpace, PixelFormat},
+    blend::{BlendMode, ColorEncoding},
+};
+
+/// Every blend mode in declaration order.
+///
+/// Renderers create a blend state per mode, indexed by `mode as usize`.
+pub const BLEND_MODES: [BlendMode; 3] = [
+    BlendMode::Straight,
+    BlendMode::Premultiplied,
+    BlendMode::Additive,
+];
+
+/// Conversion of texture colors applied by shaders.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transfer {
+    /// Colors are not converted.
+    #[default]
+    None,
+
+    /// sRGB encoded colors are decoded to linear.
+    SrgbToLinear,
+
+    /// Linear colors are encoded to sRGB.
+    LinearToSrgb,
+}
+
+impl Transfer {
+    /// Conversion from texture `encoding` to the encoding `backbuffer` expects.
+    ///
+    /// HDR10 backbuffers are treated as sRGB encoded.
+    pub fn new(encoding: ColorEncoding, backbuffer: &BackbufferInfo) -> Self {
+        match (encoding, is_linear(backbuffer)) {
+            (ColorEncoding::Srgb, true) => Self::SrgbToLinear,
+            (ColorEncoding::Linear, false) => Self::LinearToSrgb,
+            _ => Self::None,
+        }
+    }
+
+    /// Parameters `[t, a, b, c, g, d, e]` of the transfer function.
+    pub const fn params(self) -> [f32; 7] {
+        match self {
+            Transfer::None => [f32::MAX, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0],
+            Transfer::SrgbToLinear => [
+                0.04045,
+                1.0 / 12.92,
+                1.0 / 1.055,
+                0.055 / 1.055,
+                2.4,
+                1.0,
+                0.0,
+            ],
+            Transfer::LinearToSrgb => [0.0031308, 12.92, 1.0, 0.0, 1.0 / 2.4, 1.055, -0.055],
+        }
+    }
+
+    /// Convert a color channel.
+    pub fn apply(self, x: f32) -> f32 {
+        transfer(self.params(), x)
+    }
+}
+
+/// Whether if the backbuffer expects linear colors from shaders.
+fn is_linear(backbuffer: &BackbufferInfo) -> bool {
+    // sRGB formats are encoded by hardware on write
+    matches!(
+        backbuffer.format,
+        PixelFormat::Rgba8UnormSrgb | PixelFormat::Bgra8UnormSrgb
+    ) || backbuffer.color_space == ColorSpace::ScRgb
+}
+
+fn transfer([t, a, b, c, g, d, e]: [f32; 7], x: f32) -> f32 {
+    let x = x.max(0.0);
+    if x <= t {
+        x * a
+    } else {
+        (x * b + c).powf(g) * d + e
+    }
+}
+
+/// Pixel shader constants of a layer.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ShaderConstants {
+    /// Multiplied to alpha.
+    pub opacity: f32,
+
+    /// Multiplied to colors. Opacity for premultiplied textures, otherwise `1.0`.
+    pub color_scale: f32,
+
+    /// `1.0` if colors are divided by alpha before the transfer function and multiplied back after.
+    pub unpremultiply: f32,
+
+    /// Transfer function parameters.
+    pub transfer: [f32; 7],
+}
+
+impl ShaderConstants {
+    pub fn new(mode: BlendMode, transfer: Transfer, opacity: f32) -> Self {
+        let premultiplied = mode == BlendMode::Premultiplied;
+        Self {
+            opacity,
+            color_scale: if premultiplied { opacity } else { 1.0 },
+            unpremultiply: if premultiplied && transfer != Transfer::None {
+                1.0
+            } else {
+                0.0
+            },
+            transfer: transfer.params(),
+        }
+    }
+
+    /// Constants laid out as three `float4` shaders read,
+    /// `[opacity, color_scale, unpremultiply, 0]`, `[t, a, b, c]` and `[g, d, e, 0]`.
+    pub fn as_array(&self) -> [f32; 12] {
+        let [t, a, b, c, g, d, e] = self.transfer;
+        [
+            self.opacity,
+            self.color_scale,
+            self.unpremultiply,
+            0.0,
+            t,
+            a,
+            b,
+            c,
+            g,
+            d,
+            e,
+            0.0,
+        ]
+    }
+}
+
+/// Shade a texel the same way renderer shaders do.
+///
+/// Returns the color renderers output to the blend stage.
+pub fn shade(constants: &ShaderConstants, [r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let k = if constants.unpremultiply != 0.0 && a > 0.0 {
+        a
+    } else {
+        1.0
+    };
+    let convert = |x: f32| transfer(constants.transfer, x / k) * k * constants.color_scale;
+
+    [convert(r), convert(g), convert(b), a * constants.opacity]
+}
+
+/// Blend a shaded color onto a destination color the same way renderer blend states do.
+pub fn blend(mode: BlendMode, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+    let [sr, sg, sb, sa] = src;
+    let [dr, dg, db, da] = dst;
+
+    match mode {
+        // SRC_ALPHA, INV_SRC_ALPHA / ONE, INV_SRC_ALPHA
+        BlendMode::Straight => [
+            sr * sa + dr * (1.0 - sa),
+            sg * sa + dg * (1.0 - sa),
+            sb * sa + db * (1.0 - sa),
+            sa + da * (1.0 - sa),
+        ],
+
+        // ONE, INV_SRC_ALPHA / ONE, INV_SRC_ALPHA
+        BlendMode::Premultiplied => [
+            sr + dr * (1.0 - sa),
+            sg + dg * (1.0 - sa),
+            sb + db * (1.0 - sa),
+            sa + da * (1.0 - sa),
+        ],
+
+        // SRC_ALPHA, ONE / ZERO, ONE
+        BlendMode::Additive => [sr * sa + dr, sg * sa + dg, sb * sa + db, da],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_approx(actual: [f32; 4], expected: [f32; 4]) {
+        for (a, e) in actual.into_iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{actual:?} != {expected:?}");
+        }
+    }
+
+    fn backbuffer(format: PixelFormat, color_space: ColorSpace) -> BackbufferInfo {
+        BackbufferInfo {
+            format,
+            color_space,
+            ..Default::default()
+        }
+    }
+
+    fn draw(
+        mode: BlendMode,
+        transfer: Transfer,
+        opacity: f32,
+        texel: [f32; 4],
+        dst: [f32; 4],
+    ) -> [f32; 4] {
+        let constants = ShaderConstants::new(mode, transfer, opacity);
+        blend(mode, shade(&constants, texel), dst)
+    }
+
+    #[test]
+    fn transfer_new() {
+        let unorm = backbuffer(PixelFormat::Bgra8Unorm, ColorSpace::Srgb);
+        let srgb = backbuffer(PixelFormat::Bgra8UnormSrgb, ColorSpace::Srgb);
+        let scrgb = backbuffer(PixelFormat::Rgba16Float, ColorSpace::ScRgb);
+        let hdr10 = backbuffer(PixelFormat::Rgb10A2Unorm, ColorSpace::Hdr10);
+
+        assert_eq!(Transfer::new(ColorEncoding::Srgb, &unorm), Transfer::None);
+        assert_eq!(
+            Transfer::new(ColorEncoding::Srgb, &srgb),
+            Transfer::SrgbToLinear
+        );
+        assert_eq!(
+            Transfer::new(ColorEncoding::Srgb, &scrgb),
+            Transfer::SrgbToLinear
+        );
+        assert_eq!(Transfer::new(ColorEncoding::Srgb, &hdr10), Transfer::None);
+
+        assert_eq!(
+            Transfer::new(ColorEncoding::Linear, &unorm),
+            Transfer::LinearToSrgb
+        );
+        assert_eq!(Transfer::new(ColorEncoding::Linear, &srgb), Transfer::None);
+        assert_eq!(Transfer::new(ColorEncoding::Linear, &scrgb), Transfer::None);
+        assert_eq!(
+            Transfer::new(ColorEncoding::Linear, &hdr10),
+            Transfer::LinearToSrgb
+        );
+    }
+
+    #[test]
+    fn transfer_apply() {
+        for x in [0.0, 0.002, 0.25, 0.5, 1.0] {
+            assert_eq!(Transfer::None.apply(x), x);
+        }
+        assert_eq!(Transfer::SrgbToLinear.apply(-1.0), 0.0);
+
+        // Linear segments
+        assert!((Transfer::SrgbToLinear.apply(0.04) - 0.04 / 12.92).abs() < 1e-6);
+        assert!((Transfer::LinearToSrgb.apply(0.003) - 0.003 * 12.92).abs() < 1e-6);
+
+        // Power segments
+        assert!((Transfer::SrgbToLinear.apply(0.5) - 0.214_041).abs() < 1e-5);
+        assert!((Transfer::LinearToSrgb.apply(0.214_041) - 0.5).abs() < 1e-5);
+        assert!((Transfer::SrgbToLinear.apply(1.0) - 1.0).abs() < 1e-5);
+        assert!((Transfer::LinearToSrgb.apply(1.0) - 1.0).abs() < 1e-5);
+
+        for i in 0..=100 {
+            let x = i as f32 / 100.0;
+            let y = Transfer::LinearToSrgb.apply(Transfer::SrgbToLinear.apply(x));
+            assert!((x - y).abs() < 1e-4, "{x} != {y}");
+        }
+    }
+
+    #[test]
+    fn shader_constants() {
+        let straight = ShaderConstants::new(BlendMode::Straight, Transfer::SrgbToLinear, 0.5);
+        assert_eq!(straight.opacity, 0.5);
+        assert_eq!(straight.color_scale, 1.0);
+        assert_eq!(straight.unpremultiply, 0.0);
+        assert_eq!(straight.transfer, Transfer::SrgbToLinear.params());
+
+        let premultiplied =
+            ShaderConstants::new(BlendMode::Premultiplied, Transfer::SrgbToLinear, 0.5);
+        assert_eq!(premultiplied.color_scale, 0.5);
+        assert_eq!(premultiplied.unpremultiply, 1.0);
+
+        let premultiplied = ShaderConstants::new(BlendMode::Premultiplied, Transfer::None, 0.5);
+        assert_eq!(premultiplied.unpremultiply, 0.0);
+
+        let additive = ShaderConstants::new(BlendMode::Additive, Transfer::LinearToSrgb, 0.25);
+        assert_eq!(additive.color_scale, 1.0);
+        assert_eq!(additive.unpremultiply, 0.0);
+
+        let [t, a, b, c, g, d, e] = Transfer::LinearToSrgb.params();
+        assert_eq!(
+            additive.as_array(),
+            [0.25, 1.0, 0.0, 0.0, t, a, b, c, g, d, e, 0.0]
+        );
+    }
+
+    #[test]
+    fn straight() {
+        let dst = [0.0, 0.0, 1.0, 1.0];
+
+        assert_approx(
+            draw(
+                BlendMode::Straight,
+                Transfer::None,
+                1.0,
+                [1.0, 0.0, 0.0, 0.5],
+                dst,
+            ),
+            [0.5, 0.0, 0.5, 1.0],
+        );
+        assert_approx(
+            draw(
+                BlendMode::Straight,
+                Transfer::None,
+                0.5,
+                [1.0, 0.0, 0.0, 0.5],
+                dst,
+            ),
+            [0.25, 0.0, 0.75, 1.0],
+        );
+        assert_approx(
+            draw(
+                BlendMode::Straight,
+                Transfer::None,
+                1.0,
+                [1.0, 0.5, 0.0, 1.0],
+                dst,
+            ),
+            [1.0, 0.5, 0.0, 1.0],
+        );
+        assert_approx(
+            draw(
+                BlendMode::Straight,
+                Transfer::None,
+                0.5,
+                [0.0, 0.0, 0.0, 0.5],
+                [1.0, 1.0, 1.0, 0.0],
+            ),
+            [0.75, 0.75, 0.75, 0.25],
+        );
+    }
+
+    #[test]
+    fn premultiplied() {
+        let dst = [0.0, 0.0, 1.0, 1.0];
+
+        assert_approx(
+            draw(
+                BlendMode::Premultiplied,
+                Transfer::None,
+                1.0,
+                [0.5, 0.0, 0.0, 0.5],
+                dst,
+            ),
+            [0.5, 0.0, 0.5, 1.0],
+        );
+        assert_approx(
+            draw(
+                BlendMode::Premultiplied,
+                Transfer::None,
+                0.5,
+                [0.5, 0.0, 0.0, 0.5],
+                dst,
+            ),
+            [0.25, 0.0, 0.75, 1.0],
+        );
+
+        // Fully transparent texels add nothing
+        assert_approx(
+            draw(
+                BlendMode::Premultiplied,
+                Transfer::SrgbToLinear,
+                1.0,
+                [0.0, 0.0, 0.0, 0.0],
+                dst,
+            ),
+            dst,
+        );
+    }
+
+    #[test]
+    fn premultiplied_matches_straight() {
+        let dst = [0.2, 0.4, 0.6, 0.8];
+        let color = [0.9, 0.5, 0.1];
+
+        for transfer in [
+            Transfer::None,
+            Transfer::SrgbToLinear,
+            Transfer::LinearToSrgb,
+        ] {
+            for opacity in [0.0, 0.3, 1.0] {
+                for alpha in [0.25, 0.5, 1.0] {
+                    let [r, g, b] = color;
+                    let straight = draw(
+                        BlendMode::Straight,
+                        transfer,
+                        opacity,
+                        [r, g, b, alpha],
+                        dst,
+                    );
+                    let premultiplied = draw(
+                        BlendMode::Premultiplied,
+                        transfer,
+                        opacity,
+                        [r * alpha, g * alpha, b * alpha, alpha],
+                        dst,
+                    );
+
+                    assert_approx(premultiplied, straight);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn additive() {
+        let dst = [0.2, 0.2, 0.2, 0.4];
+
+        assert_approx(
+            draw(
+                BlendMode::Additive,
+                Transfer::None,
+                1.0,
+                [0.5, 1.0, 0.0, 0.5],
+                dst,
+            ),
+            [0.45, 0.7, 0.2, 0.4],
+        );
+        assert_approx(
+            draw(
+                BlendMode::Additive,
+                Transfer::None,
+                0.5,
+                [0.5, 1.0, 0.0, 0.5],
+                dst,
+            ),
+            [0.325, 0.45, 0.2, 0.4],
+        );
+    }
+
+    #[test]
+    fn transfer_is_applied_before_blending() {
+        let linear = Transfer::SrgbToLinear.apply(0.5);
+
+        for mode in BLEND_MODES {
+            let [r, _, _, _] = draw(
+                mode,
+                Transfer::SrgbToLinear,
+                1.0,
+                [0.5, 0.5, 0.5, 1.0],
+                [0.0, 0.0, 0.0, 1.0],
+            );
+            assert!((r - linear).abs() < 1e-5, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn zero_opacity_keeps_destination() {
+        let dst = [0.1, 0.2, 0.3, 0.4];
+
+        for mode in BLEND_MODES {
+            for transfer in [
+                Transfer::None,
+                Transfer::SrgbToLinear,
+                Transfer::LinearToSrgb,
+            ] {
+                assert_approx(draw(mode, transfer, 0.0, [0.9, 0.8, 0.7, 1.0], dst), dst);
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Dirty region tracking of layer textures.
+//!
+//! Contents of client textures can change anytime, so renderers copying them must copy whole textures on every frame.
+//! Once a client invalidates regions of a texture, renderers only copy the invalidated regions,
+//! until another texture is committed.
+
+use core::mem;
+
+/// Maximum number of rectangles tracked, before the whole texture is considered dirty.
+const MAX_DIRTY_RECTS: usize = 32;
+
+/// Rectangle relative to a texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl DirtyRect {
+    /// Clip the rectangle to the texture size.
+    ///
+    /// Returns [`None`] if nothing is left.
+    pub fn clip(&self, size: (u32, u32)) -> Option<Self> {
+        let right = self.x.saturating_add(self.width).min(size.0);
+        let bottom = self.y.saturating_add(self.height).min(size.1);
+        if self.x >= right || self.y >= bottom {
+            return None;
+        }
+
+        Some(Self {
+            x: self.x,
+            y: self.y,
+            width: right - self.x,
+            height: bottom - self.y,
+        })
+    }
+}
+
+/// Region of a texture changed since renderers last copied it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirtyRegion {
+    /// The whole texture.
+    Full,
+
+    /// Only the rectangles. Empty if nothing changed.
+    Rects(Vec<DirtyRect>),
+}
+
+/// Dirty region of a texture, accumulated until taken.
+pub(crate) struct DirtyTracker {
+    /// Whether changes are reported by invalidating regions.
+    tracked: bool,
+    region: DirtyRegion,
+}
+
+impl DirtyTracker {
+    pub const fn new() -> Self {
+        Self {
+            tracked: false,
+            region: DirtyRegion::Full,
+        }
+    }
+
+    /// Stop tracking, as contents of a new texture can change anytime.
+    pub fn untrack(&mut self) {
+        self.tracked = false;
+        self.region = DirtyRegion::Full;
+    }
+
+    /// Start tracking.
+    ///
+    /// The whole texture stays dirty until taken, so a newly committed texture is still copied entirely.
+    pub fn track(&mut self) {
+        self.tracked = true;
+    }
+
+    /// Mark the whole texture dirty.
+    pub fn mark_full(&mut self) {
+        self.region = DirtyRegion::Full;
+    }
+
+    /// Mark the rectangles dirty, starting tracking if not yet.
+    pub fn mark(&mut self, rects: &[DirtyRect]) {
+        if !self.tracked {
+            self.track();
+        }
+
+        if let DirtyRegion::Rects(ref mut dirty) = self.region {
+            dirty.extend(
+                rects
+                    .iter()
+                    .filter(|rect| rect.width > 0 && rect.height > 0),
+            );
+            if dirty.len() > MAX_DIRTY_RECTS {
+                self.region = DirtyRegion::Full;
+            }
+        }
+    }
+
+    /// Take the dirty region accumulated since the last call.
+    ///
+    /// Returns [`DirtyRegion::Full`] every time if not tracking.
+    pub fn take(&mut self) -> DirtyRegion {
+        if !self.tracked {
+            return DirtyRegion::Full;
+        }
+
+        mem::replace(&mut self.region, DirtyRegion::Rects(Vec::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn rect(x: u32, y: u32, width: u32, height: u32) -> DirtyRect {
+        DirtyRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn untracked_is_always_full() {
+        let mut tracker = DirtyTracker::new();
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+    }
+
+    #[test]
+    fn full_copy_pending_after_track() {
+        let mut tracker = DirtyTracker::new();
+        tracker.track();
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+        assert_eq!(tracker.take(), DirtyRegion::Rects(Vec::new()));
+    }
+
+    #[test]
+    fn full_copy_pending_after_mark() {
+        // First invalidation of a new texture starts tracking
+        let mut tracker = DirtyTracker::new();
+        tracker.mark(&[rect(0, 0, 10, 10)]);
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+
+        tracker.mark(&[rect(0, 0, 10, 10)]);
+        assert_eq!(tracker.take(), DirtyRegion::Rects(vec![rect(0, 0, 10, 10)]));
+    }
+
+    #[test]
+    fn accumulates_until_taken() {
+        let mut tracker = DirtyTracker::new();
+        tracker.track();
+        tracker.take();
+
+        tracker.mark(&[rect(0, 0, 10, 10), rect(0, 0, 0, 10), rect(0, 0, 10, 0)]);
+        tracker.mark(&[rect(5, 5, 1, 1)]);
+        assert_eq!(
+            tracker.take(),
+            DirtyRegion::Rects(vec![rect(0, 0, 10, 10), rect(5, 5, 1, 1)])
+        );
+        assert_eq!(tracker.take(), DirtyRegion::Rects(Vec::new()));
+
+        tracker.mark_full();
+        tracker.mark(&[rect(0, 0, 10, 10)]);
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+    }
+
+    #[test]
+    fn untrack() {
+        let mut tracker = DirtyTracker::new();
+        tracker.track();
+        tracker.take();
+        tracker.mark(&[rect(0, 0, 10, 10)]);
+
+        tracker.untrack();
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+    }
+
+    #[test]
+    fn overflow_to_full() {
+        let mut tracker = DirtyTracker::new();
+        tracker.track();
+        tracker.take();
+
+        tracker.mark(&[rect(0, 0, 1, 1); MAX_DIRTY_RECTS]);
+        assert!(
+            matches!(tracker.take(), DirtyRegion::Rects(rects) if rects.len() == MAX_DIRTY_RECTS)
+        );
+
+        tracker.mark(&[rect(0, 0, 1, 1); MAX_DIRTY_RECTS]);
+        tracker.mark(&[rect(0, 0, 1, 1)]);
+        assert_eq!(tracker.take(), DirtyRegion::Full);
+        assert_eq!(tracker.take(), DirtyRegion::Rects(Vec::new()));
+    }
+
+    #[test]
+    fn clip() {
+        let size = (100, 50);
+        assert_eq!(rect(10, 10, 20, 20).clip(size), Some(rect(10, 10, 20, 20)));
+        assert_eq!(rect(0, 0, 100, 50).clip(size), Some(rect(0, 0, 100, 50)));
+        assert_eq!(rect(90, 40, 20, 20).clip(size), Some(rect(90, 40, 10, 10)));
+        assert_eq!(rect(99, 49, 1, 1).clip(size), Some(rect(99, 49, 1, 1)));
+
+        // Outside or empty
+        assert_eq!(rect(100, 0, 10, 10).clip(size), None);
+        assert_eq!(rect(0, 50, 10, 10).clip(size), None);
+        assert_eq!(rect(10, 10, 0, 10).clip(size), None);
+        assert_eq!(rect(0, 0, 10, 10).clip((0, 0)), None);
+
+        // Does not overflow
+        assert_eq!(
+            rect(u32::MAX, 0, u32::MAX, 10).clip((u32::MAX, u32::MAX)),
+            None
+        );
+        assert_eq!(
+            rect(10, 10, u32::MAX, u32::MAX).clip(size),
+            Some(rect(10, 10, 90, 40))
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Rolling frame time statistics of a surface.
+//!
+//! [`FrameTimes`] keeps recent present intervals and aggregates them into [`FrameStats`].
+//! It only depends on the given present times, so it does not depend on any graphics api.
+
+use core::time::Duration;
+use std::{collections::VecDeque, time::Instant};
+
+use asdf_overlay_event::stats::FrameStats;
+
+/// Maximum number of present intervals kept for statistics.
+pub const MAX_SAMPLES: usize = 1024;
+
+/// Recent present intervals of a surface.
+#[derive(Debug, Default)]
+pub struct FrameTimes {
+    /// Present intervals in microseconds, from oldest to newest.
+    intervals: VecDeque<u32>,
+
+    last_present: Option<Instant>,
+
+    report_interval: Option<Duration>,
+    last_report: Option<Instant>,
+}
+
+impl FrameTimes {
+    /// Set interval of periodic statistics reports.
+    /// If [`None`] is given, statistics are not reported.
+    pub fn set_report_interval(&mut self, interval: Option<Duration>) {
+        self.report_interval = interval;
+        self.last_report = None;
+    }
+
+    /// Record a present at `now`.
+    ///
+    /// Returns statistics if a periodic report is due.
+    pub fn record(&mut self, now: Instant) -> Option<FrameStats> {
+        if let Some(last) = self.last_present.replace(now) {
+            self.push(now.saturating_duration_since(last));
+        }
+
+        let interval = self.report_interval?;
+        let last_report = *self.last_report.get_or_insert(now);
+        if now.saturating_duration_since(last_report) < interval {
+            return None;
+        }
+
+        self.last_report = Some(now);
+        self.stats()
+    }
+
+    /// Push a present interval, discarding the oldest one if there are [`MAX_SAMPLES`] intervals.
+    pub fn push(&mut self, interval: Duration) {
+        if self.intervals.len() >= MAX_SAMPLES {
+            self.intervals.pop_front();
+        }
+
+        self.intervals
+            .push_back(interval.as_micros().min(u32::MAX as u128) as u32);
+    }
+
+    /// Aggregate recent present intervals.
+    ///
+    /// Returns [`None`] if there are no present intervals yet.
+    pub fn stats(&self) -> Option<FrameStats> {
+        let samples = self.intervals.len();
+        if samples == 0 {
+            return None;
+        }
+
+        let mut sorted = self.intervals.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mean = mean_micros(&sorted);
+        let variance = sorted
+            .iter()
+            .map(|&interval| {
+                let diff = interval as f64 - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / samples as f64;
+
+        Some(FrameStats {
+            samples: samples as u32,
+            fps: fps(mean),
+            frame_time_ms: (mean / 1000.0) as f32,
+            low_1_percent_fps: fps(mean_micros(slowest(&sorted, 0.01))),
+            low_0_1_percent_fps: fps(mean_micros(slowest(&sorted, 0.001))),
+            jitter_ms: (variance.sqrt() / 1000.0) as f32,
+        })
+    }
+}
+
+/// Slowest `ratio` of intervals sorted in descending order, containing at least one interval.
+fn slowest(sorted: &[u32], ratio: f64) -> &[u32] {
+    let count = ((sorted.len() as f64 * ratio).ceil() as usize).clamp(1, sorted.len());
+    &sorted[..count]
+}
+
+fn mean_micros(intervals: &[u32]) -> f64 {
+    intervals
+        .iter()
+        .map(|&interval| interval as f64)
+        .sum::<f64>()
+        / intervals.len() as f64
+}
+
+fn fps(mean_micros: f64) -> f32 {
+    if mean_micros <= 0.0 {
+        return 0.0;
+    }
+
+    (1_000_000.0 / mean_micros) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn times(intervals_ms: impl IntoIterator<Item = u64>) -> FrameTimes {
+        let mut times = FrameTimes::default();
+        for interval in intervals_ms {
+            times.push(Duration::from_millis(interval));
+        }
+
+        times
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(FrameTimes::default().stats(), None);
+    }
+
+    #[test]
+    fn constant_intervals() {
+        let stats = times([10; 100]).stats().unwrap();
+        assert_eq!(stats.samples, 100);
+        assert_close(stats.fps, 100.0);
+        assert_close(stats.frame_time_ms, 10.0);
+        assert_close(stats.low_1_percent_fps, 100.0);
+        assert_close(stats.low_0_1_percent_fps, 100.0);
+        assert_close(stats.jitter_ms, 0.0);
+    }
+
+    #[test]
+    fn rolling_window() {
+        let mut times = times([100; MAX_SAMPLES]);
+        assert_eq!(times.stats().unwrap().samples, MAX_SAMPLES as u32);
+
+        // Oldest intervals are discarded
+        for _ in 0..MAX_SAMPLES {
+            times.push(Duration::from_millis(10));
+        }
+        let stats = times.stats().unwrap();
+        assert_eq!(stats.samples, MAX_SAMPLES as u32);
+        assert_close(stats.frame_time_ms, 10.0);
+        assert_close(stats.low_0_1_percent_fps, 100.0);
+    }
+
+    #[test]
+    fn percentile_lows() {
+        // 990 frames of 10ms and 10 frames of 50ms
+        let stats = times(core::iter::repeat_n(10, 990).chain(core::iter::repeat_n(50, 10)))
+            .stats()
+            .unwrap();
+
+        assert_close(stats.frame_time_ms, 10.4);
+        // Slowest 1% is 10 frames of 50ms
+        assert_close(stats.low_1_percent_fps, 20.0);
+        // Slowest 0.1% is rounded up to one frame
+        assert_close(stats.low_0_1_percent_fps, 20.0);
+    }
+
+    #[test]
+    fn percentile_lows_of_few_samples() {
+        // At least one interval is used
+        let stats = times([10, 20, 40]).stats().unwrap();
+        assert_close(stats.low_1_percent_fps, 25.0);
+        assert_close(stats.low_0_1_percent_fps, 25.0);
+    }
+
+    #[test]
+    fn jitter() {
+        // Mean 15ms, standard deviation 5ms
+        let stats = times([10, 20, 10, 20]).stats().unwrap();
+        assert_close(stats.frame_time_ms, 15.0);
+        assert_close(stats.jitter_ms, 5.0);
+    }
+
+    #[test]
+    fn zero_intervals() {
+        let stats = times([0, 0]).stats().unwrap();
+        assert_eq!(stats.fps, 0.0);
+        assert_eq!(stats.jitter_ms, 0.0);
+    }
+
+    #[test]
+    fn record() {
+        let start = Instant::now();
+        let mut times = FrameTimes::default();
+
+        // First present has no interval
+        assert_eq!(times.record(start), None);
+        assert_eq!(times.stats(), None);
+
+        times.set_report_interval(Some(Duration::from_millis(100)));
+        let mut reports = 0;
+        for frame in 1..=30 {
+            if times
+                .record(start + Duration::from_millis(frame * 10))
+                .is_some()
+            {
+                reports += 1;
+            }
+        }
+        // Reports at 110ms, 210ms
+        assert_eq!(reports, 2);
+        assert_eq!(times.stats().unwrap().samples, 30);
+
+        times.set_report_interval(None);
+        assert_eq!(times.record(start + Duration::from_secs(1)), None);
+    }
+}
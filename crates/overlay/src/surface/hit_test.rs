@@ -0,0 +1,306 @@
+//! Hit-testing of overlay surfaces.
+//!
+//! Surfaces can declare interactive regions, so only cursor inputs over those regions are captured,
+//! and everything else reaches the application.
+
+/// Describe interactive region of an overlay surface.
+#[derive(Debug, Clone, Default)]
+pub enum HitRegion {
+    /// Surface does not capture any cursor input.
+    #[default]
+    None,
+
+    /// Entire surface captures cursor inputs.
+    Full,
+
+    /// Only cursor inputs inside of the rectangles are captured.
+    Rects(Vec<HitRect>),
+
+    /// Only cursor inputs over pixels with alpha value equal or greater than `threshold` are captured.
+    AlphaMask {
+        /// Alpha threshold.
+        threshold: u8,
+
+        /// Mask built from the surface texture.
+        /// [`None`] if there is no texture or the mask cannot be built.
+        mask: Option<HitMask>,
+    },
+}
+
+impl HitRegion {
+    /// Check if the point relative to the surface is inside of the region.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        match self {
+            HitRegion::None => false,
+            HitRegion::Full => true,
+            HitRegion::Rects(rects) => rects.iter().any(|rect| rect.contains(x, y)),
+            HitRegion::AlphaMask { mask, .. } => {
+                mask.as_ref().is_some_and(|mask| mask.contains(x, y))
+            }
+        }
+    }
+}
+
+/// Rectangle relative to the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HitRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl HitRect {
+    /// Check if the point is inside of the rectangle.
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        let (x, y) = (x as i64 - self.x as i64, y as i64 - self.y as i64);
+        x >= 0 && y >= 0 && x < self.width as i64 && y < self.height as i64
+    }
+}
+
+/// CPU side bitmask of interactive pixels.
+#[derive(Clone)]
+pub struct HitMask {
+    width: u32,
+    height: u32,
+    bits: Vec<u64>,
+}
+
+impl HitMask {
+    /// Build mask from alpha channel of a mapped pixel buffer.
+    ///
+    /// * `pixel_size` is byte size of a pixel, and `alpha_offset` is byte offset of alpha channel in a pixel.
+    /// * `data` must contain `height` rows of `row_pitch` bytes. Excess rows are ignored.
+    ///
+    /// Returns an empty mask if `row_pitch` or `pixel_size` is zero.
+    pub fn from_alpha(
+        data: &[u8],
+        size: (u32, u32),
+        row_pitch: usize,
+        pixel_size: usize,
+        alpha_offset: usize,
+        threshold: u8,
+    ) -> Self {
+        let (width, height) = size;
+        let mut bits = vec![0_u64; (width as usize * height as usize).div_ceil(64)];
+        if row_pitch == 0 || pixel_size == 0 {
+            return Self {
+                width,
+                height,
+                bits,
+            };
+        }
+
+        for (y, row) in data.chunks(row_pitch).take(height as usize).enumerate() {
+            for (x, pixel) in row
+                .chunks_exact(pixel_size)
+                .take(width as usize)
+                .enumerate()
+            {
+                if pixel[alpha_offset] >= threshold {
+                    let index = y * width as usize + x;
+                    bits[index / 64] |= 1 << (index % 64);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            bits,
+        }
+    }
+
+    /// Size of the mask.
+    #[inline]
+    pub const fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Check if the pixel at the point is interactive.
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return false;
+        }
+
+        let index = y as usize * self.width as usize + x as usize;
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+}
+
+impl core::fmt::Debug for HitMask {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HitMask")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BGRA pixels, with alpha values of each row padded to `row_pitch`.
+    fn bgra(rows: &[&[u8]], row_pitch: usize) -> Vec<u8> {
+        let mut data = vec![0; rows.len() * row_pitch];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, alpha) in row.iter().enumerate() {
+                data[y * row_pitch + x * 4 + 3] = *alpha;
+            }
+        }
+
+        data
+    }
+
+    #[test]
+    fn rect_contains() {
+        let rect = HitRect {
+            x: 10,
+            y: 20,
+            width: 30,
+            height: 40,
+        };
+
+        assert!(rect.contains(10, 20));
+        assert!(rect.contains(39, 59));
+        assert!(!rect.contains(40, 59));
+        assert!(!rect.contains(39, 60));
+        assert!(!rect.contains(9, 20));
+        assert!(!rect.contains(10, 19));
+    }
+
+    #[test]
+    fn rect_contains_without_overflow() {
+        let rect = HitRect {
+            x: i32::MAX,
+            y: i32::MIN,
+            width: u32::MAX,
+            height: 1,
+        };
+
+        assert!(rect.contains(i32::MAX, i32::MIN));
+        assert!(!rect.contains(i32::MIN, i32::MIN));
+
+        let empty = HitRect {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        };
+        assert!(!empty.contains(0, 0));
+    }
+
+    #[test]
+    fn region_contains() {
+        assert!(!HitRegion::None.contains(0, 0));
+        assert!(HitRegion::Full.contains(-100, 100));
+
+        let rects = HitRegion::Rects(vec![
+            HitRect {
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 10,
+            },
+            HitRect {
+                x: 20,
+                y: 0,
+                width: 10,
+                height: 10,
+            },
+        ]);
+        assert!(rects.contains(5, 5));
+        assert!(rects.contains(25, 5));
+        assert!(!rects.contains(15, 5));
+        assert!(!HitRegion::Rects(vec![]).contains(0, 0));
+
+        let no_mask = HitRegion::AlphaMask {
+            threshold: 1,
+            mask: None,
+        };
+        assert!(!no_mask.contains(0, 0));
+
+        let mask = HitRegion::AlphaMask {
+            threshold: 1,
+            mask: Some(HitMask::from_alpha(
+                &bgra(&[&[0, 255]], 8),
+                (2, 1),
+                8,
+                4,
+                3,
+                1,
+            )),
+        };
+        assert!(!mask.contains(0, 0));
+        assert!(mask.contains(1, 0));
+    }
+
+    #[test]
+    fn mask_alpha_threshold() {
+        let data = bgra(&[&[0, 127, 128, 255]], 16);
+        let mask = HitMask::from_alpha(&data, (4, 1), 16, 4, 3, 128);
+
+        assert_eq!(mask.size(), (4, 1));
+        assert!(!mask.contains(0, 0));
+        assert!(!mask.contains(1, 0));
+        assert!(mask.contains(2, 0));
+        assert!(mask.contains(3, 0));
+
+        // Every pixel passes zero threshold
+        let mask = HitMask::from_alpha(&data, (4, 1), 16, 4, 3, 0);
+        assert!((0..4).all(|x| mask.contains(x, 0)));
+    }
+
+    #[test]
+    fn mask_edge_pixels() {
+        // Row pitch is wider than the row, and padding is opaque
+        let mut data = bgra(&[&[255, 0, 255], &[0, 0, 0], &[255, 0, 255]], 16);
+        data[15] = 255;
+        data[31] = 255;
+        let mask = HitMask::from_alpha(&data, (3, 3), 16, 4, 3, 1);
+
+        assert!(mask.contains(0, 0));
+        assert!(mask.contains(2, 0));
+        assert!(mask.contains(0, 2));
+        assert!(mask.contains(2, 2));
+        assert!(!mask.contains(1, 1));
+        assert!(!mask.contains(3, 0));
+        assert!(!mask.contains(3, 1));
+
+        // Outside of the mask
+        assert!(!mask.contains(-1, 0));
+        assert!(!mask.contains(0, -1));
+        assert!(!mask.contains(0, 3));
+        assert!(!mask.contains(i32::MAX, i32::MAX));
+    }
+
+    #[test]
+    fn mask_across_words() {
+        // 65 pixels span two words
+        let mut row = [0; 65];
+        row[63] = 255;
+        row[64] = 255;
+        let data = bgra(&[&row], 65 * 4);
+        let mask = HitMask::from_alpha(&data, (65, 1), 65 * 4, 4, 3, 1);
+
+        assert!(!mask.contains(62, 0));
+        assert!(mask.contains(63, 0));
+        assert!(mask.contains(64, 0));
+    }
+
+    #[test]
+    fn mask_short_data() {
+        // Missing rows are not interactive
+        let data = bgra(&[&[255, 255]], 8);
+        let mask = HitMask::from_alpha(&data, (2, 2), 8, 4, 3, 1);
+        assert!(mask.contains(1, 0));
+        assert!(!mask.contains(0, 1));
+
+        let mask = HitMask::from_alpha(&data, (2, 1), 0, 4, 3, 1);
+        assert_eq!(mask.size(), (2, 1));
+        assert!(!mask.contains(0, 0));
+    }
+}
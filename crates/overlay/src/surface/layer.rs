@@ -0,0 +1,475 @@
+//! Z-ordered overlay layers of a surface.
+//!
+//! Every surface has a base layer with id [`BASE_LAYER`], which surface wide requests apply to.
+//! Additional layers have their own texture, layout, transform, visibility, z index and interactive region,
+//! and are drawn from the lowest z index to the highest.
+//! Cursor inputs are hit-tested against visible layers from the highest z index to the lowest.
+//!
+//! [`Layers`] only keeps bookkeeping of layers and is generic over the layer texture,
+//! so it does not depend on any graphics api.
+
+use asdf_overlay_event::{
+    blend::{Blend, BlendMode},
+    layout::{Layout, Placement, Transform},
+};
+
+use crate::surface::{
+    blend::{ShaderConstants, Transfer},
+    hit_test::HitRegion,
+};
+
+/// Id of the base layer. The base layer always exists and cannot be removed.
+pub const BASE_LAYER: u32 = 0;
+
+/// Maximum number of layers in a surface, including the base layer.
+pub const MAX_LAYERS: usize = 16;
+
+/// A layer drawn in a surface.
+#[derive(Debug)]
+pub struct Layer<T> {
+    /// Layout of the layer texture.
+    pub layout: Layout,
+
+    /// Opacity, scale and fit mode of the layer texture.
+    pub transform: Transform,
+
+    /// Whether if the layer is drawn.
+    pub visible: bool,
+
+    /// Z index of the layer. Layers with higher z index are drawn on top.
+    ///
+    /// Layers with the same z index are ordered by their ids.
+    pub z_index: i32,
+
+    /// Blend mode and color encoding of the layer texture.
+    pub blend: Blend,
+
+    /// Interactive region of the layer, relative to the layer texture.
+    pub hit_region: HitRegion,
+
+    texture: T,
+}
+
+impl<T> Layer<T> {
+    /// Create a visible layer with z index `0`.
+    pub fn new(texture: T) -> Self {
+        Self {
+            layout: Layout::default(),
+            transform: Transform::default(),
+            visible: true,
+            z_index: 0,
+            blend: Blend::default(),
+            hit_region: HitRegion::None,
+            texture,
+        }
+    }
+
+    /// Texture of the layer.
+    #[inline]
+    pub const fn texture(&self) -> &T {
+        &self.texture
+    }
+
+    /// Resolve placement of the layer texture against the surface size and texture size.
+    pub fn resolve(&self, surface: (u32, u32), texture: (u32, u32)) -> Placement {
+        let size = self.transform.size(surface, texture);
+        self.layout.resolve(surface, size)
+    }
+
+    /// Convert the point relative to the surface to the point relative to the layer texture,
+    /// even if the point is outside of the texture.
+    ///
+    /// Returns [`None`] if the texture is drawn with zero size.
+    pub fn texture_point(
+        &self,
+        surface: (u32, u32),
+        texture: (u32, u32),
+        x: i32,
+        y: i32,
+    ) -> Option<(i32, i32)> {
+        let placement = self.resolve(surface, texture);
+        if placement.width == 0 || placement.height == 0 {
+            return None;
+        }
+
+        // Map back to texture pixels, as the texture can be drawn scaled
+        Some((
+            ((x - placement.x) as i64 * texture.0 as i64).div_euclid(placement.width as i64) as i32,
+            ((y - placement.y) as i64 * texture.1 as i64).div_euclid(placement.height as i64)
+                as i32,
+        ))
+    }
+
+    /// Reset layout, transform, visibility, z index, blend and interactive region of the layer.
+    pub fn reset(&mut self) {
+        self.layout = Layout::default();
+        self.transform = Transform::default();
+        self.visible = true;
+        self.z_index = 0;
+        self.blend = Blend::default();
+        self.hit_region = HitRegion::None;
+    }
+}
+
+/// Layers of a surface, ordered from bottom to top.
+#[derive(Debug)]
+pub struct Layers<T> {
+    /// Sorted by z index and id.
+    layers: Vec<(u32, Layer<T>)>,
+}
+
+impl<T> Layers<T> {
+    /// Create layers containing only the base layer.
+    pub fn new(base: T) -> Self {
+        Self {
+            layers: vec![(BASE_LAYER, Layer::new(base))],
+        }
+    }
+
+    /// The base layer.
+    pub fn base(&self) -> &Layer<T> {
+        self.get(BASE_LAYER).expect("base layer must exist")
+    }
+
+    /// Get a layer by id.
+    pub fn get(&self, id: u32) -> Option<&Layer<T>> {
+        self.position(id).map(|i| &self.layers[i].1)
+    }
+
+    /// Check if the layer exists.
+    pub fn contains(&self, id: u32) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Number of layers, including the base layer.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Iterate layers from bottom to top.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Layer<T>)> {
+        self.layers.iter().map(|(id, layer)| (*id, layer))
+    }
+
+    /// Iterate visible layers from bottom to top.
+    pub fn visible(&self) -> impl Iterator<Item = (u32, &Layer<T>)> {
+        self.iter().filter(|(_, layer)| layer.visible)
+    }
+
+    /// Iterate visible layers drawn over the point relative to the surface, from top to bottom.
+    ///
+    /// `texture_size` returns size of the layer texture, or [`None`] if the layer has no texture.
+    /// Yields layer id, the layer and the point relative to the layer texture.
+    pub fn at(
+        &self,
+        surface: (u32, u32),
+        x: i32,
+        y: i32,
+        texture_size: impl Fn(&Layer<T>) -> Option<(u32, u32)>,
+    ) -> impl Iterator<Item = (u32, &Layer<T>, (i32, i32))> {
+        self.layers
+            .iter()
+            .rev()
+            .filter(|(_, layer)| layer.visible)
+            .filter_map(move |(id, layer)| {
+                let (width, height) = texture_size(layer)?;
+                let (x, y) = layer.texture_point(surface, (width, height), x, y)?;
+                if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+                    return None;
+                }
+
+                Some((*id, layer, (x, y)))
+            })
+    }
+
+    /// Run closure with the layer, creating it with `texture` if it doesn't exist.
+    /// Layers are reordered after the closure returns.
+    ///
+    /// Returns [`None`] if the layer doesn't exist and the surface already has [`MAX_LAYERS`] layers.
+    pub fn update<R>(
+        &mut self,
+        id: u32,
+        texture: impl FnOnce() -> T,
+        f: impl FnOnce(&mut Layer<T>) -> R,
+    ) -> Option<R> {
+        let index = match self.position(id) {
+            Some(index) => index,
+            None => {
+                if self.layers.len() >= MAX_LAYERS {
+                    return None;
+                }
+
+                self.layers.push((id, Layer::new(texture())));
+                self.layers.len() - 1
+            }
+        };
+
+        let res = f(&mut self.layers[index].1);
+        self.sort();
+        Some(res)
+    }
+
+    /// Remove a layer.
+    ///
+    /// Returns [`None`] if the layer doesn't exist or it is the base layer.
+    pub fn remove(&mut self, id: u32) -> Option<Layer<T>> {
+        if id == BASE_LAYER {
+            return None;
+        }
+
+        let index = self.position(id)?;
+        Some(self.layers.remove(index).1)
+    }
+
+    /// Remove every layer except the base layer and reset the base layer.
+    pub fn reset(&mut self) {
+        self.layers.retain(|(id, _)| *id == BASE_LAYER);
+        self.layers[0].1.reset();
+    }
+
+    fn position(&self, id: u32) -> Option<usize> {
+        self.layers.iter().position(|(layer_id, _)| *layer_id == id)
+    }
+
+    fn sort(&mut self) {
+        self.layers.sort_by_key(|(id, layer)| (layer.z_index, *id));
+    }
+}
+
+/// Visible layer resolved for drawing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerDraw {
+    /// Layer id.
+    pub id: u32,
+
+    /// Placement of the layer texture relative to the left-top corner of the surface.
+    pub placement: Placement,
+
+    /// Opacity of the layer texture, clamped to `0.0..=1.0`.
+    pub opacity: f32,
+
+    /// How the layer texture is blended onto the surface.
+    pub blend: BlendMode,
+
+    /// Conversion of the layer texture colors to the surface backbuffer encoding.
+    pub transfer: Transfer,
+}
+
+impl LayerDraw {
+    /// Pixel shader constants of the layer.
+    pub fn shader_constants(&self) -> ShaderConstants {
+        ShaderConstants::new(self.blend, self.transfer, self.opacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use asdf_overlay_event::layout::{Anchor, FitMode, Length};
+
+    use super::*;
+    use crate::surface::hit_test::HitRect;
+
+    /// Layers with texture sizes as textures.
+    fn layers() -> Layers<(u32, u32)> {
+        Layers::new((100, 100))
+    }
+
+    fn ids<T>(layers: &Layers<T>) -> Vec<u32> {
+        layers.iter().map(|(id, _)| id).collect()
+    }
+
+    fn at(layers: &Layers<(u32, u32)>, x: i32, y: i32) -> Vec<(u32, (i32, i32))> {
+        layers
+            .at((800, 600), x, y, |layer| Some(*layer.texture()))
+            .map(|(id, _, point)| (id, point))
+            .collect()
+    }
+
+    #[test]
+    fn base_layer() {
+        let mut layers = layers();
+        assert_eq!(ids(&layers), [BASE_LAYER]);
+        assert_eq!(*layers.base().texture(), (100, 100));
+        assert!(layers.remove(BASE_LAYER).is_none());
+        assert_eq!(layers.len(), 1);
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut layers = layers();
+        assert_eq!(layers.update(2, || (10, 10), |_| ()), Some(()));
+        assert_eq!(layers.update(1, || (20, 20), |_| ()), Some(()));
+        assert_eq!(ids(&layers), [BASE_LAYER, 1, 2]);
+
+        // Existing layers keep their textures
+        layers.update(1, || unreachable!(), |_| ());
+        assert_eq!(*layers.get(1).unwrap().texture(), (20, 20));
+
+        assert_eq!(*layers.remove(1).unwrap().texture(), (20, 20));
+        assert!(layers.remove(1).is_none());
+        assert!(!layers.contains(1));
+        assert_eq!(ids(&layers), [BASE_LAYER, 2]);
+    }
+
+    #[test]
+    fn ordered_by_z_index_then_id() {
+        let mut layers = layers();
+        layers.update(1, || (0, 0), |layer| layer.z_index = 10);
+        layers.update(2, || (0, 0), |layer| layer.z_index = -1);
+        layers.update(3, || (0, 0), |_| ());
+        assert_eq!(ids(&layers), [2, BASE_LAYER, 3, 1]);
+
+        // Reordered after update
+        layers.update(1, || (0, 0), |layer| layer.z_index = -5);
+        assert_eq!(ids(&layers), [1, 2, BASE_LAYER, 3]);
+
+        layers.update(BASE_LAYER, || (0, 0), |layer| layer.z_index = 100);
+        assert_eq!(ids(&layers), [1, 2, 3, BASE_LAYER]);
+    }
+
+    #[test]
+    fn visibility() {
+        let mut layers = layers();
+        layers.update(1, || (0, 0), |layer| layer.visible = false);
+        layers.update(2, || (0, 0), |_| ());
+
+        let visible = layers.visible().map(|(id, _)| id).collect::<Vec<_>>();
+        assert_eq!(visible, [BASE_LAYER, 2]);
+        assert_eq!(ids(&layers), [BASE_LAYER, 1, 2]);
+    }
+
+    #[test]
+    fn max_layers() {
+        let mut layers = layers();
+        for id in 1..MAX_LAYERS as u32 {
+            assert!(layers.update(id, || (0, 0), |_| ()).is_some());
+        }
+        assert_eq!(layers.len(), MAX_LAYERS);
+
+        assert!(
+            layers
+                .update(MAX_LAYERS as u32, || (0, 0), |_| ())
+                .is_none()
+        );
+        assert!(!layers.contains(MAX_LAYERS as u32));
+
+        // Existing layers can still be updated
+        assert!(layers.update(1, || (0, 0), |_| ()).is_some());
+
+        layers.remove(1);
+        assert!(
+            layers
+                .update(MAX_LAYERS as u32, || (0, 0), |_| ())
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut layers = layers();
+        layers.update(1, || (0, 0), |_| ());
+        layers.update(
+            BASE_LAYER,
+            || (0, 0),
+            |layer| {
+                layer.visible = false;
+                layer.z_index = 3;
+                layer.hit_region = HitRegion::Full;
+            },
+        );
+
+        layers.reset();
+        assert_eq!(ids(&layers), [BASE_LAYER]);
+        let base = layers.base();
+        assert!(base.visible);
+        assert_eq!(base.z_index, 0);
+        assert!(matches!(base.hit_region, HitRegion::None));
+    }
+
+    #[test]
+    fn at_iterates_from_top_to_bottom() {
+        let mut layers = layers();
+        layers.update(
+            1,
+            || (50, 50),
+            |layer| {
+                layer.layout.x = Length::Pixels(25);
+                layer.layout.y = Length::Pixels(25);
+            },
+        );
+        layers.update(2, || (10, 10), |layer| layer.z_index = -1);
+
+        assert_eq!(at(&layers, 30, 30), [(1, (5, 5)), (BASE_LAYER, (30, 30))]);
+        assert_eq!(at(&layers, 5, 5), [(BASE_LAYER, (5, 5)), (2, (5, 5))]);
+        assert_eq!(at(&layers, 100, 100), []);
+        assert_eq!(at(&layers, -1, 0), []);
+
+        // Hidden layers are skipped
+        layers.update(1, || (0, 0), |layer| layer.visible = false);
+        assert_eq!(at(&layers, 30, 30), [(BASE_LAYER, (30, 30))]);
+    }
+
+    #[test]
+    fn at_maps_scaled_layers() {
+        let mut layers = Layers::new((100, 50));
+        layers.update(
+            BASE_LAYER,
+            || (0, 0),
+            |layer| {
+                layer.layout.anchor = Anchor::BottomRight;
+                layer.transform.fit = FitMode::Stretch;
+            },
+        );
+
+        // Drawn stretched to 800x600
+        assert_eq!(
+            layers
+                .at((800, 600), 400, 300, |layer| Some(*layer.texture()))
+                .map(|(id, _, point)| (id, point))
+                .collect::<Vec<_>>(),
+            [(BASE_LAYER, (50, 25))]
+        );
+
+        // Layers without texture are skipped
+        assert_eq!(layers.at((800, 600), 400, 300, |_| None).count(), 0);
+    }
+
+    #[test]
+    fn texture_point_outside_of_texture() {
+        let layer = Layer::new(());
+        let layout = Layout::absolute(10, 10);
+        let layer = Layer { layout, ..layer };
+
+        assert_eq!(
+            layer.texture_point((800, 600), (100, 100), 5, 200),
+            Some((-5, 190))
+        );
+        assert_eq!(layer.texture_point((800, 600), (0, 100), 5, 5), None);
+    }
+
+    #[test]
+    fn layer_hit_region() {
+        let mut layers = layers();
+        layers.update(
+            1,
+            || (50, 50),
+            |layer| {
+                layer.hit_region = HitRegion::Rects(vec![HitRect {
+                    x: 0,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                }]);
+            },
+        );
+
+        let hit = |x, y| {
+            layers
+                .at((800, 600), x, y, |layer| Some(*layer.texture()))
+                .any(|(_, layer, (x, y))| layer.hit_region.contains(x, y))
+        };
+        assert!(hit(5, 5));
+        assert!(!hit(20, 20));
+    }
+}
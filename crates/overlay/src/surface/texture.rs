@@ -8,22 +8,48 @@
 //! You can still have surface texture without keyed mutex,
 //! however you must flush it manually on changes and will have worse performance.
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::{
+    slice,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use anyhow::Context;
-use parking_lot::{RwLock, RwLockReadGuard};
+use asdf_overlay_bitmap_ring::{BitmapFormat, BitmapInfo};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard};
+use scopeguard::defer;
 use windows::{
     Win32::{
         Foundation::{CloseHandle, HANDLE},
         Graphics::{
-            Direct3D11::{D3D11_TEXTURE2D_DESC, ID3D11Device, ID3D11Device1, ID3D11Texture2D},
-            Dxgi::{Common::DXGI_FORMAT, IDXGIKeyedMutex},
+            Direct3D11::{
+                D3D11_BIND_SHADER_RESOURCE, D3D11_CPU_ACCESS_READ, D3D11_MAP_FLAG_DO_NOT_WAIT,
+                D3D11_MAP_READ, D3D11_MAPPED_SUBRESOURCE, D3D11_RESOURCE_MISC_SHARED_KEYEDMUTEX,
+                D3D11_SUBRESOURCE_DATA, D3D11_TEXTURE2D_DESC, D3D11_USAGE_DEFAULT,
+                D3D11_USAGE_STAGING, ID3D11Device, ID3D11Device1, ID3D11DeviceContext,
+                ID3D11Texture2D,
+            },
+            Dxgi::{
+                Common::{
+                    DXGI_FORMAT, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_B8G8R8A8_UNORM_SRGB,
+                    DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_FORMAT_R8G8B8A8_UNORM_SRGB, DXGI_SAMPLE_DESC,
+                },
+                DXGI_ERROR_WAS_STILL_DRAWING, IDXGIKeyedMutex, IDXGIResource,
+            },
         },
     },
     core::Interface,
 };
 
-use crate::surface::SharedTextureHandle;
+use crate::{
+    interop::DxInterop,
+    surface::{
+        SharedTextureHandle,
+        bitmap_shm::BitmapShm,
+        dirty::{DirtyRect, DirtyRegion, DirtyTracker},
+        hit_test::HitMask,
+    },
+    util::with_keyed_mutex,
+};
 
 /// Overlay surface texture.
 pub struct OverlaySurface {
@@ -68,6 +94,71 @@ impl OverlaySurface {
         }
     }
 
+    /// Create a shared texture with keyed mutex, initialized with `data`.
+    ///
+    /// Used for surfaces the overlay uploads by itself, so renderers can open it like client textures.
+    pub(crate) fn create(
+        device: &ID3D11Device,
+        size: (u32, u32),
+        format: DXGI_FORMAT,
+        data: &[u8],
+        row_pitch: u32,
+    ) -> anyhow::Result<Self> {
+        let mut texture = None;
+        unsafe {
+            device
+                .CreateTexture2D(
+                    &D3D11_TEXTURE2D_DESC {
+                        Width: size.0,
+                        Height: size.1,
+                        MipLevels: 1,
+                        ArraySize: 1,
+                        Format: format,
+                        SampleDesc: DXGI_SAMPLE_DESC {
+                            Count: 1,
+                            Quality: 0,
+                        },
+                        Usage: D3D11_USAGE_DEFAULT,
+                        BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as _,
+                        CPUAccessFlags: 0,
+                        MiscFlags: D3D11_RESOURCE_MISC_SHARED_KEYEDMUTEX.0 as _,
+                    },
+                    Some(&D3D11_SUBRESOURCE_DATA {
+                        pSysMem: data.as_ptr().cast(),
+                        SysMemPitch: row_pitch,
+                        SysMemSlicePitch: 0,
+                    }),
+                    Some(&mut texture),
+                )
+                .context("cannot create surface texture")?;
+            let texture = texture.context("cannot create surface texture")?;
+            let handle = texture.cast::<IDXGIResource>()?.GetSharedHandle()?;
+
+            Ok(Self {
+                mutex: Some(texture.cast::<IDXGIKeyedMutex>()?),
+                texture,
+                handle: SharedTextureHandle::Kmt(handle.0 as _),
+                size,
+                format,
+            })
+        }
+    }
+
+    /// Overwrite the texture with `data`, having the same size and format.
+    pub(crate) fn write(
+        &self,
+        cx: &ID3D11DeviceContext,
+        data: &[u8],
+        row_pitch: u32,
+    ) -> anyhow::Result<()> {
+        with_keyed_mutex(self.mutex(), || unsafe {
+            cx.UpdateSubresource(&self.texture, 0, None, data.as_ptr().cast(), row_pitch, 0);
+            cx.Flush();
+        })?;
+
+        Ok(())
+    }
+
     #[inline]
     /// [`IDXGIKeyedMutex`] of the surface texture.
     pub const fn mutex(&self) -> Option<&IDXGIKeyedMutex> {
@@ -109,8 +200,155 @@ impl Drop for OverlaySurface {
     }
 }
 
+/// Staging texture reused to read back surface textures.
+///
+/// Textures are copied on one frame and mapped on later frames, so readbacks never wait for the gpu.
+#[derive(Default)]
+pub(crate) struct AlphaReadback {
+    staging: Option<(ID3D11Texture2D, (u32, u32), DXGI_FORMAT)>,
+
+    /// Whether a copy to the staging texture is not mapped yet.
+    copying: bool,
+}
+
+impl AlphaReadback {
+    /// Staging texture matching size and format of the surface, created if needed.
+    fn staging(
+        &mut self,
+        interop: &DxInterop,
+        surface: &OverlaySurface,
+    ) -> anyhow::Result<ID3D11Texture2D> {
+        if let Some((ref staging, size, format)) = self.staging
+            && size == surface.size
+            && format == surface.format
+        {
+            return Ok(staging.clone());
+        }
+
+        let mut staging = None;
+        unsafe {
+            interop
+                .device
+                .CreateTexture2D(
+                    &D3D11_TEXTURE2D_DESC {
+                        Width: surface.size.0,
+                        Height: surface.size.1,
+                        MipLevels: 1,
+                        ArraySize: 1,
+                        Format: surface.format,
+                        SampleDesc: DXGI_SAMPLE_DESC {
+                            Count: 1,
+                            Quality: 0,
+                        },
+                        Usage: D3D11_USAGE_STAGING,
+                        BindFlags: 0,
+                        CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as _,
+                        MiscFlags: 0,
+                    },
+                    None,
+                    Some(&mut staging),
+                )
+                .context("cannot create staging texture")?;
+        }
+        let staging = staging.context("cannot create staging texture")?;
+
+        self.staging = Some((staging.clone(), surface.size, surface.format));
+        Ok(staging)
+    }
+
+    /// Whether a copied texture is waiting to be mapped.
+    #[inline]
+    pub const fn copying(&self) -> bool {
+        self.copying
+    }
+
+    /// Start copying the surface texture to the staging texture.
+    ///
+    /// Call [`AlphaReadback::try_alpha_mask`] on later frames to build the mask.
+    pub fn copy(&mut self, interop: &DxInterop, surface: &OverlaySurface) -> anyhow::Result<()> {
+        alpha_layout(surface.format)?;
+        let staging = self.staging(interop, surface)?;
+
+        let cx = interop.cx.lock();
+        with_keyed_mutex(surface.mutex(), || unsafe {
+            cx.CopyResource(&staging, &surface.texture);
+            cx.Flush();
+        })?;
+
+        self.copying = true;
+        Ok(())
+    }
+
+    /// Build [`HitMask`] from alpha channel of the copied texture.
+    ///
+    /// Returns [`None`] if nothing is copied or the gpu is still copying.
+    pub fn try_alpha_mask(
+        &mut self,
+        interop: &DxInterop,
+        threshold: u8,
+    ) -> anyhow::Result<Option<HitMask>> {
+        if !self.copying {
+            return Ok(None);
+        }
+        let Some((ref staging, size, format)) = self.staging else {
+            return Ok(None);
+        };
+        let (pixel_size, alpha_offset) = alpha_layout(format)?;
+
+        unsafe {
+            let cx = interop.cx.lock();
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            match cx.Map(
+                staging,
+                0,
+                D3D11_MAP_READ,
+                D3D11_MAP_FLAG_DO_NOT_WAIT.0 as _,
+                Some(&mut mapped),
+            ) {
+                Ok(_) => {}
+                Err(err) if err.code() == DXGI_ERROR_WAS_STILL_DRAWING => return Ok(None),
+                Err(err) => {
+                    self.copying = false;
+                    return Err(err.into());
+                }
+            }
+            self.copying = false;
+            defer!({
+                cx.Unmap(staging, 0);
+            });
+
+            let data = slice::from_raw_parts(
+                mapped.pData.cast::<u8>(),
+                mapped.RowPitch as usize * size.1 as usize,
+            );
+            Ok(Some(HitMask::from_alpha(
+                data,
+                size,
+                mapped.RowPitch as usize,
+                pixel_size,
+                alpha_offset,
+                threshold,
+            )))
+        }
+    }
+}
+
+/// Byte size of a pixel and byte offset of its alpha channel.
+fn alpha_layout(format: DXGI_FORMAT) -> anyhow::Result<(usize, usize)> {
+    match format {
+        DXGI_FORMAT_R8G8B8A8_UNORM
+        | DXGI_FORMAT_R8G8B8A8_UNORM_SRGB
+        | DXGI_FORMAT_B8G8R8A8_UNORM
+        | DXGI_FORMAT_B8G8R8A8_UNORM_SRGB => Ok((4, 3)),
+        format => anyhow::bail!("unsupported format for alpha mask: {format:?}"),
+    }
+}
+
 pub struct OverlayTextureSlot {
     inner: RwLock<Option<OverlaySurface>>,
+    /// Bitmap ring buffer the texture is uploaded from, if any.
+    bitmap: Mutex<Option<BitmapShm>>,
+    dirty: Mutex<DirtyTracker>,
     updated: AtomicBool,
 }
 
@@ -118,6 +356,8 @@ impl OverlayTextureSlot {
     pub(crate) const fn new() -> Self {
         Self {
             inner: RwLock::new(None),
+            bitmap: Mutex::new(None),
+            dirty: Mutex::new(DirtyTracker::new()),
             updated: AtomicBool::new(true),
         }
     }
@@ -130,6 +370,25 @@ impl OverlayTextureSlot {
     #[inline]
     pub fn invalidate(&self) {
         self.updated.store(true, Ordering::Relaxed);
+        self.dirty.lock().mark_full();
+    }
+
+    /// Mark regions of the texture changed.
+    /// Afterward, only invalidated regions are considered changed until another texture is committed.
+    pub(super) fn invalidate_rects(&self, rects: &[DirtyRect]) {
+        self.dirty.lock().mark(rects);
+    }
+
+    /// Take the region of the texture changed since the last call.
+    #[inline]
+    pub fn take_dirty(&self) -> DirtyRegion {
+        self.dirty.lock().take()
+    }
+
+    /// Mark the whole texture changed, so it is copied entirely on the next call to [`Self::take_dirty`].
+    #[inline]
+    pub(super) fn mark_dirty(&self) {
+        self.dirty.lock().mark_full();
     }
 
     pub(super) fn update(
@@ -138,6 +397,8 @@ impl OverlayTextureSlot {
         handle: Option<SharedTextureHandle>,
     ) -> anyhow::Result<()> {
         self.updated.store(true, Ordering::Relaxed);
+        self.dirty.lock().untrack();
+        *self.bitmap.lock() = None;
         let Some(handle) = handle else {
             *self.inner.write() = None;
             return Ok(());
@@ -147,6 +408,79 @@ impl OverlayTextureSlot {
         Ok(())
     }
 
+    /// Upload texture from the bitmap ring buffer in the named shared memory.
+    /// The texture is removed until the first frame is uploaded.
+    pub(super) fn update_bitmap_shm(&self, name: Option<&str>) -> anyhow::Result<()> {
+        let bitmap = name.map(BitmapShm::open).transpose()?;
+
+        self.updated.store(true, Ordering::Relaxed);
+        // Textures only change when frames are uploaded
+        self.dirty.lock().track();
+        *self.inner.write() = None;
+        *self.bitmap.lock() = bitmap;
+        Ok(())
+    }
+
+    /// Upload the latest frame of the bitmap ring buffer, if there is a new one.
+    ///
+    /// Skipped if the last frame is being consumed on another thread.
+    pub(super) fn poll_bitmap(&self, interop: &DxInterop) -> anyhow::Result<()> {
+        let Some(mut bitmap) = self.bitmap.try_lock() else {
+            return Ok(());
+        };
+        let Some(ref mut bitmap) = *bitmap else {
+            return Ok(());
+        };
+
+        if let Some((info, data)) = bitmap.poll()? {
+            self.upload(interop, info, data)?;
+            self.dirty.lock().mark_full();
+        }
+
+        Ok(())
+    }
+
+    /// Run closure with the last uploaded frame of the bitmap ring buffer,
+    /// on each call until it returns `true`.
+    pub(super) fn consume_bitmap(&self, f: impl FnOnce(&BitmapInfo, &[u8]) -> bool) {
+        if let Some(ref mut bitmap) = *self.bitmap.lock() {
+            bitmap.consume(f);
+        }
+    }
+
+    fn upload(&self, interop: &DxInterop, info: &BitmapInfo, data: &[u8]) -> anyhow::Result<()> {
+        let size = (info.width, info.height);
+        let format = match info.format {
+            BitmapFormat::Bgra8 => DXGI_FORMAT_B8G8R8A8_UNORM,
+            BitmapFormat::Rgba8 => DXGI_FORMAT_R8G8B8A8_UNORM,
+        };
+
+        let inner = self.inner.upgradable_read();
+        match *inner {
+            Some(ref surface) if surface.size == size && surface.format == format => {
+                surface.write(&interop.cx.lock(), data, info.stride)
+            }
+
+            _ => {
+                let surface = if size.0 == 0 || size.1 == 0 {
+                    None
+                } else {
+                    Some(OverlaySurface::create(
+                        &interop.device,
+                        size,
+                        format,
+                        data,
+                        info.stride,
+                    )?)
+                };
+
+                *RwLockUpgradableReadGuard::upgrade(inner) = surface;
+                self.updated.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+    }
+
     #[inline]
     pub fn take_update(&self) -> bool {
         self.updated.swap(false, Ordering::Relaxed)
@@ -53,7 +53,12 @@ pub(super) extern "system" fn present(
                 let physical_device = table.physical_device;
                 if let Err(err) = Surfaces::with(
                     data.surface.as_raw(),
-                    || setup_fn(physical_device, data),
+                    || {
+                        let window_id =
+                            get_surface_hwnd(data.surface).context("invalid surface handle")?;
+                        Ok((SurfaceType::Vulkan { window_id }, data.image_size))
+                    },
+                    |api, size| setup_fn(physical_device, data, api, size),
                     |backend| {
                         let semaphore = draw_overlay(
                             &table,
@@ -75,6 +80,8 @@ pub(super) extern "system" fn present(
                 ) {
                     error!("Vulkan overlay error. err: {err:?}");
                 }
+
+                Surfaces::presented(data.surface.as_raw());
             });
         }
 
@@ -95,19 +102,13 @@ pub(super) extern "system" fn present(
 fn setup_fn(
     physical_device: vk::PhysicalDevice,
     data: &SwapchainData,
+    api: SurfaceType,
+    size: (u32, u32),
 ) -> anyhow::Result<SurfaceState> {
-    let window_id = get_surface_hwnd(data.surface).context("invalid surface handle")?;
     let interop = DxInterop::new(get_dxgi_adapter(physical_device).as_ref())?;
     let gpu_id = interop.gpu_id;
 
-    SurfaceState::new(
-        interop,
-        data.image_size,
-        SurfaceInfo {
-            api: SurfaceType::Vulkan { window_id },
-            gpu_id,
-        },
-    )
+    SurfaceState::new(interop, size, data.backbuffer, SurfaceInfo { api, gpu_id })
 }
 
 fn get_dxgi_adapter(physical_device: vk::PhysicalDevice) -> Option<IDXGIAdapter> {
@@ -177,21 +178,28 @@ fn draw_overlay(
         }
     };
 
-    let Some(size) = state.texture_size() else {
-        return Ok(None);
-    };
+    renderer
+        .retain_textures(|id| state.contains_layer(id))
+        .context("dropping renderer textures")?;
+
+    let mut props = None;
+    state
+        .take_texture_updates(|id, surface| {
+            let props = props.get_or_insert_with(|| {
+                get_physical_device_memory_properties(table.physical_device).unwrap()
+            });
 
-    if state.texture.take_update() {
-        let props = get_physical_device_memory_properties(table.physical_device).unwrap();
+            renderer.update_texture(id, surface, props)
+        })
+        .context("updating renderer texture")?;
 
-        renderer
-            .update_texture(state.texture.get().as_ref(), data.format, &props)
-            .context("updating renderer texture")?;
+    let layers = state.layer_draws();
+    if layers.is_empty() {
+        return Ok(None);
     }
 
-    let position = state.position();
     let screen = state.size();
     renderer
-        .draw(queue, wait_semaphores, index, position, size, screen)
+        .draw(queue, wait_semaphores, index, screen, &layers)
         .context("renderer draw")
 }
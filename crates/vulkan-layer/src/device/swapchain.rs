@@ -1,5 +1,8 @@
 use asdf_overlay::{event_sink::OverlayEventSink, surface::Surfaces};
-use asdf_overlay_event::{Event, SurfaceEvent};
+use asdf_overlay_event::{
+    Event, SurfaceEvent,
+    backbuffer::{BackbufferInfo, ColorSpace, PixelFormat, PresentMode},
+};
 use ash::vk::{self, AllocationCallbacks, Handle, SurfaceKHR};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
@@ -18,6 +21,9 @@ pub struct SwapchainData {
     /// Format of the swapchain images.
     pub format: vk::Format,
 
+    /// Backbuffer properties of the swapchain.
+    pub backbuffer: BackbufferInfo,
+
     /// Vulkan overlay renderer
     pub(crate) renderer: Mutex<Option<VulkanRenderer>>,
 }
@@ -59,14 +65,33 @@ pub(super) extern "system" fn create_swapchain(
         return res;
     }
 
-    let swapchain = unsafe { *swapchain }.as_raw();
+    let swapchain = unsafe { *swapchain };
+
+    let mut image_count = 0;
+    unsafe {
+        _ = (DISPATCH_TABLE
+            .get(&device.as_raw())
+            .unwrap()
+            .swapchain_fn
+            .get_swapchain_images_khr)(device, swapchain, &mut image_count, 0 as _);
+    }
+
+    let backbuffer = BackbufferInfo {
+        format: map_vk_format(info.image_format),
+        color_space: map_vk_color_space(info.image_color_space),
+        // swapchain images are not multisampled
+        sample_count: 1,
+        buffer_count: image_count,
+        present_mode: map_vk_present_mode(info.present_mode),
+    };
 
     SWAPCHAIN_MAP.insert(
-        swapchain,
+        swapchain.as_raw(),
         SwapchainData {
             surface: info.surface,
             image_size: (info.image_extent.width, info.image_extent.height),
             format: info.image_format,
+            backbuffer,
             renderer: Mutex::new(None),
         },
     );
@@ -76,11 +101,13 @@ pub(super) extern "system" fn create_swapchain(
         let extent = info.image_extent;
 
         state.resize(extent.width, extent.height);
+        state.set_backbuffer(backbuffer);
         OverlayEventSink::emit(Event::Surface {
             id,
             event: SurfaceEvent::Resized {
                 width: extent.width,
                 height: extent.height,
+                backbuffer,
             },
         });
     });
@@ -102,12 +129,45 @@ pub(super) extern "system" fn destroy_swapchain(
     unsafe { (table.swapchain_fn.destroy_swapchain_khr)(device, swapchain, callback) }
 }
 
+fn map_vk_format(format: vk::Format) -> PixelFormat {
+    match format {
+        vk::Format::R8G8B8A8_UNORM => PixelFormat::Rgba8Unorm,
+        vk::Format::R8G8B8A8_SRGB => PixelFormat::Rgba8UnormSrgb,
+        vk::Format::B8G8R8A8_UNORM => PixelFormat::Bgra8Unorm,
+        vk::Format::B8G8R8A8_SRGB => PixelFormat::Bgra8UnormSrgb,
+        vk::Format::A2B10G10R10_UNORM_PACK32 => PixelFormat::Rgb10A2Unorm,
+        vk::Format::A2R10G10B10_UNORM_PACK32 => PixelFormat::Bgr10A2Unorm,
+        vk::Format::R16G16B16A16_SFLOAT => PixelFormat::Rgba16Float,
+        vk::Format::R5G6B5_UNORM_PACK16 => PixelFormat::B5G6R5Unorm,
+        _ => PixelFormat::Unknown,
+    }
+}
+
+fn map_vk_color_space(color_space: vk::ColorSpaceKHR) -> ColorSpace {
+    match color_space {
+        vk::ColorSpaceKHR::SRGB_NONLINEAR => ColorSpace::Srgb,
+        vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT => ColorSpace::ScRgb,
+        vk::ColorSpaceKHR::HDR10_ST2084_EXT => ColorSpace::Hdr10,
+        _ => ColorSpace::Unknown,
+    }
+}
+
+fn map_vk_present_mode(present_mode: vk::PresentModeKHR) -> PresentMode {
+    match present_mode {
+        vk::PresentModeKHR::IMMEDIATE => PresentMode::Immediate,
+        vk::PresentModeKHR::MAILBOX => PresentMode::Mailbox,
+        vk::PresentModeKHR::FIFO => PresentMode::Fifo { sync_interval: 1 },
+        vk::PresentModeKHR::FIFO_RELAXED => PresentMode::FifoRelaxed,
+        _ => PresentMode::Unknown,
+    }
+}
+
 fn cleanup_swapchain(swapchain: vk::SwapchainKHR) {
     let Some((_, data)) = SWAPCHAIN_MAP.remove(&swapchain.as_raw()) else {
         return;
     };
 
     Surfaces::state(data.surface.as_raw(), |state| {
-        state.texture.invalidate();
+        state.invalidate_textures();
     });
 }
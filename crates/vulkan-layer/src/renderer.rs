@@ -4,7 +4,13 @@ mod shaders;
 use core::mem;
 
 use anyhow::Context;
-use asdf_overlay::surface::{SharedTextureHandle, texture::OverlaySurface};
+use asdf_overlay::surface::{
+    SharedTextureHandle,
+    blend::BLEND_MODES,
+    layer::{LayerDraw, MAX_LAYERS},
+    texture::OverlaySurface,
+};
+use asdf_overlay_event::blend::BlendMode;
 use ash::{
     Device,
     vk::{self, Format},
@@ -26,12 +32,13 @@ pub struct VulkanRenderer {
     descriptor_pool: vk::DescriptorPool,
     sampler: vk::Sampler,
     texture_layout: vk::DescriptorSetLayout,
-    texture_descriptor_set: vk::DescriptorSet,
-    texture: Option<(vk::DeviceMemory, vk::Image, vk::ImageView)>,
+    /// Layer textures keyed by layer id.
+    textures: Vec<(u32, VulkanTexture)>,
 
     pipeline_layout: vk::PipelineLayout,
     render_pass: vk::RenderPass,
-    pipeline: vk::Pipeline,
+    /// Pipelines indexed by blend mode.
+    pipelines: [vk::Pipeline; 3],
 
     frame_datas: Vec<FrameData>,
 }
@@ -48,12 +55,13 @@ impl VulkanRenderer {
         let descriptor_pool = create_descriptor_pool(&device)?;
         let sampler = create_sampler(&device)?;
         let texture_layout = create_texture_layout(&device, sampler)?;
-        let texture_descriptor_set =
-            create_texture_descriptor_set(&device, descriptor_pool, texture_layout)?;
 
         let pipeline_layout = create_pipeline_layout(&device, texture_layout)?;
         let render_pass = create_render_pass(&device, format)?;
-        let pipeline = create_pipeline(&device, image_size, pipeline_layout, render_pass)?;
+        let mut pipelines = [vk::Pipeline::null(); 3];
+        for (pipeline, mode) in pipelines.iter_mut().zip(BLEND_MODES) {
+            *pipeline = create_pipeline(&device, image_size, pipeline_layout, render_pass, mode)?;
+        }
         let mut frame_data = Vec::with_capacity(swapchain_images.len());
         for &swapchain_image in swapchain_images {
             frame_data.push(
@@ -75,31 +83,29 @@ impl VulkanRenderer {
             descriptor_pool,
             sampler,
             texture_layout,
-            texture_descriptor_set,
-            texture: None,
+            textures: Vec::new(),
 
             pipeline_layout,
             render_pass,
-            pipeline,
+            pipelines,
 
             frame_datas: frame_data,
         })
     }
 
-    /// Update renderer overlay texture using given shared Direct3D11 texture.
+    /// Update renderer texture of the layer using given shared Direct3D11 texture.
     pub fn update_texture(
         &mut self,
+        id: u32,
         surface: Option<&OverlaySurface>,
-        screen_format: vk::Format,
         props: &vk::PhysicalDeviceMemoryProperties,
     ) -> anyhow::Result<()> {
         unsafe {
-            if let Some((memory, image, view)) = self.texture.take() {
+            if let Some(index) = self.textures.iter().position(|(layer, _)| *layer == id) {
                 self.device.device_wait_idle()?;
 
-                self.device.destroy_image_view(view, None);
-                self.device.destroy_image(image, None);
-                self.device.free_memory(memory, None);
+                let (_, texture) = self.textures.remove(index);
+                self.destroy_texture(texture);
             }
 
             let Some(surface) = surface else {
@@ -112,7 +118,7 @@ impl VulkanRenderer {
                 SharedTextureHandle::Kmt(_) => vk::ExternalMemoryHandleTypeFlags::D3D11_TEXTURE_KMT,
                 SharedTextureHandle::Nt(_) => vk::ExternalMemoryHandleTypeFlags::D3D11_TEXTURE,
             };
-            let format = map_dxgi_format_to_vk(surface.format(), screen_format)
+            let format = map_dxgi_format_to_vk(surface.format())
                 .context("unsupported DXGI format for overlay texture")?;
 
             let mut external_memory_image_info =
@@ -208,34 +214,115 @@ impl VulkanRenderer {
                 None,
             )?;
 
+            let descriptor_set = match create_texture_descriptor_set(
+                &self.device,
+                self.descriptor_pool,
+                self.texture_layout,
+            ) {
+                Ok(set) => set,
+                Err(err) => {
+                    self.destroy_texture(VulkanTexture {
+                        memory,
+                        image,
+                        view,
+                        descriptor_set: vk::DescriptorSet::null(),
+                    });
+                    return Err(err);
+                }
+            };
+
             let image_info = [vk::DescriptorImageInfo::default()
                 .image_view(view)
                 .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)];
             let descriptor_writes = [vk::WriteDescriptorSet::default()
-                .dst_set(self.texture_descriptor_set)
+                .dst_set(descriptor_set)
                 .dst_binding(0)
                 .descriptor_count(1)
                 .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
                 .image_info(&image_info)];
             self.device.update_descriptor_sets(&descriptor_writes, &[]);
 
-            self.texture = Some((memory, image, view));
+            self.textures.push((
+                id,
+                VulkanTexture {
+                    memory,
+                    image,
+                    view,
+                    descriptor_set,
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Drop textures of layers not matching the predicate.
+    pub fn retain_textures(&mut self, mut f: impl FnMut(u32) -> bool) -> anyhow::Result<()> {
+        if self.textures.iter().all(|(id, _)| f(*id)) {
+            return Ok(());
+        }
+
+        unsafe {
+            self.device.device_wait_idle()?;
         }
 
+        for (id, texture) in mem::take(&mut self.textures) {
+            if f(id) {
+                self.textures.push((id, texture));
+            } else {
+                self.destroy_texture(texture);
+            }
+        }
         Ok(())
     }
 
-    /// Draw overlay.
+    /// Destroy the texture. The texture must not be in use.
+    fn destroy_texture(&self, texture: VulkanTexture) {
+        unsafe {
+            if texture.descriptor_set != vk::DescriptorSet::null() {
+                _ = self
+                    .device
+                    .free_descriptor_sets(self.descriptor_pool, &[texture.descriptor_set]);
+            }
+
+            self.device.destroy_image_view(texture.view, None);
+            self.device.destroy_image(texture.image, None);
+            self.device.free_memory(texture.memory, None);
+        }
+    }
+
+    /// Draw overlay layers from bottom to top.
     pub fn draw(
         &mut self,
         queue: vk::Queue,
         wait_semaphores: &[vk::Semaphore],
         index: u32,
-        position: (i32, i32),
-        size: (u32, u32),
         screen: (u32, u32),
+        layers: &[LayerDraw],
     ) -> anyhow::Result<Option<vk::Semaphore>> {
-        if self.texture.is_none() {
+        let textures = layers
+            .iter()
+            .filter_map(|layer| {
+                let (_, texture) = self.textures.iter().find(|(id, _)| *id == layer.id)?;
+                Some((layer, texture.descriptor_set))
+            })
+            .collect::<Vec<_>>();
+
+        // Only render area covering every layer
+        let Some(render_area) = textures
+            .iter()
+            .map(|(layer, _)| {
+                let placement = layer.placement;
+                (
+                    placement.x.max(0),
+                    placement.y.max(0),
+                    (placement.x + placement.width as i32).min(screen.0 as i32),
+                    (placement.y + placement.height as i32).min(screen.1 as i32),
+                )
+            })
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+            .filter(|area| area.0 < area.2 && area.1 < area.3)
+        else {
             return Ok(None);
         };
 
@@ -248,12 +335,6 @@ impl VulkanRenderer {
             self.device.reset_fences(&[frame_data.fence])?;
         }
 
-        let rect: [f32; 4] = [
-            (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
-            (position.1 as f32 / screen.1 as f32) * 2.0 - 1.0,
-            (size.0 as f32 / screen.0 as f32) * 2.0,
-            (size.1 as f32 / screen.1 as f32) * 2.0,
-        ];
         let command_buffer = frame_data.command_buffer;
         unsafe {
             self.device.begin_command_buffer(
@@ -262,50 +343,61 @@ impl VulkanRenderer {
                     .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
             )?;
 
-            let offset = vk::Offset2D {
-                x: position.0.max(0),
-                y: position.1.max(0),
-            };
             self.device.cmd_begin_render_pass(
                 command_buffer,
                 &vk::RenderPassBeginInfo::default()
                     .render_pass(self.render_pass)
                     .framebuffer(frame_data.framebuffer)
                     .render_area(vk::Rect2D {
-                        offset,
+                        offset: vk::Offset2D {
+                            x: render_area.0,
+                            y: render_area.1,
+                        },
                         extent: vk::Extent2D {
-                            width: size.0.min(screen.0 - offset.x as u32),
-                            height: size.1.min(screen.1 - offset.y as u32),
+                            width: (render_area.2 - render_area.0) as u32,
+                            height: (render_area.3 - render_area.1) as u32,
                         },
                     }),
                 vk::SubpassContents::INLINE,
             );
 
-            let descriptor_sets = [self.texture_descriptor_set];
-            self.device.cmd_bind_descriptor_sets(
-                command_buffer,
-                vk::PipelineBindPoint::GRAPHICS,
-                self.pipeline_layout,
-                0,
-                &descriptor_sets,
-                &[],
-            );
+            for (layer, descriptor_set) in textures {
+                let (position, size) = (layer.placement.position(), layer.placement.size());
+                // Overlay rect followed by fragment shader constants
+                let mut constants = [0.0_f32; 16];
+                constants[..4].copy_from_slice(&[
+                    (position.0 as f32 / screen.0 as f32) * 2.0 - 1.0,
+                    (position.1 as f32 / screen.1 as f32) * 2.0 - 1.0,
+                    (size.0 as f32 / screen.0 as f32) * 2.0,
+                    (size.1 as f32 / screen.1 as f32) * 2.0,
+                ]);
+                constants[4..].copy_from_slice(&layer.shader_constants().as_array());
+
+                self.device.cmd_bind_pipeline(
+                    command_buffer,
+                    vk::PipelineBindPoint::GRAPHICS,
+                    self.pipelines[layer.blend as usize],
+                );
 
-            self.device.cmd_bind_pipeline(
-                command_buffer,
-                vk::PipelineBindPoint::GRAPHICS,
-                self.pipeline,
-            );
+                self.device.cmd_bind_descriptor_sets(
+                    command_buffer,
+                    vk::PipelineBindPoint::GRAPHICS,
+                    self.pipeline_layout,
+                    0,
+                    &[descriptor_set],
+                    &[],
+                );
 
-            self.device.cmd_push_constants(
-                command_buffer,
-                self.pipeline_layout,
-                vk::ShaderStageFlags::VERTEX,
-                0,
-                bytemuck::cast_slice(&rect),
-            );
+                self.device.cmd_push_constants(
+                    command_buffer,
+                    self.pipeline_layout,
+                    vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+                    0,
+                    bytemuck::cast_slice(&constants),
+                );
 
-            self.device.cmd_draw(command_buffer, 4, 1, 0, 0);
+                self.device.cmd_draw(command_buffer, 4, 1, 0, 0);
+            }
 
             self.device.cmd_end_render_pass(command_buffer);
 
@@ -330,48 +422,29 @@ impl VulkanRenderer {
     }
 }
 
-fn map_dxgi_format_to_vk(format: DXGI_FORMAT, screen_format: vk::Format) -> Option<vk::Format> {
-    // All Surface is gamma corrected, so we need to check if the screen is gamma corrected or not.
-
-    match (format, is_srgb(screen_format)) {
-        // Both screen and surface are SRGB. Use SRGB format to avoid double gamma correction.
-        (DXGI_FORMAT_R8G8B8A8_UNORM, true) => Some(vk::Format::R8G8B8A8_SRGB),
-        (DXGI_FORMAT_B8G8R8A8_UNORM, true) => Some(vk::Format::B8G8R8A8_SRGB),
-
-        // Screen is not SRGB. Use UNORM format to render gamma corrected.
-        (DXGI_FORMAT_R8G8B8A8_UNORM_SRGB, false) => Some(vk::Format::R8G8B8A8_UNORM),
-        (DXGI_FORMAT_B8G8R8A8_UNORM_SRGB, false) => Some(vk::Format::B8G8R8A8_UNORM),
+/// Overlay layer texture imported from shared Direct3D11 texture.
+struct VulkanTexture {
+    memory: vk::DeviceMemory,
+    image: vk::Image,
+    view: vk::ImageView,
+    descriptor_set: vk::DescriptorSet,
+}
 
-        (DXGI_FORMAT_R8G8B8A8_UNORM, _) => Some(vk::Format::R8G8B8A8_UNORM),
-        (DXGI_FORMAT_B8G8R8A8_UNORM, _) => Some(vk::Format::B8G8R8A8_UNORM),
-        (DXGI_FORMAT_R8G8B8A8_UNORM_SRGB, _) => Some(vk::Format::R8G8B8A8_SRGB),
-        (DXGI_FORMAT_B8G8R8A8_UNORM_SRGB, _) => Some(vk::Format::B8G8R8A8_SRGB),
-        (DXGI_FORMAT_R16G16B16A16_UNORM, _) => Some(vk::Format::R16G16B16A16_UNORM),
-        (DXGI_FORMAT_R16G16B16A16_FLOAT, _) => Some(vk::Format::R16G16B16A16_SFLOAT),
+fn map_dxgi_format_to_vk(format: DXGI_FORMAT) -> Option<vk::Format> {
+    // sRGB textures are sampled without decoding, shaders convert colors to the screen encoding.
+    match format {
+        DXGI_FORMAT_R8G8B8A8_UNORM | DXGI_FORMAT_R8G8B8A8_UNORM_SRGB => {
+            Some(vk::Format::R8G8B8A8_UNORM)
+        }
+        DXGI_FORMAT_B8G8R8A8_UNORM | DXGI_FORMAT_B8G8R8A8_UNORM_SRGB => {
+            Some(vk::Format::B8G8R8A8_UNORM)
+        }
+        DXGI_FORMAT_R16G16B16A16_UNORM => Some(vk::Format::R16G16B16A16_UNORM),
+        DXGI_FORMAT_R16G16B16A16_FLOAT => Some(vk::Format::R16G16B16A16_SFLOAT),
         _ => None,
     }
 }
 
-fn is_srgb(foramt: vk::Format) -> bool {
-    matches!(
-        foramt,
-        vk::Format::R8_SRGB
-            | vk::Format::R8G8_SRGB
-            | vk::Format::R8G8B8_SRGB
-            | vk::Format::B8G8R8_SRGB
-            | vk::Format::R8G8B8A8_SRGB
-            | vk::Format::B8G8R8A8_SRGB
-            | vk::Format::BC1_RGB_SRGB_BLOCK
-            | vk::Format::BC1_RGBA_SRGB_BLOCK
-            | vk::Format::BC2_SRGB_BLOCK
-            | vk::Format::BC3_SRGB_BLOCK
-            | vk::Format::BC7_SRGB_BLOCK
-            | vk::Format::ETC2_R8G8B8_SRGB_BLOCK
-            | vk::Format::ETC2_R8G8B8A1_SRGB_BLOCK
-            | vk::Format::ETC2_R8G8B8A8_SRGB_BLOCK
-    )
-}
-
 impl Drop for VulkanRenderer {
     fn drop(&mut self) {
         unsafe {
@@ -390,16 +463,16 @@ impl Drop for VulkanRenderer {
                 self.device.destroy_image_view(frame_data.view, None);
             }
 
+            for (_, texture) in mem::take(&mut self.textures) {
+                self.destroy_texture(texture);
+            }
+
             self.device
                 .destroy_descriptor_pool(self.descriptor_pool, None);
 
-            if let Some((memory, image, view)) = self.texture {
-                self.device.destroy_image_view(view, None);
-                self.device.destroy_image(image, None);
-                self.device.free_memory(memory, None);
+            for pipeline in self.pipelines {
+                self.device.destroy_pipeline(pipeline, None);
             }
-
-            self.device.destroy_pipeline(self.pipeline, None);
             self.device.destroy_render_pass(self.render_pass, None);
             self.device
                 .destroy_pipeline_layout(self.pipeline_layout, None);
@@ -416,10 +489,11 @@ fn create_descriptor_pool(device: &Device) -> anyhow::Result<vk::DescriptorPool>
         device
             .create_descriptor_pool(
                 &vk::DescriptorPoolCreateInfo::default()
-                    .max_sets(1)
+                    .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+                    .max_sets(MAX_LAYERS as _)
                     .pool_sizes(&[vk::DescriptorPoolSize::default()
                         .ty(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
-                        .descriptor_count(1)]),
+                        .descriptor_count(MAX_LAYERS as _)]),
                 None,
             )
             .context("failed to create DescriptorPool")
@@ -526,9 +600,9 @@ fn create_pipeline_layout(
     unsafe {
         let set_layouts = [texture_layout];
         let push_constants_ranges = [vk::PushConstantRange {
-            stage_flags: vk::ShaderStageFlags::VERTEX,
+            stage_flags: vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
             offset: 0,
-            size: mem::size_of::<[f32; 4]>() as _,
+            size: mem::size_of::<[f32; 16]>() as _,
         }];
 
         device
@@ -548,6 +622,7 @@ fn create_pipeline(
     size: (u32, u32),
     layout: vk::PipelineLayout,
     render_pass: vk::RenderPass,
+    mode: BlendMode,
 ) -> anyhow::Result<vk::Pipeline> {
     unsafe {
         let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default();
@@ -579,13 +654,34 @@ fn create_pipeline(
             .rasterization_samples(vk::SampleCountFlags::TYPE_1);
         let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfo::default();
 
+        let (src, dst, src_alpha, dst_alpha) = match mode {
+            BlendMode::Straight => (
+                vk::BlendFactor::SRC_ALPHA,
+                vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+                vk::BlendFactor::ONE,
+                vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Premultiplied => (
+                vk::BlendFactor::ONE,
+                vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+                vk::BlendFactor::ONE,
+                vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Additive => (
+                vk::BlendFactor::SRC_ALPHA,
+                vk::BlendFactor::ONE,
+                vk::BlendFactor::ZERO,
+                vk::BlendFactor::ONE,
+            ),
+        };
         let color_blend_attachments = [vk::PipelineColorBlendAttachmentState::default()
             .blend_enable(true)
-            .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
-            .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+            .src_color_blend_factor(src)
+            .dst_color_blend_factor(dst)
             .color_blend_op(vk::BlendOp::ADD)
-            .src_alpha_blend_factor(vk::BlendFactor::ONE)
-            .dst_alpha_blend_factor(vk::BlendFactor::ZERO)
+            .src_alpha_blend_factor(src_alpha)
+            .dst_alpha_blend_factor(dst_alpha)
+            .alpha_blend_op(vk::BlendOp::ADD)
             .color_write_mask(vk::ColorComponentFlags::RGBA)];
         let color_blend_state =
             vk::PipelineColorBlendStateCreateInfo::default().attachments(&color_blend_attachments);
@@ -5,7 +5,30 @@ layout(location = 0) in vec2 TexCoord;
 
 layout(binding = 0) uniform sampler2D tex;
 
+layout(push_constant) uniform constants
+{
+	// opacity, color scale, unpremultiply
+	layout(offset = 16) vec4 params;
+	// transfer function, x <= t ? x * a : pow(x * b + c, g) * d + e
+	// t, a, b, c
+	vec4 transfer0;
+	// g, d, e
+	vec4 transfer1;
+} PushConstants;
+
+vec3 transfer(vec3 x)
+{
+    x = max(x, 0.0);
+    vec3 curve = pow(x * PushConstants.transfer0.z + PushConstants.transfer0.w, vec3(PushConstants.transfer1.x))
+        * PushConstants.transfer1.y + PushConstants.transfer1.z;
+    return mix(curve, x * PushConstants.transfer0.y, lessThanEqual(x, vec3(PushConstants.transfer0.x)));
+}
+
 void main()
 {
-    FragColor = vec4(texture(tex, TexCoord));
+    vec4 color = texture(tex, TexCoord);
+    float k = PushConstants.params.z != 0.0 && color.a > 0.0 ? color.a : 1.0;
+    color.rgb = transfer(color.rgb / k) * k * PushConstants.params.y;
+    color.a *= PushConstants.params.x;
+    FragColor = color;
 }
@@ -0,0 +1,209 @@
+//! Rules for ignoring irrelevant windows.
+
+use crate::{WindowInfo, WindowStyle};
+
+/// Describe rules for ignoring windows.
+///
+/// Ignored windows are not tracked, so no event is sent for them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowFilter {
+    /// Window class names to ignore, compared case-insensitively.
+    pub ignore_classes: Vec<String>,
+
+    /// Title pattern windows must match.
+    ///
+    /// `*` matches any sequence of characters and `?` matches any single character.
+    /// Compared case-insensitively. If [`None`] is given, titles are not checked.
+    pub title_pattern: Option<String>,
+
+    /// Minimum client area width.
+    pub min_width: u32,
+
+    /// Minimum client area height.
+    pub min_height: u32,
+
+    /// Whether to ignore child windows.
+    pub ignore_child: bool,
+
+    /// Whether to ignore windows owned by another window.
+    pub ignore_owned: bool,
+}
+
+impl WindowFilter {
+    /// Check if the window should be ignored.
+    pub fn ignores(&self, info: &WindowInfo, width: u32, height: u32) -> bool {
+        if width < self.min_width || height < self.min_height {
+            return true;
+        }
+
+        if self.ignore_child && info.style.contains(WindowStyle::CHILD) {
+            return true;
+        }
+
+        if self.ignore_owned && info.style.contains(WindowStyle::OWNED) {
+            return true;
+        }
+
+        if self
+            .ignore_classes
+            .iter()
+            .any(|class| class.eq_ignore_ascii_case(&info.class_name))
+        {
+            return true;
+        }
+
+        if let Some(ref pattern) = self.title_pattern
+            && !glob_match(&pattern.to_lowercase(), &info.title.to_lowercase())
+        {
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Match text against a glob pattern supporting `*` and `?`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in pattern and text position it matched to
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+
+            Some(&ch) if ch == '?' || ch == text[t] => {
+                p += 1;
+                t += 1;
+            }
+
+            _ => {
+                // Backtrack by extending the last `*` by one character
+                let Some((star_p, star_t)) = star else {
+                    return false;
+                };
+
+                star = Some((star_p, star_t + 1));
+                p = star_p + 1;
+                t = star_t + 1;
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(class_name: &str, title: &str, style: WindowStyle) -> WindowInfo {
+        WindowInfo {
+            title: title.to_string(),
+            class_name: class_name.to_string(),
+            style,
+            thread_id: 1,
+            scale: 1.0,
+            monitor: None,
+            focused: false,
+        }
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("abc", "abc"));
+        assert!(!glob_match("abc", "abcd"));
+        assert!(!glob_match("abcd", "abc"));
+
+        assert!(glob_match("a?c", "abc"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("???", "한글a"));
+
+        assert!(glob_match("a*", "a"));
+        assert!(glob_match("*c", "abc"));
+        assert!(glob_match("a*c", "abbbc"));
+        assert!(!glob_match("a*c", "abcb"));
+        assert!(glob_match("**a**", "bab"));
+        assert!(glob_match("*?", "a"));
+        assert!(!glob_match("*?", ""));
+    }
+
+    #[test]
+    fn glob_backtrack() {
+        assert!(glob_match("*ab*ab", "aabxabab"));
+        assert!(glob_match("a*b*c", "abxbxc"));
+        assert!(!glob_match("a*b*c", "abxbx"));
+        assert!(glob_match("*.exe", "game.exe.exe"));
+    }
+
+    #[test]
+    fn default_ignores_nothing() {
+        let filter = WindowFilter::default();
+        assert!(!filter.ignores(&info("", "", WindowStyle::all()), 0, 0));
+    }
+
+    #[test]
+    fn ignores_small() {
+        let filter = WindowFilter {
+            min_width: 100,
+            min_height: 50,
+            ..Default::default()
+        };
+        let info = info("Class", "Title", WindowStyle::empty());
+
+        assert!(!filter.ignores(&info, 100, 50));
+        assert!(filter.ignores(&info, 99, 50));
+        assert!(filter.ignores(&info, 100, 49));
+    }
+
+    #[test]
+    fn ignores_styles() {
+        let filter = WindowFilter {
+            ignore_child: true,
+            ignore_owned: true,
+            ..Default::default()
+        };
+
+        assert!(!filter.ignores(&info("", "", WindowStyle::FULLSCREEN), 0, 0));
+        assert!(filter.ignores(&info("", "", WindowStyle::CHILD), 0, 0));
+        assert!(filter.ignores(&info("", "", WindowStyle::OWNED), 0, 0));
+
+        let filter = WindowFilter::default();
+        assert!(!filter.ignores(&info("", "", WindowStyle::CHILD), 0, 0));
+        assert!(!filter.ignores(&info("", "", WindowStyle::OWNED), 0, 0));
+    }
+
+    #[test]
+    fn ignores_classes() {
+        let filter = WindowFilter {
+            ignore_classes: vec!["ConsoleWindowClass".to_string()],
+            ..Default::default()
+        };
+
+        assert!(filter.ignores(&info("consolewindowclass", "", WindowStyle::empty()), 0, 0));
+        assert!(!filter.ignores(&info("ConsoleWindow", "", WindowStyle::empty()), 0, 0));
+    }
+
+    #[test]
+    fn ignores_title() {
+        let filter = WindowFilter {
+            title_pattern: Some("My Game*".to_string()),
+            ..Default::default()
+        };
+
+        assert!(!filter.ignores(&info("", "my game - 1.0", WindowStyle::empty()), 0, 0));
+        assert!(!filter.ignores(&info("", "MY GAME", WindowStyle::empty()), 0, 0));
+        assert!(filter.ignores(&info("", "Launcher", WindowStyle::empty()), 0, 0));
+        assert!(filter.ignores(&info("", "", WindowStyle::empty()), 0, 0));
+    }
+}
@@ -2,6 +2,12 @@
 //!
 //! Raw inputs are not handled here, as they can be listened directly.
 
+mod gamepad;
+mod key_code;
+
+pub use gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadInput};
+pub use key_code::{KeyCode, ParseKeyCodeError};
+
 use core::num::NonZeroU8;
 
 /// Describe an input event captured from a window.
@@ -12,6 +18,14 @@ pub enum InputEvent {
     Cursor(CursorInput),
     /// A keyboard input.
     Keyboard(KeyboardInput),
+    /// A touch input.
+    Touch(TouchInput),
+    /// A pen input.
+    Pen(PenInput),
+    /// A gamepad input.
+    ///
+    /// Gamepad inputs are sent to the window of the thread polling gamepads.
+    Gamepad(GamepadInput),
 }
 
 /// Describe a cursor related input.
@@ -22,6 +36,92 @@ pub struct CursorInput {
     pub event: CursorEvent,
     /// Cursor position relative to the left-top corner client area of the window.
     pub pos: InputPosition,
+    /// Modifier key states when the input occurred.
+    pub modifiers: Modifiers,
+    /// Cursor buttons held down after the input occurred.
+    pub buttons: CursorButtons,
+}
+
+/// Describe a touch contact input.
+///
+/// Note that the system also sends cursor inputs converted from the primary touch contact.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchInput {
+    /// Identifier of the touch contact.
+    ///
+    /// The identifier is unique while the contact is alive, and may be reused afterward.
+    pub pointer_id: u32,
+    /// The phase of the touch contact.
+    pub phase: PointerPhase,
+    /// Whether if this is the first contact of multiple touch contacts.
+    pub primary: bool,
+    /// Touch position relative to the left-top corner client area of the window.
+    pub pos: InputPosition,
+    /// Normalized pressure in `0.0..=1.0` range, if the device reports it.
+    pub pressure: Option<f32>,
+}
+
+/// Describe a pen input.
+///
+/// Note that the system also sends cursor inputs converted from the pen input.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenInput {
+    /// Identifier of the pen.
+    pub pointer_id: u32,
+    /// The phase of the pen input.
+    pub phase: PointerPhase,
+    /// Pen position relative to the left-top corner client area of the window.
+    pub pos: InputPosition,
+    /// Normalized pressure in `0.0..=1.0` range, if the device reports it.
+    pub pressure: Option<f32>,
+    /// Tilt of the pen, if the device reports it.
+    pub tilt: Option<PenTilt>,
+    /// Whether if the eraser end of the pen is used.
+    pub eraser: bool,
+    /// Whether if the barrel button of the pen is pressed.
+    pub barrel: bool,
+}
+
+/// Describe tilt of a pen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenTilt {
+    /// Angle between the Y-Z plane and the pen in degrees, in `-90..=90` range.
+    ///
+    /// Positive value means tilted toward right.
+    pub x: i32,
+
+    /// Angle between the X-Z plane and the pen in degrees, in `-90..=90` range.
+    ///
+    /// Positive value means tilted toward user.
+    pub y: i32,
+}
+
+/// Describe the phase of a touch or pen input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerPhase {
+    /// Pointer entered detection range of the window, such as a pen hovering.
+    Enter,
+
+    /// Pointer made contact.
+    Down,
+
+    /// Pointer moved while in range.
+    Move,
+
+    /// Pointer contact is lifted.
+    Up,
+
+    /// Pointer left detection range of the window.
+    Leave,
+
+    /// Pointer input is canceled by the system.
+    ///
+    /// You should discard any action made by the pointer.
+    Cancel,
 }
 
 /// Describe a cursor event.
@@ -48,6 +148,19 @@ pub enum CursorEvent {
     /// Cursor is moved.
     Move,
 
+    /// Mouse is moved, reported as relative motion from raw inputs.
+    ///
+    /// Unlike [`CursorEvent::Move`], this is still reported when the cursor is locked in place,
+    /// and is not affected by cursor speed or acceleration settings.
+    /// Only sent if the window is listening to raw motion.
+    RawMotion {
+        /// Horizontal motion. Positive value means right.
+        dx: i32,
+
+        /// Vertical motion. Positive value means down.
+        dy: i32,
+    },
+
     /// Wheel is scrolled.
     Scroll {
         /// The axis of the scroll.
@@ -88,8 +201,31 @@ pub enum KeyboardInput {
         /// The key code of the input.
         key: Key,
 
+        /// The physical location of the key, regardless of the keyboard layout.
+        physical_key: KeyCode,
+
+        /// The hardware scan code of the key.
+        ///
+        /// Extended keys have `0xE0` prefix in the high byte. (e.g. `0xE01D` for the right Ctrl key)
+        scan_code: u16,
+
         /// The state of the key input.
         state: KeyInputState,
+
+        /// Whether if this input is generated by holding the key down.
+        ///
+        /// Always false for [`KeyInputState::Released`] state.
+        repeat: bool,
+
+        /// The number of times the keystroke is repeated as a result of holding the key down.
+        ///
+        /// This is usually 1, but may be larger if the repeated keystrokes are coalesced.
+        repeat_count: u16,
+
+        /// Modifier key states after the key input occurred.
+        ///
+        /// Pressing a modifier key includes the key itself.
+        modifiers: Modifiers,
     },
 
     /// A character input without involving IME.
@@ -105,6 +241,8 @@ pub enum KeyboardInput {
 }
 
 /// Describe a virtual key code.
+///
+/// Convert to [`KeyCode`] for a platform independent representation.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Key {
@@ -126,6 +264,28 @@ impl Key {
     }
 }
 
+/// Describe a key combination which ends input blocking when pressed.
+///
+/// Ctrl, Shift and Alt must match exactly. For example, a chord with only `ctrl`
+/// is not triggered while Shift is also held down. Windows keys are not checked.
+///
+/// The chord does not reach to the window, including key releases after input blocking ends.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EscapeChord {
+    /// Whether if either Ctrl key should be held down.
+    pub ctrl: bool,
+
+    /// Whether if either Shift key should be held down.
+    pub shift: bool,
+
+    /// Whether if either Alt key should be held down.
+    pub alt: bool,
+
+    /// The key which triggers the chord when pressed.
+    pub key: Key,
+}
+
 /// Describe a mouse button.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -247,6 +407,116 @@ pub struct ImeCandidateList {
     pub candidates: Vec<String>,
 }
 
+bitflags::bitflags! {
+    /// Describe modifier key states.
+    ///
+    /// Left and right variants are tracked separately.
+    /// Use [`Modifiers::shift`], [`Modifiers::ctrl`], [`Modifiers::alt`] and [`Modifiers::win`] to check either side.
+    #[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+    pub struct Modifiers: u16 {
+        /// Left Shift key is held down.
+        const LSHIFT = 1;
+
+        /// Right Shift key is held down.
+        const RSHIFT = 1 << 1;
+
+        /// Left Ctrl key is held down.
+        const LCTRL = 1 << 2;
+
+        /// Right Ctrl key is held down.
+        const RCTRL = 1 << 3;
+
+        /// Left Alt key is held down.
+        const LALT = 1 << 4;
+
+        /// Right Alt key is held down.
+        const RALT = 1 << 5;
+
+        /// Left Windows key is held down.
+        const LWIN = 1 << 6;
+
+        /// Right Windows key is held down.
+        const RWIN = 1 << 7;
+
+        /// Caps Lock is toggled on.
+        const CAPS_LOCK = 1 << 8;
+
+        /// Num Lock is toggled on.
+        const NUM_LOCK = 1 << 9;
+
+        /// Either Shift key.
+        const SHIFT = Self::LSHIFT.bits() | Self::RSHIFT.bits();
+
+        /// Either Ctrl key.
+        const CTRL = Self::LCTRL.bits() | Self::RCTRL.bits();
+
+        /// Either Alt key.
+        const ALT = Self::LALT.bits() | Self::RALT.bits();
+
+        /// Either Windows key.
+        const WIN = Self::LWIN.bits() | Self::RWIN.bits();
+    }
+}
+
+impl Modifiers {
+    /// Whether if either Shift key is held down.
+    #[inline]
+    pub const fn shift(self) -> bool {
+        self.intersects(Self::SHIFT)
+    }
+
+    /// Whether if either Ctrl key is held down.
+    #[inline]
+    pub const fn ctrl(self) -> bool {
+        self.intersects(Self::CTRL)
+    }
+
+    /// Whether if either Alt key is held down.
+    #[inline]
+    pub const fn alt(self) -> bool {
+        self.intersects(Self::ALT)
+    }
+
+    /// Whether if either Windows key is held down.
+    #[inline]
+    pub const fn win(self) -> bool {
+        self.intersects(Self::WIN)
+    }
+}
+
+bitflags::bitflags! {
+    /// Describe cursor buttons held down.
+    #[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+    pub struct CursorButtons: u8 {
+        /// Left button
+        const LEFT = 1;
+
+        /// Right button
+        const RIGHT = 1 << 1;
+
+        /// Wheel button
+        const MIDDLE = 1 << 2;
+
+        /// Extra button 1
+        const BACK = 1 << 3;
+
+        /// Extra button 2
+        const FORWARD = 1 << 4;
+    }
+}
+
+impl From<CursorAction> for CursorButtons {
+    fn from(action: CursorAction) -> Self {
+        match action {
+            CursorAction::Left => CursorButtons::LEFT,
+            CursorAction::Right => CursorButtons::RIGHT,
+            CursorAction::Middle => CursorButtons::MIDDLE,
+            CursorAction::Back => CursorButtons::BACK,
+            CursorAction::Forward => CursorButtons::FORWARD,
+        }
+    }
+}
+
 bitflags::bitflags! {
     /// Describe IME conversion modes.
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -291,4 +561,111 @@ const _: () = {
             )?))
         }
     }
+
+    impl Serialize for Modifiers {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_u16(self.bits())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Modifiers {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            Ok(Modifiers::from_bits_retain(u16::deserialize(deserializer)?))
+        }
+    }
+
+    impl Serialize for CursorButtons {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_u8(self.bits())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CursorButtons {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            Ok(CursorButtons::from_bits_retain(u8::deserialize(
+                deserializer,
+            )?))
+        }
+    }
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_either_side() {
+        for (left, right, check) in [
+            (
+                Modifiers::LSHIFT,
+                Modifiers::RSHIFT,
+                Modifiers::shift as fn(Modifiers) -> bool,
+            ),
+            (Modifiers::LCTRL, Modifiers::RCTRL, Modifiers::ctrl),
+            (Modifiers::LALT, Modifiers::RALT, Modifiers::alt),
+            (Modifiers::LWIN, Modifiers::RWIN, Modifiers::win),
+        ] {
+            assert!(check(left));
+            assert!(check(right));
+            assert!(check(left | right));
+            assert!(!check(Modifiers::all() - left - right));
+        }
+    }
+
+    #[test]
+    fn modifiers_locks_are_not_keys() {
+        let locks = Modifiers::CAPS_LOCK | Modifiers::NUM_LOCK;
+        assert!(!locks.shift());
+        assert!(!locks.ctrl());
+        assert!(!locks.alt());
+        assert!(!locks.win());
+    }
+
+    #[test]
+    fn cursor_buttons_from_action() {
+        let buttons = [
+            CursorAction::Left,
+            CursorAction::Right,
+            CursorAction::Middle,
+            CursorAction::Back,
+            CursorAction::Forward,
+        ]
+        .into_iter()
+        .map(CursorButtons::from)
+        .fold(CursorButtons::empty(), |buttons, button| {
+            assert!(!buttons.intersects(button));
+            buttons | button
+        });
+
+        assert_eq!(buttons, CursorButtons::all());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn flags_serde_round_trip() {
+        let modifiers = Modifiers::LCTRL | Modifiers::RALT | Modifiers::NUM_LOCK;
+        let json = serde_json::to_string(&modifiers).unwrap();
+        assert_eq!(json, modifiers.bits().to_string());
+        assert_eq!(serde_json::from_str::<Modifiers>(&json).unwrap(), modifiers);
+
+        let buttons = CursorButtons::LEFT | CursorButtons::FORWARD;
+        let json = serde_json::to_string(&buttons).unwrap();
+        assert_eq!(json, buttons.bits().to_string());
+        assert_eq!(
+            serde_json::from_str::<CursorButtons>(&json).unwrap(),
+            buttons
+        );
+    }
+}
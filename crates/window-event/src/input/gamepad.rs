@@ -0,0 +1,120 @@
+use super::KeyInputState;
+
+/// Describe a gamepad input.
+///
+/// Gamepad states are read when the game polls them with `XInputGetState`.
+/// Events are only emitted and blocked inside the polling,
+/// so nothing is received while the game does not poll gamepads.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadInput {
+    /// Index of the gamepad, in `0..4` range.
+    pub user_index: u8,
+
+    /// The type of gamepad input.
+    pub event: GamepadEvent,
+}
+
+/// Describe a gamepad event.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadEvent {
+    /// Gamepad is connected.
+    Connected,
+
+    /// Gamepad is disconnected.
+    Disconnected,
+
+    /// A gamepad button is pressed or released.
+    Button {
+        /// The button.
+        button: GamepadButton,
+
+        /// State of the button.
+        state: KeyInputState,
+    },
+
+    /// Value of a gamepad axis has changed.
+    Axis {
+        /// The axis.
+        axis: GamepadAxis,
+
+        /// Normalized value of the axis.
+        ///
+        /// Thumb sticks are in `-1.0..=1.0` range, positive value means right or up.
+        /// Triggers are in `0.0..=1.0` range.
+        ///
+        /// No deadzone is applied.
+        value: f32,
+    },
+}
+
+/// Describe a gamepad button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadButton {
+    /// Up button of the directional pad.
+    DPadUp,
+
+    /// Down button of the directional pad.
+    DPadDown,
+
+    /// Left button of the directional pad.
+    DPadLeft,
+
+    /// Right button of the directional pad.
+    DPadRight,
+
+    /// Start (Menu) button.
+    Start,
+
+    /// Back (View) button.
+    Back,
+
+    /// Left thumb stick button.
+    LeftThumb,
+
+    /// Right thumb stick button.
+    RightThumb,
+
+    /// Left shoulder (bumper) button.
+    LeftShoulder,
+
+    /// Right shoulder (bumper) button.
+    RightShoulder,
+
+    /// A button.
+    A,
+
+    /// B button.
+    B,
+
+    /// X button.
+    X,
+
+    /// Y button.
+    Y,
+}
+
+/// Describe a gamepad axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadAxis {
+    /// Horizontal axis of the left thumb stick.
+    LeftThumbX,
+
+    /// Vertical axis of the left thumb stick.
+    LeftThumbY,
+
+    /// Horizontal axis of the right thumb stick.
+    RightThumbX,
+
+    /// Vertical axis of the right thumb stick.
+    RightThumbY,
+
+    /// Left analog trigger.
+    LeftTrigger,
+
+    /// Right analog trigger.
+    RightTrigger,
+}
@@ -0,0 +1,1165 @@
+//! Keyboard key codes following W3C UI Events `code` values.
+
+use core::{fmt, num::NonZeroU8, str::FromStr};
+
+use super::Key;
+
+/// Describe a keyboard key.
+///
+/// Variants are named after the key on a US QWERTY layout,
+/// following [W3C UI Events `code` values](https://www.w3.org/TR/uievents-code/).
+/// For example, [`KeyCode::KeyQ`] is the key labeled `A` on a French AZERTY layout
+/// when describing a physical key location.
+///
+/// The string form of a key code is the variant name, such as `"ShiftLeft"` or `"KeyA"`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum KeyCode {
+    // Alphanumeric section
+    Backquote,
+    Backslash,
+    BracketLeft,
+    BracketRight,
+    Comma,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Equal,
+    IntlBackslash,
+    IntlRo,
+    IntlYen,
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Minus,
+    Period,
+    Quote,
+    Semicolon,
+    Slash,
+
+    // Functional keys in the alphanumeric section
+    AltLeft,
+    AltRight,
+    Backspace,
+    CapsLock,
+    ContextMenu,
+    ControlLeft,
+    ControlRight,
+    Enter,
+    MetaLeft,
+    MetaRight,
+    ShiftLeft,
+    ShiftRight,
+    Space,
+    Tab,
+    Convert,
+    KanaMode,
+    Lang1,
+    Lang2,
+    NonConvert,
+
+    // Control pad section
+    Delete,
+    End,
+    Home,
+    Insert,
+    PageDown,
+    PageUp,
+
+    // Arrow pad section
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+
+    // Numpad section
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadComma,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadEnter,
+    NumpadEqual,
+    NumpadMultiply,
+    NumpadSubtract,
+
+    // Function section
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+
+    // Media keys
+    BrowserBack,
+    BrowserFavorites,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
+    BrowserSearch,
+    BrowserStop,
+    LaunchApp1,
+    LaunchApp2,
+    LaunchMail,
+    MediaPlayPause,
+    MediaSelect,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    Power,
+    Sleep,
+    WakeUp,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+
+    /// The key is not known.
+    Unidentified,
+}
+
+impl KeyCode {
+    /// Convert a virtual-key code and extended bit to [`KeyCode`].
+    ///
+    /// Left and right variants of modifier keys are recognized from either the extended bit
+    /// or the side specific virtual-key codes.
+    /// Returns [`KeyCode::Unidentified`] if the key has no matching key code.
+    ///
+    /// Several keys map to the same key code, so [`KeyCode::to_key`] may not give back the same key.
+    /// * Generic `VK_SHIFT` comes back as `VK_LSHIFT` or `VK_RSHIFT`.
+    /// * Side specific `VK_LCONTROL`, `VK_RCONTROL`, `VK_LMENU` and `VK_RMENU` come back as
+    ///   generic `VK_CONTROL` or `VK_MENU` with the extended bit of the side.
+    /// * Numpad keys while Num Lock is off, such as `VK_END` without the extended bit,
+    ///   come back as the numpad keys while Num Lock is on, such as `VK_NUMPAD1`.
+    pub const fn from_key(key: Key) -> Self {
+        match (key.code.get(), key.extended) {
+            (0xC0, false) => KeyCode::Backquote,
+            (0xDC, false) => KeyCode::Backslash,
+            (0xDB, false) => KeyCode::BracketLeft,
+            (0xDD, false) => KeyCode::BracketRight,
+            (0xBC, false) => KeyCode::Comma,
+            (0x30, false) => KeyCode::Digit0,
+            (0x31, false) => KeyCode::Digit1,
+            (0x32, false) => KeyCode::Digit2,
+            (0x33, false) => KeyCode::Digit3,
+            (0x34, false) => KeyCode::Digit4,
+            (0x35, false) => KeyCode::Digit5,
+            (0x36, false) => KeyCode::Digit6,
+            (0x37, false) => KeyCode::Digit7,
+            (0x38, false) => KeyCode::Digit8,
+            (0x39, false) => KeyCode::Digit9,
+            (0xBB, false) => KeyCode::Equal,
+            (0xE2, false) => KeyCode::IntlBackslash,
+            (0xC1, false) => KeyCode::IntlRo,
+            (0x41, false) => KeyCode::KeyA,
+            (0x42, false) => KeyCode::KeyB,
+            (0x43, false) => KeyCode::KeyC,
+            (0x44, false) => KeyCode::KeyD,
+            (0x45, false) => KeyCode::KeyE,
+            (0x46, false) => KeyCode::KeyF,
+            (0x47, false) => KeyCode::KeyG,
+            (0x48, false) => KeyCode::KeyH,
+            (0x49, false) => KeyCode::KeyI,
+            (0x4A, false) => KeyCode::KeyJ,
+            (0x4B, false) => KeyCode::KeyK,
+            (0x4C, false) => KeyCode::KeyL,
+            (0x4D, false) => KeyCode::KeyM,
+            (0x4E, false) => KeyCode::KeyN,
+            (0x4F, false) => KeyCode::KeyO,
+            (0x50, false) => KeyCode::KeyP,
+            (0x51, false) => KeyCode::KeyQ,
+            (0x52, false) => KeyCode::KeyR,
+            (0x53, false) => KeyCode::KeyS,
+            (0x54, false) => KeyCode::KeyT,
+            (0x55, false) => KeyCode::KeyU,
+            (0x56, false) => KeyCode::KeyV,
+            (0x57, false) => KeyCode::KeyW,
+            (0x58, false) => KeyCode::KeyX,
+            (0x59, false) => KeyCode::KeyY,
+            (0x5A, false) => KeyCode::KeyZ,
+            (0xBD, false) => KeyCode::Minus,
+            (0xBE, false) => KeyCode::Period,
+            (0xDE, false) => KeyCode::Quote,
+            (0xBA, false) => KeyCode::Semicolon,
+            (0xBF, false) => KeyCode::Slash,
+            (0x12, false) => KeyCode::AltLeft,
+            (0x12, true) => KeyCode::AltRight,
+            (0x08, false) => KeyCode::Backspace,
+            (0x14, false) => KeyCode::CapsLock,
+            (0x5D, true) => KeyCode::ContextMenu,
+            (0x11, false) => KeyCode::ControlLeft,
+            (0x11, true) => KeyCode::ControlRight,
+            (0x0D, false) => KeyCode::Enter,
+            (0x5B, true) => KeyCode::MetaLeft,
+            (0x5C, true) => KeyCode::MetaRight,
+            (0xA0, false) => KeyCode::ShiftLeft,
+            (0xA1, false) => KeyCode::ShiftRight,
+            (0x20, false) => KeyCode::Space,
+            (0x09, false) => KeyCode::Tab,
+            (0x1C, false) => KeyCode::Convert,
+            (0x15, false) => KeyCode::KanaMode,
+            (0x19, false) => KeyCode::Lang2,
+            (0x1D, false) => KeyCode::NonConvert,
+            (0x2E, true) => KeyCode::Delete,
+            (0x23, true) => KeyCode::End,
+            (0x24, true) => KeyCode::Home,
+            (0x2D, true) => KeyCode::Insert,
+            (0x22, true) => KeyCode::PageDown,
+            (0x21, true) => KeyCode::PageUp,
+            (0x28, true) => KeyCode::ArrowDown,
+            (0x25, true) => KeyCode::ArrowLeft,
+            (0x27, true) => KeyCode::ArrowRight,
+            (0x26, true) => KeyCode::ArrowUp,
+            (0x90, true) => KeyCode::NumLock,
+            (0x60, false) => KeyCode::Numpad0,
+            (0x61, false) => KeyCode::Numpad1,
+            (0x62, false) => KeyCode::Numpad2,
+            (0x63, false) => KeyCode::Numpad3,
+            (0x64, false) => KeyCode::Numpad4,
+            (0x65, false) => KeyCode::Numpad5,
+            (0x66, false) => KeyCode::Numpad6,
+            (0x67, false) => KeyCode::Numpad7,
+            (0x68, false) => KeyCode::Numpad8,
+            (0x69, false) => KeyCode::Numpad9,
+            (0x6B, false) => KeyCode::NumpadAdd,
+            (0x6C, false) => KeyCode::NumpadComma,
+            (0x6E, false) => KeyCode::NumpadDecimal,
+            (0x6F, true) => KeyCode::NumpadDivide,
+            (0x0D, true) => KeyCode::NumpadEnter,
+            (0x92, false) => KeyCode::NumpadEqual,
+            (0x6A, false) => KeyCode::NumpadMultiply,
+            (0x6D, false) => KeyCode::NumpadSubtract,
+            (0x1B, false) => KeyCode::Escape,
+            (0x70, false) => KeyCode::F1,
+            (0x71, false) => KeyCode::F2,
+            (0x72, false) => KeyCode::F3,
+            (0x73, false) => KeyCode::F4,
+            (0x74, false) => KeyCode::F5,
+            (0x75, false) => KeyCode::F6,
+            (0x76, false) => KeyCode::F7,
+            (0x77, false) => KeyCode::F8,
+            (0x78, false) => KeyCode::F9,
+            (0x79, false) => KeyCode::F10,
+            (0x7A, false) => KeyCode::F11,
+            (0x7B, false) => KeyCode::F12,
+            (0x7C, false) => KeyCode::F13,
+            (0x7D, false) => KeyCode::F14,
+            (0x7E, false) => KeyCode::F15,
+            (0x7F, false) => KeyCode::F16,
+            (0x80, false) => KeyCode::F17,
+            (0x81, false) => KeyCode::F18,
+            (0x82, false) => KeyCode::F19,
+            (0x83, false) => KeyCode::F20,
+            (0x84, false) => KeyCode::F21,
+            (0x85, false) => KeyCode::F22,
+            (0x86, false) => KeyCode::F23,
+            (0x87, false) => KeyCode::F24,
+            (0x2C, true) => KeyCode::PrintScreen,
+            (0x91, false) => KeyCode::ScrollLock,
+            (0x13, false) => KeyCode::Pause,
+            (0xA6, true) => KeyCode::BrowserBack,
+            (0xA7, true) => KeyCode::BrowserForward,
+            (0xA8, true) => KeyCode::BrowserRefresh,
+            (0xA9, true) => KeyCode::BrowserStop,
+            (0xAA, true) => KeyCode::BrowserSearch,
+            (0xAB, true) => KeyCode::BrowserFavorites,
+            (0xAC, true) => KeyCode::BrowserHome,
+            (0xAD, true) => KeyCode::AudioVolumeMute,
+            (0xAE, true) => KeyCode::AudioVolumeDown,
+            (0xAF, true) => KeyCode::AudioVolumeUp,
+            (0xB0, true) => KeyCode::MediaTrackNext,
+            (0xB1, true) => KeyCode::MediaTrackPrevious,
+            (0xB2, true) => KeyCode::MediaStop,
+            (0xB3, true) => KeyCode::MediaPlayPause,
+            (0xB4, true) => KeyCode::LaunchMail,
+            (0xB5, true) => KeyCode::MediaSelect,
+            (0xB6, true) => KeyCode::LaunchApp1,
+            (0xB7, true) => KeyCode::LaunchApp2,
+            (0x5F, true) => KeyCode::Sleep,
+
+            // Side specific modifier keys
+            (0x10, false) => KeyCode::ShiftLeft,
+            (0x10, true) => KeyCode::ShiftRight,
+            (0xA2, false) => KeyCode::ControlLeft,
+            (0xA3, false) => KeyCode::ControlRight,
+            (0xA3, true) => KeyCode::ControlRight,
+            (0xA4, false) => KeyCode::AltLeft,
+            (0xA5, false) => KeyCode::AltRight,
+            (0xA5, true) => KeyCode::AltRight,
+
+            // Numpad keys while Num Lock is off
+            (0x2D, false) => KeyCode::Numpad0,
+            (0x23, false) => KeyCode::Numpad1,
+            (0x28, false) => KeyCode::Numpad2,
+            (0x22, false) => KeyCode::Numpad3,
+            (0x25, false) => KeyCode::Numpad4,
+            (0x0C, false) => KeyCode::Numpad5,
+            (0x27, false) => KeyCode::Numpad6,
+            (0x24, false) => KeyCode::Numpad7,
+            (0x26, false) => KeyCode::Numpad8,
+            (0x21, false) => KeyCode::Numpad9,
+            (0x2E, false) => KeyCode::NumpadDecimal,
+
+            _ => KeyCode::Unidentified,
+        }
+    }
+
+    /// Convert to a virtual-key code and extended bit.
+    ///
+    /// Converting the returned [`Key`] back using [`KeyCode::from_key`] always gives the same key code.
+    /// Returns [`None`] if the key code has no matching virtual-key code.
+    ///
+    /// Keys with several virtual-key codes give a single canonical one,
+    /// refer to [`KeyCode::from_key`] for details.
+    pub const fn to_key(self) -> Option<Key> {
+        let (code, extended) = match self {
+            KeyCode::Backquote => (0xC0, false),
+            KeyCode::Backslash => (0xDC, false),
+            KeyCode::BracketLeft => (0xDB, false),
+            KeyCode::BracketRight => (0xDD, false),
+            KeyCode::Comma => (0xBC, false),
+            KeyCode::Digit0 => (0x30, false),
+            KeyCode::Digit1 => (0x31, false),
+            KeyCode::Digit2 => (0x32, false),
+            KeyCode::Digit3 => (0x33, false),
+            KeyCode::Digit4 => (0x34, false),
+            KeyCode::Digit5 => (0x35, false),
+            KeyCode::Digit6 => (0x36, false),
+            KeyCode::Digit7 => (0x37, false),
+            KeyCode::Digit8 => (0x38, false),
+            KeyCode::Digit9 => (0x39, false),
+            KeyCode::Equal => (0xBB, false),
+            KeyCode::IntlBackslash => (0xE2, false),
+            KeyCode::IntlRo => (0xC1, false),
+            KeyCode::KeyA => (0x41, false),
+            KeyCode::KeyB => (0x42, false),
+            KeyCode::KeyC => (0x43, false),
+            KeyCode::KeyD => (0x44, false),
+            KeyCode::KeyE => (0x45, false),
+            KeyCode::KeyF => (0x46, false),
+            KeyCode::KeyG => (0x47, false),
+            KeyCode::KeyH => (0x48, false),
+            KeyCode::KeyI => (0x49, false),
+            KeyCode::KeyJ => (0x4A, false),
+            KeyCode::KeyK => (0x4B, false),
+            KeyCode::KeyL => (0x4C, false),
+            KeyCode::KeyM => (0x4D, false),
+            KeyCode::KeyN => (0x4E, false),
+            KeyCode::KeyO => (0x4F, false),
+            KeyCode::KeyP => (0x50, false),
+            KeyCode::KeyQ => (0x51, false),
+            KeyCode::KeyR => (0x52, false),
+            KeyCode::KeyS => (0x53, false),
+            KeyCode::KeyT => (0x54, false),
+            KeyCode::KeyU => (0x55, false),
+            KeyCode::KeyV => (0x56, false),
+            KeyCode::KeyW => (0x57, false),
+            KeyCode::KeyX => (0x58, false),
+            KeyCode::KeyY => (0x59, false),
+            KeyCode::KeyZ => (0x5A, false),
+            KeyCode::Minus => (0xBD, false),
+            KeyCode::Period => (0xBE, false),
+            KeyCode::Quote => (0xDE, false),
+            KeyCode::Semicolon => (0xBA, false),
+            KeyCode::Slash => (0xBF, false),
+            KeyCode::AltLeft => (0x12, false),
+            KeyCode::AltRight => (0x12, true),
+            KeyCode::Backspace => (0x08, false),
+            KeyCode::CapsLock => (0x14, false),
+            KeyCode::ContextMenu => (0x5D, true),
+            KeyCode::ControlLeft => (0x11, false),
+            KeyCode::ControlRight => (0x11, true),
+            KeyCode::Enter => (0x0D, false),
+            KeyCode::MetaLeft => (0x5B, true),
+            KeyCode::MetaRight => (0x5C, true),
+            KeyCode::ShiftLeft => (0xA0, false),
+            KeyCode::ShiftRight => (0xA1, false),
+            KeyCode::Space => (0x20, false),
+            KeyCode::Tab => (0x09, false),
+            KeyCode::Convert => (0x1C, false),
+            KeyCode::KanaMode => (0x15, false),
+            KeyCode::Lang2 => (0x19, false),
+            KeyCode::NonConvert => (0x1D, false),
+            KeyCode::Delete => (0x2E, true),
+            KeyCode::End => (0x23, true),
+            KeyCode::Home => (0x24, true),
+            KeyCode::Insert => (0x2D, true),
+            KeyCode::PageDown => (0x22, true),
+            KeyCode::PageUp => (0x21, true),
+            KeyCode::ArrowDown => (0x28, true),
+            KeyCode::ArrowLeft => (0x25, true),
+            KeyCode::ArrowRight => (0x27, true),
+            KeyCode::ArrowUp => (0x26, true),
+            KeyCode::NumLock => (0x90, true),
+            KeyCode::Numpad0 => (0x60, false),
+            KeyCode::Numpad1 => (0x61, false),
+            KeyCode::Numpad2 => (0x62, false),
+            KeyCode::Numpad3 => (0x63, false),
+            KeyCode::Numpad4 => (0x64, false),
+            KeyCode::Numpad5 => (0x65, false),
+            KeyCode::Numpad6 => (0x66, false),
+            KeyCode::Numpad7 => (0x67, false),
+            KeyCode::Numpad8 => (0x68, false),
+            KeyCode::Numpad9 => (0x69, false),
+            KeyCode::NumpadAdd => (0x6B, false),
+            KeyCode::NumpadComma => (0x6C, false),
+            KeyCode::NumpadDecimal => (0x6E, false),
+            KeyCode::NumpadDivide => (0x6F, true),
+            KeyCode::NumpadEnter => (0x0D, true),
+            KeyCode::NumpadEqual => (0x92, false),
+            KeyCode::NumpadMultiply => (0x6A, false),
+            KeyCode::NumpadSubtract => (0x6D, false),
+            KeyCode::Escape => (0x1B, false),
+            KeyCode::F1 => (0x70, false),
+            KeyCode::F2 => (0x71, false),
+            KeyCode::F3 => (0x72, false),
+            KeyCode::F4 => (0x73, false),
+            KeyCode::F5 => (0x74, false),
+            KeyCode::F6 => (0x75, false),
+            KeyCode::F7 => (0x76, false),
+            KeyCode::F8 => (0x77, false),
+            KeyCode::F9 => (0x78, false),
+            KeyCode::F10 => (0x79, false),
+            KeyCode::F11 => (0x7A, false),
+            KeyCode::F12 => (0x7B, false),
+            KeyCode::F13 => (0x7C, false),
+            KeyCode::F14 => (0x7D, false),
+            KeyCode::F15 => (0x7E, false),
+            KeyCode::F16 => (0x7F, false),
+            KeyCode::F17 => (0x80, false),
+            KeyCode::F18 => (0x81, false),
+            KeyCode::F19 => (0x82, false),
+            KeyCode::F20 => (0x83, false),
+            KeyCode::F21 => (0x84, false),
+            KeyCode::F22 => (0x85, false),
+            KeyCode::F23 => (0x86, false),
+            KeyCode::F24 => (0x87, false),
+            KeyCode::PrintScreen => (0x2C, true),
+            KeyCode::ScrollLock => (0x91, false),
+            KeyCode::Pause => (0x13, false),
+            KeyCode::BrowserBack => (0xA6, true),
+            KeyCode::BrowserForward => (0xA7, true),
+            KeyCode::BrowserRefresh => (0xA8, true),
+            KeyCode::BrowserStop => (0xA9, true),
+            KeyCode::BrowserSearch => (0xAA, true),
+            KeyCode::BrowserFavorites => (0xAB, true),
+            KeyCode::BrowserHome => (0xAC, true),
+            KeyCode::AudioVolumeMute => (0xAD, true),
+            KeyCode::AudioVolumeDown => (0xAE, true),
+            KeyCode::AudioVolumeUp => (0xAF, true),
+            KeyCode::MediaTrackNext => (0xB0, true),
+            KeyCode::MediaTrackPrevious => (0xB1, true),
+            KeyCode::MediaStop => (0xB2, true),
+            KeyCode::MediaPlayPause => (0xB3, true),
+            KeyCode::LaunchMail => (0xB4, true),
+            KeyCode::MediaSelect => (0xB5, true),
+            KeyCode::LaunchApp1 => (0xB6, true),
+            KeyCode::LaunchApp2 => (0xB7, true),
+            KeyCode::Sleep => (0x5F, true),
+
+            _ => return None,
+        };
+
+        Some(Key {
+            code: NonZeroU8::new(code).unwrap(),
+            extended,
+        })
+    }
+
+    /// Get the W3C UI Events `code` name of the key code.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            KeyCode::Backquote => "Backquote",
+            KeyCode::Backslash => "Backslash",
+            KeyCode::BracketLeft => "BracketLeft",
+            KeyCode::BracketRight => "BracketRight",
+            KeyCode::Comma => "Comma",
+            KeyCode::Digit0 => "Digit0",
+            KeyCode::Digit1 => "Digit1",
+            KeyCode::Digit2 => "Digit2",
+            KeyCode::Digit3 => "Digit3",
+            KeyCode::Digit4 => "Digit4",
+            KeyCode::Digit5 => "Digit5",
+            KeyCode::Digit6 => "Digit6",
+            KeyCode::Digit7 => "Digit7",
+            KeyCode::Digit8 => "Digit8",
+            KeyCode::Digit9 => "Digit9",
+            KeyCode::Equal => "Equal",
+            KeyCode::IntlBackslash => "IntlBackslash",
+            KeyCode::IntlRo => "IntlRo",
+            KeyCode::IntlYen => "IntlYen",
+            KeyCode::KeyA => "KeyA",
+            KeyCode::KeyB => "KeyB",
+            KeyCode::KeyC => "KeyC",
+            KeyCode::KeyD => "KeyD",
+            KeyCode::KeyE => "KeyE",
+            KeyCode::KeyF => "KeyF",
+            KeyCode::KeyG => "KeyG",
+            KeyCode::KeyH => "KeyH",
+            KeyCode::KeyI => "KeyI",
+            KeyCode::KeyJ => "KeyJ",
+            KeyCode::KeyK => "KeyK",
+            KeyCode::KeyL => "KeyL",
+            KeyCode::KeyM => "KeyM",
+            KeyCode::KeyN => "KeyN",
+            KeyCode::KeyO => "KeyO",
+            KeyCode::KeyP => "KeyP",
+            KeyCode::KeyQ => "KeyQ",
+            KeyCode::KeyR => "KeyR",
+            KeyCode::KeyS => "KeyS",
+            KeyCode::KeyT => "KeyT",
+            KeyCode::KeyU => "KeyU",
+            KeyCode::KeyV => "KeyV",
+            KeyCode::KeyW => "KeyW",
+            KeyCode::KeyX => "KeyX",
+            KeyCode::KeyY => "KeyY",
+            KeyCode::KeyZ => "KeyZ",
+            KeyCode::Minus => "Minus",
+            KeyCode::Period => "Period",
+            KeyCode::Quote => "Quote",
+            KeyCode::Semicolon => "Semicolon",
+            KeyCode::Slash => "Slash",
+            KeyCode::AltLeft => "AltLeft",
+            KeyCode::AltRight => "AltRight",
+            KeyCode::Backspace => "Backspace",
+            KeyCode::CapsLock => "CapsLock",
+            KeyCode::ContextMenu => "ContextMenu",
+            KeyCode::ControlLeft => "ControlLeft",
+            KeyCode::ControlRight => "ControlRight",
+            KeyCode::Enter => "Enter",
+            KeyCode::MetaLeft => "MetaLeft",
+            KeyCode::MetaRight => "MetaRight",
+            KeyCode::ShiftLeft => "ShiftLeft",
+            KeyCode::ShiftRight => "ShiftRight",
+            KeyCode::Space => "Space",
+            KeyCode::Tab => "Tab",
+            KeyCode::Convert => "Convert",
+            KeyCode::KanaMode => "KanaMode",
+            KeyCode::Lang1 => "Lang1",
+            KeyCode::Lang2 => "Lang2",
+            KeyCode::NonConvert => "NonConvert",
+            KeyCode::Delete => "Delete",
+            KeyCode::End => "End",
+            KeyCode::Home => "Home",
+            KeyCode::Insert => "Insert",
+            KeyCode::PageDown => "PageDown",
+            KeyCode::PageUp => "PageUp",
+            KeyCode::ArrowDown => "ArrowDown",
+            KeyCode::ArrowLeft => "ArrowLeft",
+            KeyCode::ArrowRight => "ArrowRight",
+            KeyCode::ArrowUp => "ArrowUp",
+            KeyCode::NumLock => "NumLock",
+            KeyCode::Numpad0 => "Numpad0",
+            KeyCode::Numpad1 => "Numpad1",
+            KeyCode::Numpad2 => "Numpad2",
+            KeyCode::Numpad3 => "Numpad3",
+            KeyCode::Numpad4 => "Numpad4",
+            KeyCode::Numpad5 => "Numpad5",
+            KeyCode::Numpad6 => "Numpad6",
+            KeyCode::Numpad7 => "Numpad7",
+            KeyCode::Numpad8 => "Numpad8",
+            KeyCode::Numpad9 => "Numpad9",
+            KeyCode::NumpadAdd => "NumpadAdd",
+            KeyCode::NumpadComma => "NumpadComma",
+            KeyCode::NumpadDecimal => "NumpadDecimal",
+            KeyCode::NumpadDivide => "NumpadDivide",
+            KeyCode::NumpadEnter => "NumpadEnter",
+            KeyCode::NumpadEqual => "NumpadEqual",
+            KeyCode::NumpadMultiply => "NumpadMultiply",
+            KeyCode::NumpadSubtract => "NumpadSubtract",
+            KeyCode::Escape => "Escape",
+            KeyCode::F1 => "F1",
+            KeyCode::F2 => "F2",
+            KeyCode::F3 => "F3",
+            KeyCode::F4 => "F4",
+            KeyCode::F5 => "F5",
+            KeyCode::F6 => "F6",
+            KeyCode::F7 => "F7",
+            KeyCode::F8 => "F8",
+            KeyCode::F9 => "F9",
+            KeyCode::F10 => "F10",
+            KeyCode::F11 => "F11",
+            KeyCode::F12 => "F12",
+            KeyCode::F13 => "F13",
+            KeyCode::F14 => "F14",
+            KeyCode::F15 => "F15",
+            KeyCode::F16 => "F16",
+            KeyCode::F17 => "F17",
+            KeyCode::F18 => "F18",
+            KeyCode::F19 => "F19",
+            KeyCode::F20 => "F20",
+            KeyCode::F21 => "F21",
+            KeyCode::F22 => "F22",
+            KeyCode::F23 => "F23",
+            KeyCode::F24 => "F24",
+            KeyCode::PrintScreen => "PrintScreen",
+            KeyCode::ScrollLock => "ScrollLock",
+            KeyCode::Pause => "Pause",
+            KeyCode::BrowserBack => "BrowserBack",
+            KeyCode::BrowserFavorites => "BrowserFavorites",
+            KeyCode::BrowserForward => "BrowserForward",
+            KeyCode::BrowserHome => "BrowserHome",
+            KeyCode::BrowserRefresh => "BrowserRefresh",
+            KeyCode::BrowserSearch => "BrowserSearch",
+            KeyCode::BrowserStop => "BrowserStop",
+            KeyCode::LaunchApp1 => "LaunchApp1",
+            KeyCode::LaunchApp2 => "LaunchApp2",
+            KeyCode::LaunchMail => "LaunchMail",
+            KeyCode::MediaPlayPause => "MediaPlayPause",
+            KeyCode::MediaSelect => "MediaSelect",
+            KeyCode::MediaStop => "MediaStop",
+            KeyCode::MediaTrackNext => "MediaTrackNext",
+            KeyCode::MediaTrackPrevious => "MediaTrackPrevious",
+            KeyCode::Power => "Power",
+            KeyCode::Sleep => "Sleep",
+            KeyCode::WakeUp => "WakeUp",
+            KeyCode::AudioVolumeDown => "AudioVolumeDown",
+            KeyCode::AudioVolumeMute => "AudioVolumeMute",
+            KeyCode::AudioVolumeUp => "AudioVolumeUp",
+            KeyCode::Unidentified => "Unidentified",
+        }
+    }
+}
+
+impl From<Key> for KeyCode {
+    fn from(key: Key) -> Self {
+        KeyCode::from_key(key)
+    }
+}
+
+impl fmt::Display for KeyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for KeyCode {
+    type Err = ParseKeyCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Backquote" => KeyCode::Backquote,
+            "Backslash" => KeyCode::Backslash,
+            "BracketLeft" => KeyCode::BracketLeft,
+            "BracketRight" => KeyCode::BracketRight,
+            "Comma" => KeyCode::Comma,
+            "Digit0" => KeyCode::Digit0,
+            "Digit1" => KeyCode::Digit1,
+            "Digit2" => KeyCode::Digit2,
+            "Digit3" => KeyCode::Digit3,
+            "Digit4" => KeyCode::Digit4,
+            "Digit5" => KeyCode::Digit5,
+            "Digit6" => KeyCode::Digit6,
+            "Digit7" => KeyCode::Digit7,
+            "Digit8" => KeyCode::Digit8,
+            "Digit9" => KeyCode::Digit9,
+            "Equal" => KeyCode::Equal,
+            "IntlBackslash" => KeyCode::IntlBackslash,
+            "IntlRo" => KeyCode::IntlRo,
+            "IntlYen" => KeyCode::IntlYen,
+            "KeyA" => KeyCode::KeyA,
+            "KeyB" => KeyCode::KeyB,
+            "KeyC" => KeyCode::KeyC,
+            "KeyD" => KeyCode::KeyD,
+            "KeyE" => KeyCode::KeyE,
+            "KeyF" => KeyCode::KeyF,
+            "KeyG" => KeyCode::KeyG,
+            "KeyH" => KeyCode::KeyH,
+            "KeyI" => KeyCode::KeyI,
+            "KeyJ" => KeyCode::KeyJ,
+            "KeyK" => KeyCode::KeyK,
+            "KeyL" => KeyCode::KeyL,
+            "KeyM" => KeyCode::KeyM,
+            "KeyN" => KeyCode::KeyN,
+            "KeyO" => KeyCode::KeyO,
+            "KeyP" => KeyCode::KeyP,
+            "KeyQ" => KeyCode::KeyQ,
+            "KeyR" => KeyCode::KeyR,
+            "KeyS" => KeyCode::KeyS,
+            "KeyT" => KeyCode::KeyT,
+            "KeyU" => KeyCode::KeyU,
+            "KeyV" => KeyCode::KeyV,
+            "KeyW" => KeyCode::KeyW,
+            "KeyX" => KeyCode::KeyX,
+            "KeyY" => KeyCode::KeyY,
+            "KeyZ" => KeyCode::KeyZ,
+            "Minus" => KeyCode::Minus,
+            "Period" => KeyCode::Period,
+            "Quote" => KeyCode::Quote,
+            "Semicolon" => KeyCode::Semicolon,
+            "Slash" => KeyCode::Slash,
+            "AltLeft" => KeyCode::AltLeft,
+            "AltRight" => KeyCode::AltRight,
+            "Backspace" => KeyCode::Backspace,
+            "CapsLock" => KeyCode::CapsLock,
+            "ContextMenu" => KeyCode::ContextMenu,
+            "ControlLeft" => KeyCode::ControlLeft,
+            "ControlRight" => KeyCode::ControlRight,
+            "Enter" => KeyCode::Enter,
+            "MetaLeft" => KeyCode::MetaLeft,
+            "MetaRight" => KeyCode::MetaRight,
+            "ShiftLeft" => KeyCode::ShiftLeft,
+            "ShiftRight" => KeyCode::ShiftRight,
+            "Space" => KeyCode::Space,
+            "Tab" => KeyCode::Tab,
+            "Convert" => KeyCode::Convert,
+            "KanaMode" => KeyCode::KanaMode,
+            "Lang1" => KeyCode::Lang1,
+            "Lang2" => KeyCode::Lang2,
+            "NonConvert" => KeyCode::NonConvert,
+            "Delete" => KeyCode::Delete,
+            "End" => KeyCode::End,
+            "Home" => KeyCode::Home,
+            "Insert" => KeyCode::Insert,
+            "PageDown" => KeyCode::PageDown,
+            "PageUp" => KeyCode::PageUp,
+            "ArrowDown" => KeyCode::ArrowDown,
+            "ArrowLeft" => KeyCode::ArrowLeft,
+            "ArrowRight" => KeyCode::ArrowRight,
+            "ArrowUp" => KeyCode::ArrowUp,
+            "NumLock" => KeyCode::NumLock,
+            "Numpad0" => KeyCode::Numpad0,
+            "Numpad1" => KeyCode::Numpad1,
+            "Numpad2" => KeyCode::Numpad2,
+            "Numpad3" => KeyCode::Numpad3,
+            "Numpad4" => KeyCode::Numpad4,
+            "Numpad5" => KeyCode::Numpad5,
+            "Numpad6" => KeyCode::Numpad6,
+            "Numpad7" => KeyCode::Numpad7,
+            "Numpad8" => KeyCode::Numpad8,
+            "Numpad9" => KeyCode::Numpad9,
+            "NumpadAdd" => KeyCode::NumpadAdd,
+            "NumpadComma" => KeyCode::NumpadComma,
+            "NumpadDecimal" => KeyCode::NumpadDecimal,
+            "NumpadDivide" => KeyCode::NumpadDivide,
+            "NumpadEnter" => KeyCode::NumpadEnter,
+            "NumpadEqual" => KeyCode::NumpadEqual,
+            "NumpadMultiply" => KeyCode::NumpadMultiply,
+            "NumpadSubtract" => KeyCode::NumpadSubtract,
+            "Escape" => KeyCode::Escape,
+            "F1" => KeyCode::F1,
+            "F2" => KeyCode::F2,
+            "F3" => KeyCode::F3,
+            "F4" => KeyCode::F4,
+            "F5" => KeyCode::F5,
+            "F6" => KeyCode::F6,
+            "F7" => KeyCode::F7,
+            "F8" => KeyCode::F8,
+            "F9" => KeyCode::F9,
+            "F10" => KeyCode::F10,
+            "F11" => KeyCode::F11,
+            "F12" => KeyCode::F12,
+            "F13" => KeyCode::F13,
+            "F14" => KeyCode::F14,
+            "F15" => KeyCode::F15,
+            "F16" => KeyCode::F16,
+            "F17" => KeyCode::F17,
+            "F18" => KeyCode::F18,
+            "F19" => KeyCode::F19,
+            "F20" => KeyCode::F20,
+            "F21" => KeyCode::F21,
+            "F22" => KeyCode::F22,
+            "F23" => KeyCode::F23,
+            "F24" => KeyCode::F24,
+            "PrintScreen" => KeyCode::PrintScreen,
+            "ScrollLock" => KeyCode::ScrollLock,
+            "Pause" => KeyCode::Pause,
+            "BrowserBack" => KeyCode::BrowserBack,
+            "BrowserFavorites" => KeyCode::BrowserFavorites,
+            "BrowserForward" => KeyCode::BrowserForward,
+            "BrowserHome" => KeyCode::BrowserHome,
+            "BrowserRefresh" => KeyCode::BrowserRefresh,
+            "BrowserSearch" => KeyCode::BrowserSearch,
+            "BrowserStop" => KeyCode::BrowserStop,
+            "LaunchApp1" => KeyCode::LaunchApp1,
+            "LaunchApp2" => KeyCode::LaunchApp2,
+            "LaunchMail" => KeyCode::LaunchMail,
+            "MediaPlayPause" => KeyCode::MediaPlayPause,
+            "MediaSelect" => KeyCode::MediaSelect,
+            "MediaStop" => KeyCode::MediaStop,
+            "MediaTrackNext" => KeyCode::MediaTrackNext,
+            "MediaTrackPrevious" => KeyCode::MediaTrackPrevious,
+            "Power" => KeyCode::Power,
+            "Sleep" => KeyCode::Sleep,
+            "WakeUp" => KeyCode::WakeUp,
+            "AudioVolumeDown" => KeyCode::AudioVolumeDown,
+            "AudioVolumeMute" => KeyCode::AudioVolumeMute,
+            "AudioVolumeUp" => KeyCode::AudioVolumeUp,
+            "Unidentified" => KeyCode::Unidentified,
+            _ => return Err(ParseKeyCodeError),
+        })
+    }
+}
+
+/// Error returned when parsing an unknown key code name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseKeyCodeError;
+
+impl fmt::Display for ParseKeyCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown key code name")
+    }
+}
+
+impl core::error::Error for ParseKeyCodeError {}
+
+#[cfg(feature = "serde")]
+const _: () = {
+    use serde::{Deserialize, Serialize, de};
+
+    impl Serialize for KeyCode {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for KeyCode {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_str(KeyCodeVisitor)
+        }
+    }
+
+    struct KeyCodeVisitor;
+
+    impl de::Visitor<'_> for KeyCodeVisitor {
+        type Value = KeyCode;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a W3C UI Events key code name")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            v.parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every key code, in declaration order.
+    const ALL: [KeyCode; 148] = [
+        KeyCode::Backquote,
+        KeyCode::Backslash,
+        KeyCode::BracketLeft,
+        KeyCode::BracketRight,
+        KeyCode::Comma,
+        KeyCode::Digit0,
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+        KeyCode::Equal,
+        KeyCode::IntlBackslash,
+        KeyCode::IntlRo,
+        KeyCode::IntlYen,
+        KeyCode::KeyA,
+        KeyCode::KeyB,
+        KeyCode::KeyC,
+        KeyCode::KeyD,
+        KeyCode::KeyE,
+        KeyCode::KeyF,
+        KeyCode::KeyG,
+        KeyCode::KeyH,
+        KeyCode::KeyI,
+        KeyCode::KeyJ,
+        KeyCode::KeyK,
+        KeyCode::KeyL,
+        KeyCode::KeyM,
+        KeyCode::KeyN,
+        KeyCode::KeyO,
+        KeyCode::KeyP,
+        KeyCode::KeyQ,
+        KeyCode::KeyR,
+        KeyCode::KeyS,
+        KeyCode::KeyT,
+        KeyCode::KeyU,
+        KeyCode::KeyV,
+        KeyCode::KeyW,
+        KeyCode::KeyX,
+        KeyCode::KeyY,
+        KeyCode::KeyZ,
+        KeyCode::Minus,
+        KeyCode::Period,
+        KeyCode::Quote,
+        KeyCode::Semicolon,
+        KeyCode::Slash,
+        KeyCode::AltLeft,
+        KeyCode::AltRight,
+        KeyCode::Backspace,
+        KeyCode::CapsLock,
+        KeyCode::ContextMenu,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::Enter,
+        KeyCode::MetaLeft,
+        KeyCode::MetaRight,
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::Space,
+        KeyCode::Tab,
+        KeyCode::Convert,
+        KeyCode::KanaMode,
+        KeyCode::Lang1,
+        KeyCode::Lang2,
+        KeyCode::NonConvert,
+        KeyCode::Delete,
+        KeyCode::End,
+        KeyCode::Home,
+        KeyCode::Insert,
+        KeyCode::PageDown,
+        KeyCode::PageUp,
+        KeyCode::ArrowDown,
+        KeyCode::ArrowLeft,
+        KeyCode::ArrowRight,
+        KeyCode::ArrowUp,
+        KeyCode::NumLock,
+        KeyCode::Numpad0,
+        KeyCode::Numpad1,
+        KeyCode::Numpad2,
+        KeyCode::Numpad3,
+        KeyCode::Numpad4,
+        KeyCode::Numpad5,
+        KeyCode::Numpad6,
+        KeyCode::Numpad7,
+        KeyCode::Numpad8,
+        KeyCode::Numpad9,
+        KeyCode::NumpadAdd,
+        KeyCode::NumpadComma,
+        KeyCode::NumpadDecimal,
+        KeyCode::NumpadDivide,
+        KeyCode::NumpadEnter,
+        KeyCode::NumpadEqual,
+        KeyCode::NumpadMultiply,
+        KeyCode::NumpadSubtract,
+        KeyCode::Escape,
+        KeyCode::F1,
+        KeyCode::F2,
+        KeyCode::F3,
+        KeyCode::F4,
+        KeyCode::F5,
+        KeyCode::F6,
+        KeyCode::F7,
+        KeyCode::F8,
+        KeyCode::F9,
+        KeyCode::F10,
+        KeyCode::F11,
+        KeyCode::F12,
+        KeyCode::F13,
+        KeyCode::F14,
+        KeyCode::F15,
+        KeyCode::F16,
+        KeyCode::F17,
+        KeyCode::F18,
+        KeyCode::F19,
+        KeyCode::F20,
+        KeyCode::F21,
+        KeyCode::F22,
+        KeyCode::F23,
+        KeyCode::F24,
+        KeyCode::PrintScreen,
+        KeyCode::ScrollLock,
+        KeyCode::Pause,
+        KeyCode::BrowserBack,
+        KeyCode::BrowserFavorites,
+        KeyCode::BrowserForward,
+        KeyCode::BrowserHome,
+        KeyCode::BrowserRefresh,
+        KeyCode::BrowserSearch,
+        KeyCode::BrowserStop,
+        KeyCode::LaunchApp1,
+        KeyCode::LaunchApp2,
+        KeyCode::LaunchMail,
+        KeyCode::MediaPlayPause,
+        KeyCode::MediaSelect,
+        KeyCode::MediaStop,
+        KeyCode::MediaTrackNext,
+        KeyCode::MediaTrackPrevious,
+        KeyCode::Power,
+        KeyCode::Sleep,
+        KeyCode::WakeUp,
+        KeyCode::AudioVolumeDown,
+        KeyCode::AudioVolumeMute,
+        KeyCode::AudioVolumeUp,
+        KeyCode::Unidentified,
+    ];
+
+    #[test]
+    fn all_variants() {
+        assert_eq!(ALL.len(), KeyCode::Unidentified as usize + 1);
+        for (i, key_code) in ALL.into_iter().enumerate() {
+            assert_eq!(key_code as usize, i);
+        }
+    }
+
+    #[test]
+    fn key_round_trip() {
+        for key_code in ALL {
+            let Some(key) = key_code.to_key() else {
+                continue;
+            };
+
+            assert_eq!(KeyCode::from_key(key), key_code, "{key:?}");
+        }
+
+        assert_eq!(KeyCode::Unidentified.to_key(), None);
+    }
+
+    #[test]
+    fn virtual_key_round_trip() {
+        for code in 1..=u8::MAX {
+            for extended in [false, true] {
+                let key = Key::new(code, extended).unwrap();
+                let key_code = KeyCode::from_key(key);
+                if key_code == KeyCode::Unidentified {
+                    continue;
+                }
+
+                let canonical = key_code.to_key().unwrap();
+                assert_eq!(KeyCode::from_key(canonical), key_code, "{key:?}");
+                if canonical != key {
+                    assert!(is_lossy(key), "{key:?} came back as {canonical:?}");
+                }
+            }
+        }
+    }
+
+    /// Keys documented in [`KeyCode::from_key`] to come back as a different key.
+    fn is_lossy(key: Key) -> bool {
+        match (key.code.get(), key.extended) {
+            // Generic shift, side specific control and alt
+            (0x10 | 0xA2..=0xA5, _) => true,
+            // Numpad keys while Num Lock is off
+            (0x0C | 0x21..=0x28 | 0x2D | 0x2E, false) => true,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn lossy_keys() {
+        let key = |code, extended| Key::new(code, extended).unwrap();
+
+        assert_eq!(
+            KeyCode::from_key(key(0x10, false)).to_key(),
+            Some(key(0xA0, false))
+        );
+        assert_eq!(
+            KeyCode::from_key(key(0x10, true)).to_key(),
+            Some(key(0xA1, false))
+        );
+        assert_eq!(
+            KeyCode::from_key(key(0xA2, false)).to_key(),
+            Some(key(0x11, false))
+        );
+        assert_eq!(
+            KeyCode::from_key(key(0xA5, false)).to_key(),
+            Some(key(0x12, true))
+        );
+        assert_eq!(
+            KeyCode::from_key(key(0x23, false)).to_key(),
+            Some(key(0x61, false))
+        );
+        assert_eq!(KeyCode::from_key(key(0x23, true)), KeyCode::End);
+    }
+
+    #[test]
+    fn str_round_trip() {
+        for key_code in ALL {
+            assert_eq!(key_code.as_str().parse(), Ok(key_code));
+            assert_eq!(key_code.to_string(), key_code.as_str());
+        }
+
+        assert_eq!("".parse::<KeyCode>(), Err(ParseKeyCodeError));
+        assert_eq!("keya".parse::<KeyCode>(), Err(ParseKeyCodeError));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for key_code in ALL {
+            let json = serde_json::to_string(&key_code).unwrap();
+            assert_eq!(json, format!("\"{}\"", key_code.as_str()));
+            assert_eq!(serde_json::from_str::<KeyCode>(&json).unwrap(), key_code);
+        }
+
+        assert!(serde_json::from_str::<KeyCode>("\"Unknown\"").is_err());
+        assert!(serde_json::from_str::<KeyCode>("0").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 //! The [`Event`] enum and assorted types.
 
+pub mod filter;
 pub mod input;
 
 use input::InputEvent;
@@ -19,7 +20,30 @@ pub enum Event {
     ///
     /// The user may turn off input blocking at any time,
     /// for example, by pressing Alt+F4 on Windows.
-    InputBlockingEnded,
+    InputBlockingEnded {
+        /// Why input blocking has ended.
+        reason: InputBlockingEndReason,
+    },
+}
+
+/// Describe why input blocking has ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputBlockingEndReason {
+    /// Input blocking is turned off by the client.
+    ClientRequest,
+
+    /// The user requested to close the window, for example, by pressing Alt+F4 on Windows.
+    UserClose,
+
+    /// The user pressed the configured escape chord.
+    EscapeChord,
+
+    /// The client sent no request within the configured watchdog timeout.
+    Watchdog,
+
+    /// The client is disconnected or the overlay is shutting down.
+    ClientDisconnect,
 }
 
 /// Describe a window event.
@@ -33,6 +57,9 @@ pub enum WindowEvent {
 
         /// Initial height of the window
         height: u32,
+
+        /// Window information
+        info: WindowInfo,
     },
 
     /// Window size is changed.
@@ -44,6 +71,44 @@ pub enum WindowEvent {
         height: u32,
     },
 
+    /// Window title is changed.
+    TitleChanged {
+        /// New title of the window
+        title: String,
+    },
+
+    /// Window has gained or lost keyboard focus by activation.
+    ///
+    /// The window loses focus when the user switches to another window, for example, by Alt+Tab.
+    FocusChanged {
+        /// Whether if the window is focused
+        focused: bool,
+    },
+
+    /// Window is moved.
+    Moved {
+        /// New X position of the left-top corner of the client area, in screen coordinates
+        x: i32,
+
+        /// New Y position of the left-top corner of the client area, in screen coordinates
+        y: i32,
+    },
+
+    /// Window is minimized.
+    Minimized,
+
+    /// Window is restored from minimized state.
+    Restored,
+
+    /// DPI of the window is changed.
+    ///
+    /// This is sent when the window is moved to a monitor with different DPI,
+    /// or the DPI setting of the monitor is changed.
+    DpiChanged {
+        /// New DPI scale factor. `1.0` is 96 DPI.
+        scale: f32,
+    },
+
     /// Input event related to this window.
     ///
     /// You only receive this event if you are listening to input events
@@ -54,3 +119,99 @@ pub enum WindowEvent {
     /// This is likely the last event for this window.
     Destroyed,
 }
+
+/// Describe a window.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowInfo {
+    /// Title of the window.
+    pub title: String,
+
+    /// Window class name.
+    pub class_name: String,
+
+    /// Style of the window.
+    pub style: WindowStyle,
+
+    /// Id of the thread which created the window.
+    pub thread_id: u32,
+
+    /// DPI scale factor of the window. `1.0` is 96 DPI.
+    pub scale: f32,
+
+    /// Monitor which has the largest intersection with the window.
+    pub monitor: Option<MonitorInfo>,
+
+    /// Whether if the window was the foreground window when the information was collected.
+    ///
+    /// Later changes are reported by [`WindowEvent::FocusChanged`].
+    pub focused: bool,
+}
+
+/// Describe a monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorInfo {
+    /// X position of the monitor in screen coordinates.
+    pub x: i32,
+
+    /// Y position of the monitor in screen coordinates.
+    pub y: i32,
+
+    /// Width of the monitor.
+    pub width: u32,
+
+    /// Height of the monitor.
+    pub height: u32,
+
+    /// Refresh rate of the monitor in Hz.
+    ///
+    /// `0` or `1` means the hardware default refresh rate.
+    pub refresh_rate: u32,
+
+    /// Whether if the monitor is the primary monitor.
+    pub primary: bool,
+}
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    /// Describe style of a window.
+    pub struct WindowStyle: u8 {
+        /// Window covers the whole monitor.
+        const FULLSCREEN = 1;
+
+        /// Window has no title bar and sizing border.
+        const BORDERLESS = 1 << 1;
+
+        /// Window is a child window.
+        const CHILD = 1 << 2;
+
+        /// Window is owned by another window, like dialogs and splash screens.
+        const OWNED = 1 << 3;
+    }
+}
+
+#[cfg(feature = "serde")]
+const _: () = {
+    use serde::{Deserialize, Serialize};
+
+    impl Serialize for WindowStyle {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_u8(self.bits())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for WindowStyle {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            Ok(WindowStyle::from_bits_retain(u8::deserialize(
+                deserializer,
+            )?))
+        }
+    }
+};
@@ -0,0 +1,194 @@
+//! Gamepad inputs from XInput states.
+
+use asdf_overlay_window_event::{
+    Event, WindowEvent,
+    input::{GamepadAxis, GamepadButton, GamepadEvent, GamepadInput, InputEvent, KeyInputState},
+};
+use parking_lot::Mutex;
+use windows::Win32::{
+    System::Threading::GetCurrentThreadId,
+    UI::{
+        Input::KeyboardAndMouse::GetActiveWindow,
+        Input::XboxController::{
+            XINPUT_GAMEPAD, XINPUT_GAMEPAD_A, XINPUT_GAMEPAD_B, XINPUT_GAMEPAD_BACK,
+            XINPUT_GAMEPAD_BUTTON_FLAGS, XINPUT_GAMEPAD_DPAD_DOWN, XINPUT_GAMEPAD_DPAD_LEFT,
+            XINPUT_GAMEPAD_DPAD_RIGHT, XINPUT_GAMEPAD_DPAD_UP, XINPUT_GAMEPAD_LEFT_SHOULDER,
+            XINPUT_GAMEPAD_LEFT_THUMB, XINPUT_GAMEPAD_RIGHT_SHOULDER, XINPUT_GAMEPAD_RIGHT_THUMB,
+            XINPUT_GAMEPAD_START, XINPUT_GAMEPAD_X, XINPUT_GAMEPAD_Y, XUSER_MAX_COUNT,
+        },
+    },
+};
+
+use std::sync::atomic::Ordering;
+
+use crate::{Backends, event::EventSink, window::ListenInputFlags};
+
+const BUTTONS: [(XINPUT_GAMEPAD_BUTTON_FLAGS, GamepadButton); 14] = [
+    (XINPUT_GAMEPAD_DPAD_UP, GamepadButton::DPadUp),
+    (XINPUT_GAMEPAD_DPAD_DOWN, GamepadButton::DPadDown),
+    (XINPUT_GAMEPAD_DPAD_LEFT, GamepadButton::DPadLeft),
+    (XINPUT_GAMEPAD_DPAD_RIGHT, GamepadButton::DPadRight),
+    (XINPUT_GAMEPAD_START, GamepadButton::Start),
+    (XINPUT_GAMEPAD_BACK, GamepadButton::Back),
+    (XINPUT_GAMEPAD_LEFT_THUMB, GamepadButton::LeftThumb),
+    (XINPUT_GAMEPAD_RIGHT_THUMB, GamepadButton::RightThumb),
+    (XINPUT_GAMEPAD_LEFT_SHOULDER, GamepadButton::LeftShoulder),
+    (XINPUT_GAMEPAD_RIGHT_SHOULDER, GamepadButton::RightShoulder),
+    (XINPUT_GAMEPAD_A, GamepadButton::A),
+    (XINPUT_GAMEPAD_B, GamepadButton::B),
+    (XINPUT_GAMEPAD_X, GamepadButton::X),
+    (XINPUT_GAMEPAD_Y, GamepadButton::Y),
+];
+
+/// Last known gamepad states of each user index.
+static STATES: Mutex<[Option<XINPUT_GAMEPAD>; XUSER_MAX_COUNT as usize]> =
+    Mutex::new([None; XUSER_MAX_COUNT as usize]);
+
+/// Update gamepad state read by `XInputGetState` and emit changes.
+///
+/// `state` is [`None`] if the gamepad is not connected.
+pub fn update(user_index: u32, state: Option<XINPUT_GAMEPAD>) {
+    // State is tracked regardless of listening, so listening does not start with stale changes.
+    let Some(last) = STATES.lock().get_mut(user_index as usize).map(|last| {
+        let prev = *last;
+        *last = state;
+        prev
+    }) else {
+        return;
+    };
+
+    if last == state {
+        return;
+    }
+
+    let Some(window_id) = target_window() else {
+        return;
+    };
+    if !listening(window_id) {
+        return;
+    }
+
+    let emit = |event: GamepadEvent| {
+        EventSink::emit(Event::Window {
+            id: window_id,
+            event: WindowEvent::Input(InputEvent::Gamepad(GamepadInput {
+                user_index: user_index as u8,
+                event,
+            })),
+        });
+    };
+
+    let (last, state) = match (last, state) {
+        (None, Some(state)) => {
+            emit(GamepadEvent::Connected);
+            (XINPUT_GAMEPAD::default(), state)
+        }
+
+        (Some(_), None) => {
+            emit(GamepadEvent::Disconnected);
+            return;
+        }
+
+        (Some(last), Some(state)) => (last, state),
+
+        (None, None) => return,
+    };
+
+    for (flag, button) in BUTTONS {
+        let pressed = state.wButtons.contains(flag);
+        if last.wButtons.contains(flag) == pressed {
+            continue;
+        }
+
+        emit(GamepadEvent::Button {
+            button,
+            state: if pressed {
+                KeyInputState::Pressed
+            } else {
+                KeyInputState::Released
+            },
+        });
+    }
+
+    for (axis, last, value) in [
+        (GamepadAxis::LeftThumbX, last.sThumbLX, state.sThumbLX),
+        (GamepadAxis::LeftThumbY, last.sThumbLY, state.sThumbLY),
+        (GamepadAxis::RightThumbX, last.sThumbRX, state.sThumbRX),
+        (GamepadAxis::RightThumbY, last.sThumbRY, state.sThumbRY),
+    ] {
+        if last != value {
+            emit(GamepadEvent::Axis {
+                axis,
+                value: (value as f32 / i16::MAX as f32).max(-1.0),
+            });
+        }
+    }
+
+    for (axis, last, value) in [
+        (
+            GamepadAxis::LeftTrigger,
+            last.bLeftTrigger,
+            state.bLeftTrigger,
+        ),
+        (
+            GamepadAxis::RightTrigger,
+            last.bRightTrigger,
+            state.bRightTrigger,
+        ),
+    ] {
+        if last != value {
+            emit(GamepadEvent::Axis {
+                axis,
+                value: value as f32 / u8::MAX as f32,
+            });
+        }
+    }
+}
+
+/// Window the polling thread belongs to.
+///
+/// Active window of the calling thread is preferred, then a window created by the thread.
+/// Games polling from a worker thread have no window, so the focused window of the process is used.
+#[inline]
+fn target_window() -> Option<u32> {
+    let hwnd = unsafe { GetActiveWindow() };
+    if !hwnd.is_invalid() {
+        return Some(hwnd.0 as u32);
+    }
+
+    let thread_id = unsafe { GetCurrentThreadId() };
+    let windows = &Backends::get().windows;
+    let mut thread_window = None;
+    for window in windows.iter() {
+        if window.thread_id != thread_id {
+            continue;
+        }
+
+        if window.focused.load(Ordering::Relaxed) {
+            return Some(*window.key());
+        }
+        thread_window.get_or_insert(*window.key());
+    }
+    if thread_window.is_some() {
+        return thread_window;
+    }
+
+    windows
+        .iter()
+        .find(|window| window.focused.load(Ordering::Relaxed))
+        .map(|window| *window.key())
+}
+
+#[inline]
+fn listening(window_id: u32) -> bool {
+    let backends = Backends::get();
+    if backends.input_blocked() {
+        return true;
+    }
+
+    backends
+        .window_state(window_id, |state| {
+            state.input_flags().contains(ListenInputFlags::GAMEPAD)
+        })
+        .unwrap_or_default()
+}
@@ -4,9 +4,13 @@ use core::{
     ptr,
     sync::atomic::{AtomicUsize, Ordering},
 };
+use std::time::{Duration, Instant};
 
-use asdf_overlay_window_event::{Event, WindowEvent};
-use parking_lot::RwLock;
+use asdf_overlay_window_event::{
+    Event, InputBlockingEndReason, WindowEvent, filter::WindowFilter, input::EscapeChord,
+};
+use parking_lot::{Mutex, RwLock};
+use tracing::{debug, error};
 use windows::Win32::{
     Foundation::RECT,
     UI::WindowsAndMessaging::{
@@ -15,14 +19,27 @@ use windows::Win32::{
 };
 
 use crate::{
-    event::EventSink, message_loop::MessageLoopState, types::IntDashMap, window::WindowProcState,
+    event::EventSink,
+    message_loop::MessageLoopState,
+    types::IntDashMap,
+    window::{self, FilterKey, WindowProcState},
 };
 
+/// Minimum interval between sweeps of destroyed windows from the ignored windows.
+const IGNORED_WINDOWS_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
 pub struct GlobalState {
     pub message_loops: IntDashMap<u32, MessageLoopState>,
     pub windows: IntDashMap<u32, WindowProcState>,
+    /// Windows ignored by the window filter, with their state when filtered.
+    ignored_windows: IntDashMap<u32, FilterKey>,
+    /// Last time destroyed windows were swept from the ignored windows.
+    ignored_windows_swept: Mutex<Instant>,
+    window_filter: RwLock<WindowFilter>,
 
     blocking_cursor: AtomicUsize,
+    escape_chord: RwLock<Option<EscapeChord>>,
+    watchdog: Mutex<Option<Watchdog>>,
     pub blocking_state: RwLock<Option<InputBlockingState>>,
 }
 
@@ -31,7 +48,12 @@ impl GlobalState {
         Self {
             message_loops: IntDashMap::default(),
             windows: IntDashMap::default(),
+            ignored_windows: IntDashMap::default(),
+            ignored_windows_swept: Mutex::new(Instant::now()),
+            window_filter: RwLock::new(WindowFilter::default()),
             blocking_cursor: AtomicUsize::new(default_cursor().0 as usize),
+            escape_chord: RwLock::new(None),
+            watchdog: Mutex::new(None),
             blocking_state: RwLock::new(None),
         }
     }
@@ -47,6 +69,44 @@ impl GlobalState {
             .store(cursor.unwrap_or_default().0 as _, Ordering::Relaxed);
     }
 
+    #[inline]
+    pub fn escape_chord(&self) -> Option<EscapeChord> {
+        *self.escape_chord.read()
+    }
+
+    pub fn set_escape_chord(&self, chord: Option<EscapeChord>) {
+        *self.escape_chord.write() = chord;
+    }
+
+    pub fn set_blocking_watchdog(&self, timeout: Option<Duration>) {
+        *self.watchdog.lock() = timeout.map(|timeout| Watchdog {
+            timeout,
+            fed: Instant::now(),
+        });
+    }
+
+    /// Record that the client is still responding.
+    pub fn feed_watchdog(&self) {
+        if let Some(ref mut watchdog) = *self.watchdog.lock() {
+            watchdog.fed = Instant::now();
+        }
+    }
+
+    /// Unblock inputs if the client has not responded within the watchdog timeout.
+    pub fn check_watchdog(&self) {
+        if !self.input_blocked() {
+            return;
+        }
+
+        let expired = match *self.watchdog.lock() {
+            Some(ref watchdog) => watchdog.fed.elapsed() >= watchdog.timeout,
+            None => false,
+        };
+        if expired {
+            self.unblock_input(InputBlockingEndReason::Watchdog);
+        }
+    }
+
     /// Check if input is currently blocked.
     #[inline]
     pub fn input_blocked(&self) -> bool {
@@ -68,11 +128,12 @@ impl GlobalState {
             window.block_input();
         }
 
+        self.feed_watchdog();
         *self.blocking_state.write() = Some(InputBlockingState { clip_cursor });
     }
 
     /// Unblock inputs.
-    pub fn unblock_input(&self) {
+    pub fn unblock_input(&self, reason: InputBlockingEndReason) {
         let Some(state) = self.blocking_state.write().take() else {
             return;
         };
@@ -89,7 +150,7 @@ impl GlobalState {
             window.unblock_input();
         }
 
-        EventSink::emit(Event::InputBlockingEnded);
+        EventSink::emit(Event::InputBlockingEnded { reason });
     }
 
     /// Get or initialize the message loop state for the given thread ID.
@@ -123,11 +184,42 @@ impl GlobalState {
         self.message_loops.remove(&thread_id);
     }
 
-    pub fn window_state<R>(&self, window_id: u32, f: impl FnOnce(&WindowProcState) -> R) -> R {
+    /// Get or initialize the window state for the given window ID.
+    ///
+    /// Returns [`None`] if the window is ignored by the window filter or failed to initialize.
+    pub fn window_state<R>(
+        &self,
+        window_id: u32,
+        f: impl FnOnce(&WindowProcState) -> R,
+    ) -> Option<R> {
         if let Some(state) = self.windows.get(&window_id) {
-            return f(state.value());
+            return Some(f(state.value()));
         }
 
+        // Check before initializing, so ignored windows are not subclassed
+        let filter = self.window_filter.read();
+        if *filter != WindowFilter::default() {
+            let key = FilterKey::of(window_id)?;
+            if self
+                .ignored_windows
+                .get(&window_id)
+                .is_some_and(|ignored| *ignored == key)
+            {
+                return None;
+            }
+
+            if window::is_filtered(window_id, &key, &filter) {
+                drop(filter);
+                debug!("ignoring window: {window_id}");
+                self.ignored_windows.insert(window_id, key);
+                self.sweep_ignored_windows();
+                return None;
+            }
+
+            self.ignored_windows.remove(&window_id);
+        }
+        drop(filter);
+
         let state = self
             .windows
             .entry(window_id)
@@ -137,7 +229,11 @@ impl GlobalState {
                 let (width, height) = state.size();
                 EventSink::emit(Event::Window {
                     id: window_id,
-                    event: WindowEvent::Added { width, height },
+                    event: WindowEvent::Added {
+                        width,
+                        height,
+                        info: state.info(),
+                    },
                 });
 
                 if self.input_blocked() {
@@ -146,9 +242,36 @@ impl GlobalState {
 
                 Ok(state)
             })
-            .expect("failed to initialize window state")
+            .inspect_err(|err| error!("failed to initialize window state. err: {err:?}"))
+            .ok()?
             .downgrade();
-        f(state.value())
+        Some(f(state.value()))
+    }
+
+    /// Remove destroyed windows from the ignored windows, at most once per [`IGNORED_WINDOWS_SWEEP_INTERVAL`].
+    ///
+    /// Ignored windows are not subclassed, so their destruction is not notified.
+    fn sweep_ignored_windows(&self) {
+        {
+            let Some(mut swept) = self.ignored_windows_swept.try_lock() else {
+                return;
+            };
+            if swept.elapsed() < IGNORED_WINDOWS_SWEEP_INTERVAL {
+                return;
+            }
+            *swept = Instant::now();
+        }
+
+        self.ignored_windows
+            .retain(|&id, _| FilterKey::of(id).is_some());
+    }
+
+    /// Set rules for ignoring windows.
+    ///
+    /// The rules only apply to windows identified afterward.
+    pub fn set_window_filter(&self, filter: WindowFilter) {
+        *self.window_filter.write() = filter;
+        self.ignored_windows.clear();
     }
 
     pub fn cleanup_window(&self, window_id: u32) {
@@ -163,15 +286,24 @@ impl GlobalState {
     }
 
     pub fn reset(&self) {
-        self.unblock_input();
+        self.unblock_input(InputBlockingEndReason::ClientDisconnect);
         for state in self.windows.iter() {
             state.reset();
         }
 
         self.set_blocking_cursor(Some(default_cursor()));
+        self.set_escape_chord(None);
+        self.set_blocking_watchdog(None);
+        self.set_window_filter(WindowFilter::default());
     }
 }
 
+struct Watchdog {
+    timeout: Duration,
+    // Last time the client responded.
+    fed: Instant,
+}
+
 pub struct InputBlockingState {
     // Old cursor clipping rectangle, if any.
     pub clip_cursor: Option<RECT>,
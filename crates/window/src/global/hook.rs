@@ -1,17 +1,25 @@
-use core::ffi::c_void;
+use core::{
+    ffi::c_void,
+    mem,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use asdf_overlay_hook::DetourHook;
 use once_cell::sync::OnceCell;
-use tracing::{Level, debug};
+use tracing::{Level, debug, warn};
 use windows::{
     Win32::{
-        Foundation::{POINT, RECT},
-        UI::Input::{HRAWINPUT, RAW_INPUT_DATA_COMMAND_FLAGS, RAWINPUT},
+        Foundation::{ERROR_EMPTY, ERROR_SUCCESS, HANDLE, HMODULE, POINT, RECT},
+        System::LibraryLoader::{GetModuleHandleA, GetProcAddress},
+        UI::Input::{
+            HRAWINPUT, RAW_INPUT_DATA_COMMAND_FLAGS, RAWINPUT,
+            XboxController::{XINPUT_GAMEPAD, XINPUT_KEYSTROKE, XINPUT_STATE},
+        },
     },
-    core::BOOL,
+    core::{BOOL, PCSTR, PCWSTR, s},
 };
 
-use crate::Backends;
+use crate::{Backends, gamepad, raw_input};
 
 windows::core::link!("user32.dll" "system" fn ClipCursor(lprect: *const RECT) -> BOOL);
 windows::core::link!("user32.dll" "system" fn SetCursorPos(x: i32, y: i32) -> BOOL);
@@ -33,6 +41,7 @@ windows::core::link!(
     ) -> u32
 );
 windows::core::link!("user32.dll" "system" fn GetRawInputBuffer(pdata: *mut RAWINPUT, pcbsize: *mut u32, cbsizeheader: u32) -> u32);
+windows::core::link!("kernel32.dll" "system" fn LoadLibraryExW(lplibfilename: PCWSTR, hfile: HANDLE, dwflags: u32) -> HMODULE);
 
 pub struct Hook {
     pub clip_cursor: DetourHook<ClipCursorFn>,
@@ -45,9 +54,35 @@ pub struct Hook {
     pub get_key_state: DetourHook<GetKeyStateFn>,
     pub get_keyboard_state: DetourHook<GetKeyboardStateFn>,
     pub get_raw_input_buffer: DetourHook<GetRawInputBufferFn>,
+
+    /// Used to hook XInput modules loaded after installation.
+    pub load_library_ex_w: Option<DetourHook<LoadLibraryExWFn>>,
 }
 pub static HOOK: OnceCell<Hook> = OnceCell::new();
 
+/// XInput hooks of each module in [`XINPUT_MODULES`].
+static XINPUT: [XInputHook; XINPUT_MODULES.len()] =
+    [const { XInputHook::new() }; XINPUT_MODULES.len()];
+
+struct XInputHook {
+    /// Whether hooking the module was attempted.
+    attempted: AtomicBool,
+
+    get_state: OnceCell<DetourHook<XInputGetStateFn>>,
+    /// `XInputGetKeystroke` is not available before XInput 1.3.
+    get_keystroke: OnceCell<DetourHook<XInputGetKeystrokeFn>>,
+}
+
+impl XInputHook {
+    const fn new() -> Self {
+        Self {
+            attempted: AtomicBool::new(false),
+            get_state: OnceCell::new(),
+            get_keystroke: OnceCell::new(),
+        }
+    }
+}
+
 type ClipCursorFn = unsafe extern "system" fn(*const RECT) -> BOOL;
 type SetCursorPosFn = unsafe extern "system" fn(i32, i32) -> BOOL;
 
@@ -58,6 +93,38 @@ type GetAsyncKeyStateFn = unsafe extern "system" fn(i32) -> i16;
 type GetKeyStateFn = unsafe extern "system" fn(i32) -> i16;
 type GetKeyboardStateFn = unsafe extern "system" fn(*mut u8) -> BOOL;
 type GetRawInputBufferFn = unsafe extern "system" fn(*mut RAWINPUT, *mut u32, u32) -> u32;
+type LoadLibraryExWFn = unsafe extern "system" fn(PCWSTR, HANDLE, u32) -> HMODULE;
+type XInputGetStateFn = unsafe extern "system" fn(u32, *mut XINPUT_STATE) -> u32;
+type XInputGetKeystrokeFn = unsafe extern "system" fn(u32, u32, *mut XINPUT_KEYSTROKE) -> u32;
+
+/// XInput modules and their detour functions.
+const XINPUT_MODULES: [(PCSTR, XInputGetStateFn, XInputGetKeystrokeFn); 5] = [
+    (
+        s!("xinput1_4.dll"),
+        hooked_xinput_get_state::<0>,
+        hooked_xinput_get_keystroke::<0>,
+    ),
+    (
+        s!("xinput1_3.dll"),
+        hooked_xinput_get_state::<1>,
+        hooked_xinput_get_keystroke::<1>,
+    ),
+    (
+        s!("xinput9_1_0.dll"),
+        hooked_xinput_get_state::<2>,
+        hooked_xinput_get_keystroke::<2>,
+    ),
+    (
+        s!("xinput1_2.dll"),
+        hooked_xinput_get_state::<3>,
+        hooked_xinput_get_keystroke::<3>,
+    ),
+    (
+        s!("xinput1_1.dll"),
+        hooked_xinput_get_state::<4>,
+        hooked_xinput_get_keystroke::<4>,
+    ),
+];
 
 pub fn install() -> anyhow::Result<()> {
     HOOK.get_or_try_init(|| unsafe {
@@ -94,6 +161,20 @@ pub fn install() -> anyhow::Result<()> {
         let get_raw_input_buffer =
             DetourHook::attach(GetRawInputBuffer as _, hooked_get_raw_input_buffer as _)?;
 
+        debug!("hooking LoadLibraryExW");
+        let load_library_ex_w = match DetourHook::attach(
+            LoadLibraryExW as _,
+            hooked_load_library_ex_w as _,
+        ) {
+            Ok(hook) => Some(hook),
+            Err(err) => {
+                warn!(
+                    "failed to hook LoadLibraryExW, XInput loaded later is not hooked. err: {err}"
+                );
+                None
+            }
+        };
+
         Ok::<_, anyhow::Error>(Hook {
             clip_cursor,
             set_cursor_pos,
@@ -105,9 +186,68 @@ pub fn install() -> anyhow::Result<()> {
             get_key_state,
             get_keyboard_state,
             get_raw_input_buffer,
+            load_library_ex_w,
         })
     })?;
 
+    install_xinput();
+    Ok(())
+}
+
+/// Hook XInput functions of every loaded XInput module not hooked yet.
+///
+/// Failing to hook a module is logged and the module is not retried.
+fn install_xinput() {
+    for (index, (name, get_state, get_keystroke)) in XINPUT_MODULES.into_iter().enumerate() {
+        let hook = &XINPUT[index];
+        if hook.attempted.load(Ordering::Acquire) {
+            continue;
+        }
+
+        let Ok(module) = (unsafe { GetModuleHandleA(name) }) else {
+            continue;
+        };
+        if hook.attempted.swap(true, Ordering::AcqRel) {
+            continue;
+        }
+
+        if let Err(err) = unsafe { hook_xinput(hook, module, get_state, get_keystroke) } {
+            warn!("failed to hook XInput module {}. err: {err}", unsafe {
+                name.display()
+            });
+        }
+    }
+}
+
+unsafe fn hook_xinput(
+    hook: &XInputHook,
+    module: HMODULE,
+    hooked_get_state: XInputGetStateFn,
+    hooked_get_keystroke: XInputGetKeystrokeFn,
+) -> anyhow::Result<()> {
+    let Some(get_state) = (unsafe { GetProcAddress(module, s!("XInputGetState")) }) else {
+        return Ok(());
+    };
+    debug!("hooking XInputGetState");
+    _ = hook.get_state.set(unsafe {
+        DetourHook::attach(
+            mem::transmute::<unsafe extern "system" fn() -> isize, XInputGetStateFn>(get_state),
+            hooked_get_state,
+        )?
+    });
+
+    if let Some(get_keystroke) = unsafe { GetProcAddress(module, s!("XInputGetKeystroke")) } {
+        debug!("hooking XInputGetKeystroke");
+        _ = hook.get_keystroke.set(unsafe {
+            DetourHook::attach(
+                mem::transmute::<unsafe extern "system" fn() -> isize, XInputGetKeystrokeFn>(
+                    get_keystroke,
+                ),
+                hooked_get_keystroke,
+            )?
+        });
+    }
+
     Ok(())
 }
 
@@ -207,12 +347,72 @@ extern "system" fn hooked_get_raw_input_buffer(
     pcbsize: *mut u32,
     cbsizeheader: u32,
 ) -> u32 {
+    let count =
+        unsafe { HOOK.wait().get_raw_input_buffer.original_fn()(pdata, pcbsize, cbsizeheader) };
+    if count != u32::MAX {
+        unsafe { raw_input::emit_from_buffer(pdata, count) };
+    }
+
     if !Backends::get().input_blocked() {
-        return unsafe {
-            HOOK.wait().get_raw_input_buffer.original_fn()(pdata, pcbsize, cbsizeheader)
-        };
+        return count;
     }
 
+    // Raw inputs are still read and reported, but withheld from the application
     unsafe { *pcbsize = 0 };
     0
 }
+
+#[tracing::instrument(level = Level::TRACE)]
+extern "system" fn hooked_load_library_ex_w(
+    lplibfilename: PCWSTR,
+    hfile: HANDLE,
+    dwflags: u32,
+) -> HMODULE {
+    let load_library_ex_w = HOOK.wait().load_library_ex_w.as_ref().unwrap();
+    let module = unsafe { load_library_ex_w.original_fn()(lplibfilename, hfile, dwflags) };
+    if !module.is_invalid() {
+        install_xinput();
+    }
+
+    module
+}
+
+#[tracing::instrument(level = Level::TRACE)]
+extern "system" fn hooked_xinput_get_state<const MODULE: usize>(
+    user_index: u32,
+    pstate: *mut XINPUT_STATE,
+) -> u32 {
+    let get_state = XINPUT[MODULE].get_state.wait();
+    let res = unsafe { get_state.original_fn()(user_index, pstate) };
+    if pstate.is_null() {
+        return res;
+    }
+
+    let connected = res == ERROR_SUCCESS.0;
+    gamepad::update(user_index, connected.then(|| unsafe { (*pstate).Gamepad }));
+
+    if connected && Backends::get().input_blocked() {
+        // Report neutral state so the game does not react to the controller
+        unsafe {
+            (*pstate).Gamepad = XINPUT_GAMEPAD::default();
+        }
+    }
+
+    res
+}
+
+#[tracing::instrument(level = Level::TRACE)]
+extern "system" fn hooked_xinput_get_keystroke<const MODULE: usize>(
+    user_index: u32,
+    reserved: u32,
+    pkeystroke: *mut XINPUT_KEYSTROKE,
+) -> u32 {
+    let get_keystroke = XINPUT[MODULE].get_keystroke.wait();
+    let res = unsafe { get_keystroke.original_fn()(user_index, reserved, pkeystroke) };
+    if res != ERROR_SUCCESS.0 || !Backends::get().input_blocked() {
+        return res;
+    }
+
+    // Drain the keystroke queue while input is blocked
+    ERROR_EMPTY.0
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwapOption;
+
+static CURRENT: ArcSwapOption<HitTester> = ArcSwapOption::const_empty();
+
+/// Decides whether cursor inputs at a point should be captured while input is not blocked.
+pub struct HitTester {
+    test: Box<dyn Fn(u32, i32, i32) -> bool + Send + Sync>,
+}
+
+impl HitTester {
+    pub fn set(test: impl Fn(u32, i32, i32) -> bool + Send + Sync + 'static) {
+        CURRENT.store(Some(Arc::new(Self {
+            test: Box::new(test),
+        })));
+    }
+
+    #[inline]
+    pub fn test(window_id: u32, x: i32, y: i32) -> bool {
+        match *CURRENT.load() {
+            Some(ref this) => (this.test)(window_id, x, y),
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn clear() {
+        CURRENT.store(None);
+    }
+}
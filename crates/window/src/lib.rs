@@ -1,17 +1,24 @@
 mod event;
+mod gamepad;
 mod global;
+mod hit_test;
 pub mod message_loop;
+mod pointer;
+mod raw_input;
 mod types;
 pub mod window;
 
 use core::sync::atomic::{AtomicBool, Ordering};
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Duration};
 
-use asdf_overlay_window_event::Event;
+use asdf_overlay_window_event::{
+    Event, InputBlockingEndReason, filter::WindowFilter, input::EscapeChord,
+};
 use windows::Win32::UI::WindowsAndMessaging::HCURSOR;
 
 use crate::{
-    event::EventSink, global::GlobalState, message_loop::MessageLoopState, window::WindowProcState,
+    event::EventSink, global::GlobalState, hit_test::HitTester, message_loop::MessageLoopState,
+    window::WindowProcState,
 };
 
 static GLOBAL: LazyLock<GlobalState> = LazyLock::new(GlobalState::new);
@@ -68,9 +75,11 @@ impl Backends {
     }
 
     /// Unblock input for the window.
+    ///
+    /// `reason` is reported to [`Event::InputBlockingEnded`].
     #[inline]
-    pub fn unblock_input(&self) {
-        Self::get().unblock_input();
+    pub fn unblock_input(&self, reason: InputBlockingEndReason) {
+        Self::get().unblock_input(reason);
     }
 
     /// Sets the cursor to be displayed while input is blocked.
@@ -79,6 +88,56 @@ impl Backends {
         Self::get().set_blocking_cursor(cursor);
     }
 
+    /// Sets the key combination which ends input blocking when pressed.
+    ///
+    /// If [`None`] is given, the escape chord is disabled.
+    #[inline]
+    pub fn set_escape_chord(&self, chord: Option<EscapeChord>) {
+        Self::get().set_escape_chord(chord);
+    }
+
+    /// Sets the watchdog timeout which ends input blocking when the client stops responding.
+    ///
+    /// Input blocking ends with [`InputBlockingEndReason::Watchdog`] if [`Backends::feed_watchdog`]
+    /// is not called within the timeout while input is blocked.
+    /// If [`None`] is given, the watchdog is disabled.
+    #[inline]
+    pub fn set_blocking_watchdog(&self, timeout: Option<Duration>) {
+        Self::get().set_blocking_watchdog(timeout);
+    }
+
+    /// Records that the client is still responding, resetting the watchdog timeout.
+    #[inline]
+    pub fn feed_watchdog(&self) {
+        Self::get().feed_watchdog();
+    }
+
+    /// Ends input blocking if the watchdog timeout has passed.
+    ///
+    /// The watchdog is checked whenever the application reads a message.
+    /// Call this periodically, so input blocking still ends if the application stops reading messages.
+    #[inline]
+    pub fn check_watchdog(&self) {
+        Self::get().check_watchdog();
+    }
+
+    /// Sets the rules for ignoring windows.
+    ///
+    /// Ignored windows are not subclassed and no event is sent for them.
+    /// The rules only apply to windows identified afterward.
+    #[inline]
+    pub fn set_window_filter(&self, filter: WindowFilter) {
+        Self::get().set_window_filter(filter);
+    }
+
+    /// Sets the hit tester deciding whether cursor inputs should be captured while input is not blocked.
+    ///
+    /// The tester is called with window id and cursor position relative to the window client area.
+    /// Cursor inputs are captured and blocked from reaching the window if it returns true.
+    pub fn set_hit_tester(&self, f: impl Fn(u32, i32, i32) -> bool + Send + Sync + 'static) {
+        HitTester::set(f);
+    }
+
     pub fn reset(&self) {
         Self::get().reset();
     }
@@ -92,8 +151,9 @@ impl Backends {
 impl Drop for Backends {
     fn drop(&mut self) {
         // Release input blocking on drop.
-        Self::get().unblock_input();
+        Self::get().unblock_input(InputBlockingEndReason::ClientDisconnect);
 
+        HitTester::clear();
         EventSink::clear();
     }
 }
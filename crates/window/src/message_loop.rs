@@ -1,11 +1,16 @@
 pub(crate) mod hook;
+mod scan_code;
 
+use core::sync::atomic::{AtomicBool, Ordering};
 use std::collections::vec_deque::VecDeque;
 
 use parking_lot::{Mutex, RwLock};
 use windows::Win32::{
     Foundation::{LPARAM, WPARAM},
-    UI::WindowsAndMessaging::{HCURSOR, PostThreadMessageA, SetCursor, ShowCursor, WM_NULL},
+    UI::{
+        Input::KeyboardAndMouse::VIRTUAL_KEY,
+        WindowsAndMessaging::{HCURSOR, PostThreadMessageA, SetCursor, ShowCursor, WM_NULL},
+    },
 };
 
 use crate::Backends;
@@ -18,6 +23,12 @@ pub struct MessageLoopState {
 
     blocking_state: RwLock<Option<InputBlockingState>>,
     proc_queue: Mutex<VecDeque<ProcDispatchFn>>,
+
+    /// Keys of the swallowed escape chord which are not released yet.
+    chord_keys: Mutex<Vec<VIRTUAL_KEY>>,
+
+    /// Whether if the thread reads raw inputs by `GetRawInputBuffer`.
+    raw_input_buffered: AtomicBool,
 }
 
 impl MessageLoopState {
@@ -26,9 +37,24 @@ impl MessageLoopState {
             id,
             blocking_state: RwLock::new(None),
             proc_queue: Mutex::new(VecDeque::new()),
+            chord_keys: Mutex::new(Vec::new()),
+            raw_input_buffered: AtomicBool::new(false),
         }
     }
 
+    /// Whether if the thread reads raw inputs by `GetRawInputBuffer`.
+    ///
+    /// Raw inputs of such threads are reported from the buffer instead of `WM_INPUT` messages.
+    #[inline]
+    pub(crate) fn raw_input_buffered(&self) -> bool {
+        self.raw_input_buffered.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn set_raw_input_buffered(&self) {
+        self.raw_input_buffered.store(true, Ordering::Relaxed);
+    }
+
     pub(crate) fn block_input(&self) {
         self.spawn_fn(|this| unsafe {
             let mut blocking_state = this.blocking_state.write();
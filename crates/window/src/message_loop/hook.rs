@@ -3,10 +3,10 @@ use std::time::Instant;
 
 use asdf_overlay_hook::DetourHook;
 use asdf_overlay_window_event::{
-    Event, WindowEvent,
+    Event, InputBlockingEndReason, WindowEvent,
     input::{
-        CursorAction, CursorEvent, CursorInput, CursorInputState, InputEvent, InputPosition, Key,
-        KeyInputState, KeyboardInput, ScrollAxis,
+        CursorAction, CursorButtons, CursorEvent, CursorInput, CursorInputState, EscapeChord,
+        InputEvent, InputPosition, Key, KeyInputState, KeyboardInput, Modifiers, ScrollAxis,
     },
 };
 use once_cell::sync::OnceCell;
@@ -20,18 +20,29 @@ use windows::{
             Controls::{self, HOVER_DEFAULT},
             Input::KeyboardAndMouse::{
                 MAPVK_VSC_TO_VK, MapVirtualKeyA, ReleaseCapture, SetCapture, TME_LEAVE,
-                TRACKMOUSEEVENT, TrackMouseEvent,
+                TRACKMOUSEEVENT, TrackMouseEvent, VIRTUAL_KEY, VK_CAPITAL, VK_CONTROL, VK_LBUTTON,
+                VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MBUTTON, VK_MENU, VK_NUMLOCK,
+                VK_RBUTTON, VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT, VK_XBUTTON1,
+                VK_XBUTTON2,
             },
             WindowsAndMessaging::{
                 self as msg, CallWindowProcA, CallWindowProcW, MSG, PEEK_MESSAGE_REMOVE_TYPE,
-                PM_REMOVE, TranslateMessage,
+                PM_REMOVE, TranslateMessage, XBUTTON2,
             },
         },
     },
     core::BOOL,
 };
 
-use crate::{Backends, event::EventSink, window::ListenInputFlags};
+use crate::{
+    Backends,
+    event::EventSink,
+    global,
+    hit_test::HitTester,
+    message_loop::{MessageLoopState, scan_code},
+    pointer, raw_input,
+    window::ListenInputFlags,
+};
 
 windows::core::link!("user32.dll" "system" fn GetMessageA(lpmsg: *mut MSG, hwnd: HWND, wmsgfiltermin: u32, wmsgfiltermax: u32) -> BOOL);
 windows::core::link!("user32.dll" "system" fn GetMessageW(lpmsg: *mut MSG, hwnd: HWND, wmsgfiltermin: u32, wmsgfiltermax: u32) -> BOOL);
@@ -122,9 +133,9 @@ fn get_message<const UNICODE: bool>(
     }
 
     let msg = unsafe { &mut *msg };
-    read_message::<UNICODE>(msg);
+    let captured = read_message::<UNICODE>(msg);
 
-    if should_filter(msg) {
+    if captured || should_filter(msg) {
         filtered_proc::<UNICODE>(msg);
         msg.message = msg::WM_NULL;
     }
@@ -163,11 +174,13 @@ fn peek_message<const UNICODE: bool>(
     }
 
     let msg = unsafe { &mut *msg };
-    if remove.contains(PM_REMOVE) {
-        read_message::<UNICODE>(msg);
-    }
+    let captured = if remove.contains(PM_REMOVE) {
+        read_message::<UNICODE>(msg)
+    } else {
+        !msg.hwnd.is_invalid() && !Backends::get().input_blocked() && hit_test(msg.hwnd.0 as _, msg)
+    };
 
-    if should_filter(msg) {
+    if captured || should_filter(msg) {
         filtered_proc::<UNICODE>(msg);
         msg.message = msg::WM_NULL;
     }
@@ -231,7 +244,9 @@ extern "system" fn hooked_peek_message_w(
 }
 
 /// Process the message.
-fn read_message<const UNICODE: bool>(msg: &MSG) {
+///
+/// Returns true if the message is a cursor input captured by hit testing.
+fn read_message<const UNICODE: bool>(msg: &mut MSG) -> bool {
     let id = unsafe { GetCurrentThreadId() };
 
     let backends = Backends::get();
@@ -240,26 +255,74 @@ fn read_message<const UNICODE: bool>(msg: &MSG) {
         backends.cleanup_message_loop(id);
     }
 
+    backends.check_watchdog();
+
+    // Swallow the key press so it does not reach to the window after unblocking.
+    if let Some(chord) = escape_chord(msg) {
+        backends.unblock_input(InputBlockingEndReason::EscapeChord);
+        backends.message_loop_state(id, |msg_loop_state| {
+            let mut keys = msg_loop_state.chord_keys.lock();
+            keys.clear();
+            keys.push(VIRTUAL_KEY(msg.wParam.0 as _));
+            for (held, key) in [
+                (chord.ctrl, VK_CONTROL),
+                (chord.shift, VK_SHIFT),
+                (chord.alt, VK_MENU),
+            ] {
+                if held {
+                    keys.push(key);
+                }
+            }
+        });
+        msg.message = msg::WM_NULL;
+    }
+
     backends.message_loop_state(id, move |msg_loop_state| {
-        if !msg.hwnd.is_invalid() {
-            let window_id = msg.hwnd.0 as _;
+        // The window has not seen the chord pressed, so swallow releases of its keys too.
+        if is_chord_key(msg_loop_state, msg) {
+            msg.message = msg::WM_NULL;
+        }
 
+        let mut captured = false;
+        let window_id = msg.hwnd.0 as u32;
+        // Ignored windows are not reported
+        if !msg.hwnd.is_invalid()
+            && let Some(input_flags) = backends.window_state(window_id, |state| state.input_flags())
+        {
             let input_blocked = backends.input_blocked();
-            let input_flags = backends.window_state(window_id, |state| state.input_flags());
 
-            if input_blocked || input_flags.contains(ListenInputFlags::CURSOR) {
+            if !input_blocked && hit_test(window_id, msg) {
+                captured = true;
+                update_hit_capture(window_id, msg);
+            }
+
+            if input_blocked || captured || input_flags.contains(ListenInputFlags::CURSOR) {
                 emit_cursor_event_from_message(window_id, msg);
+            } else if msg.message == msg::WM_MOUSEMOVE {
+                // Cursor moved out of interactive regions
+                cursor_leave(window_id);
             }
 
             if input_blocked || input_flags.contains(ListenInputFlags::KEYBOARD) {
                 emit_keyboard_event_from_message(window_id, msg);
             }
+
+            if input_blocked || input_flags.contains(ListenInputFlags::POINTER) {
+                pointer::emit_from_message(window_id, msg);
+            }
+
+            // Report once, if the thread reads the same raw inputs from the buffer
+            if msg.message == msg::WM_INPUT && !msg_loop_state.raw_input_buffered() {
+                raw_input::emit_from_message(window_id, msg);
+            }
         };
 
         for f in msg_loop_state.proc_queue.lock().drain(..) {
             f(msg_loop_state);
         }
-    });
+
+        captured
+    })
 }
 
 /// Process when the message is filtered.
@@ -334,29 +397,96 @@ fn emit_cursor_event_from_message(id: u32, msg: &MSG) {
     }
 }
 
+/// Check if the cursor message is over interactive regions,
+/// or a cursor button pressed over them is still being held.
+#[inline]
+fn hit_test(window_id: u32, msg: &MSG) -> bool {
+    let pos = match msg.message {
+        msg::WM_MOUSEMOVE
+        | msg::WM_LBUTTONDOWN
+        | msg::WM_LBUTTONUP
+        | msg::WM_LBUTTONDBLCLK
+        | msg::WM_RBUTTONDOWN
+        | msg::WM_RBUTTONUP
+        | msg::WM_RBUTTONDBLCLK
+        | msg::WM_MBUTTONDOWN
+        | msg::WM_MBUTTONUP
+        | msg::WM_MBUTTONDBLCLK
+        | msg::WM_XBUTTONDOWN
+        | msg::WM_XBUTTONUP
+        | msg::WM_XBUTTONDBLCLK => parse_cursor_position(msg.lParam),
+
+        // Wheel messages have screen coordinates
+        msg::WM_MOUSEWHEEL | msg::WM_MOUSEHWHEEL => {
+            let pos = parse_cursor_position(msg.lParam);
+            let mut point = POINT { x: pos.x, y: pos.y };
+            unsafe {
+                _ = ScreenToClient(msg.hwnd, &mut point);
+            }
+
+            InputPosition {
+                x: point.x,
+                y: point.y,
+            }
+        }
+
+        _ => return false,
+    };
+
+    Backends::get()
+        .window_state(window_id, |state| {
+            state.hit_buttons.load(Ordering::Relaxed) != 0
+        })
+        .is_some_and(|capturing| capturing || HitTester::test(window_id, pos.x, pos.y))
+}
+
+#[inline]
+fn update_hit_capture(window_id: u32, msg: &MSG) {
+    let (button, pressed) = match msg.message {
+        msg::WM_LBUTTONDOWN | msg::WM_LBUTTONDBLCLK => (CursorButtons::LEFT, true),
+        msg::WM_LBUTTONUP => (CursorButtons::LEFT, false),
+        msg::WM_RBUTTONDOWN | msg::WM_RBUTTONDBLCLK => (CursorButtons::RIGHT, true),
+        msg::WM_RBUTTONUP => (CursorButtons::RIGHT, false),
+        msg::WM_MBUTTONDOWN | msg::WM_MBUTTONDBLCLK => (CursorButtons::MIDDLE, true),
+        msg::WM_MBUTTONUP => (CursorButtons::MIDDLE, false),
+        msg::WM_XBUTTONDOWN | msg::WM_XBUTTONDBLCLK => (x_button(msg.wParam), true),
+        msg::WM_XBUTTONUP => (x_button(msg.wParam), false),
+
+        _ => return,
+    };
+
+    Backends::get().window_state(window_id, |state| {
+        if pressed {
+            state.hit_buttons.fetch_or(button.bits(), Ordering::Relaxed);
+        } else {
+            state
+                .hit_buttons
+                .fetch_and(!button.bits(), Ordering::Relaxed);
+        }
+    });
+}
+
+/// Get the extra button of a `WM_XBUTTON*` message.
+#[inline]
+fn x_button(wparam: WPARAM) -> CursorButtons {
+    if (wparam.0 >> 16) as u16 == XBUTTON2 {
+        CursorButtons::FORWARD
+    } else {
+        CursorButtons::BACK
+    }
+}
+
 #[inline]
 fn emit_keyboard_event_from_message(id: u32, msg: &MSG) {
     match msg.message {
         msg::WM_KEYDOWN | msg::WM_SYSKEYDOWN => {
-            if let Some(key) = to_key(msg.lParam) {
-                EventSink::emit(keyboard_input(
-                    id,
-                    KeyboardInput::Key {
-                        key,
-                        state: KeyInputState::Pressed,
-                    },
-                ));
+            if let Some(input) = key_input(msg.lParam, KeyInputState::Pressed) {
+                EventSink::emit(keyboard_input(id, input));
             }
         }
         msg::WM_KEYUP | msg::WM_SYSKEYUP => {
-            if let Some(key) = to_key(msg.lParam) {
-                EventSink::emit(keyboard_input(
-                    id,
-                    KeyboardInput::Key {
-                        key,
-                        state: KeyInputState::Released,
-                    },
-                ));
+            if let Some(input) = key_input(msg.lParam, KeyInputState::Released) {
+                EventSink::emit(keyboard_input(id, input));
             }
         }
         msg::WM_CHAR | msg::WM_SYSCHAR => {
@@ -395,22 +525,22 @@ fn cursor_action(hwnd: u32, action: CursorAction, pressed: bool, lparam: LPARAM)
     };
 
     let state = if pressed {
-        let click_count = Backends::get().window_state(hwnd, |state| {
-            state.get_click_count(pos.x, pos.y, index, Instant::now())
-        });
+        let click_count = Backends::get()
+            .window_state(hwnd, |state| {
+                state.get_click_count(pos.x, pos.y, index, Instant::now())
+            })
+            .unwrap_or(1);
 
         CursorInputState::Pressed { click_count }
     } else {
         CursorInputState::Released
     };
 
-    EventSink::emit(Event::Window {
-        id: hwnd,
-        event: WindowEvent::Input(InputEvent::Cursor(CursorInput {
-            event: CursorEvent::Action { action, state },
-            pos,
-        })),
-    });
+    EventSink::emit(cursor_input(
+        hwnd,
+        CursorEvent::Action { action, state },
+        pos,
+    ));
 }
 
 #[inline]
@@ -433,22 +563,10 @@ fn cursor_move(hwnd: u32, lparam: LPARAM) {
             });
         };
 
-        EventSink::emit(Event::Window {
-            id: hwnd,
-            event: WindowEvent::Input(InputEvent::Cursor(CursorInput {
-                event: CursorEvent::Enter,
-                pos,
-            })),
-        });
+        EventSink::emit(cursor_input(hwnd, CursorEvent::Enter, pos));
     });
 
-    EventSink::emit(Event::Window {
-        id: hwnd,
-        event: WindowEvent::Input(InputEvent::Cursor(CursorInput {
-            event: CursorEvent::Move,
-            pos,
-        })),
-    });
+    EventSink::emit(cursor_input(hwnd, CursorEvent::Move, pos));
 }
 
 fn cursor_leave(id: u32) {
@@ -476,13 +594,7 @@ fn cursor_leave(id: u32) {
         }
         state.cursor_hovering.store(false, Ordering::Relaxed);
 
-        EventSink::emit(Event::Window {
-            id,
-            event: WindowEvent::Input(InputEvent::Cursor(CursorInput {
-                event: CursorEvent::Leave,
-                pos,
-            })),
-        });
+        EventSink::emit(cursor_input(id, CursorEvent::Leave, pos));
     });
 }
 
@@ -491,20 +603,18 @@ fn cursor_scroll(hwnd: u32, wparam: WPARAM, lparam: LPARAM, horizontal: bool) {
     let [_, delta] = bytemuck::cast::<_, [i16; 2]>(wparam.0 as u32);
     let pos = parse_cursor_position(lparam);
 
-    EventSink::emit(Event::Window {
-        id: hwnd,
-        event: WindowEvent::Input(InputEvent::Cursor(CursorInput {
-            event: CursorEvent::Scroll {
-                axis: if horizontal {
-                    ScrollAxis::X
-                } else {
-                    ScrollAxis::Y
-                },
-                delta,
+    EventSink::emit(cursor_input(
+        hwnd,
+        CursorEvent::Scroll {
+            axis: if horizontal {
+                ScrollAxis::X
+            } else {
+                ScrollAxis::Y
             },
-            pos,
-        })),
-    });
+            delta,
+        },
+        pos,
+    ));
 }
 
 #[inline]
@@ -515,6 +625,7 @@ fn is_filter_target(message: u32) -> bool {
         // to legacy WM_LBUTTON*/WM_MOUSEMOVE/WM_MOUSEWHEEL messages.
         // Those legacy messages then re-enter this WndProc where they are
         // emitted to the message loop and blocked.
+        // Touch and pen inputs are emitted from the message loop before that.
         msg::WM_POINTERUPDATE
             | msg::WM_POINTERDOWN
             | msg::WM_POINTERUP
@@ -566,6 +677,117 @@ fn should_filter(msg: &MSG) -> bool {
     Backends::get().input_blocked()
 }
 
+/// Get the escape chord if the message is a key press matching it while input is blocked.
+#[inline]
+fn escape_chord(msg: &MSG) -> Option<EscapeChord> {
+    if !matches!(msg.message, msg::WM_KEYDOWN | msg::WM_SYSKEYDOWN) {
+        return None;
+    }
+
+    let backends = Backends::get();
+    if !backends.input_blocked() {
+        return None;
+    }
+
+    let chord = backends.escape_chord()?;
+    if to_key(msg.lParam) != Some(chord.key) {
+        return None;
+    }
+
+    let modifiers = modifiers();
+    (modifiers.ctrl() == chord.ctrl
+        && modifiers.shift() == chord.shift
+        && modifiers.alt() == chord.alt)
+        .then_some(chord)
+}
+
+/// Check if the message is a release or repeat of a swallowed escape chord key.
+///
+/// Keys are forgotten once released, or pressed again if the release was missed.
+fn is_chord_key(msg_loop_state: &MessageLoopState, msg: &MSG) -> bool {
+    let pressed = match msg.message {
+        msg::WM_KEYDOWN | msg::WM_SYSKEYDOWN => true,
+        msg::WM_KEYUP | msg::WM_SYSKEYUP => false,
+        _ => return false,
+    };
+
+    let mut keys = msg_loop_state.chord_keys.lock();
+    let key = VIRTUAL_KEY(msg.wParam.0 as _);
+    let Some(index) = keys.iter().position(|&chord_key| chord_key == key) else {
+        return false;
+    };
+
+    // Bit 30 is set if the key was already down, which is a repeat
+    if pressed && msg.lParam.0 & (1 << 30) != 0 {
+        return true;
+    }
+
+    keys.swap_remove(index);
+    !pressed
+}
+
+/// Read key state of the current thread.
+///
+/// GetKeyState reports neutral state while blocked, so call the original one.
+#[inline]
+fn key_state(key: VIRTUAL_KEY) -> i16 {
+    unsafe { global::hook::HOOK.wait().get_key_state.original_fn()(key.0 as _) }
+}
+
+/// Read modifier key states of the current thread.
+fn modifiers() -> Modifiers {
+    const KEYS: [(VIRTUAL_KEY, Modifiers); 8] = [
+        (VK_LSHIFT, Modifiers::LSHIFT),
+        (VK_RSHIFT, Modifiers::RSHIFT),
+        (VK_LCONTROL, Modifiers::LCTRL),
+        (VK_RCONTROL, Modifiers::RCTRL),
+        (VK_LMENU, Modifiers::LALT),
+        (VK_RMENU, Modifiers::RALT),
+        (VK_LWIN, Modifiers::LWIN),
+        (VK_RWIN, Modifiers::RWIN),
+    ];
+
+    let mut modifiers = Modifiers::empty();
+    for (key, modifier) in KEYS {
+        modifiers.set(modifier, key_state(key) < 0);
+    }
+
+    // Low-order bit is set if the key is toggled
+    modifiers.set(Modifiers::CAPS_LOCK, key_state(VK_CAPITAL) & 1 == 1);
+    modifiers.set(Modifiers::NUM_LOCK, key_state(VK_NUMLOCK) & 1 == 1);
+    modifiers
+}
+
+/// Read cursor buttons held down on the current thread.
+fn cursor_buttons() -> CursorButtons {
+    const KEYS: [(VIRTUAL_KEY, CursorButtons); 5] = [
+        (VK_LBUTTON, CursorButtons::LEFT),
+        (VK_RBUTTON, CursorButtons::RIGHT),
+        (VK_MBUTTON, CursorButtons::MIDDLE),
+        (VK_XBUTTON1, CursorButtons::BACK),
+        (VK_XBUTTON2, CursorButtons::FORWARD),
+    ];
+
+    let mut buttons = CursorButtons::empty();
+    for (key, button) in KEYS {
+        buttons.set(button, key_state(key) < 0);
+    }
+    buttons
+}
+
+#[inline(always)]
+pub(crate) fn cursor_input(id: u32, event: CursorEvent, pos: InputPosition) -> Event {
+    Event::Window {
+        id,
+        event: WindowEvent::Input(InputEvent::Cursor(CursorInput {
+            event,
+            pos,
+            modifiers: modifiers(),
+            buttons: cursor_buttons(),
+        })),
+    }
+}
+
 #[inline(always)]
 fn keyboard_input(id: u32, input: KeyboardInput) -> Event {
     Event::Window {
@@ -574,6 +796,28 @@ fn keyboard_input(id: u32, input: KeyboardInput) -> Event {
     }
 }
 
+/// Parse key input from the lParam of a key message.
+#[inline]
+fn key_input(lparam: LPARAM, state: KeyInputState) -> Option<KeyboardInput> {
+    let key = to_key(lparam)?;
+
+    let [repeat_count, flags] = bytemuck::cast::<_, [u16; 2]>(lparam.0 as u32);
+    let scan_code = (flags & 0xFF) | if key.extended { 0xE000 } else { 0 };
+
+    // Previous key state bit is set if the key was already down
+    let repeat = state == KeyInputState::Pressed && flags & (1 << 14) != 0;
+
+    Some(KeyboardInput::Key {
+        key,
+        physical_key: scan_code::to_physical_key(scan_code),
+        scan_code,
+        state,
+        repeat,
+        repeat_count,
+        modifiers: modifiers(),
+    })
+}
+
 #[inline]
 fn to_key(lparam: LPARAM) -> Option<Key> {
     let [_, _, code, flags] = bytemuck::cast::<_, [u8; 4]>(lparam.0 as u32);
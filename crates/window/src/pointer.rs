@@ -0,0 +1,121 @@
+//! Touch and pen inputs from pointer messages.
+
+use asdf_overlay_window_event::{
+    Event, WindowEvent,
+    input::{InputEvent, InputPosition, PenInput, PenTilt, PointerPhase, TouchInput},
+};
+use windows::Win32::{
+    Foundation::{HWND, POINT},
+    Graphics::Gdi::ScreenToClient,
+    UI::{
+        Input::Pointer::{
+            GetPointerPenInfo, GetPointerTouchInfo, GetPointerType, POINTER_FLAG_CANCELED,
+            POINTER_FLAG_PRIMARY, POINTER_INFO, POINTER_PEN_INFO, POINTER_TOUCH_INFO,
+        },
+        WindowsAndMessaging::{
+            self as msg, MSG, PEN_FLAG_BARREL, PEN_FLAG_ERASER, PEN_MASK_PRESSURE, PEN_MASK_TILT_X,
+            PEN_MASK_TILT_Y, POINTER_INPUT_TYPE, PT_PEN, PT_TOUCH, TOUCH_MASK_PRESSURE,
+        },
+    },
+};
+
+use crate::event::EventSink;
+
+/// Maximum pressure value reported by pointer devices.
+const MAX_PRESSURE: f32 = 1024.0;
+
+/// Emit touch or pen input from a `WM_POINTER*` message.
+pub fn emit_from_message(window_id: u32, msg: &MSG) {
+    let Some(phase) = to_phase(msg.message) else {
+        return;
+    };
+
+    // Low word of wParam is the pointer id
+    let pointer_id = msg.wParam.0 as u16 as u32;
+    let mut ty = POINTER_INPUT_TYPE::default();
+    if unsafe { GetPointerType(pointer_id, &mut ty) }.is_err() {
+        return;
+    }
+
+    let input = match ty {
+        PT_TOUCH => {
+            let mut info = POINTER_TOUCH_INFO::default();
+            if unsafe { GetPointerTouchInfo(pointer_id, &mut info) }.is_err() {
+                return;
+            }
+
+            InputEvent::Touch(TouchInput {
+                pointer_id,
+                phase: pointer_phase(phase, &info.pointerInfo),
+                primary: info.pointerInfo.pointerFlags.contains(POINTER_FLAG_PRIMARY),
+                pos: to_client(msg.hwnd, info.pointerInfo.ptPixelLocation),
+                pressure: (info.touchMask & TOUCH_MASK_PRESSURE != 0)
+                    .then(|| info.pressure as f32 / MAX_PRESSURE),
+            })
+        }
+
+        PT_PEN => {
+            let mut info = POINTER_PEN_INFO::default();
+            if unsafe { GetPointerPenInfo(pointer_id, &mut info) }.is_err() {
+                return;
+            }
+
+            InputEvent::Pen(PenInput {
+                pointer_id,
+                phase: pointer_phase(phase, &info.pointerInfo),
+                pos: to_client(msg.hwnd, info.pointerInfo.ptPixelLocation),
+                pressure: (info.penMask & PEN_MASK_PRESSURE != 0)
+                    .then(|| info.pressure as f32 / MAX_PRESSURE),
+                tilt: (info.penMask & (PEN_MASK_TILT_X | PEN_MASK_TILT_Y) != 0).then_some(
+                    PenTilt {
+                        x: info.tiltX,
+                        y: info.tiltY,
+                    },
+                ),
+                eraser: info.penFlags & PEN_FLAG_ERASER != 0,
+                barrel: info.penFlags & PEN_FLAG_BARREL != 0,
+            })
+        }
+
+        // Mouse and touchpad inputs are emitted as cursor inputs
+        _ => return,
+    };
+
+    EventSink::emit(Event::Window {
+        id: window_id,
+        event: WindowEvent::Input(input),
+    });
+}
+
+#[inline]
+fn to_phase(message: u32) -> Option<PointerPhase> {
+    Some(match message {
+        msg::WM_POINTERENTER => PointerPhase::Enter,
+        msg::WM_POINTERDOWN => PointerPhase::Down,
+        msg::WM_POINTERUPDATE => PointerPhase::Move,
+        msg::WM_POINTERUP => PointerPhase::Up,
+        msg::WM_POINTERLEAVE => PointerPhase::Leave,
+        _ => return None,
+    })
+}
+
+#[inline]
+fn pointer_phase(phase: PointerPhase, info: &POINTER_INFO) -> PointerPhase {
+    if info.pointerFlags.contains(POINTER_FLAG_CANCELED) {
+        PointerPhase::Cancel
+    } else {
+        phase
+    }
+}
+
+#[inline]
+fn to_client(hwnd: HWND, mut point: POINT) -> InputPosition {
+    unsafe {
+        _ = ScreenToClient(hwnd, &mut point);
+    }
+
+    InputPosition {
+        x: point.x,
+        y: point.y,
+    }
+}
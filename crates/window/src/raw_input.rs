@@ -0,0 +1,134 @@
+//! Relative mouse motion from raw inputs.
+
+use core::{mem, ptr};
+
+use asdf_overlay_window_event::input::{CursorEvent, InputPosition};
+use windows::Win32::{
+    Foundation::{HWND, POINT},
+    Graphics::Gdi::ScreenToClient,
+    System::Threading::GetCurrentThreadId,
+    UI::{
+        Input::{
+            GetRawInputData, HRAWINPUT, KeyboardAndMouse::GetActiveWindow, MOUSE_MOVE_ABSOLUTE,
+            RAWINPUT, RAWINPUTHEADER, RID_INPUT, RIM_TYPEMOUSE,
+        },
+        WindowsAndMessaging::MSG,
+    },
+};
+
+use crate::{
+    Backends, event::EventSink, global, message_loop::hook::cursor_input, window::ListenInputFlags,
+};
+
+/// Emit relative mouse motion from a `WM_INPUT` message.
+pub fn emit_from_message(window_id: u32, msg: &MSG) {
+    if !listening(window_id) {
+        return;
+    }
+
+    let mut input = RAWINPUT::default();
+    let mut size = mem::size_of::<RAWINPUT>() as u32;
+    let read = unsafe {
+        GetRawInputData(
+            HRAWINPUT(msg.lParam.0 as _),
+            RID_INPUT,
+            Some(ptr::from_mut(&mut input).cast()),
+            &mut size,
+            mem::size_of::<RAWINPUTHEADER>() as u32,
+        )
+    };
+    // Not a mouse input or failed to read
+    if read == u32::MAX || read == 0 {
+        return;
+    }
+
+    emit(window_id, &input, to_client(msg.hwnd, msg.pt));
+}
+
+/// Emit relative mouse motion from raw inputs read by `GetRawInputBuffer`.
+///
+/// The raw inputs are sent to the active window of the calling thread.
+/// Once called, `WM_INPUT` messages of the thread are no longer reported.
+///
+/// # Safety
+/// `data` must point to `count` raw inputs filled by `GetRawInputBuffer`.
+pub unsafe fn emit_from_buffer(data: *const RAWINPUT, count: u32) {
+    Backends::get().message_loop_state(unsafe { GetCurrentThreadId() }, |state| {
+        state.set_raw_input_buffered();
+    });
+
+    let hwnd = unsafe { GetActiveWindow() };
+    if hwnd.is_invalid() || data.is_null() {
+        return;
+    }
+
+    let window_id = hwnd.0 as u32;
+    if !listening(window_id) {
+        return;
+    }
+
+    let pos = {
+        let mut point = POINT::default();
+        unsafe {
+            _ = global::hook::HOOK.wait().get_cursor_pos.original_fn()(&mut point);
+        }
+
+        to_client(hwnd, point)
+    };
+
+    let mut input = data;
+    for _ in 0..count {
+        let raw = unsafe { &*input };
+        emit(window_id, raw, pos);
+
+        // Same as NEXTRAWINPUTBLOCK macro
+        let align = mem::size_of::<usize>();
+        let next = (raw.header.dwSize as usize).next_multiple_of(align);
+        input = unsafe { input.byte_add(next) };
+    }
+}
+
+#[inline]
+fn listening(window_id: u32) -> bool {
+    Backends::get()
+        .window_state(window_id, |state| {
+            state.input_flags().contains(ListenInputFlags::RAW_MOTION)
+        })
+        .unwrap_or_default()
+}
+
+#[inline]
+fn emit(window_id: u32, input: &RAWINPUT, pos: InputPosition) {
+    if input.header.dwType != RIM_TYPEMOUSE.0 {
+        return;
+    }
+
+    let mouse = unsafe { &input.data.mouse };
+    // Absolute motions are sent by tablets and remote desktops
+    if mouse.usFlags.0 & MOUSE_MOVE_ABSOLUTE.0 != 0 {
+        return;
+    }
+
+    let (dx, dy) = (mouse.lLastX, mouse.lLastY);
+    if dx == 0 && dy == 0 {
+        return;
+    }
+
+    EventSink::emit(cursor_input(
+        window_id,
+        CursorEvent::RawMotion { dx, dy },
+        pos,
+    ));
+}
+
+#[inline]
+fn to_client(hwnd: HWND, mut point: POINT) -> InputPosition {
+    unsafe {
+        _ = ScreenToClient(hwnd, &mut point);
+    }
+
+    InputPosition {
+        x: point.x,
+        y: point.y,
+    }
+}
@@ -1,4 +1,5 @@
 mod click_state;
+mod info;
 mod proc;
 
 use core::{
@@ -7,14 +8,16 @@ use core::{
 };
 use std::time::Instant;
 
+use asdf_overlay_window_event::{WindowInfo, filter::WindowFilter};
 use parking_lot::{Mutex, RwLock};
 use windows::Win32::{
-    Foundation::{HWND, LPARAM, RECT, WPARAM},
+    Foundation::{HWND, LPARAM, POINT, RECT, WPARAM},
+    Graphics::Gdi::ClientToScreen,
     UI::{
         Input::Ime::{HIMC, ImmAssociateContext, ImmCreateContext, ImmDestroyContext},
         WindowsAndMessaging::{
-            DefWindowProcA, GWLP_WNDPROC, GetClientRect, GetWindowThreadProcessId,
-            SetWindowLongPtrA, WM_IME_SETCONTEXT, WNDPROC,
+            DefWindowProcA, GWLP_WNDPROC, GetClientRect, GetForegroundWindow,
+            GetWindowThreadProcessId, IsIconic, SetWindowLongPtrA, WM_IME_SETCONTEXT, WNDPROC,
         },
     },
 };
@@ -32,7 +35,16 @@ pub struct WindowProcState {
     pub thread_id: u32,
 
     pub(crate) cursor_hovering: AtomicBool,
+    /// Cursor buttons pressed over interactive region.
+    ///
+    /// Cursor inputs are captured until every button is released.
+    pub(crate) hit_buttons: AtomicU8,
+    /// Whether the window is minimized.
+    pub(crate) minimized: AtomicBool,
+    /// Whether the window is focused.
+    pub(crate) focused: AtomicBool,
     size: (AtomicU32, AtomicU32),
+    position: Mutex<(i32, i32)>,
 
     input_flags: AtomicU8,
     blocking_state: Mutex<Option<InputBlockData>>,
@@ -59,6 +71,7 @@ impl WindowProcState {
         };
         let thread_id = unsafe { GetWindowThreadProcessId(HWND(id as _), None) };
         let size = get_client_size(HWND(id as _))?;
+        let position = get_client_position(HWND(id as _));
 
         Ok(Self {
             original_proc,
@@ -66,7 +79,11 @@ impl WindowProcState {
             thread_id,
 
             cursor_hovering: AtomicBool::new(false),
+            hit_buttons: AtomicU8::new(0),
+            minimized: AtomicBool::new(unsafe { IsIconic(HWND(id as _)) }.as_bool()),
+            focused: AtomicBool::new(unsafe { GetForegroundWindow() } == HWND(id as _)),
             size: (AtomicU32::new(size.0), AtomicU32::new(size.1)),
+            position: Mutex::new(position),
 
             input_flags: AtomicU8::new(0),
             blocking_state: Mutex::new(None),
@@ -78,6 +95,7 @@ impl WindowProcState {
 
     pub fn reset(&self) {
         self.input_flags.store(0, Ordering::Relaxed);
+        self.hit_buttons.store(0, Ordering::Relaxed);
     }
 
     pub fn size(&self) -> (u32, u32) {
@@ -87,6 +105,11 @@ impl WindowProcState {
         )
     }
 
+    /// Collect current information of the window.
+    pub fn info(&self) -> WindowInfo {
+        info::window_info(HWND(self.id as _))
+    }
+
     pub fn input_flags(&self) -> ListenInputFlags {
         ListenInputFlags::from_bits_retain(self.input_flags.load(Ordering::Relaxed))
     }
@@ -100,6 +123,11 @@ impl WindowProcState {
         self.size.1.store(height, Ordering::Relaxed);
     }
 
+    /// Update client position in screen coordinates. Returns true if the position changed.
+    pub(crate) fn set_position(&self, x: i32, y: i32) -> bool {
+        mem::replace(&mut *self.position.lock(), (x, y)) != (x, y)
+    }
+
     pub(crate) fn get_click_count(&self, x: i32, y: i32, button: u32, new_time: Instant) -> u32 {
         self.click_state
             .lock()
@@ -180,9 +208,48 @@ bitflags::bitflags! {
         const CURSOR = 0b00000001;
         /// Listen for keyboard events.
         const KEYBOARD = 0b00000010;
+        /// Listen for relative mouse motion from raw inputs.
+        const RAW_MOTION = 0b00000100;
+        /// Listen for touch and pen events.
+        const POINTER = 0b00001000;
+        /// Listen for gamepad events.
+        const GAMEPAD = 0b00010000;
+    }
+}
+
+/// Thread, client area size and title of a window when it was checked against the filter.
+///
+/// Ignored windows are checked again if it changes, as windows can grow, be renamed or their ids can be reused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FilterKey {
+    thread_id: u32,
+    size: (u32, u32),
+    title: String,
+}
+
+impl FilterKey {
+    /// Returns [`None`] if the window is destroyed.
+    pub fn of(id: u32) -> Option<Self> {
+        let hwnd = HWND(id as _);
+        let thread_id = unsafe { GetWindowThreadProcessId(hwnd, None) };
+        if thread_id == 0 {
+            return None;
+        }
+
+        Some(Self {
+            thread_id,
+            size: get_client_size(hwnd).unwrap_or_default(),
+            title: info::window_title(hwnd),
+        })
     }
 }
 
+/// Check if the window is ignored by the filter.
+pub(crate) fn is_filtered(id: u32, key: &FilterKey, filter: &WindowFilter) -> bool {
+    let (width, height) = key.size;
+    filter.ignores(&info::window_info(HWND(id as _)), width, height)
+}
+
 /// Get client area size of the window.
 fn get_client_size(win: HWND) -> anyhow::Result<(u32, u32)> {
     unsafe {
@@ -191,3 +258,10 @@ fn get_client_size(win: HWND) -> anyhow::Result<(u32, u32)> {
         Ok((rect.right as u32, rect.bottom as u32))
     }
 }
+
+/// Get position of the client area in screen coordinates.
+fn get_client_position(win: HWND) -> (i32, i32) {
+    let mut pos = POINT::default();
+    _ = unsafe { ClientToScreen(win, &mut pos) };
+    (pos.x, pos.y)
+}
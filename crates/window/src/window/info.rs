@@ -0,0 +1,123 @@
+use core::mem;
+
+use asdf_overlay_window_event::{MonitorInfo, WindowInfo, WindowStyle};
+use windows::{
+    Win32::{
+        Foundation::{HWND, RECT},
+        Graphics::Gdi::{
+            DEVMODEW, ENUM_CURRENT_SETTINGS, EnumDisplaySettingsW, GetMonitorInfoW,
+            MONITOR_DEFAULTTONULL, MONITORINFO, MONITORINFOEXW, MonitorFromWindow,
+        },
+        UI::{
+            HiDpi::GetDpiForWindow,
+            WindowsAndMessaging::{
+                GW_OWNER, GWL_STYLE, GetClassNameW, GetForegroundWindow, GetWindow, GetWindowLongW,
+                GetWindowRect, GetWindowThreadProcessId, InternalGetWindowText,
+                MONITORINFOF_PRIMARY, USER_DEFAULT_SCREEN_DPI, WS_CAPTION, WS_CHILD, WS_THICKFRAME,
+            },
+        },
+    },
+    core::PCWSTR,
+};
+
+/// Collect information of the window.
+pub(super) fn window_info(hwnd: HWND) -> WindowInfo {
+    let monitor = monitor_info(hwnd);
+
+    WindowInfo {
+        title: window_title(hwnd),
+        class_name: {
+            let mut buf = [0_u16; 256];
+            let len = unsafe { GetClassNameW(hwnd, &mut buf) };
+            String::from_utf16_lossy(&buf[..len.max(0) as usize])
+        },
+        style: window_style(hwnd, monitor.as_ref()),
+        thread_id: unsafe { GetWindowThreadProcessId(hwnd, None) },
+        scale: unsafe { GetDpiForWindow(hwnd) } as f32 / USER_DEFAULT_SCREEN_DPI as f32,
+        monitor,
+        focused: unsafe { GetForegroundWindow() } == hwnd,
+    }
+}
+
+/// Get title of the window.
+///
+/// Unlike `GetWindowTextW`, this does not send `WM_GETTEXT` to the window so it cannot deadlock.
+pub(crate) fn window_title(hwnd: HWND) -> String {
+    let mut buf = [0_u16; 512];
+    let len = unsafe { InternalGetWindowText(hwnd, &mut buf) };
+    String::from_utf16_lossy(&buf[..len.max(0) as usize])
+}
+
+fn window_style(hwnd: HWND, monitor: Option<&MonitorInfo>) -> WindowStyle {
+    let raw_style = unsafe { GetWindowLongW(hwnd, GWL_STYLE) } as u32;
+
+    let mut style = WindowStyle::empty();
+    if raw_style & WS_CHILD.0 != 0 {
+        style |= WindowStyle::CHILD;
+    } else if raw_style & (WS_CAPTION.0 | WS_THICKFRAME.0) == 0 {
+        style |= WindowStyle::BORDERLESS;
+    }
+
+    if unsafe { GetWindow(hwnd, GW_OWNER) }.is_ok_and(|owner| !owner.is_invalid()) {
+        style |= WindowStyle::OWNED;
+    }
+
+    let mut rect = RECT::default();
+    if let Some(monitor) = monitor
+        && unsafe { GetWindowRect(hwnd, &mut rect) }.is_ok()
+        && rect.left <= monitor.x
+        && rect.top <= monitor.y
+        && rect.right >= monitor.x + monitor.width as i32
+        && rect.bottom >= monitor.y + monitor.height as i32
+    {
+        style |= WindowStyle::FULLSCREEN;
+    }
+
+    style
+}
+
+fn monitor_info(hwnd: HWND) -> Option<MonitorInfo> {
+    let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL) };
+    if monitor.is_invalid() {
+        return None;
+    }
+
+    let mut info = MONITORINFOEXW {
+        monitorInfo: MONITORINFO {
+            cbSize: mem::size_of::<MONITORINFOEXW>() as u32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    if !unsafe { GetMonitorInfoW(monitor, &raw mut info.monitorInfo) }.as_bool() {
+        return None;
+    }
+
+    let mut mode = DEVMODEW {
+        dmSize: mem::size_of::<DEVMODEW>() as u16,
+        ..Default::default()
+    };
+    let refresh_rate = if unsafe {
+        EnumDisplaySettingsW(
+            PCWSTR(info.szDevice.as_ptr()),
+            ENUM_CURRENT_SETTINGS,
+            &mut mode,
+        )
+    }
+    .as_bool()
+    {
+        mode.dmDisplayFrequency
+    } else {
+        0
+    };
+
+    let rect = info.monitorInfo.rcMonitor;
+    Some(MonitorInfo {
+        x: rect.left,
+        y: rect.top,
+        width: (rect.right - rect.left) as u32,
+        height: (rect.bottom - rect.top) as u32,
+        refresh_rate,
+        primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+    })
+}
@@ -1,8 +1,8 @@
-use core::{alloc::Layout, mem, slice};
+use core::{alloc::Layout, mem, slice, sync::atomic::Ordering};
 use std::alloc;
 
 use asdf_overlay_window_event::{
-    Event, WindowEvent,
+    Event, InputBlockingEndReason, WindowEvent,
     input::{ConversionMode, Ime, ImeCandidateList, InputEvent, KeyboardInput},
 };
 use scopeguard::defer;
@@ -22,7 +22,7 @@ use windows::Win32::{
             KeyboardAndMouse::GetKeyboardLayout,
         },
         WindowsAndMessaging::{
-            self as msg, CallWindowProcA, DefWindowProcA, SetCursor, WM_NCDESTROY,
+            self as msg, CallWindowProcA, DefWindowProcA, IsIconic, SetCursor, WM_NCDESTROY,
         },
     },
 };
@@ -30,7 +30,9 @@ use windows::Win32::{
 use crate::{
     Backends,
     event::EventSink,
-    window::{ImeState, ListenInputFlags, get_client_size},
+    window::{
+        ImeState, ListenInputFlags, get_client_position, get_client_size, info::window_title,
+    },
 };
 
 #[tracing::instrument(level = Level::TRACE)]
@@ -54,8 +56,22 @@ pub(super) unsafe extern "system" fn hooked_wnd_proc(
         return ret;
     }
 
-    let original_proc = Backends::get().window_state(hwnd.0 as u32, |state| state.original_proc);
-    unsafe { CallWindowProcA(original_proc, hwnd, msg, wparam, lparam) }
+    let original_proc = Backends::get()
+        .window_state(hwnd.0 as u32, |state| state.original_proc)
+        .flatten();
+    let ret = unsafe { CallWindowProcA(original_proc, hwnd, msg, wparam, lparam) };
+
+    // Title is set after the window procedure processed the message
+    if msg == msg::WM_SETTEXT {
+        EventSink::emit(Event::Window {
+            id: hwnd.0 as u32,
+            event: WindowEvent::TitleChanged {
+                title: window_title(hwnd),
+            },
+        });
+    }
+
+    ret
 }
 
 #[inline]
@@ -63,20 +79,91 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
     match msg {
         msg::WM_WINDOWPOSCHANGED => {
             let winpos = unsafe { *(lparam.0 as *const msg::WINDOWPOS) };
-            if winpos.flags.0 & msg::SWP_NOSIZE.0 != 0 {
-                return None;
+            if winpos.flags.0 & msg::SWP_NOSIZE.0 == 0 {
+                let (width, height) = get_client_size(HWND(hwnd as _)).unwrap();
+                Backends::get().window_state(hwnd, |state| {
+                    state.set_size(width, height);
+                    EventSink::emit(Event::Window {
+                        id: hwnd,
+                        event: WindowEvent::Resized { width, height },
+                    });
+                });
             }
 
-            let (width, height) = get_client_size(HWND(hwnd as _)).unwrap();
-            Backends::get().window_state(hwnd, |state| {
-                state.set_size(width, height);
+            // Minimized windows are moved to offscreen
+            if winpos.flags.0 & msg::SWP_NOMOVE.0 == 0
+                && !unsafe { IsIconic(HWND(hwnd as _)) }.as_bool()
+            {
+                let (x, y) = get_client_position(HWND(hwnd as _));
+                let changed = Backends::get()
+                    .window_state(hwnd, |state| state.set_position(x, y))
+                    .unwrap_or_default();
+
+                if changed {
+                    EventSink::emit(Event::Window {
+                        id: hwnd,
+                        event: WindowEvent::Moved { x, y },
+                    });
+                }
+            }
+        }
+
+        msg::WM_SIZE
+            if matches!(
+                wparam.0 as u32,
+                msg::SIZE_RESTORED | msg::SIZE_MINIMIZED | msg::SIZE_MAXIMIZED
+            ) =>
+        {
+            let minimized = wparam.0 as u32 == msg::SIZE_MINIMIZED;
+            let changed = Backends::get()
+                .window_state(hwnd, |state| {
+                    state.minimized.swap(minimized, Ordering::Relaxed) != minimized
+                })
+                .unwrap_or_default();
+
+            if changed {
                 EventSink::emit(Event::Window {
                     id: hwnd,
-                    event: WindowEvent::Resized { width, height },
+                    event: if minimized {
+                        WindowEvent::Minimized
+                    } else {
+                        WindowEvent::Restored
+                    },
                 });
+            }
+        }
+
+        msg::WM_DPICHANGED => {
+            // Low word of wParam is the new X-axis DPI, which is same as Y-axis DPI
+            let dpi = wparam.0 as u16;
+            EventSink::emit(Event::Window {
+                id: hwnd,
+                event: WindowEvent::DpiChanged {
+                    scale: dpi as f32 / msg::USER_DEFAULT_SCREEN_DPI as f32,
+                },
             });
         }
 
+        msg::WM_ACTIVATE => {
+            let focused = wparam.0 as u16 as u32 != msg::WA_INACTIVE;
+            let changed = Backends::get()
+                .window_state(hwnd, |state| {
+                    state.focused.swap(focused, Ordering::Relaxed) != focused
+                })
+                .unwrap_or_default();
+
+            if changed {
+                EventSink::emit(Event::Window {
+                    id: hwnd,
+                    event: WindowEvent::FocusChanged { focused },
+                });
+            }
+
+            if Backends::get().input_blocked() {
+                return Some(LRESULT(0));
+            }
+        }
+
         // set cursor in client area
         msg::WM_SETCURSOR
             if {
@@ -96,7 +183,7 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
         msg::WM_CLOSE => {
             let global_state = Backends::get();
             if global_state.input_blocked() {
-                global_state.unblock_input();
+                global_state.unblock_input(InputBlockingEndReason::UserClose);
                 return Some(LRESULT(0));
             }
         }
@@ -107,7 +194,6 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
 
         // block other keyboard, mouse event
         msg::WM_CAPTURECHANGED
-        | msg::WM_ACTIVATE
         | msg::WM_ACTIVATEAPP
         | msg::WM_SETFOCUS
         | msg::WM_KILLFOCUS
@@ -129,9 +215,11 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
         msg::WM_IME_NOTIFY => {
             let input_blocked = Backends::get().input_blocked();
             let listening_keyboard = input_blocked
-                || Backends::get().window_state(hwnd, |state| {
-                    state.input_flags().contains(ListenInputFlags::KEYBOARD)
-                });
+                || Backends::get()
+                    .window_state(hwnd, |state| {
+                        state.input_flags().contains(ListenInputFlags::KEYBOARD)
+                    })
+                    .unwrap_or_default();
             if !listening_keyboard {
                 return None;
             }
@@ -145,9 +233,11 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
         msg::WM_INPUTLANGCHANGE => {
             let input_blocked = Backends::get().input_blocked();
             let listening_keyboard = input_blocked
-                || Backends::get().window_state(hwnd, |state| {
-                    state.input_flags().contains(ListenInputFlags::KEYBOARD)
-                });
+                || Backends::get()
+                    .window_state(hwnd, |state| {
+                        state.input_flags().contains(ListenInputFlags::KEYBOARD)
+                    })
+                    .unwrap_or_default();
             if !listening_keyboard {
                 return None;
             }
@@ -164,9 +254,11 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
         msg::WM_IME_SETCONTEXT => {
             let input_blocked = Backends::get().input_blocked();
             let listening_keyboard = input_blocked
-                || Backends::get().window_state(hwnd, |state| {
-                    state.input_flags().contains(ListenInputFlags::KEYBOARD)
-                });
+                || Backends::get()
+                    .window_state(hwnd, |state| {
+                        state.input_flags().contains(ListenInputFlags::KEYBOARD)
+                    })
+                    .unwrap_or_default();
             if !listening_keyboard {
                 return None;
             }
@@ -210,15 +302,19 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
         msg::WM_IME_COMPOSITION => {
             let input_blocked = Backends::get().input_blocked();
             let listening_keyboard = input_blocked
-                || Backends::get().window_state(hwnd, |state| {
-                    state.input_flags().contains(ListenInputFlags::KEYBOARD)
-                });
+                || Backends::get()
+                    .window_state(hwnd, |state| {
+                        state.input_flags().contains(ListenInputFlags::KEYBOARD)
+                    })
+                    .unwrap_or_default();
 
             if !listening_keyboard {
                 return None;
             }
 
-            let ime = Backends::get().window_state(hwnd, |state| *state.ime.read());
+            let ime = Backends::get()
+                .window_state(hwnd, |state| *state.ime.read())
+                .unwrap_or(ImeState::Disabled);
             if ime != ImeState::Disabled {
                 with_himc(hwnd, |himc| {
                     let comp = IME_COMPOSITION_STRING(lparam.0 as _);
@@ -269,9 +365,11 @@ fn process_wnd_proc(hwnd: u32, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Opti
         }
 
         msg::WM_IME_ENDCOMPOSITION => {
-            let ime = Backends::get().window_state(hwnd, |state| {
-                mem::replace(&mut *state.ime.write(), ImeState::Disabled)
-            });
+            let ime = Backends::get()
+                .window_state(hwnd, |state| {
+                    mem::replace(&mut *state.ime.write(), ImeState::Disabled)
+                })
+                .unwrap_or(ImeState::Disabled);
 
             if ime == ImeState::Compose {
                 let himc = unsafe { ImmGetContext(HWND(hwnd as _)) };
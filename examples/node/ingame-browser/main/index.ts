@@ -63,10 +63,9 @@ async function createOverlayWindow(pid: number) {
   let aState: KeyInputState = 'Released';
   overlay.event.on('window_keyboard_input', (_, input) => {
     keybind: if (input.type === 'Key') {
-      const key = input.key;
-      if (key.code === 0x10 && !key.extended) {
+      if (input.keyCode === 'ShiftLeft') {
         shiftState = input.state;
-      } else if (key.code === 0x41) {
+      } else if (input.keyCode === 'KeyA') {
         aState = input.state;
       } else {
         break keybind;
@@ -51,7 +51,7 @@ impl App for MyApp {
             });
     }
 
-    fn on_input_blocking_ended(&mut self) {
+    fn on_input_blocking_ended(&mut self, _reason: InputBlockingEndReason) {
         self.input_block = false;
     }
 }
@@ -32,7 +32,7 @@ async fn main() -> anyhow::Result<()> {
     while let Some(event) = event.recv().await {
         dbg!(&event);
 
-        if let OverlayEvent::InputBlockingEnded = event {
+        if let OverlayEvent::InputBlockingEnded { .. } = event {
             break;
         }
     }
@@ -1,14 +1,12 @@
 use core::time::Duration;
 use std::env;
 
-use anyhow::{Context, bail};
+use anyhow::Context;
 use asdf_overlay_client::{
     OverlayDll,
-    common::{
-        event::{OverlayEvent, surface::SurfaceEvent},
-        request::surface::SetPosition,
-    },
+    common::request::surface::SetPosition,
     inject,
+    state::{MainSurfacePreference, OverlayState},
 };
 use asdf_overlay_surface_util::surface::OverlaySurface;
 use tokio::time::sleep;
@@ -22,7 +20,7 @@ async fn main() -> anyhow::Result<()> {
         .join("packages/core");
 
     // inject overlay dll into target process
-    let (mut conn, mut event) = inject(
+    let (mut conn, event) = inject(
         pid.parse::<u32>().context("invalid pid")?,
         OverlayDll {
             x64: Some(&dll_dir.join("asdf_overlay-x64.dll")),
@@ -33,19 +31,12 @@ async fn main() -> anyhow::Result<()> {
     )
     .await?;
 
-    let id = loop {
-        let Some(event) = event.recv().await else {
-            bail!("failed to receive main surface");
-        };
-
-        if let OverlayEvent::Surface {
-            id,
-            event: SurfaceEvent::Added { .. },
-        } = event
-        {
-            break id;
-        }
-    };
+    let (state, _event) = OverlayState::new(event);
+    let id = state
+        .wait_for_main_surface(MainSurfacePreference::default())
+        .await
+        .context("failed to receive main surface")?
+        .id;
 
     sleep(Duration::from_secs(1)).await;
 
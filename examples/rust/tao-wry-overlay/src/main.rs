@@ -1,14 +1,12 @@
 use core::mem;
 use std::{env, sync::Arc};
 
-use anyhow::{Context, bail};
+use anyhow::Context;
 use asdf_overlay_client::{
     OverlayDll,
     client::{IpcClientConn, IpcClientEventStream},
-    common::{
-        event::{OverlayEvent, surface::SurfaceEvent},
-        request::surface::UpdateSharedHandle,
-    },
+    common::{event::OverlayEvent, request::surface::UpdateSharedHandle},
+    state::{MainSurfacePreference, OverlayState},
 };
 use asdf_overlay_surface_util::capture::D3DCapturePool;
 use tao::{
@@ -39,10 +37,15 @@ async fn main() -> anyhow::Result<()> {
         .parse::<u32>()
         .context("pid is not a valid number")?;
 
-    let (conn, mut event) = setup_overlay_client(pid).await?;
+    let (conn, event) = setup_overlay_client(pid).await?;
     let conn = Arc::new(tokio::sync::Mutex::new(conn));
 
-    let surface_id = fetch_main_surface_id(&mut event).await?;
+    let (state, mut event) = OverlayState::new(event);
+    let surface_id = state
+        .wait_for_main_surface(MainSurfacePreference::default())
+        .await
+        .context("failed to receive main surface")?
+        .id;
     eprintln!("main surface id: {surface_id}");
 
     let view_size = (1280, 720);
@@ -152,22 +155,6 @@ async fn main() -> anyhow::Result<()> {
     });
 }
 
-async fn fetch_main_surface_id(event: &mut IpcClientEventStream) -> anyhow::Result<u64> {
-    loop {
-        let Some(event) = event.recv().await else {
-            bail!("failed to receive main surface");
-        };
-
-        if let OverlayEvent::Surface {
-            id,
-            event: SurfaceEvent::Added { .. },
-        } = event
-        {
-            return Ok(id);
-        }
-    }
-}
-
 async fn setup_overlay_client(pid: u32) -> anyhow::Result<(IpcClientConn, IpcClientEventStream)> {
     let dll_dir = env::current_dir()
         .expect("cannot find pwd")
@@ -2,6 +2,7 @@ pub mod ime;
 pub mod input;
 pub mod surface;
 pub mod tracing;
+pub mod window;
 
 use anyhow::Context;
 use asdf_overlay_client::client::IpcClientEventStream;
@@ -13,9 +14,10 @@ use napi::{
     threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode, UnknownReturnValue},
 };
 
-use crate::event::input::InputEvent;
-use crate::event::surface::SurfaceInfo;
+use crate::event::input::{CursorInput, InputBlockingEndReason, InputEvent};
+use crate::event::surface::{BackbufferInfo, FrameStats, SurfaceInfo};
 use crate::event::tracing::TracingMetadata;
+use crate::event::window::WindowInfo;
 
 pub(crate) struct VarArgs(
     Box<dyn FnOnce(napi::sys::napi_env) -> napi::Result<Vec<napi::sys::napi_value>>>,
@@ -69,13 +71,41 @@ pub(crate) async fn event_task(mut stream: IpcClientEventStream, emit_tsfn: Emit
     while let Some(event) = stream.recv().await {
         match event {
             OverlayEvent::Window { id, event } => match event {
-                WindowEvent::Added { width, height } => {
-                    emitter.emit(("window_added", id, width, height));
+                WindowEvent::Added {
+                    width,
+                    height,
+                    info,
+                } => {
+                    emitter.emit(("window_added", id, width, height, WindowInfo::from(info)));
                 }
 
                 WindowEvent::Resized { width, height } => {
                     emitter.emit(("window_resized", id, width, height));
                 }
+                WindowEvent::TitleChanged { title } => {
+                    emitter.emit(("window_title_changed", id, title));
+                }
+
+                WindowEvent::FocusChanged { focused } => {
+                    emitter.emit(("window_focus_changed", id, focused));
+                }
+
+                WindowEvent::Moved { x, y } => {
+                    emitter.emit(("window_moved", id, x, y));
+                }
+
+                WindowEvent::Minimized => {
+                    emitter.emit(("window_minimized", id));
+                }
+
+                WindowEvent::Restored => {
+                    emitter.emit(("window_restored", id));
+                }
+
+                WindowEvent::DpiChanged { scale } => {
+                    emitter.emit(("window_dpi_changed", id, scale as f64));
+                }
+
                 WindowEvent::Input(input) => match InputEvent::from(input) {
                     InputEvent::Cursor { event } => {
                         emitter.emit(("window_cursor_input", id, event));
@@ -83,6 +113,15 @@ pub(crate) async fn event_task(mut stream: IpcClientEventStream, emit_tsfn: Emit
                     InputEvent::Keyboard { event } => {
                         emitter.emit(("window_keyboard_input", id, event));
                     }
+                    InputEvent::Touch { event } => {
+                        emitter.emit(("window_touch_input", id, event));
+                    }
+                    InputEvent::Pen { event } => {
+                        emitter.emit(("window_pen_input", id, event));
+                    }
+                    InputEvent::Gamepad { event } => {
+                        emitter.emit(("window_gamepad_input", id, event));
+                    }
                 },
 
                 WindowEvent::Destroyed => {
@@ -90,8 +129,8 @@ pub(crate) async fn event_task(mut stream: IpcClientEventStream, emit_tsfn: Emit
                 }
             },
 
-            OverlayEvent::InputBlockingEnded => {
-                emitter.emit(("input_blocking_ended",));
+            OverlayEvent::InputBlockingEnded { reason } => {
+                emitter.emit(("input_blocking_ended", InputBlockingEndReason::from(reason)));
             }
 
             OverlayEvent::Surface { id, event } => match event {
@@ -99,12 +138,38 @@ pub(crate) async fn event_task(mut stream: IpcClientEventStream, emit_tsfn: Emit
                     width,
                     height,
                     info,
+                    backbuffer,
+                } => {
+                    emitter.emit((
+                        "surface_added",
+                        id,
+                        width,
+                        height,
+                        SurfaceInfo::from(info),
+                        BackbufferInfo::from(backbuffer),
+                    ));
+                }
+
+                SurfaceEvent::Resized {
+                    width,
+                    height,
+                    backbuffer,
                 } => {
-                    emitter.emit(("surface_added", id, width, height, SurfaceInfo::from(info)));
+                    emitter.emit((
+                        "surface_resized",
+                        id,
+                        width,
+                        height,
+                        BackbufferInfo::from(backbuffer),
+                    ));
                 }
 
-                SurfaceEvent::Resized { width, height } => {
-                    emitter.emit(("surface_resized", id, width, height));
+                SurfaceEvent::Presented { frame, timestamp } => {
+                    emitter.emit(("surface_presented", id, frame, timestamp));
+                }
+
+                SurfaceEvent::Stats { stats } => {
+                    emitter.emit(("surface_stats", id, FrameStats::from(stats)));
                 }
 
                 SurfaceEvent::Destroyed => {
@@ -112,6 +177,21 @@ pub(crate) async fn event_task(mut stream: IpcClientEventStream, emit_tsfn: Emit
                 }
             },
 
+            OverlayEvent::SurfaceCursorInput {
+                id,
+                window_id,
+                layer,
+                input,
+            } => {
+                emitter.emit((
+                    "surface_cursor_input",
+                    id,
+                    window_id,
+                    CursorInput::from(input),
+                    layer,
+                ));
+            }
+
             OverlayEvent::Tracing(event) => match event {
                 TracingEvent::Enter(metadata) => {
                     emitter.emit(("tracing_enter", TracingMetadata::from(metadata)));
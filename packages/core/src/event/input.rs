@@ -1,4 +1,4 @@
-use asdf_overlay_client::common::event::window::input;
+use asdf_overlay_client::common::event::window::{self, input};
 use napi_derive::napi;
 
 use crate::event::ime::Ime;
@@ -6,6 +6,9 @@ use crate::event::ime::Ime;
 pub enum InputEvent {
     Cursor { event: CursorInput },
     Keyboard { event: KeyboardInput },
+    Touch { event: TouchInput },
+    Pen { event: PenInput },
+    Gamepad { event: GamepadInput },
 }
 
 impl From<input::InputEvent> for InputEvent {
@@ -17,6 +20,13 @@ impl From<input::InputEvent> for InputEvent {
             input::InputEvent::Keyboard(keyboard) => InputEvent::Keyboard {
                 event: keyboard.into(),
             },
+            input::InputEvent::Touch(touch) => InputEvent::Touch {
+                event: touch.into(),
+            },
+            input::InputEvent::Pen(pen) => InputEvent::Pen { event: pen.into() },
+            input::InputEvent::Gamepad(gamepad) => InputEvent::Gamepad {
+                event: gamepad.into(),
+            },
         }
     }
 }
@@ -31,6 +41,12 @@ pub struct CursorInput {
     pub y: i32,
 
     pub kind: CursorInputKind,
+
+    /// Modifier key states. Bitflags of `Modifier`.
+    pub modifiers: u16,
+
+    /// Cursor buttons held down. Bitflags of `CursorButton`.
+    pub buttons: u8,
 }
 
 impl From<input::CursorInput> for CursorInput {
@@ -39,6 +55,8 @@ impl From<input::CursorInput> for CursorInput {
             x: input.pos.x,
             y: input.pos.y,
             kind: input.event.into(),
+            modifiers: input.modifiers.bits(),
+            buttons: input.buttons.bits(),
         }
     }
 }
@@ -54,6 +72,9 @@ pub enum CursorInputKind {
     /// Cursor has moved
     Move,
 
+    /// Mouse has moved, in relative motion from raw inputs
+    RawMotion { dx: i32, dy: i32 },
+
     /// Cursor button has been pressed or released
     Action {
         action: CursorAction,
@@ -70,6 +91,7 @@ impl From<input::CursorEvent> for CursorInputKind {
             input::CursorEvent::Enter => CursorInputKind::Enter,
             input::CursorEvent::Leave => CursorInputKind::Leave,
             input::CursorEvent::Move => CursorInputKind::Move,
+            input::CursorEvent::RawMotion { dx, dy } => CursorInputKind::RawMotion { dx, dy },
             input::CursorEvent::Action { state, action } => CursorInputKind::Action {
                 action: action.into(),
                 state: state.into(),
@@ -82,10 +104,267 @@ impl From<input::CursorEvent> for CursorInputKind {
     }
 }
 
+/// Describe a touch contact input.
+#[napi(object)]
+pub struct TouchInput {
+    /// Identifier of the touch contact.
+    pub pointer_id: u32,
+
+    pub phase: PointerPhase,
+
+    /// Whether if this is the first contact of multiple touch contacts.
+    pub primary: bool,
+
+    /// X position relative to window.
+    pub x: i32,
+
+    /// Y position relative to window.
+    pub y: i32,
+
+    /// Normalized pressure in `0.0..=1.0` range, if the device reports it.
+    pub pressure: Option<f64>,
+}
+
+impl From<input::TouchInput> for TouchInput {
+    fn from(input: input::TouchInput) -> Self {
+        TouchInput {
+            pointer_id: input.pointer_id,
+            phase: input.phase.into(),
+            primary: input.primary,
+            x: input.pos.x,
+            y: input.pos.y,
+            pressure: input.pressure.map(f64::from),
+        }
+    }
+}
+
+/// Describe a pen input.
+#[napi(object)]
+pub struct PenInput {
+    /// Identifier of the pen.
+    pub pointer_id: u32,
+
+    pub phase: PointerPhase,
+
+    /// X position relative to window.
+    pub x: i32,
+
+    /// Y position relative to window.
+    pub y: i32,
+
+    /// Normalized pressure in `0.0..=1.0` range, if the device reports it.
+    pub pressure: Option<f64>,
+
+    /// Tilt angle toward right in degrees, if the device reports it.
+    pub tilt_x: Option<i32>,
+
+    /// Tilt angle toward user in degrees, if the device reports it.
+    pub tilt_y: Option<i32>,
+
+    /// Whether if the eraser end of the pen is used.
+    pub eraser: bool,
+
+    /// Whether if the barrel button of the pen is pressed.
+    pub barrel: bool,
+}
+
+impl From<input::PenInput> for PenInput {
+    fn from(input: input::PenInput) -> Self {
+        PenInput {
+            pointer_id: input.pointer_id,
+            phase: input.phase.into(),
+            x: input.pos.x,
+            y: input.pos.y,
+            pressure: input.pressure.map(f64::from),
+            tilt_x: input.tilt.map(|tilt| tilt.x),
+            tilt_y: input.tilt.map(|tilt| tilt.y),
+            eraser: input.eraser,
+            barrel: input.barrel,
+        }
+    }
+}
+
+/// Touch or pen input phase.
+#[napi(string_enum)]
+pub enum PointerPhase {
+    /// Pointer entered detection range of the window
+    Enter,
+
+    /// Pointer made contact
+    Down,
+
+    /// Pointer moved while in range
+    Move,
+
+    /// Pointer contact is lifted
+    Up,
+
+    /// Pointer left detection range of the window
+    Leave,
+
+    /// Pointer input is canceled by the system
+    Cancel,
+}
+
+impl From<input::PointerPhase> for PointerPhase {
+    fn from(phase: input::PointerPhase) -> Self {
+        match phase {
+            input::PointerPhase::Enter => PointerPhase::Enter,
+            input::PointerPhase::Down => PointerPhase::Down,
+            input::PointerPhase::Move => PointerPhase::Move,
+            input::PointerPhase::Up => PointerPhase::Up,
+            input::PointerPhase::Leave => PointerPhase::Leave,
+            input::PointerPhase::Cancel => PointerPhase::Cancel,
+        }
+    }
+}
+
+/// Describe a gamepad input.
+///
+/// Only emitted while the game polls gamepads with `XInputGetState`.
+#[napi(object)]
+pub struct GamepadInput {
+    /// Index of the gamepad, in `0..4` range.
+    pub user_index: u8,
+
+    pub kind: GamepadInputKind,
+}
+
+impl From<input::GamepadInput> for GamepadInput {
+    fn from(input: input::GamepadInput) -> Self {
+        GamepadInput {
+            user_index: input.user_index,
+            kind: input.event.into(),
+        }
+    }
+}
+
+#[napi]
+pub enum GamepadInputKind {
+    /// Gamepad is connected
+    Connected,
+
+    /// Gamepad is disconnected
+    Disconnected,
+
+    /// Gamepad button has been pressed or released
+    Button {
+        button: GamepadButton,
+        state: KeyInputState,
+    },
+
+    /// Gamepad axis value has changed.
+    /// Thumb sticks are in `-1.0..=1.0` range and triggers are in `0.0..=1.0` range
+    Axis { axis: GamepadAxis, value: f64 },
+}
+
+impl From<input::GamepadEvent> for GamepadInputKind {
+    fn from(event: input::GamepadEvent) -> Self {
+        match event {
+            input::GamepadEvent::Connected => GamepadInputKind::Connected,
+            input::GamepadEvent::Disconnected => GamepadInputKind::Disconnected,
+            input::GamepadEvent::Button { button, state } => GamepadInputKind::Button {
+                button: button.into(),
+                state: state.into(),
+            },
+            input::GamepadEvent::Axis { axis, value } => GamepadInputKind::Axis {
+                axis: axis.into(),
+                value: value.into(),
+            },
+        }
+    }
+}
+
+/// Gamepad buttons.
+#[napi(string_enum)]
+pub enum GamepadButton {
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Start,
+    Back,
+    LeftThumb,
+    RightThumb,
+    LeftShoulder,
+    RightShoulder,
+    A,
+    B,
+    X,
+    Y,
+}
+
+impl From<input::GamepadButton> for GamepadButton {
+    fn from(button: input::GamepadButton) -> Self {
+        match button {
+            input::GamepadButton::DPadUp => GamepadButton::DPadUp,
+            input::GamepadButton::DPadDown => GamepadButton::DPadDown,
+            input::GamepadButton::DPadLeft => GamepadButton::DPadLeft,
+            input::GamepadButton::DPadRight => GamepadButton::DPadRight,
+            input::GamepadButton::Start => GamepadButton::Start,
+            input::GamepadButton::Back => GamepadButton::Back,
+            input::GamepadButton::LeftThumb => GamepadButton::LeftThumb,
+            input::GamepadButton::RightThumb => GamepadButton::RightThumb,
+            input::GamepadButton::LeftShoulder => GamepadButton::LeftShoulder,
+            input::GamepadButton::RightShoulder => GamepadButton::RightShoulder,
+            input::GamepadButton::A => GamepadButton::A,
+            input::GamepadButton::B => GamepadButton::B,
+            input::GamepadButton::X => GamepadButton::X,
+            input::GamepadButton::Y => GamepadButton::Y,
+        }
+    }
+}
+
+/// Gamepad axes.
+#[napi(string_enum)]
+pub enum GamepadAxis {
+    LeftThumbX,
+    LeftThumbY,
+    RightThumbX,
+    RightThumbY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl From<input::GamepadAxis> for GamepadAxis {
+    fn from(axis: input::GamepadAxis) -> Self {
+        match axis {
+            input::GamepadAxis::LeftThumbX => GamepadAxis::LeftThumbX,
+            input::GamepadAxis::LeftThumbY => GamepadAxis::LeftThumbY,
+            input::GamepadAxis::RightThumbX => GamepadAxis::RightThumbX,
+            input::GamepadAxis::RightThumbY => GamepadAxis::RightThumbY,
+            input::GamepadAxis::LeftTrigger => GamepadAxis::LeftTrigger,
+            input::GamepadAxis::RightTrigger => GamepadAxis::RightTrigger,
+        }
+    }
+}
+
 #[napi]
 pub enum KeyboardInput {
     /// A key is pressed or released.
-    Key { key: Key, state: KeyInputState },
+    Key {
+        key: Key,
+
+        /// Key code converted from the virtual-key code.
+        key_code: KeyCode,
+
+        /// Physical location of the key, regardless of the keyboard layout.
+        physical_key: KeyCode,
+
+        /// Hardware scan code of the key. Extended keys have `0xE0` prefix in the high byte.
+        scan_code: u16,
+
+        state: KeyInputState,
+
+        /// Whether if this input is generated by holding the key down.
+        repeat: bool,
+
+        /// Number of repeated keystrokes coalesced into this input.
+        repeat_count: u16,
+
+        /// Modifier key states after the key input. Bitflags of `Modifier`.
+        modifiers: u16,
+    },
 
     /// A character input due to a key press without involving IME.
     Char {
@@ -103,9 +382,23 @@ pub enum KeyboardInput {
 impl From<input::KeyboardInput> for KeyboardInput {
     fn from(input: input::KeyboardInput) -> Self {
         match input {
-            input::KeyboardInput::Key { key, state } => KeyboardInput::Key {
+            input::KeyboardInput::Key {
+                key,
+                physical_key,
+                scan_code,
+                state,
+                repeat,
+                repeat_count,
+                modifiers,
+            } => KeyboardInput::Key {
                 key: key.into(),
+                key_code: input::KeyCode::from(key).into(),
+                physical_key: physical_key.into(),
+                scan_code,
                 state: state.into(),
+                repeat,
+                repeat_count,
+                modifiers: modifiers.bits(),
             },
             input::KeyboardInput::Char(ch) => KeyboardInput::Char { ch: ch.to_string() },
             input::KeyboardInput::Ime(ime) => KeyboardInput::Ime { ime: ime.into() },
@@ -134,6 +427,483 @@ impl From<input::Key> for Key {
     }
 }
 
+impl From<Key> for Option<input::Key> {
+    fn from(key: Key) -> Self {
+        input::Key::new(key.code, key.extended)
+    }
+}
+
+/// Keyboard key, named after W3C UI Events `code` values.
+#[napi(string_enum)]
+pub enum KeyCode {
+    Backquote,
+    Backslash,
+    BracketLeft,
+    BracketRight,
+    Comma,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Equal,
+    IntlBackslash,
+    IntlRo,
+    IntlYen,
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Minus,
+    Period,
+    Quote,
+    Semicolon,
+    Slash,
+    AltLeft,
+    AltRight,
+    Backspace,
+    CapsLock,
+    ContextMenu,
+    ControlLeft,
+    ControlRight,
+    Enter,
+    MetaLeft,
+    MetaRight,
+    ShiftLeft,
+    ShiftRight,
+    Space,
+    Tab,
+    Convert,
+    KanaMode,
+    Lang1,
+    Lang2,
+    NonConvert,
+    Delete,
+    End,
+    Home,
+    Insert,
+    PageDown,
+    PageUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadComma,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadEnter,
+    NumpadEqual,
+    NumpadMultiply,
+    NumpadSubtract,
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    BrowserBack,
+    BrowserFavorites,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
+    BrowserSearch,
+    BrowserStop,
+    LaunchApp1,
+    LaunchApp2,
+    LaunchMail,
+    MediaPlayPause,
+    MediaSelect,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    Power,
+    Sleep,
+    WakeUp,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+    Unidentified,
+}
+
+impl From<input::KeyCode> for KeyCode {
+    fn from(key: input::KeyCode) -> Self {
+        match key {
+            input::KeyCode::Backquote => KeyCode::Backquote,
+            input::KeyCode::Backslash => KeyCode::Backslash,
+            input::KeyCode::BracketLeft => KeyCode::BracketLeft,
+            input::KeyCode::BracketRight => KeyCode::BracketRight,
+            input::KeyCode::Comma => KeyCode::Comma,
+            input::KeyCode::Digit0 => KeyCode::Digit0,
+            input::KeyCode::Digit1 => KeyCode::Digit1,
+            input::KeyCode::Digit2 => KeyCode::Digit2,
+            input::KeyCode::Digit3 => KeyCode::Digit3,
+            input::KeyCode::Digit4 => KeyCode::Digit4,
+            input::KeyCode::Digit5 => KeyCode::Digit5,
+            input::KeyCode::Digit6 => KeyCode::Digit6,
+            input::KeyCode::Digit7 => KeyCode::Digit7,
+            input::KeyCode::Digit8 => KeyCode::Digit8,
+            input::KeyCode::Digit9 => KeyCode::Digit9,
+            input::KeyCode::Equal => KeyCode::Equal,
+            input::KeyCode::IntlBackslash => KeyCode::IntlBackslash,
+            input::KeyCode::IntlRo => KeyCode::IntlRo,
+            input::KeyCode::IntlYen => KeyCode::IntlYen,
+            input::KeyCode::KeyA => KeyCode::KeyA,
+            input::KeyCode::KeyB => KeyCode::KeyB,
+            input::KeyCode::KeyC => KeyCode::KeyC,
+            input::KeyCode::KeyD => KeyCode::KeyD,
+            input::KeyCode::KeyE => KeyCode::KeyE,
+            input::KeyCode::KeyF => KeyCode::KeyF,
+            input::KeyCode::KeyG => KeyCode::KeyG,
+            input::KeyCode::KeyH => KeyCode::KeyH,
+            input::KeyCode::KeyI => KeyCode::KeyI,
+            input::KeyCode::KeyJ => KeyCode::KeyJ,
+            input::KeyCode::KeyK => KeyCode::KeyK,
+            input::KeyCode::KeyL => KeyCode::KeyL,
+            input::KeyCode::KeyM => KeyCode::KeyM,
+            input::KeyCode::KeyN => KeyCode::KeyN,
+            input::KeyCode::KeyO => KeyCode::KeyO,
+            input::KeyCode::KeyP => KeyCode::KeyP,
+            input::KeyCode::KeyQ => KeyCode::KeyQ,
+            input::KeyCode::KeyR => KeyCode::KeyR,
+            input::KeyCode::KeyS => KeyCode::KeyS,
+            input::KeyCode::KeyT => KeyCode::KeyT,
+            input::KeyCode::KeyU => KeyCode::KeyU,
+            input::KeyCode::KeyV => KeyCode::KeyV,
+            input::KeyCode::KeyW => KeyCode::KeyW,
+            input::KeyCode::KeyX => KeyCode::KeyX,
+            input::KeyCode::KeyY => KeyCode::KeyY,
+            input::KeyCode::KeyZ => KeyCode::KeyZ,
+            input::KeyCode::Minus => KeyCode::Minus,
+            input::KeyCode::Period => KeyCode::Period,
+            input::KeyCode::Quote => KeyCode::Quote,
+            input::KeyCode::Semicolon => KeyCode::Semicolon,
+            input::KeyCode::Slash => KeyCode::Slash,
+            input::KeyCode::AltLeft => KeyCode::AltLeft,
+            input::KeyCode::AltRight => KeyCode::AltRight,
+            input::KeyCode::Backspace => KeyCode::Backspace,
+            input::KeyCode::CapsLock => KeyCode::CapsLock,
+            input::KeyCode::ContextMenu => KeyCode::ContextMenu,
+            input::KeyCode::ControlLeft => KeyCode::ControlLeft,
+            input::KeyCode::ControlRight => KeyCode::ControlRight,
+            input::KeyCode::Enter => KeyCode::Enter,
+            input::KeyCode::MetaLeft => KeyCode::MetaLeft,
+            input::KeyCode::MetaRight => KeyCode::MetaRight,
+            input::KeyCode::ShiftLeft => KeyCode::ShiftLeft,
+            input::KeyCode::ShiftRight => KeyCode::ShiftRight,
+            input::KeyCode::Space => KeyCode::Space,
+            input::KeyCode::Tab => KeyCode::Tab,
+            input::KeyCode::Convert => KeyCode::Convert,
+            input::KeyCode::KanaMode => KeyCode::KanaMode,
+            input::KeyCode::Lang1 => KeyCode::Lang1,
+            input::KeyCode::Lang2 => KeyCode::Lang2,
+            input::KeyCode::NonConvert => KeyCode::NonConvert,
+            input::KeyCode::Delete => KeyCode::Delete,
+            input::KeyCode::End => KeyCode::End,
+            input::KeyCode::Home => KeyCode::Home,
+            input::KeyCode::Insert => KeyCode::Insert,
+            input::KeyCode::PageDown => KeyCode::PageDown,
+            input::KeyCode::PageUp => KeyCode::PageUp,
+            input::KeyCode::ArrowDown => KeyCode::ArrowDown,
+            input::KeyCode::ArrowLeft => KeyCode::ArrowLeft,
+            input::KeyCode::ArrowRight => KeyCode::ArrowRight,
+            input::KeyCode::ArrowUp => KeyCode::ArrowUp,
+            input::KeyCode::NumLock => KeyCode::NumLock,
+            input::KeyCode::Numpad0 => KeyCode::Numpad0,
+            input::KeyCode::Numpad1 => KeyCode::Numpad1,
+            input::KeyCode::Numpad2 => KeyCode::Numpad2,
+            input::KeyCode::Numpad3 => KeyCode::Numpad3,
+            input::KeyCode::Numpad4 => KeyCode::Numpad4,
+            input::KeyCode::Numpad5 => KeyCode::Numpad5,
+            input::KeyCode::Numpad6 => KeyCode::Numpad6,
+            input::KeyCode::Numpad7 => KeyCode::Numpad7,
+            input::KeyCode::Numpad8 => KeyCode::Numpad8,
+            input::KeyCode::Numpad9 => KeyCode::Numpad9,
+            input::KeyCode::NumpadAdd => KeyCode::NumpadAdd,
+            input::KeyCode::NumpadComma => KeyCode::NumpadComma,
+            input::KeyCode::NumpadDecimal => KeyCode::NumpadDecimal,
+            input::KeyCode::NumpadDivide => KeyCode::NumpadDivide,
+            input::KeyCode::NumpadEnter => KeyCode::NumpadEnter,
+            input::KeyCode::NumpadEqual => KeyCode::NumpadEqual,
+            input::KeyCode::NumpadMultiply => KeyCode::NumpadMultiply,
+            input::KeyCode::NumpadSubtract => KeyCode::NumpadSubtract,
+            input::KeyCode::Escape => KeyCode::Escape,
+            input::KeyCode::F1 => KeyCode::F1,
+            input::KeyCode::F2 => KeyCode::F2,
+            input::KeyCode::F3 => KeyCode::F3,
+            input::KeyCode::F4 => KeyCode::F4,
+            input::KeyCode::F5 => KeyCode::F5,
+            input::KeyCode::F6 => KeyCode::F6,
+            input::KeyCode::F7 => KeyCode::F7,
+            input::KeyCode::F8 => KeyCode::F8,
+            input::KeyCode::F9 => KeyCode::F9,
+            input::KeyCode::F10 => KeyCode::F10,
+            input::KeyCode::F11 => KeyCode::F11,
+            input::KeyCode::F12 => KeyCode::F12,
+            input::KeyCode::F13 => KeyCode::F13,
+            input::KeyCode::F14 => KeyCode::F14,
+            input::KeyCode::F15 => KeyCode::F15,
+            input::KeyCode::F16 => KeyCode::F16,
+            input::KeyCode::F17 => KeyCode::F17,
+            input::KeyCode::F18 => KeyCode::F18,
+            input::KeyCode::F19 => KeyCode::F19,
+            input::KeyCode::F20 => KeyCode::F20,
+            input::KeyCode::F21 => KeyCode::F21,
+            input::KeyCode::F22 => KeyCode::F22,
+            input::KeyCode::F23 => KeyCode::F23,
+            input::KeyCode::F24 => KeyCode::F24,
+            input::KeyCode::PrintScreen => KeyCode::PrintScreen,
+            input::KeyCode::ScrollLock => KeyCode::ScrollLock,
+            input::KeyCode::Pause => KeyCode::Pause,
+            input::KeyCode::BrowserBack => KeyCode::BrowserBack,
+            input::KeyCode::BrowserFavorites => KeyCode::BrowserFavorites,
+            input::KeyCode::BrowserForward => KeyCode::BrowserForward,
+            input::KeyCode::BrowserHome => KeyCode::BrowserHome,
+            input::KeyCode::BrowserRefresh => KeyCode::BrowserRefresh,
+            input::KeyCode::BrowserSearch => KeyCode::BrowserSearch,
+            input::KeyCode::BrowserStop => KeyCode::BrowserStop,
+            input::KeyCode::LaunchApp1 => KeyCode::LaunchApp1,
+            input::KeyCode::LaunchApp2 => KeyCode::LaunchApp2,
+            input::KeyCode::LaunchMail => KeyCode::LaunchMail,
+            input::KeyCode::MediaPlayPause => KeyCode::MediaPlayPause,
+            input::KeyCode::MediaSelect => KeyCode::MediaSelect,
+            input::KeyCode::MediaStop => KeyCode::MediaStop,
+            input::KeyCode::MediaTrackNext => KeyCode::MediaTrackNext,
+            input::KeyCode::MediaTrackPrevious => KeyCode::MediaTrackPrevious,
+            input::KeyCode::Power => KeyCode::Power,
+            input::KeyCode::Sleep => KeyCode::Sleep,
+            input::KeyCode::WakeUp => KeyCode::WakeUp,
+            input::KeyCode::AudioVolumeDown => KeyCode::AudioVolumeDown,
+            input::KeyCode::AudioVolumeMute => KeyCode::AudioVolumeMute,
+            input::KeyCode::AudioVolumeUp => KeyCode::AudioVolumeUp,
+            input::KeyCode::Unidentified => KeyCode::Unidentified,
+        }
+    }
+}
+
+impl From<KeyCode> for input::KeyCode {
+    fn from(key: KeyCode) -> Self {
+        match key {
+            KeyCode::Backquote => input::KeyCode::Backquote,
+            KeyCode::Backslash => input::KeyCode::Backslash,
+            KeyCode::BracketLeft => input::KeyCode::BracketLeft,
+            KeyCode::BracketRight => input::KeyCode::BracketRight,
+            KeyCode::Comma => input::KeyCode::Comma,
+            KeyCode::Digit0 => input::KeyCode::Digit0,
+            KeyCode::Digit1 => input::KeyCode::Digit1,
+            KeyCode::Digit2 => input::KeyCode::Digit2,
+            KeyCode::Digit3 => input::KeyCode::Digit3,
+            KeyCode::Digit4 => input::KeyCode::Digit4,
+            KeyCode::Digit5 => input::KeyCode::Digit5,
+            KeyCode::Digit6 => input::KeyCode::Digit6,
+            KeyCode::Digit7 => input::KeyCode::Digit7,
+            KeyCode::Digit8 => input::KeyCode::Digit8,
+            KeyCode::Digit9 => input::KeyCode::Digit9,
+            KeyCode::Equal => input::KeyCode::Equal,
+            KeyCode::IntlBackslash => input::KeyCode::IntlBackslash,
+            KeyCode::IntlRo => input::KeyCode::IntlRo,
+            KeyCode::IntlYen => input::KeyCode::IntlYen,
+            KeyCode::KeyA => input::KeyCode::KeyA,
+            KeyCode::KeyB => input::KeyCode::KeyB,
+            KeyCode::KeyC => input::KeyCode::KeyC,
+            KeyCode::KeyD => input::KeyCode::KeyD,
+            KeyCode::KeyE => input::KeyCode::KeyE,
+            KeyCode::KeyF => input::KeyCode::KeyF,
+            KeyCode::KeyG => input::KeyCode::KeyG,
+            KeyCode::KeyH => input::KeyCode::KeyH,
+            KeyCode::KeyI => input::KeyCode::KeyI,
+            KeyCode::KeyJ => input::KeyCode::KeyJ,
+            KeyCode::KeyK => input::KeyCode::KeyK,
+            KeyCode::KeyL => input::KeyCode::KeyL,
+            KeyCode::KeyM => input::KeyCode::KeyM,
+            KeyCode::KeyN => input::KeyCode::KeyN,
+            KeyCode::KeyO => input::KeyCode::KeyO,
+            KeyCode::KeyP => input::KeyCode::KeyP,
+            KeyCode::KeyQ => input::KeyCode::KeyQ,
+            KeyCode::KeyR => input::KeyCode::KeyR,
+            KeyCode::KeyS => input::KeyCode::KeyS,
+            KeyCode::KeyT => input::KeyCode::KeyT,
+            KeyCode::KeyU => input::KeyCode::KeyU,
+            KeyCode::KeyV => input::KeyCode::KeyV,
+            KeyCode::KeyW => input::KeyCode::KeyW,
+            KeyCode::KeyX => input::KeyCode::KeyX,
+            KeyCode::KeyY => input::KeyCode::KeyY,
+            KeyCode::KeyZ => input::KeyCode::KeyZ,
+            KeyCode::Minus => input::KeyCode::Minus,
+            KeyCode::Period => input::KeyCode::Period,
+            KeyCode::Quote => input::KeyCode::Quote,
+            KeyCode::Semicolon => input::KeyCode::Semicolon,
+            KeyCode::Slash => input::KeyCode::Slash,
+            KeyCode::AltLeft => input::KeyCode::AltLeft,
+            KeyCode::AltRight => input::KeyCode::AltRight,
+            KeyCode::Backspace => input::KeyCode::Backspace,
+            KeyCode::CapsLock => input::KeyCode::CapsLock,
+            KeyCode::ContextMenu => input::KeyCode::ContextMenu,
+            KeyCode::ControlLeft => input::KeyCode::ControlLeft,
+            KeyCode::ControlRight => input::KeyCode::ControlRight,
+            KeyCode::Enter => input::KeyCode::Enter,
+            KeyCode::MetaLeft => input::KeyCode::MetaLeft,
+            KeyCode::MetaRight => input::KeyCode::MetaRight,
+            KeyCode::ShiftLeft => input::KeyCode::ShiftLeft,
+            KeyCode::ShiftRight => input::KeyCode::ShiftRight,
+            KeyCode::Space => input::KeyCode::Space,
+            KeyCode::Tab => input::KeyCode::Tab,
+            KeyCode::Convert => input::KeyCode::Convert,
+            KeyCode::KanaMode => input::KeyCode::KanaMode,
+            KeyCode::Lang1 => input::KeyCode::Lang1,
+            KeyCode::Lang2 => input::KeyCode::Lang2,
+            KeyCode::NonConvert => input::KeyCode::NonConvert,
+            KeyCode::Delete => input::KeyCode::Delete,
+            KeyCode::End => input::KeyCode::End,
+            KeyCode::Home => input::KeyCode::Home,
+            KeyCode::Insert => input::KeyCode::Insert,
+            KeyCode::PageDown => input::KeyCode::PageDown,
+            KeyCode::PageUp => input::KeyCode::PageUp,
+            KeyCode::ArrowDown => input::KeyCode::ArrowDown,
+            KeyCode::ArrowLeft => input::KeyCode::ArrowLeft,
+            KeyCode::ArrowRight => input::KeyCode::ArrowRight,
+            KeyCode::ArrowUp => input::KeyCode::ArrowUp,
+            KeyCode::NumLock => input::KeyCode::NumLock,
+            KeyCode::Numpad0 => input::KeyCode::Numpad0,
+            KeyCode::Numpad1 => input::KeyCode::Numpad1,
+            KeyCode::Numpad2 => input::KeyCode::Numpad2,
+            KeyCode::Numpad3 => input::KeyCode::Numpad3,
+            KeyCode::Numpad4 => input::KeyCode::Numpad4,
+            KeyCode::Numpad5 => input::KeyCode::Numpad5,
+            KeyCode::Numpad6 => input::KeyCode::Numpad6,
+            KeyCode::Numpad7 => input::KeyCode::Numpad7,
+            KeyCode::Numpad8 => input::KeyCode::Numpad8,
+            KeyCode::Numpad9 => input::KeyCode::Numpad9,
+            KeyCode::NumpadAdd => input::KeyCode::NumpadAdd,
+            KeyCode::NumpadComma => input::KeyCode::NumpadComma,
+            KeyCode::NumpadDecimal => input::KeyCode::NumpadDecimal,
+            KeyCode::NumpadDivide => input::KeyCode::NumpadDivide,
+            KeyCode::NumpadEnter => input::KeyCode::NumpadEnter,
+            KeyCode::NumpadEqual => input::KeyCode::NumpadEqual,
+            KeyCode::NumpadMultiply => input::KeyCode::NumpadMultiply,
+            KeyCode::NumpadSubtract => input::KeyCode::NumpadSubtract,
+            KeyCode::Escape => input::KeyCode::Escape,
+            KeyCode::F1 => input::KeyCode::F1,
+            KeyCode::F2 => input::KeyCode::F2,
+            KeyCode::F3 => input::KeyCode::F3,
+            KeyCode::F4 => input::KeyCode::F4,
+            KeyCode::F5 => input::KeyCode::F5,
+            KeyCode::F6 => input::KeyCode::F6,
+            KeyCode::F7 => input::KeyCode::F7,
+            KeyCode::F8 => input::KeyCode::F8,
+            KeyCode::F9 => input::KeyCode::F9,
+            KeyCode::F10 => input::KeyCode::F10,
+            KeyCode::F11 => input::KeyCode::F11,
+            KeyCode::F12 => input::KeyCode::F12,
+            KeyCode::F13 => input::KeyCode::F13,
+            KeyCode::F14 => input::KeyCode::F14,
+            KeyCode::F15 => input::KeyCode::F15,
+            KeyCode::F16 => input::KeyCode::F16,
+            KeyCode::F17 => input::KeyCode::F17,
+            KeyCode::F18 => input::KeyCode::F18,
+            KeyCode::F19 => input::KeyCode::F19,
+            KeyCode::F20 => input::KeyCode::F20,
+            KeyCode::F21 => input::KeyCode::F21,
+            KeyCode::F22 => input::KeyCode::F22,
+            KeyCode::F23 => input::KeyCode::F23,
+            KeyCode::F24 => input::KeyCode::F24,
+            KeyCode::PrintScreen => input::KeyCode::PrintScreen,
+            KeyCode::ScrollLock => input::KeyCode::ScrollLock,
+            KeyCode::Pause => input::KeyCode::Pause,
+            KeyCode::BrowserBack => input::KeyCode::BrowserBack,
+            KeyCode::BrowserFavorites => input::KeyCode::BrowserFavorites,
+            KeyCode::BrowserForward => input::KeyCode::BrowserForward,
+            KeyCode::BrowserHome => input::KeyCode::BrowserHome,
+            KeyCode::BrowserRefresh => input::KeyCode::BrowserRefresh,
+            KeyCode::BrowserSearch => input::KeyCode::BrowserSearch,
+            KeyCode::BrowserStop => input::KeyCode::BrowserStop,
+            KeyCode::LaunchApp1 => input::KeyCode::LaunchApp1,
+            KeyCode::LaunchApp2 => input::KeyCode::LaunchApp2,
+            KeyCode::LaunchMail => input::KeyCode::LaunchMail,
+            KeyCode::MediaPlayPause => input::KeyCode::MediaPlayPause,
+            KeyCode::MediaSelect => input::KeyCode::MediaSelect,
+            KeyCode::MediaStop => input::KeyCode::MediaStop,
+            KeyCode::MediaTrackNext => input::KeyCode::MediaTrackNext,
+            KeyCode::MediaTrackPrevious => input::KeyCode::MediaTrackPrevious,
+            KeyCode::Power => input::KeyCode::Power,
+            KeyCode::Sleep => input::KeyCode::Sleep,
+            KeyCode::WakeUp => input::KeyCode::WakeUp,
+            KeyCode::AudioVolumeDown => input::KeyCode::AudioVolumeDown,
+            KeyCode::AudioVolumeMute => input::KeyCode::AudioVolumeMute,
+            KeyCode::AudioVolumeUp => input::KeyCode::AudioVolumeUp,
+            KeyCode::Unidentified => input::KeyCode::Unidentified,
+        }
+    }
+}
+
+/// Utility function to create `Key` from a `KeyCode`.
+///
+/// Returns `undefined` if the key code has no matching virtual-key code.
+#[napi]
+pub fn key_from_code(code: KeyCode) -> Option<Key> {
+    input::KeyCode::from(code).to_key().map(Key::from)
+}
+
 /// Utility function to create `Key` using key code and optional extended flag.
 #[napi]
 pub fn key(code: u8, extended: Option<bool>) -> Key {
@@ -143,6 +913,58 @@ pub fn key(code: u8, extended: Option<bool>) -> Key {
     }
 }
 
+/// Describe a key combination which ends input blocking when pressed.
+///
+/// Ctrl, Shift and Alt must match exactly. For example, a chord with only `ctrl`
+/// is not triggered while Shift is also held down. Windows keys are not checked.
+#[napi(object)]
+pub struct EscapeChord {
+    /// Whether if either Ctrl key should be held down.
+    pub ctrl: bool,
+
+    /// Whether if either Shift key should be held down.
+    pub shift: bool,
+
+    /// Whether if either Alt key should be held down.
+    pub alt: bool,
+
+    /// The key which triggers the chord when pressed.
+    pub key: Key,
+}
+
+/// Reason of input blocking end.
+#[napi(string_enum)]
+pub enum InputBlockingEndReason {
+    /// Input blocking is turned off by the client.
+    ClientRequest,
+
+    /// The user requested to close the window.
+    UserClose,
+
+    /// The user pressed the configured escape chord.
+    EscapeChord,
+
+    /// The client sent no request within the watchdog timeout.
+    Watchdog,
+
+    /// The client is disconnected.
+    ClientDisconnect,
+}
+
+impl From<window::InputBlockingEndReason> for InputBlockingEndReason {
+    fn from(reason: window::InputBlockingEndReason) -> Self {
+        match reason {
+            window::InputBlockingEndReason::ClientRequest => InputBlockingEndReason::ClientRequest,
+            window::InputBlockingEndReason::UserClose => InputBlockingEndReason::UserClose,
+            window::InputBlockingEndReason::EscapeChord => InputBlockingEndReason::EscapeChord,
+            window::InputBlockingEndReason::Watchdog => InputBlockingEndReason::Watchdog,
+            window::InputBlockingEndReason::ClientDisconnect => {
+                InputBlockingEndReason::ClientDisconnect
+            }
+        }
+    }
+}
+
 /// Cursor scroll axis.
 #[napi(string_enum)]
 pub enum ScrollAxis {
@@ -181,6 +1003,37 @@ impl From<input::CursorAction> for CursorAction {
     }
 }
 
+/// Cursor buttons held down.
+#[napi]
+pub enum CursorButton {
+    None = 0,
+    Left = 1,
+    Right = 2,
+    Middle = 4,
+    Back = 8,
+    Forward = 16,
+}
+
+/// Modifier key states.
+#[napi]
+pub enum Modifier {
+    None = 0,
+    LeftShift = 1,
+    RightShift = 2,
+    LeftCtrl = 4,
+    RightCtrl = 8,
+    LeftAlt = 16,
+    RightAlt = 32,
+    LeftWin = 64,
+    RightWin = 128,
+
+    /// Caps Lock is toggled on.
+    CapsLock = 256,
+
+    /// Num Lock is toggled on.
+    NumLock = 512,
+}
+
 /// Cursor input state.
 #[napi]
 pub enum CursorInputState {
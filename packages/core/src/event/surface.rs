@@ -46,6 +46,110 @@ impl From<common::event::surface::SurfaceType> for SurfaceType {
     }
 }
 
+/// Backbuffer properties of a surface.
+#[napi(object)]
+pub struct BackbufferInfo {
+    /// Pixel format of the backbuffer.
+    pub format: PixelFormat,
+
+    /// Color space the backbuffer is presented in.
+    pub color_space: ColorSpace,
+
+    /// Number of multisamples per pixel.
+    pub sample_count: u32,
+
+    /// Number of buffers in the swapchain.
+    pub buffer_count: u32,
+
+    /// How frames are presented to the screen.
+    pub present_mode: PresentMode,
+}
+
+impl From<common::event::surface::backbuffer::BackbufferInfo> for BackbufferInfo {
+    fn from(v: common::event::surface::backbuffer::BackbufferInfo) -> Self {
+        Self {
+            format: PixelFormat::from(v.format),
+            color_space: ColorSpace::from(v.color_space),
+            sample_count: v.sample_count,
+            buffer_count: v.buffer_count,
+            present_mode: PresentMode::from(v.present_mode),
+        }
+    }
+}
+
+#[napi(string_enum)]
+pub enum PixelFormat {
+    Unknown,
+    Rgba8Unorm,
+    Rgba8UnormSrgb,
+    Bgra8Unorm,
+    Bgra8UnormSrgb,
+    Bgrx8Unorm,
+    Rgb10A2Unorm,
+    Bgr10A2Unorm,
+    Rgba16Float,
+    B5G6R5Unorm,
+}
+
+impl From<common::event::surface::backbuffer::PixelFormat> for PixelFormat {
+    fn from(v: common::event::surface::backbuffer::PixelFormat) -> Self {
+        match v {
+            common::event::surface::backbuffer::PixelFormat::Unknown => Self::Unknown,
+            common::event::surface::backbuffer::PixelFormat::Rgba8Unorm => Self::Rgba8Unorm,
+            common::event::surface::backbuffer::PixelFormat::Rgba8UnormSrgb => Self::Rgba8UnormSrgb,
+            common::event::surface::backbuffer::PixelFormat::Bgra8Unorm => Self::Bgra8Unorm,
+            common::event::surface::backbuffer::PixelFormat::Bgra8UnormSrgb => Self::Bgra8UnormSrgb,
+            common::event::surface::backbuffer::PixelFormat::Bgrx8Unorm => Self::Bgrx8Unorm,
+            common::event::surface::backbuffer::PixelFormat::Rgb10A2Unorm => Self::Rgb10A2Unorm,
+            common::event::surface::backbuffer::PixelFormat::Bgr10A2Unorm => Self::Bgr10A2Unorm,
+            common::event::surface::backbuffer::PixelFormat::Rgba16Float => Self::Rgba16Float,
+            common::event::surface::backbuffer::PixelFormat::B5G6R5Unorm => Self::B5G6R5Unorm,
+        }
+    }
+}
+
+#[napi(string_enum)]
+pub enum ColorSpace {
+    Unknown,
+    Srgb,
+    ScRgb,
+    Hdr10,
+}
+
+impl From<common::event::surface::backbuffer::ColorSpace> for ColorSpace {
+    fn from(v: common::event::surface::backbuffer::ColorSpace) -> Self {
+        match v {
+            common::event::surface::backbuffer::ColorSpace::Unknown => Self::Unknown,
+            common::event::surface::backbuffer::ColorSpace::Srgb => Self::Srgb,
+            common::event::surface::backbuffer::ColorSpace::ScRgb => Self::ScRgb,
+            common::event::surface::backbuffer::ColorSpace::Hdr10 => Self::Hdr10,
+        }
+    }
+}
+
+#[napi]
+pub enum PresentMode {
+    Unknown,
+    Immediate,
+    Mailbox,
+    Fifo { sync_interval: u32 },
+    FifoRelaxed,
+}
+
+impl From<common::event::surface::backbuffer::PresentMode> for PresentMode {
+    fn from(v: common::event::surface::backbuffer::PresentMode) -> Self {
+        match v {
+            common::event::surface::backbuffer::PresentMode::Unknown => Self::Unknown,
+            common::event::surface::backbuffer::PresentMode::Immediate => Self::Immediate,
+            common::event::surface::backbuffer::PresentMode::Mailbox => Self::Mailbox,
+            common::event::surface::backbuffer::PresentMode::Fifo { sync_interval } => {
+                Self::Fifo { sync_interval }
+            }
+            common::event::surface::backbuffer::PresentMode::FifoRelaxed => Self::FifoRelaxed,
+        }
+    }
+}
+
 #[napi(object)]
 pub struct GpuLuid {
     pub low: u32,
@@ -69,3 +173,73 @@ impl From<GpuLuid> for common::event::surface::GpuLuid {
         }
     }
 }
+
+/// Frame time statistics measured from recent presents of a surface.
+#[napi(object)]
+pub struct FrameStats {
+    /// Number of present intervals the statistics are measured from.
+    pub samples: u32,
+
+    /// Average frames per second.
+    pub fps: f64,
+
+    /// Average frame time in milliseconds.
+    pub frame_time_ms: f64,
+
+    /// Average frames per second of the slowest 1% frames.
+    pub low_1_percent_fps: f64,
+
+    /// Average frames per second of the slowest 0.1% frames.
+    pub low_0_1_percent_fps: f64,
+
+    /// Standard deviation of present intervals in milliseconds.
+    pub jitter_ms: f64,
+}
+
+impl From<common::event::surface::stats::FrameStats> for FrameStats {
+    fn from(v: common::event::surface::stats::FrameStats) -> Self {
+        Self {
+            samples: v.samples,
+            fps: v.fps as _,
+            frame_time_ms: v.frame_time_ms as _,
+            low_1_percent_fps: v.low_1_percent_fps as _,
+            low_0_1_percent_fps: v.low_0_1_percent_fps as _,
+            jitter_ms: v.jitter_ms as _,
+        }
+    }
+}
+
+/// Rules for ignoring surfaces.
+#[napi(object)]
+pub struct SurfaceFilter {
+    /// Rendering apis to ignore. Bitflags of `SurfaceApi`.
+    pub ignore_apis: Option<u8>,
+
+    /// Minimum surface width.
+    pub min_width: Option<u32>,
+
+    /// Minimum surface height.
+    pub min_height: Option<u32>,
+}
+
+impl From<SurfaceFilter> for common::event::surface::SurfaceFilter {
+    fn from(filter: SurfaceFilter) -> Self {
+        Self {
+            ignore_apis: common::event::surface::SurfaceApis::from_bits_retain(
+                filter.ignore_apis.unwrap_or_default(),
+            ),
+            min_width: filter.min_width.unwrap_or_default(),
+            min_height: filter.min_height.unwrap_or_default(),
+        }
+    }
+}
+
+/// Rendering api flags.
+#[napi]
+pub enum SurfaceApi {
+    Opengl = 1,
+    Direct3D9 = 2,
+    Direct3D11 = 4,
+    Direct3D12 = 8,
+    Vulkan = 16,
+}
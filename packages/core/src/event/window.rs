@@ -0,0 +1,125 @@
+use asdf_overlay_client::common::event::window;
+use napi_derive::napi;
+
+#[napi(object)]
+pub struct WindowInfo {
+    /// Window title.
+    pub title: String,
+
+    /// Window class name.
+    pub class_name: String,
+
+    /// Window style. Bitflags of `WindowStyle`.
+    pub style: u8,
+
+    /// Id of the thread which created the window.
+    pub thread_id: u32,
+
+    /// DPI scale factor. `1.0` is 96 DPI.
+    pub scale: f64,
+
+    /// Monitor which has the largest intersection with the window.
+    pub monitor: Option<MonitorInfo>,
+
+    /// Whether if the window was focused when the window was added.
+    pub focused: bool,
+}
+
+impl From<window::WindowInfo> for WindowInfo {
+    fn from(info: window::WindowInfo) -> Self {
+        Self {
+            title: info.title,
+            class_name: info.class_name,
+            style: info.style.bits(),
+            thread_id: info.thread_id,
+            scale: info.scale.into(),
+            monitor: info.monitor.map(MonitorInfo::from),
+            focused: info.focused,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct MonitorInfo {
+    /// X position in screen coordinates.
+    pub x: i32,
+
+    /// Y position in screen coordinates.
+    pub y: i32,
+
+    pub width: u32,
+
+    pub height: u32,
+
+    /// Refresh rate in Hz. `0` or `1` means the hardware default refresh rate.
+    pub refresh_rate: u32,
+
+    /// Whether if the monitor is the primary monitor.
+    pub primary: bool,
+}
+
+impl From<window::MonitorInfo> for MonitorInfo {
+    fn from(info: window::MonitorInfo) -> Self {
+        Self {
+            x: info.x,
+            y: info.y,
+            width: info.width,
+            height: info.height,
+            refresh_rate: info.refresh_rate,
+            primary: info.primary,
+        }
+    }
+}
+
+/// Window style flags.
+#[napi]
+pub enum WindowStyle {
+    None = 0,
+
+    /// Window covers the whole monitor.
+    Fullscreen = 1,
+
+    /// Window has no title bar and sizing border.
+    Borderless = 2,
+
+    /// Window is a child window.
+    Child = 4,
+
+    /// Window is owned by another window.
+    Owned = 8,
+}
+
+/// Rules for ignoring windows.
+#[napi(object)]
+pub struct WindowFilter {
+    /// Window class names to ignore, compared case-insensitively.
+    pub ignore_classes: Option<Vec<String>>,
+
+    /// Title pattern windows must match. `*` and `?` wildcards are supported.
+    pub title_pattern: Option<String>,
+
+    /// Minimum client area width.
+    pub min_width: Option<u32>,
+
+    /// Minimum client area height.
+    pub min_height: Option<u32>,
+
+    /// Whether to ignore child windows.
+    pub ignore_child: Option<bool>,
+
+    /// Whether to ignore windows owned by another window.
+    pub ignore_owned: Option<bool>,
+}
+
+impl From<WindowFilter> for window::filter::WindowFilter {
+    fn from(filter: WindowFilter) -> Self {
+        Self {
+            ignore_classes: filter.ignore_classes.unwrap_or_default(),
+            title_pattern: filter.title_pattern,
+            min_width: filter.min_width.unwrap_or_default(),
+            min_height: filter.min_height.unwrap_or_default(),
+            ignore_child: filter.ignore_child.unwrap_or_default(),
+            ignore_owned: filter.ignore_owned.unwrap_or_default(),
+        }
+    }
+}
@@ -1,19 +1,29 @@
 use core::time::Duration;
 use std::path::PathBuf;
 
-use crate::event::input::Cursor;
+use crate::event::input::{Cursor, EscapeChord};
+use crate::event::surface::{FrameStats, SurfaceFilter};
+use crate::event::window::WindowFilter;
 use crate::event::{create_emit_tsfn, event_task};
-use crate::surface::UpdateSharedHandle;
+use crate::surface::{
+    Blend, HitRegion, Layout, Transform, UpdateBitmapShm, UpdateLayer, UpdateSharedHandle,
+};
 use anyhow::Context as AnyhowContext;
 use asdf_overlay_client::client::IpcClientEventStream;
 use asdf_overlay_client::common;
 use asdf_overlay_client::common::request::Requestable;
-use asdf_overlay_client::common::request::surface::{self, SetPosition, SurfaceRequestable};
+use asdf_overlay_client::common::request::surface::{
+    self, GetFrameStats, InvalidateRegion, ListenPresent, ListenStats, RemoveLayer, RequestFrame,
+    SetBlend, SetHitRegion, SetLayout, SetPosition, SetTransform, SetVisible, SurfaceRequestable,
+};
 use asdf_overlay_client::common::request::window::WindowRequestable;
 use asdf_overlay_client::{
     OverlayDll,
     client::IpcClientConn,
-    common::request::{BlockInput, SetBlockingCursor, window::ListenInput},
+    common::request::{
+        BlockInput, SetBlockingCursor, SetBlockingWatchdog, SetEscapeChord, SetFilter,
+        SetInputRouting, window::ListenInput,
+    },
     inject,
 };
 use napi::bindgen_prelude::{
@@ -125,6 +135,34 @@ impl Overlay {
         Ok(())
     }
 
+    /// Update overlay surface from bitmaps written to a `BitmapShm`.
+    #[napi]
+    pub async fn update_bitmap_shm(
+        &self,
+        id: BigInt,
+        update: UpdateBitmapShm,
+    ) -> anyhow::Result<()> {
+        self.surface_request(id, Into::<surface::UpdateBitmapShm>::into(update))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark regions of overlay texture changed.
+    /// Once invalidated, only invalidated regions are copied until the texture is updated again.
+    #[napi]
+    pub async fn invalidate_region(&self, id: BigInt, rects: Vec<DirtyRect>) -> anyhow::Result<()> {
+        self.surface_request(
+            id,
+            InvalidateRegion {
+                rects: rects.into_iter().map(Into::into).collect(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Update overlay position relative to window
     #[napi]
     pub async fn set_position(&self, id: BigInt, x: i32, y: i32) -> anyhow::Result<()> {
@@ -133,6 +171,140 @@ impl Overlay {
         Ok(())
     }
 
+    /// Update overlay layout relative to surface.
+    ///
+    /// The layout is resolved against the current surface size,
+    /// so the overlay keeps its place when the surface is resized.
+    #[napi]
+    pub async fn set_layout(&self, id: BigInt, layout: Layout) -> anyhow::Result<()> {
+        self.surface_request(
+            id,
+            SetLayout {
+                layout: layout.into(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update overlay opacity, scale and fit mode.
+    #[napi]
+    pub async fn set_transform(&self, id: BigInt, transform: Transform) -> anyhow::Result<()> {
+        self.surface_request(
+            id,
+            SetTransform {
+                transform: transform.into(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update how overlay colors are blended onto the surface.
+    #[napi]
+    pub async fn set_blend(&self, id: BigInt, blend: Blend) -> anyhow::Result<()> {
+        self.surface_request(
+            id,
+            SetBlend {
+                blend: blend.into(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Show or hide overlay, keeping its textures.
+    #[napi]
+    pub async fn set_visible(&self, id: BigInt, visible: bool) -> anyhow::Result<()> {
+        self.surface_request(id, SetVisible { visible }).await?;
+
+        Ok(())
+    }
+
+    /// Enable or disable present events on every present of the surface.
+    #[napi]
+    pub async fn listen_present(&self, id: BigInt, listen: bool) -> anyhow::Result<()> {
+        self.surface_request(id, ListenPresent { listen }).await?;
+
+        Ok(())
+    }
+
+    /// Request a present event once after the next present of the surface.
+    #[napi]
+    pub async fn request_frame(&self, id: BigInt) -> anyhow::Result<()> {
+        self.surface_request(id, RequestFrame).await?;
+
+        Ok(())
+    }
+
+    /// Get frame time statistics measured from recent presents of the surface.
+    ///
+    /// Returns `null` if the surface has not been presented twice yet.
+    #[napi]
+    pub async fn frame_stats(&self, id: BigInt) -> anyhow::Result<Option<FrameStats>> {
+        Ok(self
+            .surface_request(id, GetFrameStats)
+            .await?
+            .map(FrameStats::from))
+    }
+
+    /// Set interval of periodic frame time statistics events of the surface.
+    /// If `null` is given, statistics events are disabled.
+    #[napi]
+    pub async fn listen_stats(&self, id: BigInt, interval_ms: Option<u32>) -> anyhow::Result<()> {
+        self.surface_request(id, ListenStats { interval_ms })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Create or update an overlay layer.
+    ///
+    /// Layers are drawn from the lowest z index to the highest.
+    /// Each layer has its own interactive region, see `setHitRegion`.
+    #[napi]
+    pub async fn update_layer(&self, id: BigInt, update: UpdateLayer) -> anyhow::Result<()> {
+        self.surface_request(id, Into::<surface::UpdateLayer>::into(update))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove an overlay layer. The base layer cannot be removed.
+    #[napi]
+    pub async fn remove_layer(&self, id: BigInt, layer: u32) -> anyhow::Result<()> {
+        self.surface_request(id, RemoveLayer { layer }).await?;
+
+        Ok(())
+    }
+
+    /// Set interactive region of an overlay layer. Defaults to the base layer.
+    ///
+    /// While input is not blocked, cursor inputs over interactive regions are captured
+    /// and blocked from reaching the window. Visible layers are hit-tested from top to bottom.
+    /// `AlphaMask` is only supported on the base layer.
+    #[napi]
+    pub async fn set_hit_region(
+        &self,
+        id: BigInt,
+        region: HitRegion,
+        layer: Option<u32>,
+    ) -> anyhow::Result<()> {
+        self.surface_request(
+            id,
+            SetHitRegion {
+                layer: layer.unwrap_or_default(),
+                region: region.into(),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Set blocking cursor.
     #[napi]
     pub async fn set_blocking_cursor(&self, cursor: Option<Cursor>) -> anyhow::Result<()> {
@@ -146,11 +318,83 @@ impl Overlay {
         Ok(())
     }
 
+    /// Set key combination which ends input blocking when pressed.
+    #[napi]
+    pub async fn set_escape_chord(&self, chord: Option<EscapeChord>) -> anyhow::Result<()> {
+        let chord = chord
+            .map(|chord| {
+                Ok::<_, anyhow::Error>(common::event::window::input::EscapeChord {
+                    ctrl: chord.ctrl,
+                    shift: chord.shift,
+                    alt: chord.alt,
+                    key: Option::from(chord.key).context("invalid key code")?,
+                })
+            })
+            .transpose()?;
+
+        self.request(SetEscapeChord { chord }).await?;
+        Ok(())
+    }
+
+    /// Set timeout in milliseconds which ends input blocking when no request is sent within it.
+    #[napi]
+    pub async fn set_blocking_watchdog(&self, timeout_ms: Option<u32>) -> anyhow::Result<()> {
+        self.request(SetBlockingWatchdog { timeout_ms }).await?;
+        Ok(())
+    }
+
     /// Listen to window input without blocking
+    ///
+    /// Set `rawMotion` to listen relative mouse motion from raw inputs.
+    /// Set `pointer` to listen touch and pen inputs.
+    /// Set `gamepad` to listen gamepad inputs.
     #[napi]
-    pub async fn listen_input(&self, id: u32, cursor: bool, keyboard: bool) -> anyhow::Result<()> {
-        self.window_request(id, ListenInput { cursor, keyboard })
-            .await?;
+    pub async fn listen_input(
+        &self,
+        id: u32,
+        cursor: bool,
+        keyboard: bool,
+        raw_motion: Option<bool>,
+        pointer: Option<bool>,
+        gamepad: Option<bool>,
+    ) -> anyhow::Result<()> {
+        self.window_request(
+            id,
+            ListenInput {
+                cursor,
+                keyboard,
+                raw_motion: raw_motion.unwrap_or(false),
+                pointer: pointer.unwrap_or(false),
+                gamepad: gamepad.unwrap_or(false),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Route cursor inputs over surfaces to `surface_cursor_input` event.
+    #[napi]
+    pub async fn set_input_routing(&self, surface: bool) -> anyhow::Result<()> {
+        self.request(SetInputRouting { surface }).await?;
+
+        Ok(())
+    }
+
+    /// Set rules for ignoring windows and surfaces.
+    ///
+    /// Only applies to windows and surfaces identified afterwards.
+    #[napi]
+    pub async fn set_filter(
+        &self,
+        window: Option<WindowFilter>,
+        surface: Option<SurfaceFilter>,
+    ) -> anyhow::Result<()> {
+        self.request(SetFilter {
+            window: window.map(Into::into).unwrap_or_default(),
+            surface: surface.map(Into::into).unwrap_or_default(),
+        })
+        .await?;
 
         Ok(())
     }
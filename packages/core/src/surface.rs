@@ -1,5 +1,11 @@
 use anyhow::Context;
-use asdf_overlay_client::common::request;
+use asdf_overlay_client::{
+    common::{
+        event::surface::{blend, layout},
+        request,
+    },
+    shm,
+};
 use asdf_overlay_surface_util::{surface, ty};
 use bytemuck::try_pod_read_unaligned;
 use napi::bindgen_prelude::BufferSlice;
@@ -76,6 +82,99 @@ impl OverlaySurface {
     }
 }
 
+/// Bitmap ring buffer in named shared memory, for updating overlay without GPU access.
+#[napi]
+pub struct BitmapShm(shm::BitmapShm);
+
+#[napi]
+impl BitmapShm {
+    /// Create named shared memory holding `slotCount` frames up to `slotCapacity` bytes each.
+    #[napi(constructor)]
+    pub fn new(name: String, slot_count: u32, slot_capacity: u32) -> anyhow::Result<Self> {
+        Ok(Self(shm::BitmapShm::create(
+            name,
+            slot_count,
+            slot_capacity,
+        )?))
+    }
+
+    /// Name of the shared memory.
+    #[napi(getter)]
+    pub fn name(&self) -> String {
+        self.0.name().to_string()
+    }
+
+    /// Publish a bitmap frame. The size of frame is `width x (data.byteLength / 4 / width)`
+    #[napi]
+    pub fn write(
+        &mut self,
+        width: u32,
+        data: BufferSlice,
+        format: Option<BitmapFormat>,
+    ) -> anyhow::Result<()> {
+        let format = format.unwrap_or(BitmapFormat::Bgra8).into();
+        let info = shm::BitmapInfo::new(width, 0, format);
+        let height = data
+            .len()
+            .checked_div(info.stride as usize)
+            .context("invalid bitmap width")?;
+        let info = shm::BitmapInfo {
+            height: height as _,
+            ..info
+        };
+
+        self.0.write(info, &data)?;
+        Ok(())
+    }
+}
+
+#[napi(string_enum)]
+pub enum BitmapFormat {
+    Bgra8,
+    Rgba8,
+}
+
+impl From<BitmapFormat> for shm::BitmapFormat {
+    fn from(val: BitmapFormat) -> Self {
+        match val {
+            BitmapFormat::Bgra8 => Self::Bgra8,
+            BitmapFormat::Rgba8 => Self::Rgba8,
+        }
+    }
+}
+
+/// Update overlay texture from a [`BitmapShm`]. If `name` is not given, the texture is removed.
+#[napi(object)]
+pub struct UpdateBitmapShm {
+    pub name: Option<String>,
+}
+
+impl From<UpdateBitmapShm> for request::surface::UpdateBitmapShm {
+    fn from(val: UpdateBitmapShm) -> Self {
+        Self { name: val.name }
+    }
+}
+
+/// Changed rectangle of overlay texture.
+#[napi(object)]
+pub struct DirtyRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<DirtyRect> for request::surface::DirtyRect {
+    fn from(val: DirtyRect) -> Self {
+        Self {
+            x: val.x,
+            y: val.y,
+            width: val.width,
+            height: val.height,
+        }
+    }
+}
+
 #[napi]
 pub enum UpdateSharedHandle {
     Kmt(u32),
@@ -133,6 +232,290 @@ pub struct Rect {
     pub height: u32,
 }
 
+/// Point of the surface and overlay aligned each other.
+#[napi(string_enum)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl From<Anchor> for layout::Anchor {
+    fn from(val: Anchor) -> Self {
+        match val {
+            Anchor::TopLeft => Self::TopLeft,
+            Anchor::Top => Self::Top,
+            Anchor::TopRight => Self::TopRight,
+            Anchor::Left => Self::Left,
+            Anchor::Center => Self::Center,
+            Anchor::Right => Self::Right,
+            Anchor::BottomLeft => Self::BottomLeft,
+            Anchor::Bottom => Self::Bottom,
+            Anchor::BottomRight => Self::BottomRight,
+        }
+    }
+}
+
+/// Length relative to a surface dimension.
+#[napi]
+pub enum Length {
+    /// Length in pixels.
+    Pixels(i32),
+
+    /// Length in percent of the surface dimension.
+    Percent(f64),
+}
+
+impl From<Length> for layout::Length {
+    fn from(val: Length) -> Self {
+        match val {
+            Length::Pixels(pixels) => Self::Pixels(pixels),
+            Length::Percent(percent) => Self::Percent(percent as f32),
+        }
+    }
+}
+
+/// Describe where and how large overlay is drawn in a surface.
+#[napi(object)]
+pub struct Layout {
+    /// Anchor point of the surface and overlay. Defaults to `TopLeft`.
+    pub anchor: Option<Anchor>,
+
+    /// Horizontal offset from the anchor point.
+    pub x: Option<Length>,
+
+    /// Vertical offset from the anchor point.
+    pub y: Option<Length>,
+
+    /// Maximum width of overlay. Larger overlay is scaled down, keeping its aspect ratio.
+    pub max_width: Option<Length>,
+
+    /// Maximum height of overlay. Larger overlay is scaled down, keeping its aspect ratio.
+    pub max_height: Option<Length>,
+}
+
+impl From<Layout> for layout::Layout {
+    fn from(val: Layout) -> Self {
+        Self {
+            anchor: val.anchor.map(Into::into).unwrap_or_default(),
+            x: val.x.map(Into::into).unwrap_or_default(),
+            y: val.y.map(Into::into).unwrap_or_default(),
+            max_width: val.max_width.map(Into::into),
+            max_height: val.max_height.map(Into::into),
+        }
+    }
+}
+
+/// Describe how overlay is sized to a surface before applying scale.
+#[napi(string_enum)]
+pub enum FitMode {
+    None,
+    Contain,
+    Cover,
+    Stretch,
+}
+
+impl From<FitMode> for layout::FitMode {
+    fn from(val: FitMode) -> Self {
+        match val {
+            FitMode::None => Self::None,
+            FitMode::Contain => Self::Contain,
+            FitMode::Cover => Self::Cover,
+            FitMode::Stretch => Self::Stretch,
+        }
+    }
+}
+
+/// Describe how overlay is drawn in a surface.
+#[napi(object)]
+pub struct Transform {
+    /// Opacity of overlay, from `0.0` to `1.0`. Defaults to `1.0`.
+    pub opacity: Option<f64>,
+
+    /// Horizontal scale applied after fitting. Defaults to `1.0`.
+    pub scale_x: Option<f64>,
+
+    /// Vertical scale applied after fitting. Defaults to `1.0`.
+    pub scale_y: Option<f64>,
+
+    /// How overlay is sized to the surface. Defaults to `None`.
+    pub fit: Option<FitMode>,
+}
+
+impl From<Transform> for layout::Transform {
+    fn from(val: Transform) -> Self {
+        Self {
+            opacity: val.opacity.map_or(1.0, |opacity| opacity as f32),
+            scale_x: val.scale_x.map_or(1.0, |scale| scale as f32),
+            scale_y: val.scale_y.map_or(1.0, |scale| scale as f32),
+            fit: val.fit.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+/// How overlay colors are blended onto a surface.
+#[napi(string_enum)]
+pub enum BlendMode {
+    /// Colors are not multiplied by alpha.
+    Straight,
+
+    /// Colors are already multiplied by alpha.
+    Premultiplied,
+
+    /// Colors multiplied by alpha are added to the surface.
+    Additive,
+}
+
+impl From<BlendMode> for blend::BlendMode {
+    fn from(val: BlendMode) -> Self {
+        match val {
+            BlendMode::Straight => Self::Straight,
+            BlendMode::Premultiplied => Self::Premultiplied,
+            BlendMode::Additive => Self::Additive,
+        }
+    }
+}
+
+/// Color encoding of overlay texture.
+#[napi(string_enum)]
+pub enum ColorEncoding {
+    /// Colors are sRGB encoded.
+    Srgb,
+
+    /// Colors are linear.
+    Linear,
+}
+
+impl From<ColorEncoding> for blend::ColorEncoding {
+    fn from(val: ColorEncoding) -> Self {
+        match val {
+            ColorEncoding::Srgb => Self::Srgb,
+            ColorEncoding::Linear => Self::Linear,
+        }
+    }
+}
+
+/// Describe how overlay colors are blended onto a surface.
+#[napi(object)]
+pub struct Blend {
+    /// Blend mode of overlay. Defaults to `Straight`.
+    pub mode: Option<BlendMode>,
+
+    /// Color encoding of overlay texture. Defaults to `Srgb`.
+    pub encoding: Option<ColorEncoding>,
+}
+
+impl From<Blend> for blend::Blend {
+    fn from(val: Blend) -> Self {
+        Self {
+            mode: val.mode.map(Into::into).unwrap_or_default(),
+            encoding: val.encoding.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+/// Create or update an overlay layer. Fields left undefined keep their current value.
+#[napi(object)]
+pub struct UpdateLayer {
+    /// Layer id. Layer `0` is the base layer.
+    pub layer: u32,
+
+    /// Z index of the layer. Layers with higher z index are drawn on top.
+    pub z_index: Option<i32>,
+
+    /// Whether if the layer is drawn.
+    pub visible: Option<bool>,
+
+    /// Layout of the layer.
+    pub layout: Option<Layout>,
+
+    /// Transform of the layer.
+    pub transform: Option<Transform>,
+
+    /// Blend mode and color encoding of the layer.
+    pub blend: Option<Blend>,
+
+    /// Shared handle of the layer texture.
+    pub handle: Option<UpdateSharedHandle>,
+
+    /// Bitmap shared memory of the layer texture.
+    pub bitmap_shm: Option<UpdateBitmapShm>,
+
+    /// Changed regions of the layer texture.
+    pub invalidate: Option<Vec<DirtyRect>>,
+}
+
+impl From<UpdateLayer> for request::surface::UpdateLayer {
+    fn from(val: UpdateLayer) -> Self {
+        Self {
+            layer: val.layer,
+            z_index: val.z_index,
+            visible: val.visible,
+            layout: val.layout.map(Into::into),
+            transform: val.transform.map(Into::into),
+            blend: val.blend.map(Into::into),
+            handle: val.handle.map(Into::into),
+            bitmap_shm: val.bitmap_shm.map(Into::into),
+            invalidate: val
+                .invalidate
+                .map(|rects| rects.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+/// Describe interactive region of an overlay layer.
+#[napi]
+pub enum HitRegion {
+    /// Layer does not capture any cursor input.
+    None,
+
+    /// Entire layer captures cursor inputs.
+    Full,
+
+    /// Only cursor inputs inside of the rectangles are captured.
+    Rects(Vec<HitRect>),
+
+    /// Only cursor inputs over pixels with alpha value equal or greater than `threshold` are captured.
+    AlphaMask { threshold: u8 },
+}
+
+impl From<HitRegion> for request::surface::HitRegion {
+    fn from(val: HitRegion) -> Self {
+        match val {
+            HitRegion::None => Self::None,
+            HitRegion::Full => Self::Full,
+            HitRegion::Rects(rects) => Self::Rects(rects.into_iter().map(Into::into).collect()),
+            HitRegion::AlphaMask { threshold } => Self::AlphaMask { threshold },
+        }
+    }
+}
+
+/// Rectangle relative to overlay layer.
+#[napi(object)]
+pub struct HitRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<HitRect> for request::surface::HitRect {
+    fn from(val: HitRect) -> Self {
+        Self {
+            x: val.x,
+            y: val.y,
+            width: val.width,
+            height: val.height,
+        }
+    }
+}
+
 fn create_adapter_by_luid(luid: GpuLuid) -> anyhow::Result<Option<IDXGIAdapter>> {
     let factory =
         unsafe { CreateDXGIFactory1::<IDXGIFactory1>().context("failed to create DXGI factory")? };
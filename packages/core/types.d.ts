@@ -4,13 +4,43 @@ export declare type OverlayEventEmitter = EventEmitter<{
   /**
   * A window has been added.
   */
-  window_added: [id: number, width: number, height: number],
+  window_added: [id: number, width: number, height: number, info: WindowInfo],
 
   /**
    * A window has been resized.
    */
   window_resized: [id: number, width: number, height: number],
 
+  /**
+   * Title of a window has changed.
+   */
+  window_title_changed: [id: number, title: string],
+
+  /**
+   * A window has gained or lost focus.
+   */
+  window_focus_changed: [id: number, focused: boolean],
+
+  /**
+   * A window has been moved. Position of the client area in screen coordinates.
+   */
+  window_moved: [id: number, x: number, y: number],
+
+  /**
+   * A window has been minimized.
+   */
+  window_minimized: [id: number],
+
+  /**
+   * A window has been restored from minimized state.
+   */
+  window_restored: [id: number],
+
+  /**
+   * DPI of a window has changed. `1.0` scale is 96 DPI.
+   */
+  window_dpi_changed: [id: number, scale: number],
+
   /**
    * Cursor input from a window.
    */
@@ -21,6 +51,23 @@ export declare type OverlayEventEmitter = EventEmitter<{
    */
   window_keyboard_input: [id: number, input: KeyboardInput],
 
+  /**
+   * Touch input from a window.
+   */
+  window_touch_input: [id: number, input: TouchInput],
+
+  /**
+   * Pen input from a window.
+   */
+  window_pen_input: [id: number, input: PenInput],
+
+  /**
+   * Gamepad input from the window of the thread polling gamepads.
+   *
+   * Only emitted while the game polls gamepads with `XInputGetState`.
+   */
+  window_gamepad_input: [id: number, input: GamepadInput],
+
   /**
    * Window is destroyed.
    */
@@ -29,22 +76,49 @@ export declare type OverlayEventEmitter = EventEmitter<{
   /**
    * A surface has been added.
    */
-  surface_added: [id: bigint, width: number, height: number, info: SurfaceInfo],
+  surface_added: [
+    id: bigint,
+    width: number,
+    height: number,
+    info: SurfaceInfo,
+    backbuffer: BackbufferInfo,
+  ],
 
   /**
-   * A surface has been resized.
+   * A surface has been resized or its backbuffer properties have changed.
    */
-  surface_resized: [id: bigint, width: number, height: number],
+  surface_resized: [id: bigint, width: number, height: number, backbuffer: BackbufferInfo],
+
+  /**
+   * A frame of the surface has been presented.
+   * Only emitted while present events are enabled, or once after a frame is requested.
+   *
+   * Timestamp is in microseconds since the UNIX epoch.
+   */
+  surface_presented: [id: bigint, frame: bigint, timestamp: bigint],
+
+  /**
+   * Periodic frame time statistics of a surface.
+   * Only emitted while statistics events are enabled.
+   */
+  surface_stats: [id: bigint, stats: FrameStats],
 
   /**
    * A surface has been destroyed.
    */
   surface_destroyed: [id: bigint],
 
+  /**
+   * Cursor input routed to the topmost surface layer under the cursor.
+   * The position is relative to the layer texture. `layer` is `0` for the base layer.
+   * Only emitted if surface input routing is enabled.
+   */
+  surface_cursor_input: [id: bigint, windowId: number, input: CursorInput, layer: number],
+
   /**
    * Input blocking is interrupted and turned off.
    */
-  input_blocking_ended: [id: number],
+  input_blocking_ended: [reason: InputBlockingEndReason],
   
   /**
    * Tracing span has been entered.
@@ -1,7 +1,12 @@
-import { type MouseInputEvent, type MouseWheelInputEvent, type WebContents } from 'electron';
+import {
+  type InputEvent,
+  type MouseInputEvent,
+  type MouseWheelInputEvent,
+  type WebContents,
+} from 'electron';
 import type { OverlayWindow } from './index.js';
 import type { CursorInput, CursorInputKind, KeyboardInput } from '@asdf-overlay/core';
-import { mapCssCursor, mapKeycode } from './input/conv.js';
+import { mapCssCursor, mapKeycode, mapModifiers } from './input/conv.js';
 import { Cursor } from '@asdf-overlay/core';
 
 /**
@@ -149,6 +154,7 @@ export class ElectronOverlayInput {
 
     const movementX = globalX - this.lastWindowCursor.x;
     const movementY = globalY - this.lastWindowCursor.y;
+    this.modifiers = mapModifiers(input.modifiers, input.buttons);
 
     switch (input.kind.type) {
       case 'Enter': {
@@ -242,83 +248,22 @@ export class ElectronOverlayInput {
     this.lastWindowCursor.y = globalY;
   }
 
-  private readonly modifiersMap = {
-    shift: false,
-    ctrl: false,
-    alt: false,
-    super: false,
-    meta: false,
-  };
-
-  private modifiers: ('shift' | 'ctrl' | 'alt' | 'meta' | 'cmd')[] = [];
-  private updateModifiers(key: string, downState: boolean) {
-    switch (key) {
-      case 'Control': {
-        this.modifiersMap.ctrl = downState;
-        break;
-      }
-
-      case 'Shift': {
-        this.modifiersMap.shift = downState;
-        break;
-      }
-
-      case 'Super': {
-        this.modifiersMap.super = downState;
-        break;
-      }
-
-      case 'Meta': {
-        this.modifiersMap.meta = downState;
-        break;
-      }
-
-      case 'Alt': {
-        this.modifiersMap.alt = downState;
-        break;
-      }
-
-      default: {
-        return;
-      }
-    }
-
-    this.modifiers = [];
-    if (this.modifiersMap.shift) {
-      this.modifiers.push('shift');
-    }
-
-    if (this.modifiersMap.ctrl) {
-      this.modifiers.push('ctrl');
-    }
-
-    if (this.modifiersMap.alt) {
-      this.modifiers.push('alt');
-    }
-
-    if (this.modifiersMap.meta) {
-      this.modifiers.push('meta');
-    }
-
-    if (this.modifiersMap.super) {
-      this.modifiers.push('cmd');
-    }
-  }
+  private modifiers: InputEvent['modifiers'] = [];
 
   sendKeyboardInput(input: KeyboardInput) {
     switch (input.type) {
       case 'Key': {
-        const keyCode = mapKeycode(input.key.code);
+        const keyCode = mapKeycode(input.keyCode);
         if (!keyCode) {
           return;
         }
 
         const pressed = input.state === 'Pressed';
-        this.updateModifiers(keyCode, pressed);
+        this.modifiers = mapModifiers(input.modifiers);
         this.contents.sendInputEvent({
           type: pressed ? 'keyDown' : 'keyUp',
           keyCode,
-          modifiers: this.modifiers,
+          modifiers: input.repeat ? [...this.modifiers ?? [], 'isAutoRepeat'] : this.modifiers,
         });
         return;
       }
@@ -1,4 +1,5 @@
-import { Cursor } from '@asdf-overlay/core';
+import type { InputEvent } from 'electron';
+import { Cursor, CursorButton, type KeyCode, Modifier } from '@asdf-overlay/core';
 
 /**
  * Map CSS cursor into overlay `Cursor`.
@@ -60,135 +61,185 @@ export function mapCssCursor(cursor: string): Cursor | undefined {
 }
 
 /**
- * Map Windows virtual key code into Electron accelerator keycode.
+ * Map `KeyCode` into Electron accelerator keycode.
  *
- * @see https://learn.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes
+ * @see https://www.w3.org/TR/uievents-code/
  * @see https://www.electronjs.org/docs/latest/api/accelerator
  */
-export function mapKeycode(code: number): string | undefined {
+export function mapKeycode(code: KeyCode): string | undefined {
   return KEYS[code];
 }
 
 /**
- * Conversion map from windows keycode to Electron accelerator keycode.
+ * Conversion map from `KeyCode` to Electron accelerator keycode.
  */
-const KEYS: Record<number, string | undefined> = {
-  8: 'Backspace',
-  9: 'Tab',
-  13: 'Enter',
-  16: 'Shift',
-  17: 'Control',
-  18: 'Alt',
-  20: 'Capslock',
-  27: 'Escape',
-  32: 'Space',
-  33: 'PageUp',
-  34: 'PageDown',
-  35: 'End',
-  36: 'Home',
-  37: 'Left',
-  38: 'Up',
-  39: 'Right',
-  44: 'PrintScreen',
-  45: 'Insert',
-  46: 'Delete',
-  48: '0',
-  49: '1',
-  50: '2',
-  51: '3',
-  52: '4',
-  53: '5',
-  54: '6',
-  55: '7',
-  56: '8',
-  57: '9',
-  65: 'A',
-  66: 'B',
-  67: 'C',
-  68: 'D',
-  69: 'E',
-  70: 'F',
-  71: 'G',
-  72: 'H',
-  73: 'I',
-  74: 'J',
-  75: 'K',
-  76: 'L',
-  77: 'M',
-  78: 'N',
-  79: 'O',
-  80: 'P',
-  81: 'Q',
-  82: 'R',
-  83: 'S',
-  84: 'T',
-  85: 'U',
-  86: 'V',
-  87: 'W',
-  88: 'X',
-  89: 'Y',
-  90: 'Z',
-  91: 'Super',
-  92: 'Super',
-  93: 'Meta',
-  96: 'num0',
-  97: 'num1',
-  98: 'num2',
-  99: 'num3',
-  100: 'num4',
-  101: 'num5',
-  102: 'num6',
-  103: 'num7',
-  104: 'num8',
-  105: 'num9',
-  106: 'nummult',
-  108: 'numsub',
-  109: 'numsub',
-  110: 'numdec',
-  111: 'numdiv',
-  112: 'F1',
-  113: 'F2',
-  114: 'F3',
-  115: 'F4',
-  116: 'F5',
-  117: 'F6',
-  118: 'F7',
-  119: 'F8',
-  120: 'F9',
-  121: 'F10',
-  122: 'F11',
-  123: 'F12',
-  124: 'F13',
-  125: 'F14',
-  126: 'F15',
-  127: 'F16',
-  128: 'F17',
-  129: 'F18',
-  130: 'F19',
-  131: 'F20',
-  132: 'F21',
-  133: 'F22',
-  134: 'F23',
-  135: 'F24',
-  144: 'Numlock',
-  145: 'Scrolllock',
-  176: 'MediaNextTrack',
-  177: 'MediaPreviousTrack',
-  178: 'MediaStop',
-  179: 'MediaPlayPause',
-  181: 'VolumeMute',
-  182: 'VolumeDown',
-  183: 'VolumeUp',
-  186: ';',
-  187: '=',
-  188: ',',
-  189: '-',
-  190: '.',
-  191: '/',
-  192: '`',
-  219: '[',
-  220: '\\',
-  221: ']',
-  222: '\'',
-  225: 'AltGr',
+const KEYS: Record<string, string | undefined> = {
+  Backspace: 'Backspace',
+  Tab: 'Tab',
+  Enter: 'Enter',
+  NumpadEnter: 'Enter',
+  ShiftLeft: 'Shift',
+  ShiftRight: 'Shift',
+  ControlLeft: 'Control',
+  ControlRight: 'Control',
+  AltLeft: 'Alt',
+  AltRight: 'Alt',
+  CapsLock: 'Capslock',
+  Escape: 'Escape',
+  Space: 'Space',
+  PageUp: 'PageUp',
+  PageDown: 'PageDown',
+  End: 'End',
+  Home: 'Home',
+  ArrowLeft: 'Left',
+  ArrowUp: 'Up',
+  ArrowRight: 'Right',
+  ArrowDown: 'Down',
+  PrintScreen: 'PrintScreen',
+  Insert: 'Insert',
+  Delete: 'Delete',
+  Digit0: '0',
+  Digit1: '1',
+  Digit2: '2',
+  Digit3: '3',
+  Digit4: '4',
+  Digit5: '5',
+  Digit6: '6',
+  Digit7: '7',
+  Digit8: '8',
+  Digit9: '9',
+  KeyA: 'A',
+  KeyB: 'B',
+  KeyC: 'C',
+  KeyD: 'D',
+  KeyE: 'E',
+  KeyF: 'F',
+  KeyG: 'G',
+  KeyH: 'H',
+  KeyI: 'I',
+  KeyJ: 'J',
+  KeyK: 'K',
+  KeyL: 'L',
+  KeyM: 'M',
+  KeyN: 'N',
+  KeyO: 'O',
+  KeyP: 'P',
+  KeyQ: 'Q',
+  KeyR: 'R',
+  KeyS: 'S',
+  KeyT: 'T',
+  KeyU: 'U',
+  KeyV: 'V',
+  KeyW: 'W',
+  KeyX: 'X',
+  KeyY: 'Y',
+  KeyZ: 'Z',
+  MetaLeft: 'Super',
+  MetaRight: 'Super',
+  ContextMenu: 'Meta',
+  Numpad0: 'num0',
+  Numpad1: 'num1',
+  Numpad2: 'num2',
+  Numpad3: 'num3',
+  Numpad4: 'num4',
+  Numpad5: 'num5',
+  Numpad6: 'num6',
+  Numpad7: 'num7',
+  Numpad8: 'num8',
+  Numpad9: 'num9',
+  NumpadMultiply: 'nummult',
+  NumpadAdd: 'numadd',
+  NumpadSubtract: 'numsub',
+  NumpadDecimal: 'numdec',
+  NumpadDivide: 'numdiv',
+  F1: 'F1',
+  F2: 'F2',
+  F3: 'F3',
+  F4: 'F4',
+  F5: 'F5',
+  F6: 'F6',
+  F7: 'F7',
+  F8: 'F8',
+  F9: 'F9',
+  F10: 'F10',
+  F11: 'F11',
+  F12: 'F12',
+  F13: 'F13',
+  F14: 'F14',
+  F15: 'F15',
+  F16: 'F16',
+  F17: 'F17',
+  F18: 'F18',
+  F19: 'F19',
+  F20: 'F20',
+  F21: 'F21',
+  F22: 'F22',
+  F23: 'F23',
+  F24: 'F24',
+  NumLock: 'Numlock',
+  ScrollLock: 'Scrolllock',
+  MediaTrackNext: 'MediaNextTrack',
+  MediaTrackPrevious: 'MediaPreviousTrack',
+  MediaStop: 'MediaStop',
+  MediaPlayPause: 'MediaPlayPause',
+  AudioVolumeMute: 'VolumeMute',
+  AudioVolumeDown: 'VolumeDown',
+  AudioVolumeUp: 'VolumeUp',
+  Semicolon: ';',
+  Equal: '=',
+  Comma: ',',
+  Minus: '-',
+  Period: '.',
+  Slash: '/',
+  Backquote: '`',
+  BracketLeft: '[',
+  Backslash: '\\',
+  BracketRight: ']',
+  Quote: '\'',
 };
+
+/**
+ * Map overlay modifier and cursor button bitflags into Electron input event modifiers.
+ *
+ * @see https://www.electronjs.org/docs/latest/api/structures/input-event
+ */
+export function mapModifiers(modifiers: number, buttons = 0): InputEvent['modifiers'] {
+  const list: NonNullable<InputEvent['modifiers']> = [];
+  if (modifiers & (Modifier.LeftShift | Modifier.RightShift)) {
+    list.push('shift');
+  }
+
+  if (modifiers & (Modifier.LeftCtrl | Modifier.RightCtrl)) {
+    list.push('control');
+  }
+
+  if (modifiers & (Modifier.LeftAlt | Modifier.RightAlt)) {
+    list.push('alt');
+  }
+
+  if (modifiers & (Modifier.LeftWin | Modifier.RightWin)) {
+    list.push('meta');
+  }
+
+  if (modifiers & Modifier.CapsLock) {
+    list.push('capsLock');
+  }
+
+  if (modifiers & Modifier.NumLock) {
+    list.push('numLock');
+  }
+
+  if (buttons & CursorButton.Left) {
+    list.push('leftButtonDown');
+  }
+
+  if (buttons & CursorButton.Middle) {
+    list.push('middleButtonDown');
+  }
+
+  if (buttons & CursorButton.Right) {
+    list.push('rightButtonDown');
+  }
+
+  return list;
+}